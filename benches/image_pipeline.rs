@@ -0,0 +1,104 @@
+//! The image pipeline of the reader: decoding a page, resizing it to the reader and encoding it for the terminal.
+//! Save a baseline with `cargo bench -- --save-baseline main` before a change and compare against it with
+//! `cargo bench -- --baseline main`
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, RgbImage};
+use manga_tui::image_pipeline::decode_page;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::widgets::StatefulWidget;
+use ratatui_image::picker::{Picker, ProtocolType};
+use ratatui_image::{Resize, StatefulImage};
+
+static FONT_SIZE: (u16, u16) = (8, 16);
+
+/// The usual size of a data saver page and of a high quality one
+static PAGE_SIZES: [(&str, (u32, u32)); 2] = [("data saver", (980, 1400)), ("high quality", (1600, 2300))];
+
+/// The part of a 200x50 terminal pages are drawn in
+static READER_AREA: Rect = Rect {
+    x: 0,
+    y: 0,
+    width: 80,
+    height: 48,
+};
+
+/// The filter of each render quality
+static FILTERS: [(&str, FilterType); 3] =
+    [("fast", FilterType::Nearest), ("balanced", FilterType::Triangle), ("high", FilterType::Lanczos3)];
+
+static PROTOCOLS: [ProtocolType; 4] = [ProtocolType::Halfblocks, ProtocolType::Sixel, ProtocolType::Kitty, ProtocolType::Iterm2];
+
+/// A jpg with enough detail for decoding and resizing to do real work
+fn encoded_page((width, height): (u32, u32)) -> Vec<u8> {
+    let image = RgbImage::from_fn(width, height, |x, y| image::Rgb([(x % 256) as u8, (y % 256) as u8, ((x * y) % 256) as u8]));
+
+    let mut bytes = std::io::Cursor::new(vec![]);
+    DynamicImage::ImageRgb8(image).write_to(&mut bytes, ImageFormat::Jpeg).unwrap();
+    bytes.into_inner()
+}
+
+fn fit_to_reader(image: &DynamicImage, filter: FilterType) -> DynamicImage {
+    let height = READER_AREA.height as u32 * FONT_SIZE.1 as u32;
+    let width = image.width() * height / image.height();
+    image.resize_exact(width, height, filter)
+}
+
+/// Runs the whole image pipeline on a page, the way the reader does with the default render quality
+fn render_page_with(protocol: ProtocolType, bytes: &[u8]) {
+    let mut picker = Picker::new(FONT_SIZE);
+    picker.protocol_type = protocol;
+
+    let filter = FilterType::Triangle;
+    let resized = fit_to_reader(&decode_page(bytes).unwrap(), filter);
+
+    let mut image_state = picker.new_resize_protocol(resized);
+    let mut buf = Buffer::empty(READER_AREA);
+    StatefulWidget::render(StatefulImage::new(None).resize(Resize::Fit(Some(filter))), READER_AREA, &mut buf, &mut image_state);
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+
+    for (name, size) in PAGE_SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &encoded_page(size), |b, bytes| {
+            b.iter(|| decode_page(bytes).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn resize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resize");
+
+    for (name, size) in PAGE_SIZES {
+        let image = decode_page(&encoded_page(size)).unwrap();
+
+        for (quality, filter) in FILTERS {
+            group.bench_with_input(BenchmarkId::new(name, quality), &image, |b, image| b.iter(|| fit_to_reader(image, filter)));
+        }
+    }
+
+    group.finish();
+}
+
+fn pipeline(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pipeline");
+
+    for (name, size) in PAGE_SIZES {
+        let bytes = encoded_page(size);
+
+        for protocol in PROTOCOLS {
+            group.bench_with_input(BenchmarkId::new(name, format!("{protocol:?}")), &bytes, |b, bytes| {
+                b.iter(|| render_page_with(protocol, bytes))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, decode, resize, pipeline);
+criterion_main!(benches);
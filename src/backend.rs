@@ -7,15 +7,30 @@ use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter, IntoEnumIterator};
 
+use self::database::get_parental_lock;
 use self::error_log::ERROR_LOGS_FILE;
+use self::parental_lock::PARENTAL_LOCK;
 use crate::config::{MangaTuiConfig, CONFIG};
+use crate::global::PROFILE;
 
+pub mod cache;
 pub mod database;
+pub mod doctor;
 pub mod download;
 pub mod error_log;
+pub mod export;
 pub mod fetch;
 pub mod filter;
+pub mod hyperlink;
+pub mod import;
+#[cfg(feature = "ocr")]
+pub mod ocr;
+pub mod parental_lock;
+pub mod reading_order;
+pub mod repeated_pages;
 pub mod tui;
+pub mod update;
+pub mod watch;
 
 #[derive(Display, EnumIter)]
 pub enum AppDirectories {
@@ -46,12 +61,29 @@ impl AppDirectories {
 }
 
 pub static APP_DATA_DIR: Lazy<Option<PathBuf>> = Lazy::new(|| {
-    directories::ProjectDirs::from("", "", "manga-tui").map(|dirs| match std::env::var("MANGA_TUI_DATA_DIR").ok() {
-        Some(data_dir) => PathBuf::from(data_dir),
-        None => dirs.data_dir().to_path_buf(),
+    directories::ProjectDirs::from("", "", "manga-tui").map(|dirs| {
+        let data_dir = match std::env::var("MANGA_TUI_DATA_DIR").ok() {
+            Some(data_dir) => PathBuf::from(data_dir),
+            None => dirs.data_dir().to_path_buf(),
+        };
+        profile_data_dir(data_dir, PROFILE.get().map(String::as_str))
     })
 });
 
+/// Each profile keeps its config, database, downloads and error logs in a directory of its own, without
+/// `--profile` the data directory is used as it always was
+pub fn profile_data_dir(data_dir: PathBuf, profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(profile) => data_dir.join("profiles").join(profile),
+        None => data_dir,
+    }
+}
+
+/// Profile names end up in a path, so only letters, numbers, `-` and `_` are allowed
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|character| character.is_alphanumeric() || character == '-' || character == '_')
+}
+
 pub fn build_data_dir() -> Result<(), std::io::Error> {
     let data_dir = APP_DATA_DIR.as_ref();
     match data_dir {
@@ -73,6 +105,11 @@ pub fn build_data_dir() -> Result<(), std::io::Error> {
 
             CONFIG.set(config_contents).unwrap();
 
+            // Starting without the lock because the database could not be read would get around it
+            if let Some(lock) = get_parental_lock().map_err(std::io::Error::other)? {
+                PARENTAL_LOCK.set(lock).ok();
+            }
+
             Ok(())
         },
         None => Err(std::io::Error::other("data dir could not be found")),
@@ -108,8 +145,34 @@ pub struct Attributes {
     pub content_rating: String,
     pub state: String,
     pub created_at: String,
+    pub year: Option<u32>,
     pub publication_demographic: Option<String>,
     pub available_translated_languages: Vec<Option<String>>,
+    /// The number of the final chapter, only set once the manga is finished
+    pub last_chapter: Option<String>,
+    /// Each one has a single title by the ISO code of its language
+    #[serde(default)]
+    pub alt_titles: Vec<HashMap<String, String>>,
+    #[serde(default)]
+    pub original_language: String,
+}
+
+impl Attributes {
+    /// The title in the first of `languages` the manga has one in, among its title and alternative titles,
+    /// `original` stands for the language it was first published in. The main title is used when there is none
+    pub fn preferred_title(&self, languages: &[String]) -> String {
+        let alt_titles =
+            self.alt_titles.iter().flat_map(|titles| titles.iter().map(|(lang, title)| (lang.as_str(), title.as_str())));
+        let titles: Vec<(&str, &str)> = self.title.by_language().chain(alt_titles).collect();
+
+        languages
+            .iter()
+            .map(|lang| if lang == "original" { self.original_language.as_str() } else { lang.as_str() })
+            .find_map(|lang| titles.iter().find(|(title_lang, _)| *title_lang == lang))
+            .or_else(|| titles.first())
+            .map(|(_, title)| title.to_string())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -123,10 +186,28 @@ pub struct Title {
     pub ko: Option<String>,
     #[serde(rename = "zh-ro")]
     pub zh_ro: Option<String>,
-    #[serde(rename = "zh-ro")]
+    #[serde(rename = "ko-ro")]
     pub ko_ro: Option<String>,
 }
 
+impl Title {
+    /// The titles set with the ISO code of their language, english first and then the romanized japanese one
+    pub fn by_language(&self) -> impl Iterator<Item = (&str, &str)> {
+        [
+            ("en", &self.en),
+            ("ja-ro", &self.ja_ro),
+            ("ja", &self.ja),
+            ("ja", &self.jp),
+            ("zh", &self.zh),
+            ("ko", &self.ko),
+            ("ko-ro", &self.ko_ro),
+            ("zh-ro", &self.zh_ro),
+        ]
+        .into_iter()
+        .filter_map(|(lang, title)| title.as_deref().map(|title| (lang, title)))
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Description {
@@ -139,6 +220,8 @@ pub struct MangaSearchRelationship {
     pub id: String,
     #[serde(rename = "type")]
     pub type_field: String,
+    /// How a related manga is related to this one, like `sequel` or `prequel`
+    pub related: Option<String>,
     pub attributes: Option<MangaSearchAttributes>,
 }
 
@@ -191,6 +274,16 @@ pub struct ChapterData {
     pub relationships: Vec<Relationship>,
 }
 
+impl ChapterData {
+    /// Every chapter is related to the manga it belongs to, even when the manga is not included
+    pub fn manga_id(&self) -> Option<&str> {
+        self.relationships
+            .iter()
+            .find(|relationship| relationship.type_field == "manga")
+            .map(|relationship| relationship.id.as_str())
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChapterAttribute {
@@ -339,3 +432,439 @@ pub mod authors {
         pub version: i64,
     }
 }
+
+pub mod covers {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CoversResponse {
+        pub result: String,
+        pub response: String,
+        pub data: Vec<CoverData>,
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CoverData {
+        pub id: String,
+        pub attributes: Attributes,
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Attributes {
+        pub volume: Option<String>,
+        pub file_name: String,
+    }
+}
+
+pub mod aggregate {
+    use serde::{Deserialize, Deserializer, Serialize};
+
+    /// Mangadex sends an empty array instead of an empty object when a manga has no volumes or a
+    /// volume has no chapters, only the values are kept since the keys are repeated inside them
+    fn values_of_map_or_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum MapOrList<T> {
+            Map(std::collections::HashMap<String, T>),
+            List(Vec<T>),
+        }
+
+        Ok(match MapOrList::deserialize(deserializer)? {
+            MapOrList::Map(map) => map.into_values().collect(),
+            MapOrList::List(list) => list,
+        })
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AggregateResponse {
+        pub result: String,
+        #[serde(deserialize_with = "values_of_map_or_list")]
+        pub volumes: Vec<Volume>,
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Volume {
+        pub volume: String,
+        #[serde(deserialize_with = "values_of_map_or_list")]
+        pub chapters: Vec<Chapter>,
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Chapter {
+        pub chapter: String,
+        pub id: String,
+    }
+
+    /// How many of the chapters of a volume are translated to a language
+    #[derive(Default, Debug, Clone, PartialEq, Eq)]
+    pub struct VolumeCompleteness {
+        pub volume: String,
+        pub translated: usize,
+        pub total: usize,
+    }
+
+    impl VolumeCompleteness {
+        pub fn is_complete(&self) -> bool {
+            self.translated >= self.total
+        }
+    }
+
+    /// Numbers go first in numeric order, the rest like "none" go after them
+    fn compare_numbers(a: &str, b: &str) -> std::cmp::Ordering {
+        match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.total_cmp(&b),
+            (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+            (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+            (Err(_), Err(_)) => a.cmp(b),
+        }
+    }
+
+    impl AggregateResponse {
+        /// Goes through the volumes of `self`, the aggregate of every language, counting the chapters `translated`
+        /// also has. Chapters are compared by number in any volume, since a translation can put a chapter in
+        /// another volume or in none at all, volumes without a number go last
+        pub fn volume_completeness(&self, translated: &AggregateResponse) -> Vec<VolumeCompleteness> {
+            let translated_chapters: std::collections::HashSet<&str> = translated
+                .volumes
+                .iter()
+                .flat_map(|volume| volume.chapters.iter())
+                .map(|chapter| chapter.chapter.as_str())
+                .collect();
+
+            let mut volumes: Vec<VolumeCompleteness> = self
+                .volumes
+                .iter()
+                .map(|volume| VolumeCompleteness {
+                    volume: volume.volume.clone(),
+                    translated: volume
+                        .chapters
+                        .iter()
+                        .filter(|chapter| translated_chapters.contains(chapter.chapter.as_str()))
+                        .count(),
+                    total: volume.chapters.len(),
+                })
+                .collect();
+
+            volumes.sort_by(|a, b| compare_numbers(&a.volume, &b.volume));
+
+            volumes
+        }
+
+        /// A single upload of each chapter number in any volume, the one in `preferred` when it has that chapter,
+        /// in order of chapter number with the chapters without a number last
+        pub fn one_upload_per_chapter<'a>(&'a self, preferred: Option<&'a AggregateResponse>) -> Vec<&'a Chapter> {
+            let mut seen: std::collections::HashSet<&str> = std::collections::HashSet::new();
+
+            let mut chapters: Vec<&Chapter> = preferred
+                .into_iter()
+                .chain(std::iter::once(self))
+                .flat_map(|aggregate| aggregate.volumes.iter())
+                .flat_map(|volume| volume.chapters.iter())
+                .filter(|chapter| seen.insert(chapter.chapter.as_str()))
+                .collect();
+
+            chapters.sort_by(|a, b| compare_numbers(&a.chapter, &b.chapter));
+
+            chapters
+        }
+
+        /// The chapter with the lowest number after `chapter_number`, chapters without a number like
+        /// oneshots have no chapter after them
+        pub fn chapter_after(&self, chapter_number: &str) -> Option<&Chapter> {
+            let current: f64 = chapter_number.parse().ok()?;
+
+            self.numbered_chapters()
+                .filter(|(number, _)| *number > current)
+                .min_by(|(a, _), (b, _)| a.total_cmp(b))
+                .map(|(_, chapter)| chapter)
+        }
+
+        /// The chapter with the highest number before `chapter_number`
+        pub fn chapter_before(&self, chapter_number: &str) -> Option<&Chapter> {
+            let current: f64 = chapter_number.parse().ok()?;
+
+            self.numbered_chapters()
+                .filter(|(number, _)| *number < current)
+                .max_by(|(a, _), (b, _)| a.total_cmp(b))
+                .map(|(_, chapter)| chapter)
+        }
+
+        /// The chapter with the lowest number after the highest one in `read_numbers`, or the first chapter
+        /// when nothing was read. Chapters are compared by number so a chapter read from another
+        /// scanlation group counts as read
+        pub fn next_unread_chapter(&self, read_numbers: &[String]) -> Option<&Chapter> {
+            let last_read = read_numbers
+                .iter()
+                .filter_map(|number| number.parse::<f64>().ok())
+                .max_by(|a, b| a.total_cmp(b));
+
+            self.numbered_chapters()
+                .filter(|(number, _)| match last_read {
+                    Some(last_read) => *number > last_read,
+                    None => true,
+                })
+                .min_by(|(a, _), (b, _)| a.total_cmp(b))
+                .map(|(_, chapter)| chapter)
+        }
+
+        fn numbered_chapters(&self) -> impl Iterator<Item = (f64, &Chapter)> {
+            self.volumes
+                .iter()
+                .flat_map(|volume| volume.chapters.iter())
+                .filter_map(|chapter| chapter.chapter.parse::<f64>().ok().map(|number| (number, chapter)))
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn next_chapter_is_found_across_volumes() {
+            let response: AggregateResponse = serde_json::from_str(
+                r#"{
+                    "result": "ok",
+                    "volumes": {
+                        "1": { "volume": "1", "chapters": { "1": { "chapter": "1", "id": "a" }, "2": { "chapter": "2", "id": "b" } } },
+                        "2": { "volume": "2", "chapters": [ { "chapter": "2.5", "id": "c" }, { "chapter": "3", "id": "d" } ] },
+                        "none": { "volume": "none", "chapters": { "none": { "chapter": "none", "id": "e" } } }
+                    }
+                }"#,
+            )
+            .unwrap();
+
+            assert_eq!("b", response.chapter_after("1").unwrap().id);
+            assert_eq!("c", response.chapter_after("2").unwrap().id);
+            assert!(response.chapter_after("3").is_none());
+            assert!(response.chapter_after("none").is_none());
+
+            assert_eq!("c", response.chapter_before("3").unwrap().id);
+            assert!(response.chapter_before("1").is_none());
+
+            let empty: AggregateResponse = serde_json::from_str(r#"{ "result": "ok", "volumes": [] }"#).unwrap();
+            assert!(empty.chapter_after("1").is_none());
+        }
+
+        #[test]
+        fn next_unread_chapter_comes_after_the_highest_chapter_read() {
+            let response: AggregateResponse = serde_json::from_str(
+                r#"{
+                    "result": "ok",
+                    "volumes": {
+                        "1": { "volume": "1", "chapters": { "1": { "chapter": "1", "id": "a" }, "2": { "chapter": "2", "id": "b" } } },
+                        "none": { "volume": "none", "chapters": [ { "chapter": "3", "id": "c" }, { "chapter": "10", "id": "d" } ] }
+                    }
+                }"#,
+            )
+            .unwrap();
+
+            assert_eq!("a", response.next_unread_chapter(&[]).unwrap().id);
+            assert_eq!("c", response.next_unread_chapter(&["2".to_string(), "1".to_string(), "2".to_string()]).unwrap().id);
+            assert_eq!("d", response.next_unread_chapter(&["3".to_string(), "oneshot".to_string()]).unwrap().id);
+            assert!(response.next_unread_chapter(&["10".to_string()]).is_none());
+        }
+
+        #[test]
+        fn chapters_translated_in_another_volume_count_as_translated() {
+            let all: AggregateResponse = serde_json::from_str(
+                r#"{
+                    "result": "ok",
+                    "volumes": {
+                        "none": { "volume": "none", "chapters": { "5": { "chapter": "5", "id": "e" } } },
+                        "10": { "volume": "10", "chapters": { "3": { "chapter": "3", "id": "c" }, "4": { "chapter": "4", "id": "d" } } },
+                        "2": { "volume": "2", "chapters": { "1": { "chapter": "1", "id": "a" }, "2": { "chapter": "2", "id": "b" } } }
+                    }
+                }"#,
+            )
+            .unwrap();
+
+            let translated: AggregateResponse = serde_json::from_str(
+                r#"{
+                    "result": "ok",
+                    "volumes": {
+                        "none": { "volume": "none", "chapters": { "1": { "chapter": "1", "id": "f" }, "2": { "chapter": "2", "id": "g" } } },
+                        "10": { "volume": "10", "chapters": [ { "chapter": "4", "id": "h" } ] }
+                    }
+                }"#,
+            )
+            .unwrap();
+
+            let completeness: Vec<(String, usize, usize)> = all
+                .volume_completeness(&translated)
+                .into_iter()
+                .map(|volume| (volume.volume, volume.translated, volume.total))
+                .collect();
+
+            assert_eq!(completeness, vec![("2".to_string(), 2, 2), ("10".to_string(), 1, 2), ("none".to_string(), 0, 1)]);
+        }
+
+        #[test]
+        fn one_upload_of_each_chapter_is_kept_preferring_the_group_chosen() {
+            let all_groups: AggregateResponse = serde_json::from_str(
+                r#"{
+                    "result": "ok",
+                    "volumes": [
+                        { "volume": "none", "chapters": [ { "chapter": "2", "id": "b" }, { "chapter": "none", "id": "e" } ] },
+                        { "volume": "1", "chapters": [ { "chapter": "1", "id": "a" }, { "chapter": "2", "id": "c" } ] },
+                        { "volume": "2", "chapters": [ { "chapter": "10", "id": "d" } ] }
+                    ]
+                }"#,
+            )
+            .unwrap();
+
+            let group: AggregateResponse = serde_json::from_str(
+                r#"{
+                    "result": "ok",
+                    "volumes": { "2": { "volume": "2", "chapters": [ { "chapter": "10", "id": "f" } ] } }
+                }"#,
+            )
+            .unwrap();
+
+            let ids = |chapters: Vec<&Chapter>| -> Vec<String> { chapters.into_iter().map(|chapter| chapter.id.clone()).collect() };
+
+            assert_eq!(vec!["a", "b", "d", "e"], ids(all_groups.one_upload_per_chapter(None)));
+            assert_eq!(vec!["a", "b", "f", "e"], ids(all_groups.one_upload_per_chapter(Some(&group))));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+
+    use self::aggregate::AggregateResponse;
+    use super::*;
+
+    /// A way a response can arrive broken
+    #[derive(Debug, Clone)]
+    enum Damage {
+        CutShort(Index),
+        Missing(Index),
+        Changed(Index, u8),
+    }
+
+    fn damage() -> impl Strategy<Value = Vec<Damage>> {
+        let damage = prop_oneof![
+            any::<Index>().prop_map(Damage::CutShort),
+            any::<Index>().prop_map(Damage::Missing),
+            (any::<Index>(), prop::sample::select(b"{}[]\":,0-.aenlu ".to_vec()))
+                .prop_map(|(at, byte)| Damage::Changed(at, byte)),
+        ];
+
+        prop::collection::vec(damage, 1..=4)
+    }
+
+    fn mangle(json: &str, damage: &[Damage]) -> String {
+        let mut bytes = json.as_bytes().to_vec();
+
+        for damage in damage {
+            if bytes.is_empty() {
+                break;
+            }
+            match damage {
+                Damage::CutShort(at) => bytes.truncate(at.index(bytes.len())),
+                Damage::Missing(at) => {
+                    bytes.remove(at.index(bytes.len()));
+                },
+                Damage::Changed(at, byte) => {
+                    let at = at.index(bytes.len());
+                    bytes[at] = *byte;
+                },
+            }
+        }
+
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    fn parse_damaged<T: serde::de::DeserializeOwned + Serialize>(response: T, damage: &[Damage]) {
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(serde_json::from_str::<T>(&json).is_ok());
+
+        let _ = serde_json::from_str::<T>(&mangle(&json, damage));
+    }
+
+    #[test]
+    fn title_is_the_one_in_the_first_language_preferred() {
+        let attributes = Attributes {
+            title: Title {
+                ja_ro: Some("Komi-san wa Komyushou desu".to_string()),
+                ..Default::default()
+            },
+            alt_titles: vec![
+                HashMap::from([("ja".to_string(), "古見さんは、コミュ症です。".to_string())]),
+                HashMap::from([("en".to_string(), "Komi Can't Communicate".to_string())]),
+            ],
+            original_language: "ja".to_string(),
+            ..Default::default()
+        };
+        let languages = |languages: &[&str]| languages.iter().map(|lang| lang.to_string()).collect::<Vec<String>>();
+
+        assert_eq!("Komi-san wa Komyushou desu", attributes.preferred_title(&[]));
+        assert_eq!("Komi Can't Communicate", attributes.preferred_title(&languages(&["en", "ja-ro"])));
+        assert_eq!("古見さんは、コミュ症です。", attributes.preferred_title(&languages(&["es", "original"])));
+        assert_eq!("Komi-san wa Komyushou desu", attributes.preferred_title(&languages(&["fr"])));
+    }
+
+    proptest! {
+        #[test]
+        fn broken_responses_are_errors_instead_of_panics(damage in damage()) {
+            let manga = Data {
+                id: "a96676e5-8ae2-425e-b549-7f15dd34a6d8".to_string(),
+                attributes: Attributes {
+                    title: Title {
+                        en: Some("Komi-san wa Komyushou desu".to_string()),
+                        ..Default::default()
+                    },
+                    tags: vec![Tag::default()],
+                    available_translated_languages: vec![Some("en".to_string()), None],
+                    ..Default::default()
+                },
+                relationships: vec![MangaSearchRelationship::default()],
+            };
+
+            parse_damaged(
+                SearchMangaResponse {
+                    data: vec![manga.clone(), manga],
+                    ..Default::default()
+                },
+                &damage,
+            );
+            parse_damaged(
+                ChapterResponse {
+                    data: vec![ChapterData::default()],
+                    ..Default::default()
+                },
+                &damage,
+            );
+            parse_damaged(ChapterPagesResponse::default(), &damage);
+            parse_damaged(MangaStatisticsResponse::default(), &damage);
+
+            let aggregate =
+                r#"{ "result": "ok", "volumes": { "1": { "volume": "1", "chapters": { "1": { "chapter": "1", "id": "a" } } } } }"#;
+            let _ = serde_json::from_str::<AggregateResponse>(&mangle(aggregate, &damage));
+        }
+    }
+
+    #[test]
+    fn each_profile_has_its_own_data_directory() {
+        let data_dir = PathBuf::from("/home/user/.local/share/manga-tui");
+
+        assert_eq!(data_dir, profile_data_dir(data_dir.clone(), None));
+        assert_eq!(data_dir.join("profiles").join("kids"), profile_data_dir(data_dir.clone(), Some("kids")));
+
+        assert!(is_valid_profile_name("work_2"));
+        assert!(!is_valid_profile_name(""));
+        assert!(!is_valid_profile_name("../work"));
+    }
+}
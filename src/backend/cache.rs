@@ -0,0 +1,185 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use bytes::Bytes;
+use once_cell::sync::Lazy;
+
+/// Once the cache grows past this size the chapters written the longest time ago are removed
+pub static MAX_PAGE_CACHE_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Files which can be deleted at any time, unlike the ones in the data directory
+pub static CACHE_DIR: Lazy<Option<PathBuf>> = Lazy::new(|| match std::env::var("MANGA_TUI_CACHE_DIR").ok() {
+    Some(cache_dir) => Some(PathBuf::from(cache_dir)),
+    None => directories::ProjectDirs::from("", "", "manga-tui").map(|dirs| dirs.cache_dir().to_path_buf()),
+});
+
+/// Chapter pages already downloaded, so going back a page or re-reading a chapter does not download them again
+pub static PAGE_CACHE: Lazy<Option<PageCache>> =
+    Lazy::new(|| CACHE_DIR.as_ref().map(|cache_dir| PageCache::new(cache_dir.join("pages"))));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageCache {
+    directory: PathBuf,
+}
+
+fn is_path_segment(segment: &str) -> bool {
+    !segment.is_empty() && segment != "." && segment != ".." && !segment.contains(['/', '\\'])
+}
+
+impl PageCache {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    pub fn global() -> Option<&'static Self> {
+        PAGE_CACHE.as_ref()
+    }
+
+    /// The at-home server a page is served from changes between requests, so pages are stored by the chapter hash
+    /// and quality found at the end of the endpoint: `<base_url>/<quality>/<chapter hash>`
+    fn page_path(&self, endpoint: &str, file_name: &str) -> Option<PathBuf> {
+        let mut segments = endpoint.trim_end_matches('/').rsplit('/');
+        let hash = segments.next()?;
+        let quality = segments.next()?;
+
+        [hash, quality, file_name]
+            .iter()
+            .all(|segment| is_path_segment(segment))
+            .then(|| self.directory.join(hash).join(quality).join(file_name))
+    }
+
+    pub async fn get(&self, endpoint: &str, file_name: &str) -> Option<Bytes> {
+        let path = self.page_path(endpoint, file_name)?;
+        tokio::fs::read(path).await.ok().map(Bytes::from)
+    }
+
+    /// Written to a temporary file first so a page is never read half written
+    pub async fn insert(&self, endpoint: &str, file_name: &str, bytes: &[u8]) -> Result<(), std::io::Error> {
+        let Some(path) = self.page_path(endpoint, file_name) else {
+            return Ok(());
+        };
+
+        if let Some(chapter_directory) = path.parent() {
+            tokio::fs::create_dir_all(chapter_directory).await?;
+        }
+
+        let partial = path.with_extension("part");
+        tokio::fs::write(&partial, bytes).await?;
+        tokio::fs::rename(&partial, &path).await
+    }
+
+    /// Removes whole chapters, starting with the ones written the longest time ago, until the cache fits in `max_bytes`
+    pub fn prune(&self, max_bytes: u64) -> Result<(), std::io::Error> {
+        if !self.directory.exists() {
+            return Ok(());
+        }
+
+        let mut chapters: Vec<(SystemTime, u64, PathBuf)> = vec![];
+
+        for chapter in fs::read_dir(&self.directory)? {
+            let chapter = chapter?.path();
+            if chapter.is_dir() {
+                let (modified, size) = directory_usage(&chapter)?;
+                chapters.push((modified, size, chapter));
+            }
+        }
+
+        let mut total: u64 = chapters.iter().map(|(_, size, _)| size).sum();
+
+        chapters.sort_by_key(|(modified, ..)| *modified);
+
+        for (_, size, chapter) in chapters {
+            if total <= max_bytes {
+                break;
+            }
+            fs::remove_dir_all(chapter)?;
+            total = total.saturating_sub(size);
+        }
+
+        Ok(())
+    }
+}
+
+/// The latest modification time and total size of the files in a directory
+fn directory_usage(directory: &Path) -> Result<(SystemTime, u64), std::io::Error> {
+    let mut modified = SystemTime::UNIX_EPOCH;
+    let mut size = 0;
+
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+
+        let (entry_modified, entry_size) =
+            if metadata.is_dir() { directory_usage(&entry.path())? } else { (metadata.modified()?, metadata.len()) };
+
+        modified = modified.max(entry_modified);
+        size += entry_size;
+    }
+
+    Ok((modified, size))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn test_cache(name: &str) -> PageCache {
+        let directory = std::env::temp_dir().join(format!("manga-tui-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        PageCache::new(directory)
+    }
+
+    #[tokio::test]
+    async fn pages_are_cached_by_chapter_not_by_server() {
+        let cache = test_cache("page-cache");
+
+        cache
+            .insert("https://uploads.mangadex.org/data/chapterhash", "1-page.png", b"page")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cache.get("https://other.mangadex.network:443/token/data/chapterhash", "1-page.png").await,
+            Some(Bytes::from_static(b"page"))
+        );
+        assert_eq!(cache.get("https://uploads.mangadex.org/data-saver/chapterhash", "1-page.png").await, None);
+        assert_eq!(cache.get("https://uploads.mangadex.org/data/chapterhash", "2-page.png").await, None);
+
+        fs::remove_dir_all(&cache.directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn file_names_cannot_leave_the_cache_directory() {
+        let cache = test_cache("page-cache-traversal");
+
+        cache
+            .insert("https://uploads.mangadex.org/data/chapterhash", "../escaped.png", b"page")
+            .await
+            .unwrap();
+        cache
+            .insert("https://uploads.mangadex.org/data/..", "escaped.png", b"page")
+            .await
+            .unwrap();
+
+        assert!(!cache.directory.exists());
+    }
+
+    #[tokio::test]
+    async fn prune_removes_the_oldest_chapters_first() {
+        let cache = test_cache("page-cache-prune");
+
+        cache.insert("https://uploads.mangadex.org/data/old", "1.png", &[0; 10]).await.unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.insert("https://uploads.mangadex.org/data/new", "1.png", &[0; 10]).await.unwrap();
+
+        cache.prune(15).unwrap();
+
+        assert!(!cache.directory.join("old").exists());
+        assert!(cache.directory.join("new").exists());
+
+        fs::remove_dir_all(&cache.directory).unwrap();
+    }
+}
@@ -1,14 +1,21 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Mutex;
+use std::time::Duration;
 
-use chrono::Utc;
+use chrono::{NaiveDateTime, Utc};
 use manga_tui::build_check_exists_function;
 use once_cell::sync::Lazy;
 use rusqlite::{params, Connection};
 use strum::Display;
 
+use super::filter::ContentRating;
+use super::parental_lock::ParentalLock;
 use super::{AppDirectories, APP_DATA_DIR};
+use crate::common::{FitMode, LibraryFilters, LibrarySort, PageType, ReadingDirection};
 
-// Todo! document database schema
+/// How long a response stored in `manga_metadata` is considered fresh
+pub static MANGA_METADATA_TTL: Duration = Duration::from_secs(60 * 60);
 
 pub static DBCONN: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| {
     let conn = Connection::open(
@@ -33,6 +40,27 @@ pub static DBCONN: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| {
     )
     .unwrap();
 
+    conn.execute(
+        "CREATE TABLE if not exists parental_lock (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                pin_hash TEXT NOT NULL,
+                max_content_rating TEXT NOT NULL
+             )",
+        (),
+    )
+    .unwrap();
+
+    add_column_if_missing(&conn, "parental_lock", "salt", "TEXT NULL").unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists hints_seen (
+                name TEXT PRIMARY KEY,
+                seen_at DATETIME DEFAULT (datetime('now'))
+             )",
+        (),
+    )
+    .unwrap();
+
     let already_has_data: i32 = conn.query_row("SELECT COUNT(*) from app_version", [], |row| row.get(0)).unwrap();
 
     if already_has_data == 0 {
@@ -88,6 +116,76 @@ pub static DBCONN: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| {
     )
     .unwrap();
 
+    conn.execute(
+        "CREATE TABLE if not exists manga_reader_settings (
+                manga_id TEXT PRIMARY KEY,
+                reading_direction TEXT NOT NULL
+             )",
+        (),
+    )
+    .unwrap();
+
+    add_column_if_missing(&conn, "manga_reader_settings", "fit_mode", "TEXT NULL").unwrap();
+    add_column_if_missing(&conn, "manga_reader_settings", "double_page", "BOOLEAN NULL").unwrap();
+    add_column_if_missing(&conn, "manga_reader_settings", "spread_offset", "BOOLEAN NULL").unwrap();
+    add_column_if_missing(&conn, "manga_reader_settings", "webtoon", "BOOLEAN NULL").unwrap();
+    add_column_if_missing(&conn, "manga_reader_settings", "page_type", "TEXT NULL").unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists chapter_progress (
+                chapter_id TEXT PRIMARY KEY,
+                last_page INTEGER NOT NULL,
+                updated_at DATETIME DEFAULT (datetime('now'))
+             )",
+        (),
+    )
+    .unwrap();
+
+    add_column_if_missing(&conn, "chapter_progress", "manga_id", "TEXT NULL").unwrap();
+    add_column_if_missing(&conn, "chapter_progress", "chapter_number", "TEXT NULL").unwrap();
+    add_column_if_missing(&conn, "chapter_progress", "chapter_title", "TEXT NULL").unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists library_settings (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                sort TEXT NOT NULL,
+                downloaded_only BOOLEAN NOT NULL DEFAULT 0
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists manga_metadata (
+                key TEXT PRIMARY KEY,
+                response TEXT NOT NULL,
+                cached_at DATETIME DEFAULT (datetime('now'))
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists manga_covers (
+                manga_id TEXT PRIMARY KEY,
+                local_path TEXT NULL,
+                volume TEXT NULL,
+                file_name TEXT NULL
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists search_history (
+                search_term TEXT PRIMARY KEY,
+                times_searched INTEGER NOT NULL DEFAULT 1,
+                searched_at DATETIME DEFAULT (datetime('now'))
+             )",
+        (),
+    )
+    .unwrap();
+
     let already_has_data: i32 = conn.query_row("SELECT COUNT(*) from history_types", [], |row| row.get(0)).unwrap();
 
     if already_has_data < 2 {
@@ -101,6 +199,21 @@ pub static DBCONN: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| {
     Mutex::new(Some(conn))
 });
 
+/// Tables created by older versions are missing the columns added afterwards
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> rusqlite::Result<()> {
+    let has_column: bool = conn.query_row(
+        &format!("SELECT EXISTS(SELECT * FROM pragma_table_info('{table}') WHERE name = ?1)"),
+        params![column],
+        |row| row.get(0),
+    )?;
+
+    if !has_column {
+        conn.execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"), ())?;
+    }
+
+    Ok(())
+}
+
 build_check_exists_function!(check_chapter_exists, "chapters");
 build_check_exists_function!(check_manga_already_exists, "mangas");
 
@@ -273,6 +386,8 @@ pub fn get_chapters_history_status(id: &str) -> rusqlite::Result<Vec<MangaReadin
 pub struct MangaHistory {
     pub id: String,
     pub title: String,
+    /// Another manga in the library has the same title, see `merge_duplicate_mangas`
+    pub has_duplicates: bool,
     // img_url: Option<String>,
 }
 
@@ -282,7 +397,12 @@ pub struct MangaHistoryResponse {
     pub total_items: u32,
 }
 /// This is used in the `feed` page to retrieve the mangas the user is currently reading
-pub fn get_history(hist_type: MangaHistoryType, page: u32, search: &str) -> rusqlite::Result<MangaHistoryResponse> {
+pub fn get_history(
+    hist_type: MangaHistoryType,
+    page: u32,
+    search: &str,
+    filters: LibraryFilters,
+) -> rusqlite::Result<MangaHistoryResponse> {
     let offset = (page - 1) * 5;
     let binding = DBCONN.lock().unwrap();
     let conn = binding.as_ref().unwrap();
@@ -290,81 +410,245 @@ pub fn get_history(hist_type: MangaHistoryType, page: u32, search: &str) -> rusq
     let history_type_id: i32 =
         conn.query_row("SELECT id from history_types WHERE name = ?1", params![hist_type.to_string()], |row| row.get(0))?;
 
+    let search = search.trim().to_lowercase();
+
+    let mut conditions =
+        String::from("manga_history_union.type_id = ?1 AND (?2 = '' OR LOWER(mangas.title) LIKE '%' || ?2 || '%')");
+
+    if filters.downloaded_only {
+        conditions
+            .push_str(" AND EXISTS(SELECT * FROM chapters WHERE chapters.manga_id = mangas.id AND chapters.is_downloaded = 1)");
+    }
+
+    let order_by = match filters.sort {
+        LibrarySort::RecentlyRead => "mangas.last_read DESC",
+        LibrarySort::RecentlyAdded => "mangas.created_at DESC",
+        LibrarySort::Alphabetical => "LOWER(mangas.title) ASC",
+        LibrarySort::MostRead => {
+            "(SELECT COUNT(*) FROM chapters WHERE chapters.manga_id = mangas.id AND chapters.is_read = 1) DESC, mangas.last_read DESC"
+        },
+    };
+
     let total_mangas: u32 = conn.query_row(
-        "
-                SELECT COUNT(*) from mangas
-                INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id 
-                WHERE manga_history_union.type_id = ?1",
-        params![history_type_id],
+        &format!(
+            "SELECT COUNT(*) from mangas
+             INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id
+             WHERE {conditions}"
+        ),
+        params![history_type_id, search],
         |row| row.get(0),
     )?;
 
-    let mut get_statement = conn.prepare(
-        "SELECT  mangas.id, mangas.title from mangas 
-                     INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id 
-                     WHERE manga_history_union.type_id = ?1
-                     ORDER BY mangas.last_read DESC
-                     LIMIT 5 OFFSET ?2",
-    )?;
+    let mut get_statement = conn.prepare(&format!(
+        "SELECT mangas.id, mangas.title from mangas
+         INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id
+         WHERE {conditions}
+         ORDER BY {order_by}
+         LIMIT 5 OFFSET ?3"
+    ))?;
 
-    let mut get_statement_with_search_term = conn.prepare(
-        "SELECT  mangas.id, mangas.title from mangas 
-                     INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id 
-                     WHERE manga_history_union.type_id = ?1 AND LOWER(mangas.title) LIKE '%' || ?2 || '%'
-                     ORDER BY mangas.last_read DESC
-                     LIMIT 5 OFFSET ?3",
-    )?;
+    let iter_mangas = get_statement.query_map(params![history_type_id, search, offset], |row| {
+        Ok(MangaHistory {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            has_duplicates: false,
+        })
+    })?;
 
     let mut manga_history: Vec<MangaHistory> = vec![];
 
-    if search.trim().is_empty() {
-        let iter_mangas = get_statement.query_map(params![history_type_id, offset], |row| {
-            Ok(MangaHistory {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                // img_url: row.get(2)?,
-            })
-        })?;
+    for manga in iter_mangas {
+        manga_history.push(manga?);
+    }
 
-        for manga in iter_mangas {
-            manga_history.push(manga?);
-        }
+    let library = get_library_titles(conn)?;
 
-        Ok(MangaHistoryResponse {
-            mangas: manga_history,
-            total_items: total_mangas,
-            page,
-        })
-    } else {
-        let total_mangas_with_search: u32 = conn.query_row(
-            "
-                SELECT COUNT(*) from mangas
-                INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id 
-                WHERE manga_history_union.type_id = ?1 AND LOWER(mangas.title) LIKE '%' || ?2 || '%'",
-            params![history_type_id, search.trim().to_lowercase()],
-            |row| row.get(0),
+    for manga in manga_history.iter_mut() {
+        manga.has_duplicates = !find_duplicates(&library, &manga.id, &manga.title).is_empty();
+    }
+
+    Ok(MangaHistoryResponse {
+        mangas: manga_history,
+        total_items: total_mangas,
+        page,
+    })
+}
+
+/// Titles are compared ignoring case, punctuation and spacing, so that a series added twice is found even if its title
+/// was written slightly differently
+pub fn normalize_title(title: &str) -> String {
+    title
+        .split(|character: char| !character.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// The id and title of every manga in either the reading history or plan to read
+fn get_library_titles(conn: &Connection) -> rusqlite::Result<Vec<(String, String)>> {
+    let mut statement = conn.prepare(
+        "SELECT DISTINCT mangas.id, mangas.title FROM mangas
+         INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id",
+    )?;
+
+    let titles = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    titles.collect()
+}
+
+/// The ids of the other mangas of `library` with the same normalized title
+fn find_duplicates(library: &[(String, String)], manga_id: &str, title: &str) -> Vec<String> {
+    let title = normalize_title(title);
+
+    library
+        .iter()
+        .filter(|(id, other_title)| id != manga_id && normalize_title(other_title) == title)
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+/// Moves the chapters, reading progress, reader settings and library categories of the mangas which have the same
+/// title as `manga_id` to it, then removes them. Returns how many mangas were merged
+pub fn merge_duplicate_mangas(manga_id: &str) -> rusqlite::Result<usize> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let title: String = conn.query_row("SELECT title FROM mangas WHERE id = ?1", params![manga_id], |row| row.get(0))?;
+
+    let duplicates = find_duplicates(&get_library_titles(conn)?, manga_id, &title);
+
+    let transaction = conn.unchecked_transaction()?;
+
+    for duplicate_id in duplicates.iter() {
+        transaction.execute("UPDATE chapters SET manga_id = ?1 WHERE manga_id = ?2", params![manga_id, duplicate_id])?;
+        transaction.execute("UPDATE chapter_progress SET manga_id = ?1 WHERE manga_id = ?2", params![manga_id, duplicate_id])?;
+
+        transaction.execute(
+            "INSERT OR IGNORE INTO manga_history_union(manga_id, type_id)
+             SELECT ?1, type_id FROM manga_history_union WHERE manga_id = ?2",
+            params![manga_id, duplicate_id],
         )?;
-        let iter_mangas =
-            get_statement_with_search_term.query_map(params![history_type_id, search.trim().to_lowercase(), offset], |row| {
-                Ok(MangaHistory {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    // img_url: row.get(2)?,
-                })
-            })?;
-
-        for manga in iter_mangas {
-            manga_history.push(manga?);
-        }
+        transaction.execute("DELETE FROM manga_history_union WHERE manga_id = ?1", params![duplicate_id])?;
+
+        // The reader settings of the manga which is kept win
+        transaction.execute("UPDATE OR IGNORE manga_reader_settings SET manga_id = ?1 WHERE manga_id = ?2", params![
+            manga_id,
+            duplicate_id
+        ])?;
+        transaction.execute("DELETE FROM manga_reader_settings WHERE manga_id = ?1", params![duplicate_id])?;
+
+        transaction.execute(
+            "UPDATE mangas SET
+                created_at = MIN(created_at, (SELECT created_at FROM mangas WHERE id = ?2)),
+                last_read = MAX(last_read, (SELECT last_read FROM mangas WHERE id = ?2)),
+                img_url = COALESCE(img_url, (SELECT img_url FROM mangas WHERE id = ?2))
+             WHERE id = ?1",
+            params![manga_id, duplicate_id],
+        )?;
+        transaction.execute("DELETE FROM mangas WHERE id = ?1", params![duplicate_id])?;
+    }
 
-        Ok(MangaHistoryResponse {
-            mangas: manga_history,
-            total_items: total_mangas_with_search,
-            page,
-        })
+    transaction.commit()?;
+
+    Ok(duplicates.len())
+}
+
+pub fn get_library_filters() -> rusqlite::Result<Option<LibraryFilters>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare("SELECT sort, downloaded_only FROM library_settings WHERE id = 1")?;
+
+    let mut rows = statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?)))?;
+
+    match rows.next() {
+        Some(settings) => {
+            let (sort, downloaded_only) = settings?;
+            Ok(Some(LibraryFilters {
+                sort: LibrarySort::from_db_value(&sort).unwrap_or_default(),
+                downloaded_only,
+            }))
+        },
+        None => Ok(None),
     }
 }
 
+pub fn save_library_filters(filters: LibraryFilters) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT INTO library_settings(id, sort, downloaded_only) VALUES (1, ?1, ?2)
+         ON CONFLICT(id) DO UPDATE SET sort = excluded.sort, downloaded_only = excluded.downloaded_only",
+        params![filters.sort.to_string(), filters.downloaded_only],
+    )?;
+
+    Ok(())
+}
+
+/// Whether the hint with this `name` was already dismissed once
+pub fn hint_was_seen(name: &str) -> rusqlite::Result<bool> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.query_row("SELECT EXISTS(SELECT 1 FROM hints_seen WHERE name = ?1)", params![name], |row| row.get(0))
+}
+
+pub fn save_hint_seen(name: &str) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute("INSERT OR IGNORE INTO hints_seen(name) VALUES (?1)", params![name])?;
+
+    Ok(())
+}
+
+pub fn get_parental_lock() -> rusqlite::Result<Option<ParentalLock>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare("SELECT pin_hash, salt, max_content_rating FROM parental_lock WHERE id = 1")?;
+
+    let mut rows = statement
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, String>(2)?)))?;
+
+    match rows.next() {
+        Some(lock) => {
+            let (pin_hash, salt, max_content_rating) = lock?;
+            Ok(Some(ParentalLock {
+                pin_hash,
+                salt: salt.unwrap_or_default(),
+                max_content_rating: ContentRating::from(max_content_rating.as_str()),
+            }))
+        },
+        None => Ok(None),
+    }
+}
+
+pub fn save_parental_lock(lock: &ParentalLock) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT INTO parental_lock(id, pin_hash, salt, max_content_rating) VALUES (1, ?1, ?2, ?3)
+         ON CONFLICT(id) DO UPDATE SET pin_hash = excluded.pin_hash, salt = excluded.salt,
+         max_content_rating = excluded.max_content_rating",
+        params![lock.pin_hash, lock.salt, lock.max_content_rating.to_string()],
+    )?;
+
+    Ok(())
+}
+
+pub fn remove_parental_lock() -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute("DELETE FROM parental_lock", [])?;
+
+    Ok(())
+}
+
 pub struct MangaPlanToReadSave<'a> {
     pub id: &'a str,
     pub title: &'a str,
@@ -372,21 +656,24 @@ pub struct MangaPlanToReadSave<'a> {
 }
 
 pub fn save_plan_to_read(manga: MangaPlanToReadSave<'_>) -> rusqlite::Result<()> {
+    save_to_library(manga, MangaHistoryType::PlanToRead)
+}
+
+/// Adds a manga to either the reading history or plan to read without reading any of its chapters
+pub fn save_to_library(manga: MangaPlanToReadSave<'_>, hist_type: MangaHistoryType) -> rusqlite::Result<()> {
     let binding = DBCONN.lock().unwrap();
     let conn = binding.as_ref().unwrap();
 
     let history_type: i32 =
-        conn.query_row("SELECT id FROM history_types where name = ?1", params![MangaHistoryType::PlanToRead.to_string()], |row| {
-            row.get(0)
-        })?;
+        conn.query_row("SELECT id FROM history_types where name = ?1", params![hist_type.to_string()], |row| row.get(0))?;
 
-    let is_already_plan_to_read: bool = conn.query_row(
+    let is_already_in_library: bool = conn.query_row(
         "SELECT EXISTS(SELECT * FROM manga_history_union WHERE manga_id = ?1 AND type_id = ?2)",
         params![manga.id, history_type],
         |row| row.get(0),
     )?;
 
-    if !is_already_plan_to_read {
+    if !is_already_in_library {
         if check_manga_already_exists(manga.id, conn)? {
             conn.execute("INSERT INTO manga_history_union VALUES (?1, ?2)", (manga.id, history_type))?;
             return Ok(());
@@ -408,6 +695,26 @@ pub fn save_plan_to_read(manga: MangaPlanToReadSave<'_>) -> rusqlite::Result<()>
     Ok(())
 }
 
+/// Marks a chapter as read for a manga which is already stored, returns whether the manga was found
+pub fn mark_chapter_as_read(manga_id: &str, chapter_id: &str, chapter_title: &str) -> rusqlite::Result<bool> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    if !check_manga_already_exists(manga_id, conn)? {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "INSERT INTO chapters(id, title, manga_id, is_read) VALUES (?1, ?2, ?3, 1)
+         ON CONFLICT(id) DO UPDATE SET is_read = 1",
+        params![chapter_id, chapter_title, manga_id],
+    )?;
+
+    update_or_insert_manga_most_recent_read(manga_id, conn)?;
+
+    Ok(true)
+}
+
 pub struct SetChapterDownloaded<'a> {
     pub id: &'a str,
     pub title: &'a str,
@@ -473,3 +780,511 @@ pub fn set_chapter_downloaded(chapter: SetChapterDownloaded<'_>) -> rusqlite::Re
         Ok(())
     }
 }
+
+pub struct ReadingListEntry {
+    pub id: String,
+    pub title: String,
+    pub img_url: Option<String>,
+    pub last_read: String,
+    pub chapters_read: u32,
+    /// The number of the chapter the user was reading most recently
+    pub last_chapter: Option<String>,
+}
+
+/// Every manga of a history type with how far the user got, used to export the reading list
+pub fn get_reading_list(hist_type: MangaHistoryType) -> rusqlite::Result<Vec<ReadingListEntry>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare(
+        "SELECT mangas.id, mangas.title, mangas.img_url, mangas.last_read,
+                (SELECT COUNT(*) FROM chapters WHERE chapters.manga_id = mangas.id AND chapters.is_read = 1),
+                (SELECT chapter_number FROM chapter_progress WHERE chapter_progress.manga_id = mangas.id ORDER BY updated_at DESC LIMIT 1)
+         FROM mangas
+         INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id
+         INNER JOIN history_types ON history_types.id = manga_history_union.type_id
+         WHERE history_types.name = ?1
+         ORDER BY mangas.last_read DESC",
+    )?;
+
+    let entries = statement.query_map(params![hist_type.to_string()], |row| {
+        Ok(ReadingListEntry {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            img_url: row.get(2)?,
+            last_read: row.get(3)?,
+            chapters_read: row.get(4)?,
+            last_chapter: row.get(5)?,
+        })
+    })?;
+
+    entries.collect()
+}
+
+/// Retrieve the reading direction the user chose for a manga, if they ever changed it
+pub fn get_reading_direction(manga_id: &str) -> rusqlite::Result<Option<ReadingDirection>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare("SELECT reading_direction FROM manga_reader_settings WHERE manga_id = ?1")?;
+
+    let mut rows = statement.query_map(params![manga_id], |row| row.get::<_, String>(0))?;
+
+    match rows.next() {
+        Some(direction) => Ok(ReadingDirection::from_db_value(&direction?)),
+        None => Ok(None),
+    }
+}
+
+pub fn save_reading_direction(manga_id: &str, direction: ReadingDirection) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT INTO manga_reader_settings(manga_id, reading_direction) VALUES (?1, ?2)
+         ON CONFLICT(manga_id) DO UPDATE SET reading_direction = excluded.reading_direction",
+        params![manga_id, direction.to_string()],
+    )?;
+
+    Ok(())
+}
+
+pub fn get_fit_mode(manga_id: &str) -> rusqlite::Result<Option<FitMode>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare("SELECT fit_mode FROM manga_reader_settings WHERE manga_id = ?1")?;
+
+    let mut rows = statement.query_map(params![manga_id], |row| row.get::<_, Option<String>>(0))?;
+
+    match rows.next() {
+        Some(fit_mode) => Ok(fit_mode?.and_then(|fit_mode| FitMode::from_db_value(&fit_mode))),
+        None => Ok(None),
+    }
+}
+
+pub fn save_fit_mode(manga_id: &str, fit_mode: FitMode) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT INTO manga_reader_settings(manga_id, reading_direction, fit_mode) VALUES (?1, ?2, ?3)
+         ON CONFLICT(manga_id) DO UPDATE SET fit_mode = excluded.fit_mode",
+        params![manga_id, ReadingDirection::default().to_string(), fit_mode.to_string()],
+    )?;
+
+    Ok(())
+}
+
+/// How the pages of a manga are laid out, `None` for the settings the user never changed
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReaderLayout {
+    pub double_page: Option<bool>,
+    pub spread_offset: Option<bool>,
+    pub webtoon: Option<bool>,
+    pub page_type: Option<PageType>,
+}
+
+pub enum ReaderSetting {
+    DoublePage(bool),
+    SpreadOffset(bool),
+    Webtoon(bool),
+    PageType(PageType),
+}
+
+pub fn get_reader_layout(manga_id: &str) -> rusqlite::Result<ReaderLayout> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement =
+        conn.prepare("SELECT double_page, spread_offset, webtoon, page_type FROM manga_reader_settings WHERE manga_id = ?1")?;
+
+    let mut rows = statement.query_map(params![manga_id], |row| {
+        Ok(ReaderLayout {
+            double_page: row.get(0)?,
+            spread_offset: row.get(1)?,
+            webtoon: row.get(2)?,
+            page_type: row.get::<_, Option<String>>(3)?.and_then(|page_type| PageType::from_db_value(&page_type)),
+        })
+    })?;
+
+    match rows.next() {
+        Some(layout) => layout,
+        None => Ok(ReaderLayout::default()),
+    }
+}
+
+pub fn save_reader_setting(manga_id: &str, setting: ReaderSetting) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let (column, value): (&str, Box<dyn rusqlite::ToSql>) = match setting {
+        ReaderSetting::DoublePage(double_page) => ("double_page", Box::new(double_page)),
+        ReaderSetting::SpreadOffset(spread_offset) => ("spread_offset", Box::new(spread_offset)),
+        ReaderSetting::Webtoon(webtoon) => ("webtoon", Box::new(webtoon)),
+        ReaderSetting::PageType(page_type) => ("page_type", Box::new(page_type.to_string())),
+    };
+
+    conn.execute(
+        &format!(
+            "INSERT INTO manga_reader_settings(manga_id, reading_direction, {column}) VALUES (?1, ?2, ?3)
+             ON CONFLICT(manga_id) DO UPDATE SET {column} = excluded.{column}"
+        ),
+        params![manga_id, ReadingDirection::default().to_string(), value],
+    )?;
+
+    Ok(())
+}
+
+/// The index of the page the user was on the last time they read a chapter
+pub fn get_last_page_read(chapter_id: &str) -> rusqlite::Result<Option<usize>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare("SELECT last_page FROM chapter_progress WHERE chapter_id = ?1")?;
+
+    let mut rows = statement.query_map(params![chapter_id], |row| row.get::<_, usize>(0))?;
+
+    rows.next().transpose()
+}
+
+pub struct ChapterProgressSave<'a> {
+    pub chapter_id: &'a str,
+    pub manga_id: &'a str,
+    pub chapter_number: &'a str,
+    pub chapter_title: &'a str,
+    pub page: usize,
+}
+
+pub fn save_last_page_read(progress: ChapterProgressSave<'_>) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT INTO chapter_progress(chapter_id, manga_id, chapter_number, chapter_title, last_page) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(chapter_id) DO UPDATE SET manga_id = excluded.manga_id, chapter_number = excluded.chapter_number,
+         chapter_title = excluded.chapter_title, last_page = excluded.last_page, updated_at = datetime('now')",
+        params![progress.chapter_id, progress.manga_id, progress.chapter_number, progress.chapter_title, progress.page],
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastChapterRead {
+    pub chapter_id: String,
+    pub manga_id: String,
+    pub chapter_number: String,
+    pub chapter_title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChapterRead {
+    pub manga_id: String,
+    pub manga_title: String,
+    pub chapter_number: String,
+    pub chapter_title: String,
+    pub last_page: usize,
+    /// The last time the chapter was read, in UTC
+    pub read_at: NaiveDateTime,
+}
+
+/// How many of the chapters read most recently are shown in the reading sessions tab
+pub static CHAPTERS_READ_LIMIT: u32 = 500;
+
+/// The chapters the user has read, the most recent first, filtered by manga title
+pub fn get_chapters_read(search: &str) -> rusqlite::Result<Vec<ChapterRead>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let search = search.trim().to_lowercase();
+
+    let mut statement = conn.prepare(
+        "SELECT chapter_progress.manga_id, COALESCE(mangas.title, ''), chapter_number, chapter_title, last_page, chapter_progress.updated_at
+         FROM chapter_progress
+         LEFT JOIN mangas ON mangas.id = chapter_progress.manga_id
+         WHERE chapter_progress.manga_id IS NOT NULL AND (?1 = '' OR LOWER(mangas.title) LIKE '%' || ?1 || '%')
+         ORDER BY chapter_progress.updated_at DESC, chapter_progress.rowid DESC
+         LIMIT ?2",
+    )?;
+
+    let rows = statement.query_map(params![search, CHAPTERS_READ_LIMIT], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, usize>(4)?,
+            row.get::<_, String>(5)?,
+        ))
+    })?;
+
+    let mut chapters = vec![];
+
+    for row in rows {
+        let (manga_id, manga_title, chapter_number, chapter_title, last_page, updated_at) = row?;
+
+        if let Ok(read_at) = NaiveDateTime::parse_from_str(&updated_at, "%Y-%m-%d %H:%M:%S") {
+            chapters.push(ChapterRead {
+                manga_id,
+                manga_title,
+                chapter_number,
+                chapter_title,
+                last_page,
+                read_at,
+            });
+        }
+    }
+
+    Ok(chapters)
+}
+
+/// The numbers of the chapters of a manga marked as read, only chapters opened in the reader have their
+/// number stored
+pub fn get_read_chapter_numbers(manga_id: &str) -> rusqlite::Result<Vec<String>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare(
+        "SELECT chapter_progress.chapter_number FROM chapter_progress
+         JOIN chapters ON chapters.id = chapter_progress.chapter_id
+         WHERE chapter_progress.manga_id = ?1 AND chapters.is_read = 1",
+    )?;
+
+    let numbers = statement.query_map(params![manga_id], |row| row.get(0))?;
+
+    numbers.collect()
+}
+
+/// What the user did with a manga found when searching
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MangaEngagement {
+    /// In the reading history or plan to read
+    pub in_library: bool,
+    pub chapters_read: u32,
+    /// The final chapter of a finished manga was read
+    pub completed: bool,
+}
+
+/// The engagement with each manga, given with the number of its final chapter if it's finished, the mangas the user
+/// never added nor read are left out
+pub fn get_mangas_engagement(mangas: &[(String, Option<String>)]) -> rusqlite::Result<HashMap<String, MangaEngagement>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare(
+        "SELECT EXISTS(SELECT * FROM manga_history_union WHERE manga_id = ?1),
+                (SELECT COUNT(*) FROM chapters WHERE manga_id = ?1 AND is_read = 1),
+                EXISTS(SELECT * FROM chapter_progress
+                       JOIN chapters ON chapters.id = chapter_progress.chapter_id
+                       WHERE chapter_progress.manga_id = ?1 AND chapters.is_read = 1 AND chapter_progress.chapter_number = ?2)",
+    )?;
+
+    let mut engagement = HashMap::new();
+
+    for (manga_id, last_chapter) in mangas {
+        let manga_engagement = statement.query_row(params![manga_id, last_chapter], |row| {
+            Ok(MangaEngagement {
+                in_library: row.get(0)?,
+                chapters_read: row.get(1)?,
+                completed: row.get(2)?,
+            })
+        })?;
+
+        if manga_engagement != MangaEngagement::default() {
+            engagement.insert(manga_id.clone(), manga_engagement);
+        }
+    }
+
+    Ok(engagement)
+}
+
+/// The chapter the user was reading most recently, used by `--resume`, chapters whose progress was saved before the
+/// manga was stored with it can't be reopened and are skipped
+pub fn get_last_chapter_read() -> rusqlite::Result<Option<LastChapterRead>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare(
+        "SELECT chapter_id, manga_id, chapter_number, chapter_title FROM chapter_progress
+         WHERE manga_id IS NOT NULL
+         ORDER BY updated_at DESC, rowid DESC LIMIT 1",
+    )?;
+
+    let mut rows = statement.query_map([], |row| {
+        Ok(LastChapterRead {
+            chapter_id: row.get(0)?,
+            manga_id: row.get(1)?,
+            chapter_number: row.get(2)?,
+            chapter_title: row.get(3)?,
+        })
+    })?;
+
+    rows.next().transpose()
+}
+
+/// The cover shown for a manga instead of the one mangadex has
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoverOverride {
+    LocalFile(PathBuf),
+    /// `file_name` is the cover of `volume` in the mangadex covers
+    Volume { volume: String, file_name: String },
+}
+
+fn cover_override_from_columns(
+    local_path: Option<String>,
+    volume: Option<String>,
+    file_name: Option<String>,
+) -> Option<CoverOverride> {
+    match (local_path, volume, file_name) {
+        (Some(local_path), _, _) => Some(CoverOverride::LocalFile(PathBuf::from(local_path))),
+        (None, Some(volume), Some(file_name)) => Some(CoverOverride::Volume { volume, file_name }),
+        _ => None,
+    }
+}
+
+pub fn get_cover_override(manga_id: &str) -> rusqlite::Result<Option<CoverOverride>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare("SELECT local_path, volume, file_name FROM manga_covers WHERE manga_id = ?1")?;
+
+    let mut rows = statement.query_map(params![manga_id], |row| {
+        Ok(cover_override_from_columns(row.get(0)?, row.get(1)?, row.get(2)?))
+    })?;
+
+    Ok(rows.next().transpose()?.flatten())
+}
+
+pub fn save_cover_override(manga_id: &str, cover: &CoverOverride) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let (local_path, volume, file_name) = match cover {
+        CoverOverride::LocalFile(path) => (Some(path.to_string_lossy().to_string()), None, None),
+        CoverOverride::Volume { volume, file_name } => (None, Some(volume.as_str()), Some(file_name.as_str())),
+    };
+
+    conn.execute(
+        "INSERT INTO manga_covers(manga_id, local_path, volume, file_name) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(manga_id) DO UPDATE SET local_path = excluded.local_path, volume = excluded.volume, file_name = excluded.file_name",
+        params![manga_id, local_path, volume, file_name],
+    )?;
+
+    Ok(())
+}
+
+/// Go back to the cover mangadex has, returns whether the manga had another one
+pub fn remove_cover_override(manga_id: &str) -> rusqlite::Result<bool> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let removed = conn.execute("DELETE FROM manga_covers WHERE manga_id = ?1", params![manga_id])?;
+
+    Ok(removed > 0)
+}
+
+/// Searches older than this are not suggested anymore
+static POPULAR_SEARCHES_PERIOD_DAYS: u32 = 30;
+
+/// Searches are stored lowercased so that the same search typed differently is counted once
+pub fn save_search(search_term: &str) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT INTO search_history(search_term) VALUES (?1)
+         ON CONFLICT(search_term) DO UPDATE SET times_searched = times_searched + 1, searched_at = datetime('now')",
+        params![search_term.trim().to_lowercase()],
+    )?;
+
+    Ok(())
+}
+
+/// The searches made in the last `POPULAR_SEARCHES_PERIOD_DAYS`, the ones made the most times first
+pub fn get_popular_searches(limit: u32) -> rusqlite::Result<Vec<String>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare(
+        "SELECT search_term FROM search_history
+         WHERE searched_at >= datetime('now', ?1)
+         ORDER BY times_searched DESC, searched_at DESC LIMIT ?2",
+    )?;
+
+    let searches =
+        statement.query_map(params![format!("-{POPULAR_SEARCHES_PERIOD_DAYS} days"), limit], |row| row.get(0))?;
+
+    searches.collect()
+}
+
+/// Get a response from mangadex stored less than `MANGA_METADATA_TTL` ago, `key` is the url of the request
+pub fn get_cached_manga_metadata(key: &str) -> rusqlite::Result<Option<String>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare("SELECT response FROM manga_metadata WHERE key = ?1 AND cached_at >= datetime('now', ?2)")?;
+
+    let mut rows = statement.query_map(params![key, format!("-{} seconds", MANGA_METADATA_TTL.as_secs())], |row| row.get(0))?;
+
+    rows.next().transpose()
+}
+
+/// Store a response from mangadex, expired ones are removed in the process
+pub fn cache_manga_metadata(key: &str, response: &str) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute("DELETE FROM manga_metadata WHERE cached_at < datetime('now', ?1)", params![format!(
+        "-{} seconds",
+        MANGA_METADATA_TTL.as_secs()
+    )])?;
+
+    conn.execute(
+        "INSERT INTO manga_metadata(key, response) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET response = excluded.response, cached_at = datetime('now')",
+        params![key, response],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn titles_are_the_same_regardless_of_case_and_punctuation() {
+        assert_eq!("kaguya sama love is war", normalize_title("Kaguya-sama: Love Is War"));
+        assert_eq!(normalize_title("  kaguya sama  love is war!"), normalize_title("Kaguya-sama: Love Is War"));
+        assert_ne!(normalize_title("Kaguya-sama"), normalize_title("Kaguya-sama: Love Is War"));
+    }
+
+    #[test]
+    fn duplicates_do_not_include_the_manga_itself() {
+        let library = vec![
+            ("1".to_string(), "Chainsaw Man".to_string()),
+            ("2".to_string(), "chainsaw man".to_string()),
+            ("3".to_string(), "Chainsaw Man: Buddy Stories".to_string()),
+        ];
+
+        assert_eq!(vec!["2".to_string()], find_duplicates(&library, "1", "Chainsaw Man"));
+        assert!(find_duplicates(&library, "3", "Chainsaw Man: Buddy Stories").is_empty());
+    }
+
+    #[test]
+    fn local_cover_takes_precedence_over_volume_cover() {
+        assert_eq!(
+            Some(CoverOverride::LocalFile(PathBuf::from("/covers/one.png"))),
+            cover_override_from_columns(Some("/covers/one.png".to_string()), Some("1".to_string()), Some("cover.jpg".to_string()))
+        );
+        assert_eq!(
+            Some(CoverOverride::Volume {
+                volume: "2".to_string(),
+                file_name: "cover.jpg".to_string()
+            }),
+            cover_override_from_columns(None, Some("2".to_string()), Some("cover.jpg".to_string()))
+        );
+        assert_eq!(None, cover_override_from_columns(None, Some("2".to_string()), None));
+    }
+}
@@ -0,0 +1,174 @@
+//! `manga-tui doctor`, a report of what the terminal supports so it's easier to tell why images don't show
+use std::error::Error;
+use std::io::{stdout, Cursor};
+
+use image::io::Reader;
+use image::DynamicImage;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Position, Rect};
+use ratatui::{Terminal, TerminalOptions, Viewport};
+use ratatui_image::picker::{Picker, ProtocolType};
+use ratatui_image::{Image, Resize};
+
+use super::fetch::MangadexClient;
+use crate::view::app::detect_picker;
+
+/// How many lines tall the test image is drawn
+const TEST_IMAGE_HEIGHT: u16 = 10;
+
+/// What the environment variables tell about the terminal
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TerminalEnv {
+    pub term: Option<String>,
+    pub term_program: Option<String>,
+    pub colorterm: Option<String>,
+    pub truecolor: bool,
+    pub tmux: bool,
+    pub ssh: bool,
+}
+
+impl TerminalEnv {
+    /// `var` looks up an environment variable, it's a function so the report can be checked without
+    /// changing the environment of the tests
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let term = var("TERM");
+        let colorterm = var("COLORTERM");
+
+        Self {
+            truecolor: colorterm.as_deref().is_some_and(|colorterm| matches!(colorterm, "truecolor" | "24bit")),
+            tmux: var("TMUX").is_some()
+                || term.as_deref().is_some_and(|term| term.starts_with("tmux") || term.starts_with("screen")),
+            ssh: ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"].iter().any(|name| var(name).is_some()),
+            term_program: var("TERM_PROGRAM"),
+            term,
+            colorterm,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+    }
+
+    /// The lines of the report, with a hint for each thing known to get in the way of images
+    pub fn report(&self) -> Vec<String> {
+        let unset = || "(not set)".to_string();
+        let yes_no = |value: bool| if value { "yes" } else { "no" };
+
+        let mut lines = vec![
+            format!("manga-tui {} on {} {}", env!("CARGO_PKG_VERSION"), std::env::consts::OS, std::env::consts::ARCH),
+            format!("TERM: {}", self.term.clone().unwrap_or_else(unset)),
+            format!("TERM_PROGRAM: {}", self.term_program.clone().unwrap_or_else(unset)),
+            format!("COLORTERM: {}", self.colorterm.clone().unwrap_or_else(unset)),
+            format!("Truecolor: {}", yes_no(self.truecolor)),
+            format!("Inside tmux/screen: {}", yes_no(self.tmux)),
+            format!("Over SSH: {}", yes_no(self.ssh)),
+        ];
+
+        if self.tmux {
+            lines.push("  tmux only lets images through with `set -g allow-passthrough on` in its config".into());
+        }
+        if self.ssh {
+            lines.push("  over SSH the terminal can't be guessed from TERM_PROGRAM, the protocol may be wrong".into());
+        }
+
+        lines
+    }
+}
+
+/// The font size and the protocol manga-tui would use, and whether it shows images at all
+pub fn picker_report(picker: Option<&Picker>) -> Vec<String> {
+    match picker {
+        Some(picker) => {
+            let (width, height) = picker.font_size;
+            let mut lines =
+                vec![format!("Cell size: {width}x{height} pixels"), format!("Guessed protocol: {:?}", picker.protocol_type)];
+
+            if picker.protocol_type == ProtocolType::Halfblocks {
+                lines.push("Images: no, the terminal doesn't seem to support any graphics protocol".into());
+            } else {
+                lines.push("Images: yes".into());
+            }
+            lines
+        },
+        None => vec![
+            "Cell size: unknown, the terminal didn't tell its size in pixels".into(),
+            "Images: no, manga-tui needs the cell size to draw images".into(),
+        ],
+    }
+}
+
+async fn download_test_image() -> Result<DynamicImage, Box<dyn Error>> {
+    let bytes = MangadexClient::global().get_mangadex_image_support().await?;
+    Ok(Reader::new(Cursor::new(bytes)).with_guessed_format()?.decode()?)
+}
+
+/// Draws the image below the cursor, taking `TEST_IMAGE_HEIGHT` lines
+fn draw_with_protocol(picker: &mut Picker, image: DynamicImage) -> Result<(), Box<dyn Error>> {
+    let mut terminal = Terminal::with_options(CrosstermBackend::new(stdout()), TerminalOptions {
+        viewport: Viewport::Inline(TEST_IMAGE_HEIGHT),
+    })?;
+
+    let area = terminal.get_frame().area();
+    let protocol = picker.new_protocol(image, Rect::new(0, 0, area.width, area.height), Resize::Fit(None))?;
+
+    terminal.draw(|frame| frame.render_widget(Image::new(protocol.as_ref()), frame.area()))?;
+    terminal.set_cursor_position(Position::new(0, area.bottom().saturating_sub(1)))?;
+    println!();
+
+    Ok(())
+}
+
+/// Prints the report, then draws the same image with each protocol so the one that works can be told
+/// apart from the ones that print garbage or nothing
+pub async fn run_doctor() -> Result<(), Box<dyn Error>> {
+    let env = TerminalEnv::from_env();
+    let picker = detect_picker();
+
+    for line in env.report().into_iter().chain(picker_report(picker.as_ref())) {
+        println!("{line}");
+    }
+
+    let Some(mut picker) = picker else {
+        return Ok(());
+    };
+
+    println!("\nDownloading a test image...");
+    let image = download_test_image().await.map_err(|e| format!("could not download the test image: {e}"))?;
+
+    for protocol in [ProtocolType::Halfblocks, ProtocolType::Sixel, ProtocolType::Kitty, ProtocolType::Iterm2] {
+        println!("\n{protocol:?}:");
+        picker.protocol_type = protocol;
+        if let Err(e) = draw_with_protocol(&mut picker, image.clone()) {
+            println!("  could not draw the image: {e}");
+        }
+    }
+
+    println!("\nIf an image other than Halfblocks shows above but the guessed protocol is another one,");
+    println!("please open an issue with this report");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> TerminalEnv {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        TerminalEnv::from_vars(|name| vars.get(name).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn terminal_is_described_from_the_environment() {
+        let local = env(&[("TERM", "xterm-kitty"), ("COLORTERM", "truecolor")]);
+        assert!(local.truecolor && !local.tmux && !local.ssh);
+
+        let remote = env(&[("TERM", "tmux-256color"), ("SSH_TTY", "/dev/pts/1")]);
+        assert!(!remote.truecolor && remote.tmux && remote.ssh);
+        assert!(remote.report().iter().any(|line| line.contains("allow-passthrough")));
+
+        assert!(env(&[("TMUX", "/tmp/tmux-1000/default,1,0")]).tmux);
+    }
+}
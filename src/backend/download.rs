@@ -1,15 +1,20 @@
-use std::fs::{create_dir, File};
+use std::ffi::OsString;
+use std::fs::{create_dir, rename, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use manga_tui::exists;
 use tokio::sync::mpsc::UnboundedSender;
+#[cfg(feature = "cbz")]
 use zip::write::SimpleFileOptions;
+#[cfg(feature = "cbz")]
 use zip::ZipWriter;
 
 use super::error_log::{write_to_error_log, ErrorType};
 use super::fetch::MangadexClient;
+use super::repeated_pages::RepeatedPages;
 use super::APP_DATA_DIR;
+use crate::config::{DownloadType, MangaTuiConfig};
 use crate::view::pages::manga::MangaPageEvents;
 
 pub struct DownloadChapter<'a> {
@@ -45,6 +50,56 @@ fn create_manga_directory(chapter: &DownloadChapter<'_>) -> Result<PathBuf, std:
     Ok(chapter_language_dir)
 }
 
+/// Where a file is written until it's complete, `Ch. 1.cbz` is written to `Ch. 1.cbz.part`. The extension is
+/// appended rather than replaced since chapter names can have dots, like `Ch. 10.5`
+fn part_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(".part");
+    path.with_file_name(file_name)
+}
+
+/// Flushes the `.part` file of `path` to disk and renames it to `path`, so a crash or a power loss never
+/// leaves a half written page or archive with the name of a complete one
+fn finish_part_file(file: File, path: &Path) -> Result<(), std::io::Error> {
+    file.sync_all()?;
+    drop(file);
+    rename(part_path(path), path)?;
+
+    // the rename itself is only on disk once the directory is
+    #[cfg(unix)]
+    if let Some(directory) = path.parent() {
+        File::open(directory)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+/// Writes the whole file to its `.part` file first, see `finish_part_file`
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), std::io::Error> {
+    let mut file = File::create(part_path(path))?;
+    file.write_all(bytes)?;
+    finish_part_file(file, path)
+}
+
+/// Downloads the chapter in the format set in the config, the formats which were left out of the
+/// build are downloaded as raw images
+pub fn download_chapter(
+    download_type: &DownloadType,
+    is_downloading_all_chapters: bool,
+    chapter: DownloadChapter<'_>,
+    files: Vec<String>,
+    endpoint: String,
+    tx: UnboundedSender<MangaPageEvents>,
+) -> Result<(), std::io::Error> {
+    match download_type {
+        #[cfg(feature = "cbz")]
+        DownloadType::Cbz => download_chapter_cbz(is_downloading_all_chapters, chapter, files, endpoint, tx),
+        #[cfg(feature = "epub")]
+        DownloadType::Epub => download_chapter_epub(is_downloading_all_chapters, chapter, files, endpoint, tx),
+        _ => download_chapter_raw_images(is_downloading_all_chapters, chapter, files, endpoint, tx),
+    }
+}
+
 pub fn download_chapter_raw_images(
     is_downloading_all_chapters: bool,
     chapter: DownloadChapter<'_>,
@@ -69,16 +124,21 @@ pub fn download_chapter_raw_images(
 
     tokio::spawn(async move {
         let total_pages = files.len();
+        let skip_repeated_pages = MangaTuiConfig::get().skip_repeated_pages;
+        let mut repeated_pages = RepeatedPages::default();
+
         for (index, file_name) in files.into_iter().enumerate() {
             let image_response = MangadexClient::global().get_chapter_page(&endpoint, &file_name).await;
 
             let file_name = Path::new(&file_name);
 
             match image_response {
+                Ok(bytes) if skip_repeated_pages && repeated_pages.is_repeated(&bytes) => {},
                 Ok(bytes) => {
                     let image_name = format!("{}.{}", index + 1, file_name.extension().unwrap().to_str().unwrap());
-                    let mut image_created = File::create(chapter_dir.join(image_name)).unwrap();
-                    image_created.write_all(&bytes).unwrap();
+                    if let Err(e) = write_atomically(&chapter_dir.join(image_name), &bytes) {
+                        write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    }
 
                     if !is_downloading_all_chapters {
                         tx.send(MangaPageEvents::SetDownloadProgress((index as f64) / (total_pages as f64), chapter_id.clone()))
@@ -99,6 +159,7 @@ pub fn download_chapter_raw_images(
     Ok(())
 }
 
+#[cfg(feature = "epub")]
 pub fn download_chapter_epub(
     is_downloading_all_chapters: bool,
     chapter: DownloadChapter<'_>,
@@ -115,7 +176,8 @@ pub fn download_chapter_epub(
     tokio::spawn(async move {
         let total_pages = files.len();
 
-        let mut epub_output = File::create(chapter_dir_language.join(format!("{}.epub", chapter_name))).unwrap();
+        let epub_path = chapter_dir_language.join(format!("{}.epub", chapter_name));
+        let mut epub_output = File::create(part_path(&epub_path)).unwrap();
 
         let mut epub = epub_builder::EpubBuilder::new(epub_builder::ZipLibrary::new().unwrap()).unwrap();
 
@@ -123,10 +185,14 @@ pub fn download_chapter_epub(
 
         let _ = epub.metadata("title", chapter_name);
 
+        let skip_repeated_pages = MangaTuiConfig::get().skip_repeated_pages;
+        let mut repeated_pages = RepeatedPages::default();
+
         for (index, file_name) in files.into_iter().enumerate() {
             let image_response = MangadexClient::global().get_chapter_page(&endpoint, &file_name).await;
 
             match image_response {
+                Ok(bytes) if skip_repeated_pages && repeated_pages.is_repeated(&bytes) => {},
                 Ok(bytes) => {
                     let image_path = format!("data/{}", file_name);
 
@@ -178,6 +244,10 @@ pub fn download_chapter_epub(
 
         epub.generate(&mut epub_output).unwrap();
 
+        if let Err(e) = finish_part_file(epub_output, &epub_path) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+
         if is_downloading_all_chapters {
             tx.send(MangaPageEvents::SetDownloadAllChaptersProgress).ok();
         } else {
@@ -188,6 +258,7 @@ pub fn download_chapter_epub(
     Ok(())
 }
 
+#[cfg(feature = "cbz")]
 pub fn download_chapter_cbz(
     is_downloading_all_chapters: bool,
     chapter: DownloadChapter<'_>,
@@ -203,7 +274,8 @@ pub fn download_chapter_cbz(
 
     let chapter_name = format!("{}.cbz", chapter_name);
 
-    let chapter_zip_file = File::create(chapter_dir_language.join(chapter_name))?;
+    let chapter_zip_path = chapter_dir_language.join(chapter_name);
+    let chapter_zip_file = File::create(part_path(&chapter_zip_path))?;
 
     tokio::spawn(async move {
         let mut zip = ZipWriter::new(chapter_zip_file);
@@ -213,12 +285,16 @@ pub fn download_chapter_cbz(
             .compression_method(zip::CompressionMethod::Deflated)
             .unix_permissions(0o755);
 
+        let skip_repeated_pages = MangaTuiConfig::get().skip_repeated_pages;
+        let mut repeated_pages = RepeatedPages::default();
+
         for (index, file_name) in files.into_iter().enumerate() {
             let image_response = MangadexClient::global().get_chapter_page(&endpoint, &file_name).await;
 
             let file_name = Path::new(&file_name);
 
             match image_response {
+                Ok(bytes) if skip_repeated_pages && repeated_pages.is_repeated(&bytes) => {},
                 Ok(bytes) => {
                     let image_name = format!("{}.{}", index + 1, file_name.extension().unwrap().to_str().unwrap());
 
@@ -237,7 +313,11 @@ pub fn download_chapter_cbz(
                 Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
             }
         }
-        zip.finish().unwrap();
+        let chapter_zip_file = zip.finish().unwrap();
+
+        if let Err(e) = finish_part_file(chapter_zip_file, &chapter_zip_path) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
 
         if is_downloading_all_chapters {
             tx.send(MangaPageEvents::SetDownloadAllChaptersProgress).ok();
@@ -248,3 +328,27 @@ pub fn download_chapter_cbz(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn files_are_renamed_from_their_part_file_once_written() {
+        let directory = std::env::temp_dir().join(format!("manga-tui-atomic-download-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+
+        let chapter = directory.join("Ch. 10.5 Extra.cbz");
+        assert_eq!(directory.join("Ch. 10.5 Extra.cbz.part"), part_path(&chapter));
+
+        write_atomically(&chapter, b"chapter").unwrap();
+
+        assert_eq!(b"chapter".to_vec(), fs::read(&chapter).unwrap());
+        assert!(!part_path(&chapter).exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
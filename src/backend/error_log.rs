@@ -10,9 +10,12 @@ use manga_tui::exists;
 
 use super::tui::restore;
 use super::{AppDirectories, APP_DATA_DIR};
+use crate::global::is_safe_mode;
 
 pub static ERROR_LOGS_FILE: &str = "manga-tui-error-logs.txt";
 
+pub static SAFE_MODE_LOGS_FILE: &str = "manga-tui-safe-mode-logs.txt";
+
 pub enum ErrorType<'a> {
     FromPanic(&'a PanicInfo<'a>),
     FromError(Box<dyn Error>),
@@ -32,6 +35,8 @@ pub fn write_to_error_log(e: ErrorType<'_>) {
         ErrorType::FromError(boxed_err) => format!("{} | {} \n \n", now, boxed_err),
     };
 
+    write_to_safe_mode_log(&error_format);
+
     let error_format_bytes = error_format.as_bytes();
 
     if !exists!(&error_file_name) {
@@ -45,6 +50,24 @@ pub fn write_to_error_log(e: ErrorType<'_>) {
     }
 }
 
+/// Everything that happens is written here when running with `--safe-mode`, so that users can send it along
+/// with bug reports about terminals where nothing shows up correctly
+pub fn write_to_safe_mode_log(message: &str) {
+    if !is_safe_mode() {
+        return;
+    }
+
+    let Some(data_dir) = APP_DATA_DIR.as_ref() else {
+        return;
+    };
+
+    let log_file_name = data_dir.join(AppDirectories::ErrorLogs.to_string()).join(SAFE_MODE_LOGS_FILE);
+
+    if let Ok(mut safe_mode_logs) = OpenOptions::new().create(true).append(true).open(log_file_name) {
+        let _ = writeln!(safe_mode_logs, "{} | {}", offset::Local::now(), message.trim_end());
+    }
+}
+
 pub fn init_error_hooks() -> color_eyre::Result<()> {
     let (panic, error) = HookBuilder::default().into_hooks();
     let panic = panic.into_panic_hook();
@@ -0,0 +1,165 @@
+use std::fmt::Write;
+
+use clap::ValueEnum;
+use strum::Display;
+
+use super::database::{get_reading_list, MangaHistoryType, ReadingListEntry};
+use super::fetch::COVER_IMG_URL_BASE;
+
+static MANGADEX_TITLE_URL: &str = "https://mangadex.org/title";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Display, ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+/// Which part of the library is exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ExportCategory {
+    #[default]
+    All,
+    Reading,
+    PlanToRead,
+}
+
+struct ReadingListSection {
+    name: &'static str,
+    entries: Vec<ReadingListEntry>,
+}
+
+pub fn export_reading_list(category: ExportCategory, format: ExportFormat) -> rusqlite::Result<String> {
+    let mut sections: Vec<ReadingListSection> = vec![];
+
+    if category != ExportCategory::PlanToRead {
+        sections.push(ReadingListSection {
+            name: "Reading",
+            entries: get_reading_list(MangaHistoryType::ReadingHistory)?,
+        });
+    }
+
+    if category != ExportCategory::Reading {
+        sections.push(ReadingListSection {
+            name: "Plan to read",
+            entries: get_reading_list(MangaHistoryType::PlanToRead)?,
+        });
+    }
+
+    Ok(match format {
+        ExportFormat::Markdown => to_markdown(&sections),
+        ExportFormat::Html => to_html(&sections),
+    })
+}
+
+fn manga_url(entry: &ReadingListEntry) -> String {
+    format!("{}/{}", MANGADEX_TITLE_URL, entry.id)
+}
+
+fn cover_url(entry: &ReadingListEntry) -> Option<String> {
+    entry
+        .img_url
+        .as_ref()
+        .map(|file_name| format!("{}/{}/{}.512.jpg", COVER_IMG_URL_BASE, entry.id, file_name))
+}
+
+fn progress(entry: &ReadingListEntry) -> String {
+    match entry.last_chapter.as_ref() {
+        Some(chapter) => format!("{} chapters read, last read chapter {}", entry.chapters_read, chapter),
+        None => format!("{} chapters read", entry.chapters_read),
+    }
+}
+
+fn to_markdown(sections: &[ReadingListSection]) -> String {
+    let mut markdown = String::from("# Reading list\n");
+
+    for section in sections {
+        let _ = write!(markdown, "\n## {}\n\n", section.name);
+
+        if section.entries.is_empty() {
+            markdown.push_str("Nothing here yet\n");
+        }
+
+        for entry in section.entries.iter() {
+            let title = entry.title.replace('[', "\\[").replace(']', "\\]");
+            let _ = write!(markdown, "- [{}]({}) | {}", title, manga_url(entry), progress(entry));
+            if let Some(cover) = cover_url(entry) {
+                let _ = write!(markdown, " | [cover]({})", cover);
+            }
+            markdown.push('\n');
+        }
+    }
+
+    markdown
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn to_html(sections: &[ReadingListSection]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Reading list</title>\n</head>\n<body>\n<h1>Reading list</h1>\n",
+    );
+
+    for section in sections {
+        let _ = writeln!(html, "<h2>{}</h2>", section.name);
+
+        if section.entries.is_empty() {
+            html.push_str("<p>Nothing here yet</p>\n");
+            continue;
+        }
+
+        html.push_str("<ul>\n");
+        for entry in section.entries.iter() {
+            let _ = write!(html, "<li><a href=\"{}\">{}</a> | {}", manga_url(entry), escape_html(&entry.title), progress(entry));
+            if let Some(cover) = cover_url(entry) {
+                let _ = write!(html, " | <a href=\"{}\">cover</a>", escape_html(&cover));
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reading_list() -> Vec<ReadingListSection> {
+        vec![ReadingListSection {
+            name: "Reading",
+            entries: vec![ReadingListEntry {
+                id: "some-id".to_string(),
+                title: "Tom & [Jerry]".to_string(),
+                img_url: Some("cover.png".to_string()),
+                last_read: String::default(),
+                chapters_read: 3,
+                last_chapter: Some("4".to_string()),
+            }],
+        }]
+    }
+
+    #[test]
+    fn reading_list_is_exported_to_markdown() {
+        let markdown = to_markdown(&reading_list());
+
+        assert!(markdown.contains("## Reading"));
+        assert!(markdown.contains(
+            "- [Tom & \\[Jerry\\]](https://mangadex.org/title/some-id) | 3 chapters read, last read chapter 4 | [cover](https://uploads.mangadex.org/covers/some-id/cover.png.512.jpg)"
+        ));
+    }
+
+    #[test]
+    fn reading_list_is_exported_to_html() {
+        let html = to_html(&reading_list());
+
+        assert!(html.contains("<a href=\"https://mangadex.org/title/some-id\">Tom &amp; [Jerry]</a>"));
+    }
+}
@@ -1,24 +1,52 @@
-use std::time::Duration as StdDuration;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration as StdDuration, Instant};
 
 use bytes::Bytes;
-use chrono::Months;
+use chrono::{Months, NaiveDate};
+use futures::future::{BoxFuture, Shared};
+use futures::FutureExt;
 use once_cell::sync::OnceCell;
-use reqwest::StatusCode;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
 
+use super::aggregate::{self, AggregateResponse, VolumeCompleteness};
+use super::covers::CoversResponse;
+use super::database::{cache_manga_metadata, get_cached_manga_metadata};
+use super::error_log::{write_to_error_log, ErrorType};
 use super::filter::Languages;
-use super::{ChapterPagesResponse, ChapterResponse, MangaStatisticsResponse, SearchMangaResponse};
-use crate::backend::filter::{Filters, IntoParam};
+use super::reading_order::{publication_order, unseen_parts, SeriesPart, MAX_SERIES_PARTS};
+use super::{ChapterData, ChapterPagesResponse, ChapterResponse, MangaStatisticsResponse, SearchMangaResponse};
+use crate::backend::filter::{ContentRating, Filters, IntoParam, Ranking};
 use crate::view::pages::manga::ChapterOrder;
 
-#[derive(Clone, Debug)]
+type InFlightRequest = Shared<BoxFuture<'static, Result<Bytes, FetchError>>>;
+
+#[derive(Clone)]
 pub struct MangadexClient {
     client: reqwest::Client,
+    circuit_breaker: Arc<CircuitBreaker>,
+    /// Requests currently being made keyed by their url, so that identical requests made at the same
+    /// time share the response instead of hitting mangadex twice
+    in_flight: Arc<Mutex<HashMap<String, InFlightRequest>>>,
+}
+
+impl std::fmt::Debug for MangadexClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MangadexClient")
+            .field("client", &self.client)
+            .field("circuit_breaker", &self.circuit_breaker)
+            .finish()
+    }
 }
 
 pub static MANGADEX_CLIENT_INSTANCE: OnceCell<MangadexClient> = once_cell::sync::OnceCell::new();
 
 static API_URL_BASE: &str = "https://api.mangadex.org";
-static COVER_IMG_URL_BASE: &str = "https://uploads.mangadex.org/covers";
+pub static COVER_IMG_URL_BASE: &str = "https://uploads.mangadex.org/covers";
 
 pub static ITEMS_PER_PAGE_CHAPTERS: u32 = 16;
 
@@ -26,171 +54,847 @@ pub static ITEMS_PER_PAGE_LATEST_CHAPTERS: u32 = 5;
 
 pub static ITEMS_PER_PAGE_SEARCH: u32 = 10;
 
+/// Mangadex doesn't return search results past this many, no matter how many it found
+pub static MAX_SEARCH_RESULTS: u32 = 10_000;
+
+pub static ITEMS_PER_PAGE_ALL_CHAPTERS: u32 = 100;
+
+/// Chapters shown in the latest updates tab, it's also the most mangas which can be requested by id at once
+pub static ITEMS_PER_PAGE_LATEST_UPDATES: u32 = 100;
+
+/// Timeout for requests the user is actively waiting on, like searching or opening a manga
+pub static INTERACTIVE_REQUEST_TIMEOUT: StdDuration = StdDuration::from_secs(8);
+
+/// Chapter pages are bigger so they get more time to download
+pub static PAGE_REQUEST_TIMEOUT: StdDuration = StdDuration::from_secs(20);
+
+/// After this much time waiting for an interactive request the user is told that mangadex is being slow
+pub static SLOW_REQUEST_THRESHOLD: StdDuration = StdDuration::from_secs(3);
+
+/// How many requests in a row have to fail before requests to mangadex are paused
+pub static CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 5;
+
+/// How long requests to mangadex are paused once the circuit breaker trips
+pub static CIRCUIT_BREAKER_COOLDOWN: StdDuration = StdDuration::from_secs(30);
+
+/// Errors are wrapped in `Arc` so that requests which were coalesced can all receive the same error
+#[derive(Debug, Clone)]
+pub enum FetchError {
+    Request(Arc<reqwest::Error>),
+    Deserialize(Arc<serde_json::Error>),
+    /// Mangadex failed too many times in a row so the request was not made
+    CircuitOpen(StdDuration),
+    /// The chapter was deleted or has no pages to read
+    ChapterUnavailable,
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Request(e) => e.fmt(f),
+            Self::Deserialize(e) => e.fmt(f),
+            Self::CircuitOpen(remaining) => write!(
+                f,
+                "requests to mangadex are paused after {} consecutive failures, retrying in {}s",
+                CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+                remaining.as_secs()
+            ),
+            Self::ChapterUnavailable => write!(f, "the chapter is no longer available"),
+        }
+    }
+}
+
+impl Error for FetchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Request(e) => Some(e.as_ref()),
+            Self::Deserialize(e) => Some(e.as_ref()),
+            Self::CircuitOpen(_) | Self::ChapterUnavailable => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Request(Arc::new(value))
+    }
+}
+
+impl From<serde_json::Error> for FetchError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Deserialize(Arc::new(value))
+    }
+}
+
+/// Keeps track of consecutive failed requests so that during an outage every keystroke doesn't
+/// spawn requests which are going to fail anyway
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: AtomicU32,
+    open_until: Mutex<Option<Instant>>,
+}
+
+impl CircuitBreaker {
+    /// Returns how long requests are still paused for, if they are
+    fn remaining_cooldown(&self) -> Option<StdDuration> {
+        let mut open_until = self.open_until.lock().unwrap();
+        match *open_until {
+            Some(until) => {
+                let remaining = until.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    // Let the next request through, if it fails the breaker trips again
+                    *open_until = None;
+                    None
+                } else {
+                    Some(remaining)
+                }
+            },
+            None => None,
+        }
+    }
+
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::SeqCst);
+    }
+
+    fn record_failure(&self) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::SeqCst) + 1;
+        if failures >= CIRCUIT_BREAKER_FAILURE_THRESHOLD {
+            *self.open_until.lock().unwrap() = Some(Instant::now() + CIRCUIT_BREAKER_COOLDOWN);
+        }
+    }
+}
+
 impl MangadexClient {
     pub fn global() -> &'static MangadexClient {
         MANGADEX_CLIENT_INSTANCE.get().expect("could not build mangadex client")
     }
 
     pub fn new(client: reqwest::Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            circuit_breaker: Arc::new(CircuitBreaker::default()),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
     }
 
-    pub async fn search_mangas(
-        &self,
-        search_term: &str,
-        page: u32,
-        filters: Filters,
-    ) -> Result<SearchMangaResponse, reqwest::Error> {
-        let offset = (page - 1) * ITEMS_PER_PAGE_SEARCH;
+    /// If mangadex has been failing, returns how long requests will be paused for
+    pub fn circuit_breaker_cooldown(&self) -> Option<StdDuration> {
+        self.circuit_breaker.remaining_cooldown()
+    }
 
-        let search_by_title = if search_term.trim().is_empty() { "".to_string() } else { format!("title={search_term}") };
+    /// Sends the request unless the circuit breaker is open, connection errors and server errors count
+    /// as failures
+    async fn send(&self, request: RequestBuilder) -> Result<Response, FetchError> {
+        if let Some(remaining) = self.circuit_breaker.remaining_cooldown() {
+            return Err(FetchError::CircuitOpen(remaining));
+        }
+
+        match request.send().await {
+            Ok(response) => {
+                if response.status().is_server_error() {
+                    self.circuit_breaker.record_failure();
+                } else {
+                    self.circuit_breaker.record_success();
+                }
+                Ok(response)
+            },
+            Err(e) => {
+                self.circuit_breaker.record_failure();
+                Err(e.into())
+            },
+        }
+    }
 
-        let url = format!(
-            "{}/manga?{}&includes[]=cover_art&includes[]=author&includes[]=artist&limit=10&offset={}{}&includedTagsMode=AND&excludedTagsMode=OR&hasAvailableChapters=true",
-            API_URL_BASE,
-            search_by_title,
-            offset,
-            filters.into_param(),
-        );
+    /// Gets the body of the response, if an identical request is already being made then this waits
+    /// for that one to finish instead of making a new one
+    async fn get_bytes(&self, request: RequestBuilder) -> Result<Bytes, FetchError> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let url = request.url().to_string();
+
+        let in_flight_request = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(&url) {
+                Some(in_flight_request) => in_flight_request.clone(),
+                None => {
+                    let mangadex_client = self.clone();
+                    let key = url.clone();
+                    let in_flight_request = async move {
+                        let response = mangadex_client.send(RequestBuilder::from_parts(client, request)).await;
+                        let bytes = match response {
+                            Ok(response) => response.bytes().await.map_err(FetchError::from),
+                            Err(e) => Err(e),
+                        };
+                        mangadex_client.in_flight.lock().unwrap().remove(&key);
+                        bytes
+                    }
+                    .boxed()
+                    .shared();
+
+                    in_flight.insert(url, in_flight_request.clone());
+                    in_flight_request
+                },
+            }
+        };
+
+        in_flight_request.await
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, FetchError> {
+        let bytes = self.get_bytes(request).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Same as `get_json` but the response is first looked up in the `manga_metadata` table, so that
+    /// mangas which were recently viewed load instantly
+    async fn get_json_cached<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, FetchError> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let key = request.url().to_string();
+
+        let cache_key = key.clone();
+        match tokio::task::spawn_blocking(move || get_cached_manga_metadata(&cache_key)).await {
+            Ok(Ok(Some(cached))) => {
+                if let Ok(response) = serde_json::from_str(&cached) {
+                    return Ok(response);
+                }
+            },
+            Ok(Ok(None)) => {},
+            Ok(Err(e)) => write_to_error_log(ErrorType::FromError(Box::new(e))),
+            Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
+        }
+
+        let bytes = self.get_bytes(RequestBuilder::from_parts(client, request)).await?;
+        let response = serde_json::from_slice(&bytes)?;
+
+        // The response is already here, so there is no need to wait for it to be written
+        if let Ok(body) = String::from_utf8(bytes.to_vec()) {
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = cache_manga_metadata(&key, &body) {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                }
+            });
+        }
+
+        Ok(response)
+    }
+
+    pub async fn search_mangas(&self, search_term: &str, page: u32, filters: Filters) -> Result<SearchMangaResponse, FetchError> {
+        let url = search_mangas_url(search_term, page, filters);
 
-        self.client.get(url).send().await?.json().await
+        self.get_json_cached(self.client.get(url).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
     }
 
-    pub async fn get_cover_for_manga(&self, id_manga: &str, file_name: &str) -> Result<bytes::Bytes, reqwest::Error> {
+    pub async fn get_cover_for_manga(&self, id_manga: &str, file_name: &str) -> Result<bytes::Bytes, FetchError> {
         let file_name = format!("{}.512.jpg", file_name);
-        self.client
-            .get(format!("{}/{}/{}", COVER_IMG_URL_BASE, id_manga, file_name))
-            .send()
-            .await?
-            .bytes()
+        self.get_bytes(self.client.get(format!("{}/{}/{}", COVER_IMG_URL_BASE, id_manga, file_name)))
             .await
     }
 
-    pub async fn get_cover_for_manga_lower_quality(&self, id_manga: &str, file_name: &str) -> Result<bytes::Bytes, reqwest::Error> {
+    pub async fn get_cover_for_manga_lower_quality(&self, id_manga: &str, file_name: &str) -> Result<bytes::Bytes, FetchError> {
         let file_name = format!("{}.256.jpg", file_name);
-        self.client
-            .get(format!("{}/{}/{}", COVER_IMG_URL_BASE, id_manga, file_name))
-            .send()
-            .await?
-            .bytes()
+        self.get_bytes(self.client.get(format!("{}/{}/{}", COVER_IMG_URL_BASE, id_manga, file_name)))
             .await
     }
 
-    pub async fn get_chapter_page(&self, endpoint: &str, file_name: &str) -> Result<Bytes, reqwest::Error> {
-        self.client
+    /// The covers of each volume of a manga, ordered by volume
+    pub async fn get_volume_covers(&self, manga_id: &str) -> Result<CoversResponse, FetchError> {
+        let endpoint = format!("{}/cover?manga[]={}&limit=100&order[volume]=asc", API_URL_BASE, manga_id);
+
+        self.get_json_cached(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
+    }
+
+    /// Chapter pages are served by mangadex@home nodes which may fail on their own, so these requests
+    /// don't go through the circuit breaker
+    pub async fn get_chapter_page(&self, endpoint: &str, file_name: &str) -> Result<Bytes, FetchError> {
+        Ok(self
+            .client
             .get(format!("{}/{}", endpoint, file_name))
-            .timeout(StdDuration::from_secs(20))
+            .timeout(PAGE_REQUEST_TIMEOUT)
             .send()
             .await?
             .bytes()
-            .await
+            .await?)
     }
 
+    /// Size in bytes of a chapter page, without downloading it
+    pub async fn get_chapter_page_size(&self, endpoint: &str, file_name: &str) -> Result<Option<u64>, FetchError> {
+        Ok(self
+            .client
+            .head(format!("{}/{}", endpoint, file_name))
+            .timeout(PAGE_REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .content_length())
+    }
+
+    /// With `group` only the chapters uploaded by that scanlation group are listed, the feed of a manga can't
+    /// be filtered by group so they are searched among all the chapters instead
     pub async fn get_manga_chapters(
         &self,
         id: String,
         page: u32,
         language: Languages,
         order: ChapterOrder,
-    ) -> Result<ChapterResponse, reqwest::Error> {
+        group: Option<&str>,
+    ) -> Result<ChapterResponse, FetchError> {
         let language = language.as_iso_code();
         let page = (page - 1) * ITEMS_PER_PAGE_CHAPTERS;
 
+        let chapters = match group {
+            Some(group) => format!("chapter?manga={id}&groups[]={group}&"),
+            None => format!("manga/{id}/feed?"),
+        };
         let order = format!("order[volume]={order}&order[chapter]={order}");
+        let content_rating = ContentRating::allowed().into_param();
         let endpoint = format!(
-            "{}/manga/{}/feed?limit={ITEMS_PER_PAGE_CHAPTERS}&offset={}&{}&translatedLanguage[]={}&includes[]=scanlation_group&includeExternalUrl=0&contentRating[]=safe&contentRating[]=suggestive&contentRating[]=erotica&contentRating[]=pornographic",
-            API_URL_BASE, id, page, order, language
+            "{}/{chapters}limit={ITEMS_PER_PAGE_CHAPTERS}&offset={}&{}&translatedLanguage[]={}&includes[]=scanlation_group&includeExternalUrl=0{content_rating}",
+            API_URL_BASE, page, order, language
         );
 
-        self.client.get(endpoint).send().await?.json().await
+        self.get_json(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
     }
 
-    pub async fn get_chapter_pages(&self, id: &str) -> Result<ChapterPagesResponse, reqwest::Error> {
+    pub async fn get_chapter_pages(&self, id: &str) -> Result<ChapterPagesResponse, FetchError> {
         let endpoint = format!("{}/at-home/server/{}", API_URL_BASE, id);
 
-        self.client.get(endpoint).send().await?.json().await
+        let response = self.send(self.client.get(endpoint)).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(FetchError::ChapterUnavailable);
+        }
+
+        let response: ChapterPagesResponse = serde_json::from_slice(&response.bytes().await?)?;
+
+        if response.chapter.data.is_empty() && response.chapter.data_saver.is_empty() {
+            return Err(FetchError::ChapterUnavailable);
+        }
+
+        Ok(response)
+    }
+
+    /// The same chapter uploaded by other scanlation groups
+    pub async fn get_other_versions_of_chapter(
+        &self,
+        manga_id: &str,
+        chapter_number: &str,
+        language: Languages,
+    ) -> Result<ChapterResponse, FetchError> {
+        let content_rating = ContentRating::allowed().into_param();
+        let endpoint = format!(
+            "{}/chapter?manga={}&chapter[]={}&translatedLanguage[]={}&includes[]=scanlation_group&includeExternalUrl=0&includeEmptyPages=0{content_rating}",
+            API_URL_BASE,
+            manga_id,
+            chapter_number,
+            language.as_iso_code()
+        );
+
+        self.get_json(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
+    }
+
+    /// How many chapters of each volume are translated to `language`, out of the ones there are in every language
+    pub async fn get_volume_completeness(
+        &self,
+        manga_id: &str,
+        language: Languages,
+    ) -> Result<Vec<VolumeCompleteness>, FetchError> {
+        let endpoint = format!("{}/manga/{}/aggregate", API_URL_BASE, manga_id);
+        let all_languages: AggregateResponse =
+            self.get_json(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await?;
+
+        let translated = self.get_chapters_aggregate(manga_id, language).await?;
+
+        Ok(all_languages.volume_completeness(&translated))
+    }
+
+    /// The sequels and prequels of a manga, and theirs, together with it in publication order. The chapters in
+    /// `language` of each part are counted, the ones read are left for the caller to fill in
+    pub async fn get_series_parts(&self, manga_id: &str, language: Languages) -> Result<Vec<SeriesPart>, FetchError> {
+        let mut seen = vec![manga_id.to_string()];
+        let mut mangas = vec![self.get_one_manga(manga_id).await?.data];
+
+        let mut index = 0;
+        while index < mangas.len() && mangas.len() < MAX_SERIES_PARTS {
+            let ids = unseen_parts(&mangas[index], &mut seen);
+            if !ids.is_empty() {
+                mangas.extend(self.get_mangas_by_id(&ids).await?.data);
+            }
+            index += 1;
+        }
+        mangas.truncate(MAX_SERIES_PARTS);
+
+        let mut parts = vec![];
+        for manga in mangas {
+            let mut part = SeriesPart::new(manga);
+            let aggregate = self.get_chapters_aggregate(&part.id, language).await?;
+            part.total_chapters = aggregate.volumes.iter().map(|volume| volume.chapters.len()).sum();
+            parts.push(part);
+        }
+
+        publication_order(&mut parts);
+        Ok(parts)
+    }
+
+    async fn get_mangas_by_id(&self, ids: &[String]) -> Result<SearchMangaResponse, FetchError> {
+        let ids = ids.iter().fold(String::new(), |mut ids, id| {
+            let _ = write!(ids, "&ids[]={id}");
+            ids
+        });
+        let content_rating = ContentRating::allowed().into_param();
+        let endpoint = format!(
+            "{}/manga?limit={}{ids}&includes[]=cover_art&includes[]=author&includes[]=artist{content_rating}",
+            API_URL_BASE,
+            ids.len().max(MAX_SERIES_PARTS)
+        );
+
+        self.get_json(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
+    }
+
+    async fn get_chapters_aggregate(&self, manga_id: &str, language: Languages) -> Result<AggregateResponse, FetchError> {
+        let endpoint = format!("{}/manga/{}/aggregate?translatedLanguage[]={}", API_URL_BASE, manga_id, language.as_iso_code());
+
+        self.get_json(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
+    }
+
+    async fn get_group_chapters_aggregate(
+        &self,
+        manga_id: &str,
+        language: Languages,
+        group: &str,
+    ) -> Result<AggregateResponse, FetchError> {
+        let endpoint = format!(
+            "{}/manga/{}/aggregate?translatedLanguage[]={}&groups[]={group}",
+            API_URL_BASE,
+            manga_id,
+            language.as_iso_code()
+        );
+
+        self.get_json(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
+    }
+
+    /// A page of the chapters in `language` with a single row for each chapter number, the upload of `group` when it
+    /// has that chapter. The aggregate has the id of one upload of each chapter, the page is then searched by id
+    pub async fn get_smart_chapter_list(
+        &self,
+        manga_id: &str,
+        page: u32,
+        language: Languages,
+        order: ChapterOrder,
+        group: Option<&str>,
+    ) -> Result<ChapterResponse, FetchError> {
+        let all_groups = self.get_chapters_aggregate(manga_id, language).await?;
+        let preferred = match group {
+            Some(group) => Some(self.get_group_chapters_aggregate(manga_id, language, group).await?),
+            None => None,
+        };
+
+        let mut chapters = all_groups.one_upload_per_chapter(preferred.as_ref());
+        if order == ChapterOrder::Descending {
+            chapters.reverse();
+        }
+
+        let total = chapters.len() as i64;
+        let ids: Vec<&str> = chapters
+            .iter()
+            .skip(((page - 1) * ITEMS_PER_PAGE_CHAPTERS) as usize)
+            .take(ITEMS_PER_PAGE_CHAPTERS as usize)
+            .map(|chapter| chapter.id.as_str())
+            .collect();
+
+        if ids.is_empty() {
+            return Ok(ChapterResponse {
+                total,
+                ..Default::default()
+            });
+        }
+
+        let query = ids.iter().fold(String::new(), |mut query, id| {
+            let _ = write!(query, "&ids[]={id}");
+            query
+        });
+        let content_rating = ContentRating::allowed().into_param();
+        let endpoint = format!(
+            "{}/chapter?limit={ITEMS_PER_PAGE_CHAPTERS}{query}&includes[]=scanlation_group&includeExternalUrl=0{content_rating}",
+            API_URL_BASE
+        );
+
+        let mut response: ChapterResponse =
+            self.get_json(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await?;
+
+        response.data.sort_by_key(|chapter| ids.iter().position(|id| *id == chapter.id));
+        response.total = total;
+
+        Ok(response)
+    }
+
+    /// The aggregate only has ids, the title of the chapter is needed by the reader
+    async fn get_aggregate_chapter(
+        &self,
+        manga_id: &str,
+        chapter: &aggregate::Chapter,
+        language: Languages,
+    ) -> Result<Option<ChapterData>, FetchError> {
+        let versions = self.get_other_versions_of_chapter(manga_id, &chapter.chapter, language).await?;
+
+        let mut versions = versions.data.into_iter();
+        let same_version = versions.clone().find(|version| version.id == chapter.id);
+
+        Ok(same_version.or_else(|| versions.next()))
     }
 
-    pub async fn get_manga_statistics(&self, id_manga: &str) -> Result<MangaStatisticsResponse, reqwest::Error> {
+    /// The next chapter in `language` after `chapter_number`, `None` when it is the last one
+    pub async fn get_next_chapter(
+        &self,
+        manga_id: &str,
+        chapter_number: &str,
+        language: Languages,
+    ) -> Result<Option<ChapterData>, FetchError> {
+        let aggregate = self.get_chapters_aggregate(manga_id, language).await?;
+
+        match aggregate.chapter_after(chapter_number) {
+            Some(chapter) => self.get_aggregate_chapter(manga_id, chapter, language).await,
+            None => Ok(None),
+        }
+    }
+
+    /// The chapter in `language` to continue with after having read `read_numbers`, `None` when every
+    /// chapter was read
+    pub async fn get_next_unread_chapter(
+        &self,
+        manga_id: &str,
+        read_numbers: &[String],
+        language: Languages,
+    ) -> Result<Option<ChapterData>, FetchError> {
+        let aggregate = self.get_chapters_aggregate(manga_id, language).await?;
+
+        match aggregate.next_unread_chapter(read_numbers) {
+            Some(chapter) => self.get_aggregate_chapter(manga_id, chapter, language).await,
+            None => Ok(None),
+        }
+    }
+
+    /// The chapter in `language` before `chapter_number`, `None` when it is the first one
+    pub async fn get_previous_chapter(
+        &self,
+        manga_id: &str,
+        chapter_number: &str,
+        language: Languages,
+    ) -> Result<Option<ChapterData>, FetchError> {
+        let aggregate = self.get_chapters_aggregate(manga_id, language).await?;
+
+        match aggregate.chapter_before(chapter_number) {
+            Some(chapter) => self.get_aggregate_chapter(manga_id, chapter, language).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn get_manga_statistics(&self, id_manga: &str) -> Result<MangaStatisticsResponse, FetchError> {
         let endpoint = format!("{}/statistics/manga/{}", API_URL_BASE, id_manga);
 
-        self.client.get(endpoint).send().await?.json().await
+        self.get_json(self.client.get(endpoint)).await
     }
 
-    pub async fn get_popular_mangas(&self) -> Result<SearchMangaResponse, reqwest::Error> {
+    pub async fn get_popular_mangas(&self) -> Result<SearchMangaResponse, FetchError> {
         let current_date = chrono::offset::Local::now().date_naive().checked_sub_months(Months::new(1)).unwrap();
 
+        let content_rating = vec![ContentRating::Safe, ContentRating::Suggestive].into_param();
         let endpoint = format!(
-            "{}/manga?includes[]=cover_art&includes[]=artist&includes[]=author&order[followedCount]=desc&contentRating[]=safe&contentRating[]=suggestive&hasAvailableChapters=true&availableTranslatedLanguage[]={}&createdAtSince={}T00:00:00",
+            "{}/manga?includes[]=cover_art&includes[]=artist&includes[]=author&order[followedCount]=desc{content_rating}&hasAvailableChapters=true&availableTranslatedLanguage[]={}&createdAtSince={}T00:00:00",
             API_URL_BASE,
             Languages::get_preferred_lang().as_iso_code(),
             current_date
         );
 
-        self.client.get(endpoint).send().await?.json().await
+        self.get_json(self.client.get(endpoint)).await
+    }
+
+    /// The mangas of a list of the popular tab, `ITEMS_PER_PAGE_SEARCH` at a time
+    pub async fn get_ranked_mangas(&self, ranking: Ranking, page: u32) -> Result<SearchMangaResponse, FetchError> {
+        let created_since = chrono::offset::Local::now().date_naive().checked_sub_months(Months::new(1)).unwrap();
+        let url = ranked_mangas_url(ranking, page, created_since);
+
+        self.get_json_cached(self.client.get(url).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
     }
 
-    pub async fn get_recently_added(&self) -> Result<SearchMangaResponse, reqwest::Error> {
+    pub async fn get_recently_added(&self) -> Result<SearchMangaResponse, FetchError> {
+        let content_rating = vec![ContentRating::Safe, ContentRating::Suggestive, ContentRating::Erotic].into_param();
         let endpoint = format!(
-            "{}/manga?limit=5&contentRating[]=safe&contentRating[]=suggestive&contentRating[]=erotica&order[createdAt]=desc&includes[]=cover_art&includes[]=artist&includes[]=author&hasAvailableChapters=true&availableTranslatedLanguage[]={}",
+            "{}/manga?limit=5{content_rating}&order[createdAt]=desc&includes[]=cover_art&includes[]=artist&includes[]=author&hasAvailableChapters=true&availableTranslatedLanguage[]={}",
             API_URL_BASE,
             Languages::get_preferred_lang().as_iso_code()
         );
 
-        self.client.get(endpoint).send().await?.json().await
+        self.get_json(self.client.get(endpoint)).await
     }
 
     // Todo! store image in this repo since it may change in the future
-    pub async fn get_mangadex_image_support(&self) -> Result<Bytes, reqwest::Error> {
-        self.client
-            .get("https://mangadex.org/img/namicomi/support-dex-chan-1.png")
-            .send()
-            .await?
-            .bytes()
+    pub async fn get_mangadex_image_support(&self) -> Result<Bytes, FetchError> {
+        self.get_bytes(self.client.get("https://mangadex.org/img/namicomi/support-dex-chan-1.png"))
             .await
     }
 
-    pub async fn get_one_manga(&self, manga_id: &str) -> Result<super::feed::OneMangaResponse, reqwest::Error> {
+    pub async fn get_one_manga(&self, manga_id: &str) -> Result<super::feed::OneMangaResponse, FetchError> {
         let endpoint = format!("{}/manga/{}?includes[]=cover_art&includes[]=author&includes[]=artist", API_URL_BASE, manga_id);
-        self.client.get(endpoint).send().await?.json().await
+        self.get_json_cached(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
     }
 
-    pub async fn get_latest_chapters(&self, manga_id: &str) -> Result<ChapterResponse, reqwest::Error> {
+    pub async fn get_latest_chapters(&self, manga_id: &str) -> Result<ChapterResponse, FetchError> {
+        let content_rating = ContentRating::allowed().into_param();
         let endpoint = format!(
-            "{}/manga/{}/feed?limit={}&includes[]=scanlation_group&offset=0&contentRating[]=safe&contentRating[]=suggestive&contentRating[]=erotica&contentRating[]=pornographic&order[readableAt]=desc",
+            "{}/manga/{}/feed?limit={}&includes[]=scanlation_group&offset=0{content_rating}&order[readableAt]=desc",
             API_URL_BASE, manga_id, ITEMS_PER_PAGE_LATEST_CHAPTERS
         );
-        self.client.get(endpoint).send().await?.json().await
+        self.get_json(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await
     }
 
-    pub async fn get_tags(&self) -> Result<super::tags::TagsResponse, reqwest::Error> {
+    /// The chapters uploaded last in the preferred language across every manga, most recent first, and the
+    /// mangas they belong to
+    /// `id_group` leaves only the chapters uploaded by that scanlation group
+    pub async fn get_latest_updates(&self, id_group: Option<&str>) -> Result<(ChapterResponse, SearchMangaResponse), FetchError> {
+        let endpoint = format!(
+            "{}/chapter?limit={}&offset=0&order[readableAt]=desc&translatedLanguage[]={}&includes[]=scanlation_group&includeExternalUrl=0&includeEmptyPages=0{}{}",
+            API_URL_BASE,
+            ITEMS_PER_PAGE_LATEST_UPDATES,
+            Languages::get_preferred_lang().as_iso_code(),
+            ContentRating::searched_by_default().into_param(),
+            id_group.map(|id_group| format!("&groups[]={id_group}")).unwrap_or_default()
+        );
+        let chapters: ChapterResponse = self.get_json(self.client.get(endpoint).timeout(INTERACTIVE_REQUEST_TIMEOUT)).await?;
+
+        let mut ids: Vec<String> = vec![];
+        for manga_id in chapters.data.iter().filter_map(|chapter| chapter.manga_id()) {
+            if !ids.iter().any(|id| id == manga_id) {
+                ids.push(manga_id.to_string());
+            }
+        }
+
+        if ids.is_empty() {
+            return Ok((chapters, SearchMangaResponse::default()));
+        }
+
+        let mangas = self.get_mangas_by_id(&ids).await?;
+        Ok((chapters, mangas))
+    }
+
+    pub async fn get_tags(&self) -> Result<super::tags::TagsResponse, FetchError> {
         let endpoint = format!("{}/manga/tag", API_URL_BASE);
 
-        self.client.get(endpoint).send().await?.json().await
+        self.get_json_cached(self.client.get(endpoint)).await
+    }
+
+    pub async fn get_authors(&self, name: &str) -> Result<super::authors::AuthorsResponse, FetchError> {
+        let endpoint = format!("{}/author?name={}", API_URL_BASE, encode_query_value(name));
+
+        self.get_json(self.client.get(endpoint)).await
     }
 
-    pub async fn get_authors(&self, name: &str) -> Result<super::authors::AuthorsResponse, reqwest::Error> {
-        let endpoint = format!("{}/author?name={}", API_URL_BASE, name);
+    /// Scanlation groups have the same id and name as authors, so the response is read the same way
+    pub async fn get_scanlation_groups(&self, name: &str) -> Result<super::authors::AuthorsResponse, FetchError> {
+        let endpoint = format!("{}/group?name={}", API_URL_BASE, encode_query_value(name));
 
-        self.client.get(endpoint).send().await?.json().await
+        self.get_json(self.client.get(endpoint)).await
     }
 
-    pub async fn check_status(&self) -> Result<StatusCode, reqwest::Error> {
+    pub async fn check_status(&self) -> Result<StatusCode, FetchError> {
         let endpoint = format!("{}/ping", API_URL_BASE);
 
-        Ok(self.client.get(endpoint).send().await?.status())
+        Ok(self.send(self.client.get(endpoint)).await?.status())
     }
 
-    pub async fn get_all_chapters_for_manga(&self, id: &str, language: Languages) -> Result<ChapterResponse, reqwest::Error> {
+    /// Big series can have thousands of chapters so they are retrieved in batches of
+    /// `ITEMS_PER_PAGE_ALL_CHAPTERS` starting from `offset`, ordered from the first chapter to the last
+    pub async fn get_all_chapters_for_manga(
+        &self,
+        id: &str,
+        language: Languages,
+        offset: u32,
+    ) -> Result<ChapterResponse, FetchError> {
         let language = language.as_iso_code();
 
         let order = "order[volume]=asc&order[chapter]=asc";
 
+        let content_rating = ContentRating::allowed().into_param();
         let endpoint = format!(
-            "{}/manga/{}/feed?limit=300&offset=0&{}&translatedLanguage[]={}&includes[]=scanlation_group&includeExternalUrl=0&contentRating[]=safe&contentRating[]=suggestive&contentRating[]=erotica&contentRating[]=pornographic",
-            API_URL_BASE, id, order, language
+            "{}/manga/{}/feed?limit={ITEMS_PER_PAGE_ALL_CHAPTERS}&offset={}&{}&translatedLanguage[]={}&includes[]=scanlation_group&includeExternalUrl=0{content_rating}",
+            API_URL_BASE, id, offset, order, language
         );
 
-        self.client.get(endpoint).timeout(StdDuration::from_secs(10)).send().await?.json().await
+        self.get_json(self.client.get(endpoint).timeout(StdDuration::from_secs(10))).await
+    }
+}
+
+/// Characters which mean something in a url are percent encoded, so that a title like `Love & War`
+/// is searched as it is
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Pages start from 1
+fn search_mangas_url(search_term: &str, page: u32, filters: Filters) -> String {
+    let offset = page.saturating_sub(1) * ITEMS_PER_PAGE_SEARCH;
+
+    let search_by_title =
+        if search_term.trim().is_empty() { "".to_string() } else { format!("title={}", encode_query_value(search_term)) };
+
+    format!(
+        "{}/manga?{}&includes[]=cover_art&includes[]=author&includes[]=artist&limit=10&offset={}{}&includedTagsMode=AND&excludedTagsMode=OR",
+        API_URL_BASE,
+        search_by_title,
+        offset,
+        filters.into_param(),
+    )
+}
+
+/// Only the new titles are limited to the ones created since `created_since`
+fn ranked_mangas_url(ranking: Ranking, page: u32, created_since: NaiveDate) -> String {
+    let offset = page.saturating_sub(1) * ITEMS_PER_PAGE_SEARCH;
+
+    let created_since = match ranking {
+        Ranking::PopularNew => format!("&createdAtSince={created_since}T00:00:00"),
+        Ranking::MostFollowed | Ranking::TopRated | Ranking::RecentlyAdded => String::new(),
+    };
+
+    format!(
+        "{}/manga?includes[]=cover_art&includes[]=author&includes[]=artist&limit={ITEMS_PER_PAGE_SEARCH}&offset={}{}{}&hasAvailableChapters=true&availableTranslatedLanguage[]={}{}",
+        API_URL_BASE,
+        offset,
+        ranking.into_param(),
+        ContentRating::searched_by_default().into_param(),
+        Languages::get_preferred_lang().as_iso_code(),
+        created_since,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt::Debug;
+
+    use proptest::prelude::*;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::backend::filter::{
+        Artist, Author, ContentRating, MagazineDemographic, OriginalLanguage, PublicationStatus, SortBy,
+    };
+    use crate::global::PREFERRED_LANGUAGE;
+
+    fn search_term() -> impl Strategy<Value = String> {
+        let characters: Vec<char> = "aZ09 -_.~&#?=%+/\\[]{}\"'<>|^`;:,@!$*()\t日本語🙂".chars().collect();
+        prop::collection::vec(prop::sample::select(characters), 0..30).prop_map(|characters| characters.into_iter().collect())
+    }
+
+    fn subset<T: Clone + Debug + 'static>(items: Vec<T>) -> impl Strategy<Value = Vec<T>> {
+        let len = items.len();
+        prop::sample::subsequence(items, 0..=len)
+    }
+
+    fn filters() -> impl Strategy<Value = Filters> {
+        (
+            subset(ContentRating::iter().collect()),
+            subset(PublicationStatus::iter().collect()),
+            prop::sample::select(SortBy::iter().collect::<Vec<_>>()),
+            subset(Languages::iter().collect()),
+            subset(MagazineDemographic::iter().collect()),
+            subset(vec![Author::new("a96676e5".to_string()), Author::new("32d76d19".to_string())]),
+            subset(vec![Artist::new("8a05".to_string())]),
+            subset(OriginalLanguage::iter().collect()),
+            prop::option::of(Just(2003)),
+        )
+            .prop_map(
+                |(ratings, statuses, sort_by, languages, demographics, authors, artists, original_languages, year)| {
+                    let mut filters = Filters::default();
+
+                    filters.set_content_rating(ratings);
+                    filters.set_publication_status(statuses);
+                    filters.set_sort_by(sort_by);
+                    filters.set_languages(languages);
+                    filters.set_magazine_demographic(demographics);
+                    filters.set_authors(authors);
+                    filters.set_artists(artists);
+                    filters.set_original_languages(original_languages);
+                    filters.set_year(year);
+
+                    filters
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn any_search_makes_a_valid_url(search_term in search_term(), page in 0..100u32, filters in filters()) {
+            let _ = PREFERRED_LANGUAGE.set(Languages::default());
+
+            let url = search_mangas_url(&search_term, page, filters);
+            let url = reqwest::Url::parse(&url).unwrap_or_else(|e| panic!("{url} is not valid: {e}"));
+
+            let titles: Vec<String> = url
+                .query_pairs()
+                .filter(|(key, _)| key == "title")
+                .map(|(_, value)| value.into_owned())
+                .collect();
+            let expected_titles = if search_term.trim().is_empty() { vec![] } else { vec![search_term.clone()] };
+            prop_assert_eq!(expected_titles, titles, "{}", url);
+
+            let offsets: Vec<String> = url
+                .query_pairs()
+                .filter(|(key, _)| key == "offset")
+                .map(|(_, value)| value.into_owned())
+                .collect();
+            prop_assert_eq!(vec![(page.saturating_sub(1) * ITEMS_PER_PAGE_SEARCH).to_string()], offsets);
+
+            prop_assert!(url.query_pairs().all(|(key, _)| !key.is_empty()), "{}", url);
+        }
+    }
+
+    #[test]
+    fn only_new_titles_are_limited_by_creation_date() {
+        let _ = PREFERRED_LANGUAGE.set(Languages::default());
+        let created_since = NaiveDate::from_ymd_opt(2024, 9, 16).unwrap();
+
+        let new_titles = ranked_mangas_url(Ranking::PopularNew, 1, created_since);
+        assert!(new_titles.contains("&order[followedCount]=desc") && new_titles.contains("&createdAtSince=2024-09-16T00:00:00"));
+
+        let top_rated = ranked_mangas_url(Ranking::TopRated, 3, created_since);
+        assert!(top_rated.contains("&order[rating]=desc&") && top_rated.contains("offset=20"));
+        assert!(!top_rated.contains("createdAtSince"));
+
+        let recently_added = ranked_mangas_url(Ranking::RecentlyAdded, 1, created_since);
+        assert!(recently_added.contains("&order[createdAt]=desc&") && !recently_added.contains("createdAtSince"));
+    }
+
+    #[test]
+    fn circuit_breaker_trips_after_consecutive_failures() {
+        let breaker = CircuitBreaker::default();
+
+        for _ in 0..CIRCUIT_BREAKER_FAILURE_THRESHOLD - 1 {
+            breaker.record_failure();
+        }
+
+        assert!(breaker.remaining_cooldown().is_none());
+
+        breaker.record_failure();
+
+        assert!(breaker.remaining_cooldown().is_some());
+    }
+
+    #[test]
+    fn circuit_breaker_resets_on_success() {
+        let breaker = CircuitBreaker::default();
+
+        for _ in 0..CIRCUIT_BREAKER_FAILURE_THRESHOLD - 1 {
+            breaker.record_failure();
+        }
+
+        breaker.record_success();
+        breaker.record_failure();
+
+        assert!(breaker.remaining_cooldown().is_none());
     }
 }
@@ -1,7 +1,10 @@
 use std::fmt::{Debug, Write};
 
-use strum::{Display, EnumIter, IntoEnumIterator};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, IntoEnumIterator, ParseError};
 
+use super::parental_lock::ParentalLock;
+use crate::config::CONFIG;
 use crate::global::PREFERRED_LANGUAGE;
 use crate::view::widgets::filter_widget::state::{FilterListItem, TagListItem, TagListItemState};
 
@@ -9,18 +12,65 @@ pub trait IntoParam: Debug {
     fn into_param(self) -> String;
 }
 
-#[derive(Display, Clone, Debug)]
+/// Ordered from the least to the most explicit
+#[derive(Display, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ContentRating {
     #[strum(to_string = "safe")]
     Safe,
     #[strum(to_string = "suggestive")]
     Suggestive,
     #[strum(to_string = "erotica")]
+    #[serde(rename = "erotica")]
     Erotic,
     #[strum(to_string = "pornographic")]
     Pornographic,
 }
 
+impl ContentRating {
+    pub fn is_adult(self) -> bool {
+        matches!(self, Self::Erotic | Self::Pornographic)
+    }
+
+    /// The ones set in the config file, safe and suggestive when it's not loaded
+    pub fn searched_by_default() -> Vec<Self> {
+        match CONFIG.get() {
+            Some(config) => config.content_ratings.clone(),
+            None => vec![Self::Safe, Self::Suggestive],
+        }
+    }
+
+    /// The most explicit rating the profile can see, nothing above it is ever searched. The parental lock
+    /// of the profile lowers it further
+    pub fn ceiling() -> Self {
+        let ceiling = CONFIG.get().map_or(Self::Pornographic, |config| config.max_content_rating);
+        match ParentalLock::get() {
+            Some(lock) => ceiling.min(lock.max_content_rating),
+            None => ceiling,
+        }
+    }
+
+    /// Mangas rated above the ceiling are not shown even when they are opened from a link or the library
+    pub fn is_allowed(rating: &str) -> bool {
+        Self::from(rating) <= Self::ceiling()
+    }
+
+    /// Every rating up to the ceiling, for the requests which look up mangas of any rating
+    pub fn allowed() -> Vec<Self> {
+        Self::iter().filter(|rating| *rating <= Self::ceiling()).collect()
+    }
+}
+
+fn content_rating_param(ratings: Vec<ContentRating>, ceiling: ContentRating) -> String {
+    let ratings: Vec<ContentRating> = ratings.into_iter().filter(|rating| *rating <= ceiling).collect();
+
+    if ratings.is_empty() {
+        return format!("&contentRating[]={}", ContentRating::Safe);
+    }
+
+    ratings.iter().map(|rating| format!("&contentRating[]={rating}")).collect()
+}
+
 impl From<&str> for ContentRating {
     fn from(value: &str) -> Self {
         match value {
@@ -64,6 +114,37 @@ pub enum SortBy {
     YearAscending,
 }
 
+/// The lists of the popular tab, like the ones on the homepage of mangadex
+#[derive(Display, Clone, Copy, EnumIter, PartialEq, Eq, Default, Debug)]
+pub enum Ranking {
+    #[strum(to_string = "Popular new titles")]
+    #[default]
+    PopularNew,
+    #[strum(to_string = "Most followed")]
+    MostFollowed,
+    #[strum(to_string = "Top rated")]
+    TopRated,
+    /// The series added to mangadex the latest, to find new ones without searching
+    #[strum(to_string = "Recently added")]
+    RecentlyAdded,
+}
+
+impl Ranking {
+    pub fn next(self) -> Self {
+        Self::iter().cycle().skip_while(|ranking| *ranking != self).nth(1).unwrap()
+    }
+}
+
+impl IntoParam for Ranking {
+    fn into_param(self) -> String {
+        match self {
+            Self::PopularNew | Self::MostFollowed => SortBy::MostFollows.into_param(),
+            Self::TopRated => SortBy::HighestRating.into_param(),
+            Self::RecentlyAdded => SortBy::RecentlyAdded.into_param(),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum TagSelection {
     Included,
@@ -125,18 +206,9 @@ impl IntoParam for Tags {
 }
 
 impl IntoParam for Vec<ContentRating> {
+    /// The ratings above the ceiling of the profile are left out
     fn into_param(self) -> String {
-        let mut result = String::new();
-
-        if self.is_empty() {
-            return format!("&contentRating[]={}", ContentRating::Safe);
-        }
-
-        for cont in self {
-            result.push_str(format!("&contentRating[]={}", cont).as_str());
-        }
-
-        result
+        content_rating_param(self, ContentRating::ceiling())
     }
 }
 
@@ -217,6 +289,19 @@ impl Artist {
 #[derive(Default, Clone, Debug)]
 pub struct User<T: Clone + Default>(pub Vec<T>);
 
+/// Mangadex only searches the mangas of one scanlation group at a time
+#[derive(Default, Clone, Debug)]
+pub struct ScanlationGroup(pub Option<String>);
+
+impl IntoParam for ScanlationGroup {
+    fn into_param(self) -> String {
+        match self.0 {
+            Some(id_group) => format!("&group={}", id_group),
+            None => String::new(),
+        }
+    }
+}
+
 impl IntoParam for User<Author> {
     fn into_param(self) -> String {
         if self.0.is_empty() {
@@ -441,6 +526,75 @@ impl IntoParam for Vec<PublicationStatus> {
     }
 }
 
+/// The language a manga was first published in, not the ones it was translated to
+#[derive(Clone, Copy, Display, EnumIter, PartialEq, Eq, Debug)]
+pub enum OriginalLanguage {
+    #[strum(to_string = "Japanese (manga)")]
+    Japanese,
+    #[strum(to_string = "Korean (manhwa)")]
+    Korean,
+    #[strum(to_string = "Chinese (manhua)")]
+    Chinese,
+}
+
+impl OriginalLanguage {
+    pub fn as_iso_code(self) -> &'static str {
+        match self {
+            Self::Japanese => "ja",
+            Self::Korean => "ko",
+            Self::Chinese => "zh",
+        }
+    }
+}
+
+impl TryFrom<&str> for OriginalLanguage {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        OriginalLanguage::iter()
+            .find(|language| language.to_string() == value)
+            .ok_or(ParseError::VariantNotFound)
+    }
+}
+
+impl IntoParam for Vec<OriginalLanguage> {
+    fn into_param(self) -> String {
+        self.into_iter().fold(String::new(), |mut languages, language| {
+            let _ = write!(languages, "&originalLanguage[]={}", language.as_iso_code());
+            languages
+        })
+    }
+}
+
+/// The year a manga started being published, `None` for any year
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PublicationYear(pub Option<u32>);
+
+impl IntoParam for PublicationYear {
+    fn into_param(self) -> String {
+        match self.0 {
+            Some(year) => format!("&year={}", year),
+            None => String::new(),
+        }
+    }
+}
+
+/// Leaves out the mangas without chapters which can be read on MangaDex in the languages chosen
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AvailableChapters(pub bool);
+
+impl Default for AvailableChapters {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+impl IntoParam for AvailableChapters {
+    fn into_param(self) -> String {
+        if self.0 { "&hasAvailableChapters=true".to_string() } else { String::new() }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Filters {
     pub content_rating: Vec<ContentRating>,
@@ -450,21 +604,32 @@ pub struct Filters {
     pub magazine_demographic: Vec<MagazineDemographic>,
     pub authors: User<Author>,
     pub artists: User<Artist>,
+    pub group: ScanlationGroup,
     pub languages: Vec<Languages>,
+    pub original_languages: Vec<OriginalLanguage>,
+    pub year: PublicationYear,
+    pub available_chapters: AvailableChapters,
 }
 
 impl IntoParam for Filters {
     fn into_param(self) -> String {
+        // The languages are the ones chapters have to be available in, so they are searched only along with it
+        let languages = if self.available_chapters.0 { self.languages.into_param() } else { String::new() };
+
         format!(
-            "{}{}{}{}{}{}{}{}",
+            "{}{}{}{}{}{}{}{}{}{}{}{}",
             self.authors.into_param(),
             self.artists.into_param(),
+            self.group.into_param(),
             self.publication_status.into_param(),
-            self.languages.into_param(),
+            languages,
             self.tags.into_param(),
             self.magazine_demographic.into_param(),
+            self.original_languages.into_param(),
+            self.year.into_param(),
             self.content_rating.into_param(),
             self.sort_by.into_param(),
+            self.available_chapters.into_param(),
         )
     }
 }
@@ -472,14 +637,18 @@ impl IntoParam for Filters {
 impl Default for Filters {
     fn default() -> Self {
         Self {
-            content_rating: vec![ContentRating::Safe, ContentRating::Suggestive],
+            content_rating: ContentRating::searched_by_default(),
             publication_status: vec![],
             sort_by: SortBy::default(),
             tags: Tags(vec![]),
             magazine_demographic: vec![],
             authors: User::<Author>::default(),
             artists: User::<Artist>::default(),
+            group: ScanlationGroup::default(),
             languages: vec![*Languages::get_preferred_lang()],
+            original_languages: vec![],
+            year: PublicationYear::default(),
+            available_chapters: AvailableChapters::default(),
         }
     }
 }
@@ -505,6 +674,14 @@ impl Filters {
         self.languages = languages;
     }
 
+    pub fn set_original_languages(&mut self, original_languages: Vec<OriginalLanguage>) {
+        self.original_languages = original_languages;
+    }
+
+    pub fn set_year(&mut self, year: Option<u32>) {
+        self.year = PublicationYear(year);
+    }
+
     pub fn set_magazine_demographic(&mut self, magazine_demographics: Vec<MagazineDemographic>) {
         self.magazine_demographic = magazine_demographics;
     }
@@ -524,6 +701,14 @@ impl Filters {
     pub fn reset_artist(&mut self) {
         self.artists.0 = vec![];
     }
+
+    pub fn toggle_available_chapters(&mut self) {
+        self.available_chapters = AvailableChapters(!self.available_chapters.0);
+    }
+
+    pub fn set_group(&mut self, id_group: Option<String>) {
+        self.group = ScanlationGroup(id_group);
+    }
 }
 
 /// This test may be changed depending on the Mangadex Api
@@ -544,6 +729,17 @@ mod test {
         assert_eq!(conversion, Languages::Spanish);
     }
 
+    #[test]
+    fn content_ratings_above_the_ceiling_are_not_searched() {
+        let content_rating = vec![ContentRating::Safe, ContentRating::Pornographic, ContentRating::Suggestive];
+
+        assert_eq!(
+            "&contentRating[]=safe&contentRating[]=suggestive",
+            content_rating_param(content_rating, ContentRating::Suggestive)
+        );
+        assert_eq!("&contentRating[]=safe", content_rating_param(vec![ContentRating::Erotic], ContentRating::Safe));
+    }
+
     #[test]
     fn filter_by_content_rating_works() {
         let content_rating =
@@ -606,6 +802,12 @@ mod test {
         assert_eq!("&artists[]=id_artist1&artists[]=id_artist2", filter_artist.into_param());
     }
 
+    #[test]
+    fn filter_by_scanlation_group_works() {
+        assert_eq!("&group=id_group", ScanlationGroup(Some("id_group".to_string())).into_param());
+        assert_eq!("", ScanlationGroup::default().into_param());
+    }
+
     #[test]
     fn filter_by_author_works() {
         let sample_authors: Vec<Author> = vec![Author::new("id_author1".to_string()), Author::new("id_author2".to_string())];
@@ -637,6 +839,25 @@ mod test {
         assert_eq!("&status[]=ongoing&status[]=hiatus&status[]=completed&status[]=cancelled", publication_status.into_param());
     }
 
+    #[test]
+    fn filter_by_original_language_works() {
+        let original_languages = vec![OriginalLanguage::Japanese, OriginalLanguage::Korean, OriginalLanguage::Chinese];
+
+        assert_eq!(
+            "&originalLanguage[]=ja&originalLanguage[]=ko&originalLanguage[]=zh",
+            original_languages.into_param()
+        );
+
+        assert_eq!(Ok(OriginalLanguage::Korean), OriginalLanguage::try_from("Korean (manhwa)"));
+        assert_eq!(Err(ParseError::VariantNotFound), OriginalLanguage::try_from("Klingon"));
+    }
+
+    #[test]
+    fn filter_by_year_works() {
+        assert_eq!("&year=2019", PublicationYear(Some(2019)).into_param());
+        assert_eq!("", PublicationYear(None).into_param());
+    }
+
     #[test]
     fn filter_by_tags_works() {
         let tags = Tags::new(vec![
@@ -658,7 +879,7 @@ mod test {
         let filters = Filters::default();
 
         assert_eq!(
-            "&availableTranslatedLanguage[]=en&contentRating[]=safe&contentRating[]=suggestive&order[latestUploadedChapter]=desc",
+            "&availableTranslatedLanguage[]=en&contentRating[]=safe&contentRating[]=suggestive&order[latestUploadedChapter]=desc&hasAvailableChapters=true",
             filters.into_param()
         );
 
@@ -670,9 +891,18 @@ mod test {
 
         filters.set_languages(vec![Languages::French, Languages::Spanish]);
 
+        filters.set_original_languages(vec![OriginalLanguage::Korean]);
+
+        filters.set_year(Some(2020));
+
         assert_eq!(
-            "&authors[]=id_1&authors[]=id_2&availableTranslatedLanguage[]=fr&availableTranslatedLanguage[]=es&includedTags[]=id_1&contentRating[]=safe&contentRating[]=suggestive&order[latestUploadedChapter]=desc",
-            filters.into_param()
+            "&authors[]=id_1&authors[]=id_2&availableTranslatedLanguage[]=fr&availableTranslatedLanguage[]=es&includedTags[]=id_1&originalLanguage[]=ko&year=2020&contentRating[]=safe&contentRating[]=suggestive&order[latestUploadedChapter]=desc&hasAvailableChapters=true",
+            filters.clone().into_param()
         );
+
+        filters.toggle_available_chapters();
+
+        let param = filters.into_param();
+        assert!(!param.contains("availableTranslatedLanguage[]") && !param.contains("hasAvailableChapters"));
     }
 }
@@ -0,0 +1,137 @@
+use std::cell::RefCell;
+use std::io::Write;
+
+use crossterm::cursor::MoveTo;
+use crossterm::queue;
+use crossterm::style::{Attribute, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Modifier;
+use ratatui::text::Span;
+
+static MANGADEX_URL: &str = "https://mangadex.org";
+
+/// A part of the screen which opens `url` when it's ctrl + clicked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink {
+    pub area: Rect,
+    pub url: String,
+}
+
+thread_local! {
+    /// Filled by the widgets while the frame is rendered and emptied once it's drawn
+    static HYPERLINKS: RefCell<Vec<Hyperlink>> = const { RefCell::new(vec![]) };
+}
+
+pub fn manga_url(manga_id: &str) -> String {
+    format!("{MANGADEX_URL}/title/{manga_id}")
+}
+
+pub fn tag_url(tag_id: &str) -> String {
+    format!("{MANGADEX_URL}/tag/{tag_id}")
+}
+
+pub fn add_hyperlink(area: Rect, url: String) {
+    if !area.is_empty() {
+        HYPERLINKS.with_borrow_mut(|links| links.push(Hyperlink { area, url }));
+    }
+}
+
+pub fn take_hyperlinks() -> Vec<Hyperlink> {
+    HYPERLINKS.with_borrow_mut(std::mem::take)
+}
+
+/// The escape sequences of OSC 8 hyperlinks can't be stored in the cells of the buffer because their width would be
+/// counted as text, so once the frame is drawn the cells of each link are printed again wrapped in them
+pub fn write_hyperlinks(links: &[Hyperlink], buffer: &Buffer, writer: &mut impl Write) -> std::io::Result<()> {
+    for link in links {
+        let area = link.area.intersection(buffer.area);
+
+        for y in area.top()..area.bottom() {
+            queue!(writer, MoveTo(area.x, y), Print(format!("\x1B]8;;{}\x1B\\", link.url)))?;
+
+            let mut x = area.left();
+            while x < area.right() {
+                let cell = buffer.get(x, y);
+
+                queue!(
+                    writer,
+                    SetAttribute(Attribute::Reset),
+                    SetForegroundColor(cell.fg.into()),
+                    SetBackgroundColor(cell.bg.into())
+                )?;
+                for attribute in attributes(cell.modifier) {
+                    queue!(writer, SetAttribute(attribute))?;
+                }
+                queue!(writer, Print(cell.symbol()))?;
+
+                x += Span::raw(cell.symbol()).width().max(1) as u16;
+            }
+
+            queue!(writer, Print("\x1B]8;;\x1B\\"))?;
+        }
+    }
+
+    queue!(writer, ResetColor, SetAttribute(Attribute::Reset))?;
+    writer.flush()
+}
+
+fn attributes(modifier: Modifier) -> Vec<Attribute> {
+    [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::DIM, Attribute::Dim),
+        (Modifier::ITALIC, Attribute::Italic),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::REVERSED, Attribute::Reverse),
+        (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| modifier.contains(*flag))
+    .map(|(_, attribute)| attribute)
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::style::{Style, Stylize};
+
+    use super::*;
+
+    #[test]
+    fn links_are_taken_once() {
+        add_hyperlink(Rect::new(0, 0, 5, 1), manga_url("some-id"));
+        add_hyperlink(Rect::new(0, 1, 0, 1), tag_url("empty"));
+
+        assert_eq!(take_hyperlinks(), vec![Hyperlink {
+            area: Rect::new(0, 0, 5, 1),
+            url: "https://mangadex.org/title/some-id".to_string()
+        }]);
+        assert!(take_hyperlinks().is_empty());
+    }
+
+    #[test]
+    fn the_text_of_a_link_is_wrapped_in_osc_8() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 1));
+        buffer.set_string(0, 0, "Dandadan", Style::new().bold());
+
+        let mut output: Vec<u8> = vec![];
+        write_hyperlinks(
+            &[Hyperlink {
+                area: Rect::new(2, 0, 3, 1),
+                url: tag_url("action"),
+            }],
+            &buffer,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+
+        let link = "\x1B]8;;https://mangadex.org/tag/action\x1B\\";
+        let opened = output.find(link).unwrap() + link.len();
+        let closed = output.rfind("\x1B]8;;\x1B\\").unwrap();
+        let text: String = output[opened..closed].chars().filter(|c| "Dandadan".contains(*c)).collect();
+
+        assert_eq!(text, "nda");
+    }
+}
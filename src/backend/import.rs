@@ -0,0 +1,138 @@
+use clap::ValueEnum;
+
+use super::database::{save_to_library, MangaHistoryType, MangaPlanToReadSave};
+use super::error_log::{write_to_error_log, ErrorType};
+use super::fetch::MangadexClient;
+use crate::utils::from_manga_response;
+
+/// Where the imported mangas are added in the library
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ImportCategory {
+    Reading,
+    #[default]
+    PlanToRead,
+}
+
+impl From<ImportCategory> for MangaHistoryType {
+    fn from(value: ImportCategory) -> Self {
+        match value {
+            ImportCategory::Reading => MangaHistoryType::ReadingHistory,
+            ImportCategory::PlanToRead => MangaHistoryType::PlanToRead,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Titles of the mangas added to the library
+    pub imported: Vec<String>,
+    /// Links or ids which could not be resolved to a manga
+    pub failed: Vec<String>,
+}
+
+fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Accepts either the id of a manga or a link like `https://mangadex.org/title/{id}/some-title`
+pub fn parse_manga_id(link: &str) -> Option<String> {
+    let link = link.trim().trim_end_matches('/');
+
+    if is_uuid(link) {
+        return Some(link.to_lowercase());
+    }
+
+    let mut segments = link.split('/').skip_while(|segment| *segment != "title" && *segment != "manga");
+    segments.next()?;
+
+    segments.next().filter(|id| is_uuid(id)).map(|id| id.to_lowercase())
+}
+
+/// Links can be separated by new lines, spaces or commas, lines starting with `#` are ignored
+pub fn parse_manga_ids(text: &str) -> (Vec<String>, Vec<String>) {
+    let mut ids: Vec<String> = vec![];
+    let mut invalid: Vec<String> = vec![];
+
+    let links = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|link| !link.is_empty());
+
+    for link in links {
+        match parse_manga_id(link) {
+            Some(id) => {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            },
+            None => invalid.push(link.to_string()),
+        }
+    }
+
+    (ids, invalid)
+}
+
+/// Fetches the title and cover of each manga and adds it to the library
+pub async fn import_mangas(text: &str, category: ImportCategory) -> ImportSummary {
+    let (ids, invalid) = parse_manga_ids(text);
+
+    let mut summary = ImportSummary {
+        failed: invalid,
+        ..Default::default()
+    };
+
+    for id in ids {
+        let manga = match MangadexClient::global().get_one_manga(&id).await {
+            Ok(response) => from_manga_response(response.data),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                summary.failed.push(id);
+                continue;
+            },
+        };
+
+        let save_operation = save_to_library(
+            MangaPlanToReadSave {
+                id: &manga.id,
+                title: &manga.title,
+                img_url: manga.img_url.as_deref(),
+            },
+            category.into(),
+        );
+
+        match save_operation {
+            Ok(()) => summary.imported.push(manga.title),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                summary.failed.push(id);
+            },
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn manga_ids_are_parsed_from_links_and_ids() {
+        let text = "# my list\n\
+                    https://mangadex.org/title/a96676e5-8ae2-425e-b549-7f15dd34a6d8/komi-san-wa-komyushou-desu\n\
+                    32d76d19-8a05-4db0-9fc2-e0b0648fe9d0, https://mangadex.org/title/32D76D19-8A05-4DB0-9FC2-E0B0648FE9D0/\n\
+                    https://mangadex.org/chapter/not-a-manga";
+
+        let (ids, invalid) = parse_manga_ids(text);
+
+        assert_eq!(ids, vec!["a96676e5-8ae2-425e-b549-7f15dd34a6d8", "32d76d19-8a05-4db0-9fc2-e0b0648fe9d0"]);
+        assert_eq!(invalid, vec!["https://mangadex.org/chapter/not-a-manga"]);
+    }
+}
@@ -0,0 +1,147 @@
+//! Text recognition of the pages with the `tesseract` command, so dialogue can be copied, translated
+//! or read by other tools. Only built with the `ocr` feature
+use std::error::Error;
+use std::process::Stdio;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::filter::Languages;
+
+/// The name of the trained data tesseract uses for each language, the language pack has to be
+/// installed along with tesseract
+pub fn tesseract_language(language: Languages) -> &'static str {
+    match language {
+        Languages::French => "fra",
+        Languages::Spanish | Languages::SpanishLa => "spa",
+        Languages::Italian => "ita",
+        Languages::Japanese => "jpn",
+        Languages::Korean => "kor",
+        Languages::BrazilianPortuguese | Languages::Portuguese => "por",
+        Languages::TraditionalChinese => "chi_tra",
+        Languages::SimplifiedChinese => "chi_sim",
+        Languages::Russian => "rus",
+        Languages::German => "deu",
+        Languages::Burmese => "mya",
+        Languages::Arabic => "ara",
+        Languages::Bulgarian => "bul",
+        Languages::Vietnamese => "vie",
+        Languages::Croatian => "hrv",
+        Languages::Hungarian => "hun",
+        Languages::Dutch => "nld",
+        Languages::Mongolian => "mon",
+        Languages::Turkish => "tur",
+        Languages::Ukrainian => "ukr",
+        Languages::Thai => "tha",
+        Languages::Catalan => "cat",
+        Languages::Indonesian => "ind",
+        Languages::Filipino => "fil",
+        Languages::Hindi => "hin",
+        Languages::Romanian => "ron",
+        Languages::Hebrew => "heb",
+        Languages::Polish => "pol",
+        Languages::Persian => "fas",
+        Languages::English | Languages::Unkown => "eng",
+    }
+}
+
+/// Runs `tesseract` on the bytes of a page, which is read from stdin so the page doesn't have to be
+/// written to disk
+pub async fn extract_text(page: &[u8], language: Languages) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let mut tesseract = Command::new("tesseract")
+        .args(["stdin", "stdout", "-l", tesseract_language(language)])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("could not run tesseract, is it installed? {e}"))?;
+
+    // tesseract reads the whole image before writing anything, so stdin can be written and closed first
+    let mut stdin = tesseract.stdin.take().ok_or("could not write to tesseract")?;
+    stdin.write_all(page).await?;
+    drop(stdin);
+
+    let output = tesseract.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(format!("tesseract failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+
+    Ok(clean_text(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Runs the `translation_command` of the config with the text written to its stdin, what it prints is
+/// the translation
+pub async fn translate(text: &str, command: Option<&str>) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let command = shell_words::split(command.ok_or("translation_command is not set in the config")?)?;
+    let (program, args) = command.split_first().ok_or("translation_command is empty")?;
+
+    let mut translator = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let mut stdin = translator.stdin.take().ok_or("could not write to the translation command")?;
+    stdin.write_all(text.as_bytes()).await?;
+    drop(stdin);
+
+    let output = translator.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(format!("the translation command failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// tesseract separates blocks of text with blank lines and ends with a form feed, speech bubbles read
+/// better with a single blank line between them
+fn clean_text(text: &str) -> String {
+    text.split("\n\n")
+        .map(|block| block.trim_matches(|c: char| c.is_whitespace() || c == '\u{c}'))
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<&str>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod test {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn every_language_has_trained_data() {
+        for language in Languages::iter() {
+            assert_eq!(3, tesseract_language(language).split('_').next().unwrap().len(), "{language}");
+        }
+
+        assert_eq!("jpn", tesseract_language(Languages::Japanese));
+        assert_eq!("eng", tesseract_language(Languages::Unkown));
+    }
+
+    #[tokio::test]
+    async fn text_is_translated_by_the_command_from_stdin() {
+        let translation = translate("HELLO\n\nBYE", Some("tr A-Z a-z")).await.unwrap();
+
+        assert_eq!("hello\n\nbye", translation);
+        assert!(translate("HELLO", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn quoted_arguments_of_the_translation_command_are_kept_whole() {
+        let translation = translate("HELLO", Some("sed 's/HELLO/good bye/'")).await.unwrap();
+
+        assert_eq!("good bye", translation);
+        assert!(translate("HELLO", Some("sed 's/HELLO")).await.is_err());
+    }
+
+    #[test]
+    fn blank_blocks_are_removed_from_the_text() {
+        let text = "WHAT ARE YOU\nDOING HERE?\n\n\n\n  \n\nI LIVE HERE.\n\n\u{c}";
+
+        assert_eq!("WHAT ARE YOU\nDOING HERE?\n\nI LIVE HERE.", clean_text(text));
+    }
+}
@@ -0,0 +1,117 @@
+//! A PIN a profile can be locked with, so the content ratings it can see and its settings can't be changed
+//! without it. It's kept in the database of the profile instead of the config file so editing the config
+//! file is not enough to get past it
+use std::io::Write;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use once_cell::sync::OnceCell;
+
+use super::filter::ContentRating;
+use super::update::sha256_hex;
+
+/// Read from the database of the profile when the data directory is built
+pub static PARENTAL_LOCK: OnceCell<ParentalLock> = OnceCell::new();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParentalLock {
+    pub pin_hash: String,
+    /// Random for every lock so the hash of a PIN can't be looked up, empty for the locks made before it was added
+    pub salt: String,
+    /// The most explicit content rating the profile can see, whatever its config file says
+    pub max_content_rating: ContentRating,
+}
+
+impl ParentalLock {
+    pub fn new(pin: &str, max_content_rating: ContentRating) -> Self {
+        let salt: String = rand::random::<[u8; 16]>().iter().map(|byte| format!("{byte:02x}")).collect();
+
+        Self {
+            pin_hash: hash_pin(&salt, pin),
+            salt,
+            max_content_rating,
+        }
+    }
+
+    /// The lock of the profile being used, if it has one
+    pub fn get() -> Option<&'static Self> {
+        PARENTAL_LOCK.get()
+    }
+
+    pub fn matches(&self, pin: &str) -> bool {
+        self.pin_hash == hash_pin(&self.salt, pin.trim())
+    }
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    sha256_hex(format!("{salt}manga-tui-parental-lock:{pin}").as_bytes())
+}
+
+pub fn is_valid_pin(pin: &str) -> bool {
+    (4..=8).contains(&pin.len()) && pin.chars().all(|character| character.is_ascii_digit())
+}
+
+/// Asks for a PIN on the terminal, for `manga-tui lock` and `manga-tui unlock`. The terminal is put in raw mode
+/// while it's typed so it isn't shown
+pub fn read_pin(prompt: &str) -> std::io::Result<String> {
+    print!("{prompt}");
+    std::io::stdout().flush()?;
+
+    terminal::enable_raw_mode()?;
+    let pin = read_hidden_line();
+    terminal::disable_raw_mode()?;
+    println!();
+
+    Ok(pin?.trim().to_string())
+}
+
+fn read_hidden_line() -> std::io::Result<String> {
+    let mut line = String::new();
+
+    loop {
+        let Event::Key(key_event) = event::read()? else {
+            continue;
+        };
+
+        if key_event.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key_event.code {
+            KeyCode::Enter => return Ok(line),
+            KeyCode::Backspace => {
+                line.pop();
+            },
+            KeyCode::Esc => return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "the PIN was not typed")),
+            KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "the PIN was not typed"));
+            },
+            KeyCode::Char(character) => line.push(character),
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_the_pin_the_profile_was_locked_with_matches() {
+        let lock = ParentalLock::new("1234", ContentRating::Safe);
+
+        assert!(lock.matches("1234"));
+        assert!(lock.matches("1234\n"));
+        assert!(!lock.matches("4321"));
+        assert!(!lock.pin_hash.contains("1234"));
+
+        let same_pin = ParentalLock::new("1234", ContentRating::Safe);
+        assert!(same_pin.matches("1234"));
+        assert_ne!(lock.salt, same_pin.salt);
+        assert_ne!(lock.pin_hash, same_pin.pin_hash);
+
+        assert!(is_valid_pin("0000"));
+        assert!(!is_valid_pin("123"));
+        assert!(!is_valid_pin("12a4"));
+    }
+}
@@ -0,0 +1,144 @@
+use std::cmp::Ordering;
+
+use super::{Data, MangaSearchRelationship};
+use crate::utils::from_manga_response;
+
+/// Series with more parts than this are cut short, each part found is one more request to mangadex
+pub static MAX_SERIES_PARTS: usize = 10;
+
+/// Only sequels and prequels are parts of the same story, spin offs and adaptations are read on their own
+pub fn is_part_of_series(relationship: &MangaSearchRelationship) -> bool {
+    relationship.type_field == "manga" && matches!(relationship.related.as_deref(), Some("sequel" | "prequel"))
+}
+
+/// The ids of the parts related to `manga` which are not in `seen`, they are added to it
+pub fn unseen_parts(manga: &Data, seen: &mut Vec<String>) -> Vec<String> {
+    let mut ids = vec![];
+    for relationship in manga.relationships.iter().filter(|relationship| is_part_of_series(relationship)) {
+        if !seen.contains(&relationship.id) {
+            seen.push(relationship.id.clone());
+            ids.push(relationship.id.clone());
+        }
+    }
+    ids
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SeriesPart {
+    pub id: String,
+    pub title: String,
+    pub year: Option<u32>,
+    pub created_at: String,
+    pub chapters_read: usize,
+    pub total_chapters: usize,
+}
+
+impl SeriesPart {
+    pub fn new(manga: Data) -> Self {
+        let year = manga.attributes.year;
+        let manga = from_manga_response(manga);
+        Self {
+            id: manga.id,
+            title: manga.title,
+            year,
+            created_at: manga.created_at,
+            chapters_read: 0,
+            total_chapters: 0,
+        }
+    }
+
+    /// Chapters read in other languages can outnumber the ones in the language searched
+    pub fn is_finished(&self) -> bool {
+        self.total_chapters > 0 && self.chapters_read >= self.total_chapters
+    }
+}
+
+/// Parts without a publication year go after the ones which have it, ordered by when they were added to mangadex
+pub fn publication_order(parts: &mut [SeriesPart]) {
+    parts.sort_by(|a, b| match (a.year, b.year) {
+        (Some(a_year), Some(b_year)) => a_year.cmp(&b_year).then_with(|| a.created_at.cmp(&b.created_at)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.created_at.cmp(&b.created_at),
+    });
+}
+
+/// The chapters read and the chapters there are across all the parts of a series
+pub fn combined_progress(parts: &[SeriesPart]) -> (usize, usize) {
+    parts
+        .iter()
+        .fold((0, 0), |(read, total), part| (read + part.chapters_read.min(part.total_chapters), total + part.total_chapters))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn part(id: &str, year: Option<u32>, created_at: &str) -> SeriesPart {
+        SeriesPart {
+            id: id.to_string(),
+            year,
+            created_at: created_at.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parts_are_in_publication_order() {
+        let mut parts = vec![
+            part("no-year", None, "2019-01-01"),
+            part("sequel", Some(2015), "2018-01-01"),
+            part("first", Some(2010), "2020-01-01"),
+            part("same-year", Some(2015), "2017-01-01"),
+        ];
+
+        publication_order(&mut parts);
+
+        let ids: Vec<&str> = parts.iter().map(|part| part.id.as_str()).collect();
+        assert_eq!(ids, vec!["first", "same-year", "sequel", "no-year"]);
+    }
+
+    #[test]
+    fn only_sequels_and_prequels_are_parts_of_the_series() {
+        let relationship = |id: &str, type_field: &str, related: &str| MangaSearchRelationship {
+            id: id.to_string(),
+            type_field: type_field.to_string(),
+            related: Some(related.to_string()),
+            attributes: None,
+        };
+
+        let manga = Data {
+            relationships: vec![
+                relationship("sequel", "manga", "sequel"),
+                relationship("prequel", "manga", "prequel"),
+                relationship("spin-off", "manga", "spin_off"),
+                relationship("author", "author", "sequel"),
+            ],
+            ..Default::default()
+        };
+
+        let mut seen = vec!["prequel".to_string()];
+
+        assert_eq!(unseen_parts(&manga, &mut seen), vec!["sequel"]);
+        assert!(unseen_parts(&manga, &mut seen).is_empty());
+    }
+
+    #[test]
+    fn chapters_read_in_other_languages_are_not_counted_twice() {
+        let parts = vec![
+            SeriesPart {
+                chapters_read: 30,
+                total_chapters: 20,
+                ..Default::default()
+            },
+            SeriesPart {
+                chapters_read: 5,
+                total_chapters: 10,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(combined_progress(&parts), (25, 30));
+        assert!(parts[0].is_finished());
+    }
+}
@@ -0,0 +1,108 @@
+use image::imageops::FilterType;
+use image::DynamicImage;
+
+/// Hashes which differ in at most this many bits are the same page, re-encoded or resized copies of a page differ
+/// in a few bits while pages which only look alike, like two panels of the same scene, differ in many more
+static MAX_DISTANCE: u32 = 3;
+
+/// A difference hash: the page is shrunk to 9x8 in grayscale and each bit says whether a pixel is brighter than
+/// the one on its right
+pub fn page_hash(image: &DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            let brighter = small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | brighter as u64;
+        }
+    }
+    hash
+}
+
+/// Blank pages hash to 0 and they are left in on purpose, to separate scenes
+pub fn same_page(a: u64, b: u64) -> bool {
+    a != 0 && b != 0 && (a ^ b).count_ones() <= MAX_DISTANCE
+}
+
+/// The first of `hashes` before `index` which is the same page as the one at `index`
+pub fn repeated_page(hashes: &[Option<u64>], index: usize) -> Option<usize> {
+    let hash = (*hashes.get(index)?)?;
+    hashes[..index]
+        .iter()
+        .position(|earlier| earlier.is_some_and(|earlier| same_page(earlier, hash)))
+}
+
+/// The pages of a chapter seen so far, used to leave repeated pages out of downloads
+#[derive(Debug, Default)]
+pub struct RepeatedPages {
+    hashes: Vec<u64>,
+}
+
+impl RepeatedPages {
+    /// Pages which can't be decoded are never repeated
+    pub fn is_repeated(&mut self, bytes: &[u8]) -> bool {
+        let Ok(image) = image::load_from_memory(bytes) else {
+            return false;
+        };
+
+        let hash = page_hash(&image);
+        if self.hashes.iter().any(|seen| same_page(*seen, hash)) {
+            return true;
+        }
+
+        self.hashes.push(hash);
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use image::{ImageFormat, Rgb, RgbImage};
+
+    use super::*;
+
+    fn gradient_page(width: u32, height: u32, flip: bool) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let x = if flip { width - 1 - x } else { x };
+            let value = ((x * 255 / width) ^ (y * 255 / height)) as u8;
+            Rgb([value, value, value])
+        }))
+    }
+
+    #[test]
+    fn resized_copies_are_the_same_page() {
+        let page = gradient_page(300, 400, false);
+
+        assert!(same_page(page_hash(&page), page_hash(&page.resize_exact(150, 200, FilterType::Nearest))));
+        assert!(!same_page(page_hash(&page), page_hash(&gradient_page(300, 400, true))));
+    }
+
+    #[test]
+    fn blank_pages_are_never_repeated() {
+        let blank = DynamicImage::ImageRgb8(RgbImage::from_pixel(100, 100, Rgb([255, 255, 255])));
+
+        assert_eq!(None, repeated_page(&[Some(page_hash(&blank)), Some(page_hash(&blank))], 1));
+    }
+
+    #[test]
+    fn repeated_pages_are_found_in_downloads() {
+        let encode = |page: &DynamicImage| {
+            let mut bytes = Cursor::new(vec![]);
+            page.write_to(&mut bytes, ImageFormat::Png).unwrap();
+            bytes.into_inner()
+        };
+
+        let credits = encode(&gradient_page(200, 300, false));
+        let page = encode(&gradient_page(200, 300, true));
+
+        let mut repeated = RepeatedPages::default();
+
+        assert!(!repeated.is_repeated(&credits));
+        assert!(!repeated.is_repeated(&page));
+        assert!(repeated.is_repeated(&credits));
+        assert!(!repeated.is_repeated(b"not an image"));
+    }
+}
@@ -1,18 +1,26 @@
 use std::error::Error;
+use std::io::Write;
 use std::time::Duration;
 
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture, KeyEvent, MouseEvent};
 use crossterm::execute;
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::style::Print;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle};
 use futures::{FutureExt, StreamExt};
 use ratatui::backend::Backend;
 use ratatui::Terminal;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::JoinHandle;
 
+use super::database::LastChapterRead;
+use super::error_log::write_to_safe_mode_log;
+use super::hyperlink::{take_hyperlinks, write_hyperlinks};
 use super::ChapterPagesResponse;
 use crate::common::{Artist, Author};
+use crate::config::{MangaTuiConfig, StartTab, TaskAlert};
+use crate::global::is_safe_mode;
 use crate::view::app::{App, AppState};
+use crate::view::pages::reader::ReadChapterInfo;
 use crate::view::pages::SelectedPage;
 use crate::view::widgets::search::MangaItem;
 use crate::view::widgets::Component;
@@ -21,50 +29,150 @@ pub enum Action {
     Quit,
 }
 
+/// The pages the app can go to together with what each of them needs to be opened
+#[derive(Clone)]
+pub enum Route {
+    Home,
+    Search,
+    SearchMangasOfAuthor(Author),
+    SearchMangasOfArtist(Artist),
+    Feed,
+    Updates,
+    Popular,
+    Settings,
+    Manga(MangaItem),
+    Reader {
+        pages: ChapterPagesResponse,
+        chapter: ReadChapterInfo,
+    },
+    /// The manga page of the chapter and then the chapter itself
+    ResumeChapter(LastChapterRead),
+}
+
+/// Work which can take minutes and keeps going while another tab is open
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LongTask {
+    DownloadAllChapters { manga_title: String },
+    ImportMangas,
+}
+
+impl LongTask {
+    /// The tab the task was started from, nothing is shown if it finishes while that tab is open
+    pub fn page(&self) -> SelectedPage {
+        match self {
+            Self::DownloadAllChapters { .. } => SelectedPage::MangaTab,
+            Self::ImportMangas => SelectedPage::Feed,
+        }
+    }
+
+    pub fn alert(&self, config: &MangaTuiConfig) -> TaskAlert {
+        match self {
+            Self::DownloadAllChapters { .. } => config.download_finished_alert,
+            Self::ImportMangas => config.import_finished_alert,
+        }
+    }
+
+    pub fn finished_message(&self) -> String {
+        match self {
+            Self::DownloadAllChapters { manga_title } => format!("Finished downloading the chapters of {manga_title}"),
+            Self::ImportMangas => "Finished importing mangas to the library".to_string(),
+        }
+    }
+}
+
 /// These are the events this app will listen to
 #[derive(Clone)]
 pub enum Events {
     Tick,
+    /// Work done in the background which changes what is on screen finished, so it's drawn without waiting for
+    /// the next tick
+    Redraw,
     Key(KeyEvent),
     Mouse(MouseEvent),
-    GoToMangaPage(MangaItem),
-    GoToHome,
-    GoSearchPage,
-    GoSearchMangasAuthor(Author),
-    GoSearchMangasArtist(Artist),
-    GoFeedPage,
-    ReadChapter(ChapterPagesResponse),
+    Navigate(Route),
+    TaskFinished(LongTask),
 }
 
-/// Initialize the terminal
+/// Saves the title of the terminal window so it can be put back on exit
+static PUSH_TERMINAL_TITLE: &str = "\x1B[22;0t";
+static POP_TERMINAL_TITLE: &str = "\x1B[23;0t";
+
+static BELL: &str = "\x07";
+
+/// Initialize the terminal, in safe mode only the alternate screen is used
 pub fn init() -> std::io::Result<()> {
-    execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    if is_safe_mode() {
+        execute!(std::io::stdout(), EnterAlternateScreen)?;
+    } else {
+        execute!(std::io::stdout(), Print(PUSH_TERMINAL_TITLE), EnterAlternateScreen, EnableMouseCapture)?;
+    }
     enable_raw_mode()?;
     Ok(())
 }
 
 pub fn restore() -> std::io::Result<()> {
-    execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    if is_safe_mode() {
+        execute!(std::io::stdout(), LeaveAlternateScreen)?;
+    } else {
+        execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture, Print(POP_TERMINAL_TITLE))?;
+    }
     disable_raw_mode()?;
     Ok(())
 }
 
 ///Start app's main loop
-pub async fn run_app(backend: impl Backend) -> Result<(), Box<dyn Error>> {
+pub async fn run_app(
+    backend: impl Backend + Write,
+    start_tab: StartTab,
+    resume: Option<LastChapterRead>,
+) -> Result<(), Box<dyn Error>> {
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
+    let mut app = App::new(start_tab);
+
+    if let Some(chapter) = resume {
+        app.resume_chapter(chapter);
+    }
 
     let tick_rate = std::time::Duration::from_millis(250);
 
     let main_event_handle = handle_events(tick_rate, app.global_event_tx.clone());
 
+    let config = MangaTuiConfig::get();
+    let safe_mode = is_safe_mode();
+
+    let mut terminal_title = String::new();
+    let mut current_tab = None;
+
     while app.state == AppState::Runnning {
-        terminal.draw(|f| {
+        let frame = terminal.draw(|f| {
             app.render(f.size(), f);
         })?;
 
+        if current_tab != Some(app.current_tab) {
+            current_tab = Some(app.current_tab);
+            write_to_safe_mode_log(&format!("showing the {} page, terminal size {}", app.current_tab, frame.area));
+        }
+
+        let hyperlinks = take_hyperlinks();
+        if config.hyperlinks && !safe_mode && !hyperlinks.is_empty() {
+            let buffer = frame.buffer.clone();
+            write_hyperlinks(&hyperlinks, &buffer, terminal.backend_mut())?;
+        }
+
+        if config.set_terminal_title && !safe_mode && app.terminal_title() != terminal_title {
+            terminal_title = app.terminal_title();
+            execute!(terminal.backend_mut(), SetTitle(&terminal_title))?;
+        }
+
+        if app.take_bell() && !safe_mode {
+            execute!(terminal.backend_mut(), Print(BELL))?;
+        }
+
         if let Some(event) = app.global_event_rx.recv().await {
+            if let Events::Key(key_event) = &event {
+                write_to_safe_mode_log(&format!("key pressed {:?} {:?}", key_event.code, key_event.modifiers));
+            }
             app.handle_events(event.clone());
             match app.current_tab {
                 SelectedPage::Search => {
@@ -82,6 +190,15 @@ pub async fn run_app(backend: impl Backend) -> Result<(), Box<dyn Error>> {
                 SelectedPage::Feed => {
                     app.feed_page.handle_events(event);
                 },
+                SelectedPage::Updates => {
+                    app.updates_page.handle_events(event);
+                },
+                SelectedPage::Popular => {
+                    app.popular_page.handle_events(event);
+                },
+                SelectedPage::Settings => {
+                    app.settings_page.handle_events(event);
+                },
             };
         }
 
@@ -119,6 +236,21 @@ pub async fn run_app(backend: impl Backend) -> Result<(), Box<dyn Error>> {
                     app.feed_page.update(feed_event);
                 }
             },
+            SelectedPage::Updates => {
+                if let Ok(updates_action) = app.updates_page.local_action_rx.try_recv() {
+                    app.updates_page.update(updates_action);
+                }
+            },
+            SelectedPage::Popular => {
+                if let Ok(popular_action) = app.popular_page.local_action_rx.try_recv() {
+                    app.popular_page.update(popular_action);
+                }
+            },
+            SelectedPage::Settings => {
+                if let Ok(settings_action) = app.settings_page.local_action_rx.try_recv() {
+                    app.settings_page.update(settings_action);
+                }
+            },
         };
     }
 
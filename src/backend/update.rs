@@ -0,0 +1,360 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::Utc;
+use flate2::read::GzDecoder;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::cache::CACHE_DIR;
+
+pub static LATEST_RELEASE_URL: &str = "https://api.github.com/repos/gigamonster256/manga-tui/releases/latest";
+
+/// The latest release is asked to github at most once in this time
+pub static RELEASE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub static RELEASE_CHECK_FILE: &str = "latest-release.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    UpToDate { version: String },
+    Updated { from: String, to: String },
+}
+
+/// The result of the last time github was asked for the latest release
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseCheck {
+    /// Unix timestamp in seconds
+    pub checked_at: i64,
+    pub latest: String,
+    /// The release the user does not want to be told about again
+    pub dismissed: Option<String>,
+}
+
+impl ReleaseCheck {
+    fn path() -> Option<PathBuf> {
+        CACHE_DIR.as_ref().map(|cache_dir| cache_dir.join(RELEASE_CHECK_FILE))
+    }
+
+    pub fn read() -> Option<Self> {
+        serde_json::from_slice(&fs::read(Self::path()?).ok()?).ok()
+    }
+
+    pub fn write(&self) -> Result<(), std::io::Error> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        if let Some(cache_dir) = path.parent() {
+            fs::create_dir_all(cache_dir)?;
+        }
+
+        fs::write(path, serde_json::to_vec(self)?)
+    }
+
+    pub fn is_stale(&self, now: i64) -> bool {
+        now.saturating_sub(self.checked_at) >= RELEASE_CHECK_INTERVAL.as_secs() as i64
+    }
+
+    /// The release to tell the user about, if it is newer than the one running and was not dismissed
+    pub fn notice(&self, current: &str) -> Option<String> {
+        let latest = self.latest.trim_start_matches('v');
+
+        (is_newer(current, latest) && self.dismissed.as_deref().map(|dismissed| dismissed.trim_start_matches('v')) != Some(latest))
+            .then(|| latest.to_string())
+    }
+}
+
+async fn fetch_latest_release(client: &Client) -> Result<Release, reqwest::Error> {
+    client.get(LATEST_RELEASE_URL).send().await?.error_for_status()?.json().await
+}
+
+/// Newer release than the one running the user has not dismissed, github is only asked when the cached answer is
+/// older than `RELEASE_CHECK_INTERVAL`
+pub async fn check_for_new_release(client: &Client) -> Result<Option<String>, Box<dyn Error>> {
+    let now = Utc::now().timestamp();
+    let cached = ReleaseCheck::read();
+
+    let check = match cached {
+        Some(check) if !check.is_stale(now) => check,
+        cached => {
+            let release = fetch_latest_release(client).await?;
+            let check = ReleaseCheck {
+                checked_at: now,
+                latest: release.tag_name,
+                dismissed: cached.and_then(|cached| cached.dismissed),
+            };
+            check.write()?;
+            check
+        },
+    };
+
+    Ok(check.notice(env!("CARGO_PKG_VERSION")))
+}
+
+/// Stops telling the user about `version` on startup
+pub fn dismiss_release(version: &str) -> Result<(), std::io::Error> {
+    let mut check = ReleaseCheck::read().unwrap_or_default();
+    check.dismissed = Some(version.to_string());
+    check.write()
+}
+
+pub fn new_release_client() -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(120))
+        .user_agent(format!("manga-tui/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap()
+}
+
+pub static RELEASE_CHECKSUM_FILE: &str = "checksum.txt";
+
+/// The target the release workflow builds for the platform this binary is running on, if it builds one
+pub fn release_target() -> Option<&'static str> {
+    match (std::env::consts::ARCH, std::env::consts::OS) {
+        ("x86_64", "linux") if cfg!(target_env = "gnu") => Some("x86_64-unknown-linux-gnu"),
+        ("x86_64", "macos") => Some("x86_64-apple-darwin"),
+        ("aarch64", "macos") => Some("aarch64-apple-darwin"),
+        _ => None,
+    }
+}
+
+/// The archive the release workflow publishes for `target`, like `manga-tui-0.4.0-x86_64-unknown-linux-gnu.tar.gz`
+pub fn release_asset_name(version: &str, target: &str) -> String {
+    format!("manga-tui-{}-{target}.tar.gz", version.trim_start_matches('v'))
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+
+    Some((parts.next()??, parts.next().unwrap_or(Some(0))?, parts.next().unwrap_or(Some(0))?))
+}
+
+/// Whether `latest`, a release tag like `v0.4.0`, is a newer version than `current`
+pub fn is_newer(current: &str, latest: &str) -> bool {
+    match (parse_version(current), parse_version(latest)) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => false,
+    }
+}
+
+/// Reads the digest out of a checksum file, either a bare digest or `sha256sum` output which may list several files,
+/// files are matched by name since the release workflow lists them with the directory they were in
+pub fn parse_checksum(contents: &str, asset_name: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let digest = fields.next()?;
+        let is_digest = digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit());
+
+        match fields.next() {
+            Some(file) if file.trim_start_matches('*').rsplit('/').next() != Some(asset_name) => None,
+            _ if is_digest => Some(digest.to_lowercase()),
+            _ => None,
+        }
+    })
+}
+
+/// Hex encoded SHA-256 digest, used to check the downloaded archive against the checksum published with the release
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// The `manga-tui` binary packed in a release archive
+fn extract_executable(archive: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+    let executable_name = format!("manga-tui{}", std::env::consts::EXE_SUFFIX);
+    let mut archive = tar::Archive::new(GzDecoder::new(archive));
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.file_name().is_some_and(|name| name == executable_name.as_str()) {
+            let mut binary = Vec::new();
+            entry.read_to_end(&mut binary)?;
+            return Ok(binary);
+        }
+    }
+
+    Err(std::io::Error::new(std::io::ErrorKind::NotFound, format!("the release archive has no {executable_name}")))
+}
+
+/// Writes the new binary next to the current one and swaps them, the old binary is moved aside first since on
+/// windows a running executable can be renamed but not overwritten
+fn replace_executable(executable: &Path, new_binary: &[u8]) -> Result<(), std::io::Error> {
+    let staged = executable.with_extension("new");
+    let previous = executable.with_extension("old");
+
+    let mut file = File::create(&staged)?;
+    file.write_all(new_binary)?;
+    file.sync_all()?;
+    fs::set_permissions(&staged, fs::metadata(executable)?.permissions())?;
+
+    let _ = fs::remove_file(&previous);
+    fs::rename(executable, &previous)?;
+    if let Err(e) = fs::rename(&staged, executable) {
+        fs::rename(&previous, executable)?;
+        return Err(e);
+    }
+
+    // Windows keeps the old binary locked until this process exits, it is removed on the next update
+    let _ = fs::remove_file(&previous);
+
+    Ok(())
+}
+
+fn current_executable() -> Result<PathBuf, std::io::Error> {
+    let executable = std::env::current_exe()?;
+    Ok(fs::canonicalize(&executable).unwrap_or(executable))
+}
+
+pub async fn self_update(client: &Client) -> Result<UpdateOutcome, Box<dyn Error>> {
+    let current = env!("CARGO_PKG_VERSION").to_string();
+
+    let release = fetch_latest_release(client).await?;
+
+    if !is_newer(&current, &release.tag_name) {
+        return Ok(UpdateOutcome::UpToDate { version: current });
+    }
+
+    let target = release_target().ok_or_else(|| {
+        format!(
+            "releases are not built for {}-{}, update manga-tui the way it was installed",
+            std::env::consts::ARCH,
+            std::env::consts::OS
+        )
+    })?;
+    let asset_name = release_asset_name(&release.tag_name, target);
+
+    let find_asset = |name: &str| release.assets.iter().find(|asset| asset.name == name);
+
+    let asset = find_asset(&asset_name)
+        .ok_or_else(|| format!("release {} has no binary for this platform, expected {asset_name}", release.tag_name))?;
+    let checksum_asset = find_asset(RELEASE_CHECKSUM_FILE)
+        .ok_or_else(|| format!("release {} has no {RELEASE_CHECKSUM_FILE}, refusing to update", release.tag_name))?;
+
+    let checksum_file = client
+        .get(&checksum_asset.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let expected = parse_checksum(&checksum_file, &asset_name)
+        .ok_or_else(|| format!("{RELEASE_CHECKSUM_FILE} has no valid checksum for {asset_name}"))?;
+
+    let archive = client.get(&asset.browser_download_url).send().await?.error_for_status()?.bytes().await?;
+    let actual = sha256_hex(&archive);
+
+    if actual != expected {
+        return Err(format!("checksum mismatch for {asset_name}: expected {expected}, got {actual}").into());
+    }
+
+    replace_executable(&current_executable()?, &extract_executable(&archive)?)?;
+
+    Ok(UpdateOutcome::Updated {
+        from: current,
+        to: release.tag_name.trim_start_matches('v').to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sha256_matches_known_digests() {
+        assert_eq!(sha256_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(
+            sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn only_newer_releases_are_installed() {
+        assert!(is_newer("0.3.1", "v0.4.0"));
+        assert!(is_newer("0.3.1", "0.3.2"));
+        assert!(is_newer("0.3.1", "v1"));
+        assert!(!is_newer("0.3.1", "v0.3.1"));
+        assert!(!is_newer("0.3.1", "v0.2.9"));
+        assert!(!is_newer("0.3.1", "nightly"));
+    }
+
+    #[test]
+    fn release_notice_is_shown_until_dismissed() {
+        let check = ReleaseCheck {
+            checked_at: 0,
+            latest: "v0.4.0".to_string(),
+            dismissed: None,
+        };
+
+        assert_eq!(check.notice("0.3.1"), Some("0.4.0".to_string()));
+        assert_eq!(check.notice("0.4.0"), None);
+
+        let dismissed = ReleaseCheck {
+            dismissed: Some("0.4.0".to_string()),
+            ..check.clone()
+        };
+        assert_eq!(dismissed.notice("0.3.1"), None);
+
+        let newer_than_dismissed = ReleaseCheck {
+            latest: "v0.5.0".to_string(),
+            ..dismissed
+        };
+        assert_eq!(newer_than_dismissed.notice("0.3.1"), Some("0.5.0".to_string()));
+
+        assert!(!check.is_stale(RELEASE_CHECK_INTERVAL.as_secs() as i64 - 1));
+        assert!(check.is_stale(RELEASE_CHECK_INTERVAL.as_secs() as i64));
+    }
+
+    #[test]
+    fn checksum_is_read_from_sha256sum_output() {
+        let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        let linux = release_asset_name("v0.4.0", "x86_64-unknown-linux-gnu");
+        let macos = release_asset_name("v0.4.0", "aarch64-apple-darwin");
+
+        assert_eq!(linux, "manga-tui-0.4.0-x86_64-unknown-linux-gnu.tar.gz");
+        assert_eq!(parse_checksum(digest, &linux), Some(digest.to_string()));
+        assert_eq!(
+            parse_checksum(&format!("{} *releases/{macos}\n{}  releases/{linux}\n", "0".repeat(64), digest.to_uppercase()), &linux),
+            Some(digest.to_string())
+        );
+        assert_eq!(parse_checksum(&format!("{digest}  releases/{macos}"), &linux), None);
+        assert_eq!(parse_checksum("not a checksum", &linux), None);
+    }
+
+    #[test]
+    fn executable_is_extracted_from_the_release_archive() {
+        let executable_name = format!("manga-tui{}", std::env::consts::EXE_SUFFIX);
+        let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(7);
+        header.set_mode(0o755);
+        header.set_cksum();
+        archive.append_data(&mut header, &executable_name, b"binary!".as_slice()).unwrap();
+
+        let archive = archive.into_inner().unwrap().finish().unwrap();
+
+        assert_eq!(extract_executable(&archive).unwrap(), b"binary!");
+        assert!(extract_executable(&[]).is_err());
+    }
+}
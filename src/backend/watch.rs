@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use chrono::{Local, NaiveTime};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+
+use super::database::{set_chapter_downloaded, SetChapterDownloaded};
+use super::download::{download_chapter, DownloadChapter};
+use super::fetch::MangadexClient;
+use super::filter::Languages;
+use super::ChapterData;
+use crate::common::PageType;
+use crate::config::{ImageQuality, MangaTuiConfig};
+use crate::utils::{from_manga_response, to_filename};
+use crate::view::pages::manga::MangaPageEvents;
+
+pub struct WatchOptions {
+    pub manga_id: String,
+    pub language: Languages,
+    pub interval: Duration,
+    /// Show a desktop notification for each new chapter besides printing it
+    pub notify: bool,
+    pub download: bool,
+    /// Checks and downloads are paused in these hours, `None` when they are not set or were ignored
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// A time of the day, like the night, during which nothing is fetched in the background
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Two times like `23:00-07:00`, the hours can go past midnight
+    pub fn parse(hours: &str) -> Option<Self> {
+        let (start, end) = hours.split_once('-')?;
+        let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+        let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+        (start != end).then_some(Self { start, end })
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// The chapters in `language` which were not seen before, from the one published first to the last
+fn new_chapters(seen: &mut HashSet<String>, chapters: Vec<ChapterData>, language: Languages) -> Vec<ChapterData> {
+    let mut new_chapters: Vec<ChapterData> = chapters
+        .into_iter()
+        .filter(|chapter| chapter.attributes.translated_language == language.as_iso_code())
+        .filter(|chapter| seen.insert(chapter.id.clone()))
+        .collect();
+
+    new_chapters.sort_by(|a, b| a.attributes.readable_at.cmp(&b.attributes.readable_at));
+    new_chapters
+}
+
+fn chapter_name(chapter: &ChapterData) -> String {
+    let number = chapter.attributes.chapter.as_deref().unwrap_or_default();
+    match chapter.attributes.title.as_deref() {
+        Some(title) if !title.is_empty() => format!("Ch. {number} {title}"),
+        _ => format!("Ch. {number}"),
+    }
+}
+
+/// Uses `notify-send` on linux and `osascript` on macos, other systems only get the terminal bell
+fn send_notification(summary: &str, body: &str) {
+    let command = if cfg!(target_os = "macos") {
+        let script = format!("display notification {body:?} with title {summary:?}");
+        Command::new("osascript")
+            .args(["-e", &script])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+    } else {
+        Command::new("notify-send")
+            .args([summary, body])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+    };
+
+    if !command.is_ok_and(|status| status.success()) {
+        print!("\x07");
+    }
+}
+
+async fn download_new_chapter(manga_id: &str, manga_title: &str, chapter: &ChapterData, language: Languages) -> Result<(), String> {
+    let config = MangaTuiConfig::get();
+
+    let response = MangadexClient::global().get_chapter_pages(&chapter.id).await.map_err(|e| e.to_string())?;
+
+    let (files, quality) = match config.image_quality {
+        ImageQuality::Low => (response.chapter.data_saver, PageType::LowQuality),
+        ImageQuality::High => (response.chapter.data, PageType::HighQuality),
+    };
+
+    let endpoint = format!("{}/{}/{}", response.base_url, quality, response.chapter.hash);
+
+    let scanlator = chapter
+        .relationships
+        .iter()
+        .find(|rel| rel.type_field == "scanlation_group")
+        .and_then(|rel| rel.attributes.as_ref())
+        .map(|attributes| attributes.name.to_string())
+        .unwrap_or_default();
+
+    let title = chapter.attributes.title.clone().unwrap_or_default();
+    let number = chapter.attributes.chapter.clone().unwrap_or_default();
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<MangaPageEvents>();
+
+    download_chapter(
+        &config.download_type,
+        false,
+        DownloadChapter {
+            id_chapter: &chapter.id,
+            manga_id,
+            manga_title: &to_filename(manga_title),
+            chapter_title: &to_filename(&title),
+            number: &number,
+            scanlator: &to_filename(&scanlator),
+            lang: &language.as_human_readable(),
+        },
+        files,
+        endpoint,
+        tx,
+    )
+    .map_err(|e| e.to_string())?;
+
+    while let Some(event) = rx.recv().await {
+        if let MangaPageEvents::ChapterFinishedDownloading(_) = event {
+            break;
+        }
+    }
+
+    set_chapter_downloaded(SetChapterDownloaded {
+        id: &chapter.id,
+        title: &title,
+        manga_id,
+        manga_title,
+        img_url: None,
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Checks the chapters of a manga every `interval` until it's stopped with ctrl + c, the chapters there already are
+/// when it starts are not reported. Pressing enter turns the quiet hours off until it's pressed again
+pub async fn watch_manga(options: WatchOptions) -> Result<(), String> {
+    let client = MangadexClient::global();
+
+    let manga = client.get_one_manga(&options.manga_id).await.map_err(|e| e.to_string())?;
+    let manga = from_manga_response(manga.data);
+
+    let mut seen: HashSet<String> = HashSet::new();
+
+    let chapters = client.get_latest_chapters(&manga.id).await.map_err(|e| e.to_string())?;
+    new_chapters(&mut seen, chapters.data, options.language);
+
+    println!(
+        "Watching {} for new chapters in {}, checking every {} minutes, stop with ctrl + c",
+        manga.title,
+        options.language.as_human_readable(),
+        options.interval.as_secs() / 60
+    );
+
+    if let Some(quiet_hours) = options.quiet_hours {
+        println!(
+            "Quiet hours from {} to {}, press enter to check during them anyway",
+            quiet_hours.start.format("%H:%M"),
+            quiet_hours.end.format("%H:%M")
+        );
+    }
+
+    let mut stdin = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdin_open = options.quiet_hours.is_some();
+    let mut ignore_quiet_hours = false;
+    let mut paused = false;
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(options.interval) => {},
+            line = stdin.next_line(), if stdin_open => {
+                match line {
+                    Ok(Some(_)) => {
+                        ignore_quiet_hours = !ignore_quiet_hours;
+                        if ignore_quiet_hours {
+                            println!("Checking during quiet hours, press enter to pause again");
+                        } else {
+                            println!("Pausing during quiet hours again");
+                        }
+                    },
+                    _ => stdin_open = false,
+                }
+                continue;
+            },
+        }
+
+        let quiet_hours = options.quiet_hours.filter(|quiet_hours| quiet_hours.contains(Local::now().time()));
+        if let Some(quiet_hours) = quiet_hours.filter(|_| !ignore_quiet_hours) {
+            if !paused {
+                println!("Quiet hours, not checking for new chapters until {}", quiet_hours.end.format("%H:%M"));
+                paused = true;
+            }
+            continue;
+        }
+        paused = false;
+
+        let chapters = match client.get_latest_chapters(&manga.id).await {
+            Ok(response) => response.data,
+            Err(e) => {
+                eprintln!("Could not check for new chapters, trying again later: {e}");
+                continue;
+            },
+        };
+
+        for chapter in new_chapters(&mut seen, chapters, options.language) {
+            let name = chapter_name(&chapter);
+
+            println!("New chapter of {}: {name}", manga.title);
+
+            if options.notify {
+                send_notification(&manga.title, &format!("New chapter: {name}"));
+            }
+
+            if options.download {
+                match download_new_chapter(&manga.id, &manga.title, &chapter, options.language).await {
+                    Ok(()) => println!("Downloaded {name}"),
+                    Err(e) => eprintln!("Could not download {name}: {e}"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::ChapterAttribute;
+
+    fn chapter(id: &str, language: &str, readable_at: &str) -> ChapterData {
+        ChapterData {
+            id: id.to_string(),
+            attributes: ChapterAttribute {
+                translated_language: language.to_string(),
+                readable_at: readable_at.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn quiet_hours_can_go_past_midnight() {
+        let time = |time: &str| NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+
+        let night = QuietHours::parse("23:00-07:00").unwrap();
+        assert!(night.contains(time("23:30")));
+        assert!(night.contains(time("03:00")));
+        assert!(!night.contains(time("07:00")));
+        assert!(!night.contains(time("12:00")));
+
+        let afternoon = QuietHours::parse("13:00 - 17:30").unwrap();
+        assert!(afternoon.contains(time("13:00")));
+        assert!(!afternoon.contains(time("18:00")));
+
+        assert_eq!(None, QuietHours::parse("23:00"));
+        assert_eq!(None, QuietHours::parse("25:00-07:00"));
+        assert_eq!(None, QuietHours::parse("07:00-07:00"));
+    }
+
+    #[test]
+    fn only_unseen_chapters_in_the_language_are_new() {
+        let mut seen = HashSet::new();
+
+        let first_check = vec![chapter("1", "en", "2024-01-01")];
+        assert_eq!(new_chapters(&mut seen, first_check, Languages::English).len(), 1);
+
+        let second_check = vec![
+            chapter("3", "en", "2024-01-03"),
+            chapter("2", "en", "2024-01-02"),
+            chapter("2-es", "es", "2024-01-02"),
+            chapter("1", "en", "2024-01-01"),
+        ];
+        let ids: Vec<String> = new_chapters(&mut seen, second_check, Languages::English)
+            .into_iter()
+            .map(|chapter| chapter.id)
+            .collect();
+
+        assert_eq!(ids, vec!["2", "3"]);
+    }
+}
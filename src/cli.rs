@@ -1,7 +1,46 @@
+use std::path::PathBuf;
+
 use clap::{crate_version, Parser, Subcommand};
 use strum::IntoEnumIterator;
 
+use crate::backend::export::{ExportCategory, ExportFormat};
 use crate::backend::filter::Languages;
+use crate::backend::import::ImportCategory;
+use crate::config::StartTab;
+
+#[derive(Subcommand)]
+pub enum LibraryCommands {
+    /// Print the mangas you are reading or plan to read, with links to mangadex
+    Export {
+        #[arg(short, long, value_enum, default_value_t)]
+        format: ExportFormat,
+        #[arg(short, long, value_enum, default_value_t)]
+        category: ExportCategory,
+        /// Write the reading list to this file instead of printing it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Add the mangas listed in a file, one mangadex link or id per line, to your library
+    Import {
+        file: PathBuf,
+        #[arg(short, long, value_enum, default_value_t)]
+        category: ImportCategory,
+    },
+    /// Show another cover for a manga, either an image of yours or the cover of one of its volumes
+    Cover {
+        /// The id of the manga or its mangadex link
+        manga: String,
+        /// The image to show as the cover
+        #[arg(short, long, conflicts_with_all = ["volume", "reset"])]
+        file: Option<PathBuf>,
+        /// The volume whose cover is shown
+        #[arg(short, long, conflicts_with = "reset")]
+        volume: Option<String>,
+        /// Go back to the cover mangadex has
+        #[arg(short, long)]
+        reset: bool,
+    },
+}
 
 #[derive(Subcommand)]
 pub enum Commands {
@@ -11,6 +50,42 @@ pub enum Commands {
         #[arg(short, long)]
         set: Option<String>,
     },
+    Library {
+        #[command(subcommand)]
+        command: LibraryCommands,
+    },
+    /// Check what the terminal supports and draw a test image with each protocol, run it when images don't show
+    Doctor,
+    /// Replace this binary with the latest release from github, after checking its checksum
+    SelfUpdate,
+    /// Ask for a PIN before the settings of this profile can be changed and never show mangas rated above
+    /// `--max-content-rating`, whatever the config file says
+    Lock {
+        #[arg(short, long, default_value = "safe", value_parser = ["safe", "suggestive", "erotica", "pornographic"])]
+        max_content_rating: String,
+    },
+    /// Remove the lock of this profile, it asks for its PIN
+    Unlock,
+    /// Keep checking a manga for new chapters and print them as they come out
+    Watch {
+        /// The id of the manga or its mangadex link
+        manga: String,
+        /// Minutes between each check
+        #[arg(short, long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+        /// ISO code of the language of the chapters, `lang --print` lists them
+        #[arg(short, long, default_value = "en")]
+        lang: String,
+        /// Show a desktop notification for each new chapter
+        #[arg(short, long)]
+        notify: bool,
+        /// Download each new chapter like the manga page does
+        #[arg(short, long)]
+        download: bool,
+        /// Check for new chapters during the `quiet_hours` of the config too
+        #[arg(long)]
+        ignore_quiet_hours: bool,
+    },
 }
 
 #[derive(Parser)]
@@ -20,6 +95,19 @@ pub struct CliArgs {
     pub command: Option<Commands>,
     #[arg(short, long)]
     pub data_dir: bool,
+    /// The page to start on, overrides `start_tab` in the config file
+    #[arg(short, long, value_enum)]
+    pub tab: Option<StartTab>,
+    /// Open the reader at the last chapter and page you were reading
+    #[arg(short, long)]
+    pub resume: bool,
+    /// Plain text without images or mouse support and no background tasks, what happens is written to
+    /// the safe mode log in the error logs directory. Useful when manga-tui renders garbage in a terminal
+    #[arg(long)]
+    pub safe_mode: bool,
+    /// Keep a separate config, library, history and downloads under this name, for example `--profile kids`
+    #[arg(long)]
+    pub profile: Option<String>,
 }
 
 impl CliArgs {
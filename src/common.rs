@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use image::DynamicImage;
 use ratatui::layout::Rect;
 use ratatui_image::protocol::Protocol;
 use strum::{Display, EnumIter};
@@ -18,6 +19,12 @@ pub struct Artist {
     pub name: String,
 }
 
+#[derive(Default, Clone, Debug)]
+pub struct MangaTag {
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct Manga {
     pub id: String,
@@ -25,13 +32,14 @@ pub struct Manga {
     pub description: String,
     pub content_rating: String,
     pub publication_demographic: String,
-    pub tags: Vec<String>,
+    pub tags: Vec<MangaTag>,
     pub status: String,
     pub img_url: Option<String>,
     pub author: Author,
     pub artist: Artist,
     pub available_languages: Vec<Languages>,
     pub created_at: String,
+    pub last_chapter: Option<String>,
 }
 
 #[derive(Display, Clone, Copy, EnumIter, Default, Debug, Eq, PartialEq)]
@@ -44,6 +52,14 @@ pub enum PageType {
 }
 
 impl PageType {
+    pub fn from_db_value(value: &str) -> Option<Self> {
+        match value {
+            "data" => Some(Self::HighQuality),
+            "data-saver" => Some(Self::LowQuality),
+            _ => None,
+        }
+    }
+
     pub fn toggle(self) -> Self {
         match self {
             Self::LowQuality => Self::HighQuality,
@@ -59,6 +75,183 @@ impl PageType {
     }
 }
 
+/// The order in which the reader goes through pages, manga is usually read from right to left
+#[derive(Display, Clone, Copy, EnumIter, Default, Debug, Eq, PartialEq)]
+pub enum ReadingDirection {
+    #[strum(to_string = "left_to_right")]
+    #[default]
+    LeftToRight,
+    #[strum(to_string = "right_to_left")]
+    RightToLeft,
+}
+
+impl ReadingDirection {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::LeftToRight => Self::RightToLeft,
+            Self::RightToLeft => Self::LeftToRight,
+        }
+    }
+
+    pub fn as_human_readable(&self) -> &str {
+        match self {
+            Self::LeftToRight => "Left to right",
+            Self::RightToLeft => "Right to left",
+        }
+    }
+
+    pub fn from_db_value(value: &str) -> Option<Self> {
+        match value {
+            "left_to_right" => Some(Self::LeftToRight),
+            "right_to_left" => Some(Self::RightToLeft),
+            _ => None,
+        }
+    }
+}
+
+/// How a page is sized to the area of the reader
+#[derive(Display, Clone, Copy, EnumIter, Default, Debug, Eq, PartialEq)]
+pub enum FitMode {
+    /// The whole page is visible
+    #[strum(to_string = "fit_height")]
+    #[default]
+    FitHeight,
+    /// The page takes the whole width and is scrolled through if it's taller than the reader
+    #[strum(to_string = "fit_width")]
+    FitWidth,
+    /// The page is not resized
+    #[strum(to_string = "original")]
+    Original,
+}
+
+impl FitMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::FitHeight => Self::FitWidth,
+            Self::FitWidth => Self::Original,
+            Self::Original => Self::FitHeight,
+        }
+    }
+
+    pub fn as_human_readable(&self) -> &str {
+        match self {
+            Self::FitHeight => "Fit height",
+            Self::FitWidth => "Fit width",
+            Self::Original => "Original size",
+        }
+    }
+
+    pub fn from_db_value(value: &str) -> Option<Self> {
+        match value {
+            "fit_height" => Some(Self::FitHeight),
+            "fit_width" => Some(Self::FitWidth),
+            "original" => Some(Self::Original),
+            _ => None,
+        }
+    }
+}
+
+/// Filters for reading at night, applied to the pages before they are drawn
+#[derive(Display, Clone, Copy, EnumIter, Default, Debug, Eq, PartialEq)]
+pub enum ImageFilter {
+    #[strum(to_string = "none")]
+    #[default]
+    None,
+    #[strum(to_string = "invert")]
+    Invert,
+    #[strum(to_string = "grayscale")]
+    Grayscale,
+    /// Lowers the brightness of the page
+    #[strum(to_string = "dim")]
+    Dim,
+}
+
+impl ImageFilter {
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Invert,
+            Self::Invert => Self::Grayscale,
+            Self::Grayscale => Self::Dim,
+            Self::Dim => Self::None,
+        }
+    }
+
+    pub fn as_human_readable(&self) -> &str {
+        match self {
+            Self::None => "No filter",
+            Self::Invert => "Inverted colors",
+            Self::Grayscale => "Grayscale",
+            Self::Dim => "Dimmed",
+        }
+    }
+
+    pub fn apply(&self, image: &DynamicImage) -> DynamicImage {
+        match self {
+            Self::None => image.clone(),
+            Self::Invert => {
+                let mut inverted = image.clone();
+                inverted.invert();
+                inverted
+            },
+            Self::Grayscale => DynamicImage::ImageRgba8(image.grayscale().into_rgba8()),
+            Self::Dim => image.brighten(-80),
+        }
+    }
+}
+
+/// The order of the mangas in the feed
+#[derive(Display, Clone, Copy, EnumIter, Default, Debug, Eq, PartialEq)]
+pub enum LibrarySort {
+    #[strum(to_string = "recently_read")]
+    #[default]
+    RecentlyRead,
+    #[strum(to_string = "recently_added")]
+    RecentlyAdded,
+    #[strum(to_string = "alphabetical")]
+    Alphabetical,
+    /// The mangas with the most chapters read first
+    #[strum(to_string = "most_read")]
+    MostRead,
+}
+
+impl LibrarySort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::RecentlyRead => Self::RecentlyAdded,
+            Self::RecentlyAdded => Self::Alphabetical,
+            Self::Alphabetical => Self::MostRead,
+            Self::MostRead => Self::RecentlyRead,
+        }
+    }
+
+    pub fn as_human_readable(&self) -> &str {
+        match self {
+            Self::RecentlyRead => "Recently read",
+            Self::RecentlyAdded => "Recently added",
+            Self::Alphabetical => "Alphabetical",
+            Self::MostRead => "Most chapters read",
+        }
+    }
+
+    pub fn from_db_value(value: &str) -> Option<Self> {
+        match value {
+            "recently_read" => Some(Self::RecentlyRead),
+            "recently_added" => Some(Self::RecentlyAdded),
+            "alphabetical" => Some(Self::Alphabetical),
+            "most_read" => Some(Self::MostRead),
+            _ => None,
+        }
+    }
+}
+
+/// How the mangas in the feed are listed, stored so that it's kept between sessions
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq)]
+pub struct LibraryFilters {
+    pub sort: LibrarySort,
+    /// Only list the mangas with at least one chapter downloaded
+    pub downloaded_only: bool,
+}
+
 #[derive(Default)]
 pub struct ImageState {
     /// save the image loaded for a manga, it will be retrieved by it's id
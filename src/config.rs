@@ -1,12 +1,19 @@
 use std::fs::File;
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use clap::ValueEnum;
+use image::imageops::FilterType;
+use image::Rgb;
 use manga_tui::exists;
 use once_cell::sync::OnceCell;
+use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 
+use crate::backend::filter::{ContentRating, Languages};
+use crate::backend::watch::QuietHours;
 use crate::backend::AppDirectories;
 
 #[derive(Default, Debug, Serialize, Deserialize, Display, EnumIter)]
@@ -26,14 +33,186 @@ pub enum ImageQuality {
     High,
 }
 
-#[derive(Default, Debug, Serialize, Deserialize)]
+/// How images are scaled to the area they are drawn in, the better the result the longer it takes
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumIter)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderQuality {
+    Fast,
+    #[default]
+    Balanced,
+    High,
+}
+
+impl RenderQuality {
+    pub fn filter(self) -> FilterType {
+        match self {
+            Self::Fast => FilterType::Nearest,
+            Self::Balanced => FilterType::Triangle,
+            Self::High => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// What is drawn around the pages in the reader, halfblocks pages look very different on light and dark terminals
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumIter)]
+#[serde(rename_all = "snake_case")]
+pub enum ReaderBackground {
+    /// The background of the terminal is left as it is
+    #[default]
+    Terminal,
+    Black,
+    White,
+}
+
+impl ReaderBackground {
+    pub fn color(self) -> Option<Color> {
+        match self {
+            Self::Terminal => None,
+            Self::Black => Some(Color::Black),
+            Self::White => Some(Color::White),
+        }
+    }
+
+    /// Fills the part of the last row and column of cells a page does not cover
+    pub fn rgb(self) -> Option<Rgb<u8>> {
+        match self {
+            Self::Terminal => None,
+            Self::Black => Some(Rgb([0, 0, 0])),
+            Self::White => Some(Rgb([255, 255, 255])),
+        }
+    }
+}
+
+/// How the user is told a long task finished while another tab is open
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumIter)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskAlert {
+    #[default]
+    Off,
+    /// Rings the terminal bell, most terminals mark the window or play a sound
+    Bell,
+    /// The tabs at the top flash for a moment with a notice of what finished
+    Flash,
+    Both,
+}
+
+impl TaskAlert {
+    pub fn rings_bell(self) -> bool {
+        matches!(self, Self::Bell | Self::Both)
+    }
+
+    pub fn flashes(self) -> bool {
+        matches!(self, Self::Flash | Self::Both)
+    }
+}
+
+/// The page shown when manga-tui starts
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumIter, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum StartTab {
+    #[default]
+    Home,
+    Search,
+    /// The mangas the user is reading
+    #[serde(alias = "library")]
+    #[value(alias = "library")]
+    Feed,
+    /// The latest chapters uploaded to mangadex
+    Updates,
+    /// The most followed, best rated and newest mangas
+    Popular,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MangaTuiConfig {
     pub download_type: DownloadType,
     pub image_quality: ImageQuality,
+    /// Covers are shown while browsing so they have to be drawn quickly
+    pub cover_render_quality: RenderQuality,
+    pub page_render_quality: RenderQuality,
+    pub reader_background: ReaderBackground,
+    /// ISO code of the second language chapters are downloaded in with `D` and switched to in the reader with `L`
+    pub compare_language: Option<String>,
+    /// Where pages are saved from the reader, the `mangaDownloads` directory when it's not set
+    pub saved_pages_directory: Option<PathBuf>,
+    /// Command pages are opened with from the reader, the default image viewer when it's not set
+    pub external_viewer: Option<String>,
+    /// Command the text extracted from a page is translated with, it's given the text on stdin and prints the translation
+    pub translation_command: Option<String>,
+    /// How many pages after the one being read are fetched in the background
+    pub reader_prefetch_pages: usize,
+    /// Megabytes the decoded pages of a chapter can take before the ones read the longest time ago are unloaded
+    pub reader_memory_budget_mb: usize,
+    /// Seconds without a key press after which the reader only shows the pages, 0 never hides anything
+    pub hide_reader_ui_after_seconds: u64,
+    /// Pages which repeat an earlier page of the chapter, like credit pages, are skipped and left out of downloads
+    pub skip_repeated_pages: bool,
+    /// Hours like `23:00-07:00` during which `manga-tui watch` doesn't check for new chapters nor download them
+    pub quiet_hours: Option<String>,
+    /// The content ratings searched when the search page is opened, adult ones are searched without asking first
+    pub content_ratings: Vec<ContentRating>,
+    /// The most explicit content rating ever searched, so a profile for kids never gets adult mangas
+    pub max_content_rating: ContentRating,
+    /// Search while typing in the search bar, once no key was pressed for a moment
+    pub live_search: bool,
+    pub start_tab: StartTab,
+    /// Turned off by package managers which update manga-tui themselves
+    pub allow_self_update: bool,
+    /// Look for a newer release on startup and tell the user about it on the home page
+    pub check_for_updates: bool,
+    /// Show the manga and chapter being read in the title of the terminal window
+    pub set_terminal_title: bool,
+    /// Make manga titles and tags links to MangaDex in terminals which support OSC 8 hyperlinks
+    pub hyperlinks: bool,
+    /// What happens when downloading all the chapters of a manga finishes while another tab is open
+    pub download_finished_alert: TaskAlert,
+    /// What happens when importing mangas to the library finishes while another tab is open
+    pub import_finished_alert: TaskAlert,
+    /// Keep as few images in memory as possible, for machines like a Raspberry Pi used over SSH
+    pub low_memory_mode: bool,
+    /// ISO codes of the languages manga titles are shown in, the first one a manga has a title in is used
+    pub title_languages: Vec<String>,
+}
+
+impl Default for MangaTuiConfig {
+    fn default() -> Self {
+        Self {
+            download_type: DownloadType::default(),
+            image_quality: ImageQuality::default(),
+            cover_render_quality: RenderQuality::Fast,
+            page_render_quality: RenderQuality::Balanced,
+            reader_background: ReaderBackground::default(),
+            compare_language: None,
+            saved_pages_directory: None,
+            external_viewer: None,
+            translation_command: None,
+            reader_prefetch_pages: 5,
+            reader_memory_budget_mb: 256,
+            hide_reader_ui_after_seconds: 0,
+            skip_repeated_pages: false,
+            quiet_hours: None,
+            content_ratings: vec![ContentRating::Safe, ContentRating::Suggestive],
+            max_content_rating: ContentRating::Pornographic,
+            live_search: false,
+            start_tab: StartTab::default(),
+            allow_self_update: true,
+            check_for_updates: false,
+            set_terminal_title: true,
+            hyperlinks: true,
+            download_finished_alert: TaskAlert::default(),
+            import_finished_alert: TaskAlert::default(),
+            low_memory_mode: false,
+            title_languages: vec![],
+        }
+    }
 }
 
 pub static CONFIG_FILE: &str = "manga-tui-config.toml";
 
+/// Megabytes the decoded pages of a chapter can take in low memory mode
+static LOW_MEMORY_READER_BUDGET_MB: usize = 16;
+
 pub static CONFIG: OnceCell<MangaTuiConfig> = OnceCell::new();
 
 impl MangaTuiConfig {
@@ -41,6 +220,42 @@ impl MangaTuiConfig {
         CONFIG.get().expect("Could not get download type")
     }
 
+    pub fn saved_pages_directory(&self) -> PathBuf {
+        match self.saved_pages_directory.as_ref() {
+            Some(directory) => directory.clone(),
+            None => AppDirectories::MangaDownloads.into_path_buf().join("savedPages"),
+        }
+    }
+
+    /// The `compare_language` of the config, `None` when it's not set or is not a language MangaDex has
+    pub fn compare_language(&self) -> Option<Languages> {
+        self.compare_language.as_deref().and_then(Languages::try_from_iso_code)
+    }
+
+    /// The `quiet_hours` of the config, `None` when they are not set or can't be read
+    pub fn quiet_hours(&self) -> Option<QuietHours> {
+        self.quiet_hours.as_deref().and_then(QuietHours::parse)
+    }
+
+    /// Only the page being read is loaded in low memory mode
+    pub fn reader_prefetch_pages(&self) -> usize {
+        if self.low_memory_mode { 0 } else { self.reader_prefetch_pages }
+    }
+
+    /// Bytes the decoded pages of a chapter can take before the ones read the longest time ago are unloaded
+    pub fn reader_memory_budget(&self) -> usize {
+        let megabytes = if self.low_memory_mode {
+            self.reader_memory_budget_mb.min(LOW_MEMORY_READER_BUDGET_MB)
+        } else {
+            self.reader_memory_budget_mb
+        };
+        megabytes * 1024 * 1024
+    }
+
+    pub fn hide_reader_ui_after(&self) -> Option<Duration> {
+        (self.hide_reader_ui_after_seconds > 0).then(|| Duration::from_secs(self.hide_reader_ui_after_seconds))
+    }
+
     pub fn read_config(base_directory: &Path) -> Result<String, std::io::Error> {
         let config_file = base_directory.join(AppDirectories::Config.to_string()).join(CONFIG_FILE);
 
@@ -52,6 +267,14 @@ impl MangaTuiConfig {
         Ok(contents)
     }
 
+    /// Sets one option in the config file, `value` is written as it is so strings have to be quoted
+    pub fn save_value(base_directory: &Path, key: &str, value: &str) -> Result<(), std::io::Error> {
+        let contents = Self::read_config(base_directory)?;
+        let config_file = base_directory.join(AppDirectories::Config.to_string()).join(CONFIG_FILE);
+
+        std::fs::write(config_file, set_config_value(&contents, key, value))
+    }
+
     #[allow(clippy::format_collect)]
     pub fn write_config(base_directory: &Path) -> Result<(), std::io::Error> {
         let config_file = base_directory.join(AppDirectories::Config.to_string()).join(CONFIG_FILE);
@@ -63,10 +286,121 @@ impl MangaTuiConfig {
             # default : cbz
             download_type = "cbz"
 
-            # Download image quality, low quality means images are compressed and is recommended for slow internet connections
+            # Image quality of downloads and the one the reader starts with, low quality means images are compressed and is recommended for slow internet connections
+            # it can be changed while reading with <q>
             # values : low, high
             # default : low
             image_quality = "low"
+
+            # How covers and the pages in the reader are scaled to fit the terminal, high looks the sharpest but takes the longest
+            # values : fast, balanced, high
+            # default : fast for covers and balanced for pages
+            cover_render_quality = "fast"
+            page_render_quality = "balanced"
+
+            # The color around the pages in the reader, terminal leaves the background of your terminal
+            # values : terminal, black, white
+            # default : terminal
+            reader_background = "terminal"
+
+            # A second language to compare translations with, <D> in the manga page downloads the selected chapter in its language and in this one, side by side, and <L> in the reader switches to the same chapter in the other language
+            # values : the ISO code of a language on MangaDex, for example "en", "ja" or "es-la"
+            # default : not set
+            # compare_language = "ja"
+
+            # Where the page being read is saved with <x> in the reader
+            # default : the savedPages directory inside mangaDownloads
+            # saved_pages_directory = "/home/user/Pictures/manga"
+
+            # The command the page being read is opened with with <e> in the reader, useful if your terminal can't show images well, the path of the page is added at the end
+            # default : the default image viewer of your system
+            # external_viewer = "imv"
+
+            # The command the text of a page shown with <i> in the reader is translated with when pressing <I>, the text is written to its stdin and whatever it prints is shown over the page, only used when manga-tui is built with the `ocr` feature
+            # default : not set
+            # translation_command = "trans -brief :en"
+
+            # How many pages ahead of the current one the reader loads in the background
+            # values : any number, 0 only loads the page being read
+            # default : 5
+            reader_prefetch_pages = 5
+
+            # How many megabytes the pages loaded in the reader can take, once they take more the pages read the longest time ago are unloaded and loaded again from the disk cache when you go back to them
+            # values : any number, pages around the one being read are always kept
+            # default : 256
+            reader_memory_budget_mb = 256
+
+            # Seconds without pressing a key after which the status bar, the page list and the instructions are hidden in the reader so the pages take the whole screen, any key brings them back
+            # values : any number, 0 never hides them
+            # default : 0
+            hide_reader_ui_after_seconds = 0
+
+            # Whether pages which repeat an earlier page of the same chapter, like the credits some groups put at the start and the end, are skipped while reading and left out of downloads, it can be changed while reading with <R>
+            # values : true, false
+            # default : false
+            skip_repeated_pages = false
+
+            # Hours during which `manga-tui watch` stops checking for new chapters and downloading them, to respect night data caps or shared bandwidth, pressing enter while it runs or passing `--ignore-quiet-hours` checks during them anyway
+            # values : two times in 24 hour format, they can go past midnight
+            # default : not set
+            # quiet_hours = "23:00-07:00"
+
+            # The content ratings searched by default, they can be changed in the filters of the search page, turning on erotica or pornographic there asks for confirmation unless they are set here
+            # values : safe, suggestive, erotica, pornographic
+            # default : ["safe", "suggestive"]
+            content_ratings = ["safe", "suggestive"]
+
+            # The most explicit content rating searched anywhere, mangas rated above it are never looked up nor offered in the filters. Each profile started with `--profile` has a config file of its own, so it can be set for a profile used by kids
+            # values : safe, suggestive, erotica, pornographic
+            # default : pornographic
+            max_content_rating = "pornographic"
+
+            # Search as you type in the search bar of the search page, the search starts once you stop typing for a moment instead of pressing <Enter>
+            # values : true, false
+            # default : false
+            live_search = false
+
+            # The page manga-tui starts on, it can also be set with the `--tab` flag
+            # values : home, search, feed (or library), updates, popular
+            # default : home
+            start_tab = "home"
+
+            # Whether `manga-tui self-update` can replace this binary with the latest release, set it to false if manga-tui was installed with a package manager
+            # values : true, false
+            # default : true
+            allow_self_update = true
+
+            # Whether to look for a newer release of manga-tui on startup, at most once a day, and show a notice on the home page
+            # values : true, false
+            # default : false
+            check_for_updates = false
+
+            # Whether to show the manga and chapter being read in the title of the terminal window
+            # values : true, false
+            # default : true
+            set_terminal_title = true
+
+            # Whether manga titles and tags can be opened on MangaDex with ctrl + click, terminals without support for OSC 8 hyperlinks ignore them
+            # values : true, false
+            # default : true
+            hyperlinks = true
+
+            # What happens when a long task finishes while you are on another tab: downloading all the chapters of a manga and importing mangas to the library
+            # bell rings the terminal bell, flash makes the tabs at the top flash with a notice of what finished
+            # values : off, bell, flash, both
+            # default : off
+            download_finished_alert = "off"
+            import_finished_alert = "off"
+
+            # Keeps as few images in memory as possible for machines with little of it, like a Raspberry Pi used over SSH: the reader only loads the page being read and keeps at most 16 megabytes of pages, covers are always the small ones and the covers of the search grid are not kept
+            # values : true, false
+            # default : false
+            low_memory_mode = false
+
+            # The languages manga titles are shown in everywhere, the first one a manga has a title or an alternative title in is used, `original` is the language it was first published in
+            # values : ISO codes of languages like "en", "ja-ro" (romanized japanese) or "ko-ro", and "original"
+            # default : not set, the main title of each manga on MangaDex is shown
+            # title_languages = ["en", "ja-ro", "original"]
             "#;
 
             let contents: String = contents.trim().lines().map(|line| format!("{} \n", line.trim())).collect();
@@ -77,3 +411,54 @@ impl MangaTuiConfig {
         Ok(())
     }
 }
+
+/// Replaces the line of `key`, commented out or not, so that the comments explaining it are kept. Options the
+/// config file doesn't have yet are added at the end
+pub fn set_config_value(contents: &str, key: &str, value: &str) -> String {
+    let is_line_of_key = |line: &str| {
+        let line = line.trim_start().trim_start_matches('#').trim_start();
+        line.strip_prefix(key).is_some_and(|rest| rest.trim_start().starts_with('='))
+    };
+
+    let mut lines: Vec<String> = contents.lines().map(String::from).collect();
+    let new_line = format!("{key} = {value}");
+
+    match lines.iter_mut().find(|line| is_line_of_key(line)) {
+        Some(line) => *line = new_line,
+        None => lines.push(new_line),
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn options_are_set_where_they_are_explained() {
+        let contents = "# values : low, high\nimage_quality = \"low\"\n\n# default : not set\n# external_viewer = \"imv\"\n";
+
+        assert_eq!(
+            "# values : low, high\nimage_quality = \"high\"\n\n# default : not set\n# external_viewer = \"imv\"\n",
+            set_config_value(contents, "image_quality", "\"high\"")
+        );
+        assert_eq!(
+            "# values : low, high\nimage_quality = \"low\"\n\n# default : not set\nexternal_viewer = \"feh\"\n",
+            set_config_value(contents, "external_viewer", "\"feh\"")
+        );
+        assert!(set_config_value(contents, "live_search", "true").ends_with("external_viewer = \"imv\"\nlive_search = true\n"));
+    }
+
+    #[test]
+    fn low_memory_mode_keeps_one_page_in_the_reader() {
+        let mut config = MangaTuiConfig::default();
+        assert_eq!((5, 256 * 1024 * 1024), (config.reader_prefetch_pages(), config.reader_memory_budget()));
+
+        config.low_memory_mode = true;
+        assert_eq!((0, 16 * 1024 * 1024), (config.reader_prefetch_pages(), config.reader_memory_budget()));
+
+        config.reader_memory_budget_mb = 8;
+        assert_eq!(8 * 1024 * 1024, config.reader_memory_budget());
+    }
+}
@@ -5,6 +5,16 @@ use crate::backend::filter::Languages;
 
 pub static PREFERRED_LANGUAGE: OnceCell<Languages> = OnceCell::new();
 
+/// Set with `--safe-mode`, images, mouse capture, escape sequences and background tasks are turned off
+pub static SAFE_MODE: OnceCell<bool> = OnceCell::new();
+
+/// Set with `--profile`, the name of the directory the data of the profile is kept in
+pub static PROFILE: OnceCell<String> = OnceCell::new();
+
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE.get().copied().unwrap_or_default()
+}
+
 pub static INSTRUCTIONS_STYLE: Lazy<Style> = Lazy::new(|| Style::default().bold().underlined().yellow());
 
 pub static ERROR_STYLE: Lazy<Style> = Lazy::new(|| Style::default().bold().underlined().red().on_black());
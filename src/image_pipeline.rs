@@ -0,0 +1,9 @@
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageResult};
+
+/// Pages can be jpg, png or gif, the format is guessed from the bytes
+#[allow(deprecated)]
+pub fn decode_page(bytes: &[u8]) -> ImageResult<DynamicImage> {
+    image::io::Reader::new(Cursor::new(bytes)).with_guessed_format()?.decode()
+}
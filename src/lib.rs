@@ -19,3 +19,6 @@ macro_rules! build_check_exists_function {
         }
     };
 }
+
+/// Kept in the library so that the benchmarks in `benches` run the same code as the reader
+pub mod image_pipeline;
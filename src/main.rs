@@ -6,13 +6,25 @@ use clap::Parser;
 use ratatui::backend::CrosstermBackend;
 use reqwest::{Client, StatusCode};
 
-use self::backend::error_log::init_error_hooks;
+use self::backend::cache::{PageCache, MAX_PAGE_CACHE_BYTES};
+use self::backend::database::{
+    get_cover_override, get_last_chapter_read, remove_cover_override, remove_parental_lock, save_cover_override,
+    save_parental_lock, CoverOverride,
+};
+use self::backend::doctor::run_doctor;
+use self::backend::error_log::{init_error_hooks, write_to_error_log, write_to_safe_mode_log, ErrorType};
+use self::backend::export::export_reading_list;
 use self::backend::fetch::{MangadexClient, MANGADEX_CLIENT_INSTANCE};
-use self::backend::filter::Languages;
+use self::backend::filter::{ContentRating, Languages};
+use self::backend::import::{import_mangas, parse_manga_id};
+use self::backend::parental_lock::{is_valid_pin, read_pin, ParentalLock};
 use self::backend::tui::{init, restore, run_app};
-use self::backend::{build_data_dir, APP_DATA_DIR};
+use self::backend::update::{new_release_client, self_update, UpdateOutcome};
+use self::backend::watch::{watch_manga, WatchOptions};
+use self::backend::{build_data_dir, is_valid_profile_name, APP_DATA_DIR};
 use self::cli::CliArgs;
-use self::global::PREFERRED_LANGUAGE;
+use self::config::MangaTuiConfig;
+use self::global::{PREFERRED_LANGUAGE, PROFILE, SAFE_MODE};
 
 mod backend;
 mod cli;
@@ -22,10 +34,32 @@ mod global;
 mod utils;
 mod view;
 
+fn new_mangadex_client() -> MangadexClient {
+    let user_agent = format!(
+        "manga-tui/{} ({}/{}/{})",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::FAMILY,
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+
+    MangadexClient::new(Client::builder().timeout(Duration::from_secs(10)).user_agent(user_agent).build().unwrap())
+}
+
 #[tokio::main(flavor = "multi_thread", worker_threads = 7)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli_args = CliArgs::parse();
 
+    SAFE_MODE.set(cli_args.safe_mode).unwrap();
+
+    if let Some(profile) = cli_args.profile.clone() {
+        if !is_valid_profile_name(&profile) {
+            eprintln!("`{profile}` is not a valid profile name, only letters, numbers, `-` and `_` can be used");
+            return Ok(());
+        }
+        PROFILE.set(profile).unwrap();
+    }
+
     if cli_args.data_dir {
         let app_dir = APP_DATA_DIR.as_ref().unwrap();
         println!("{}", app_dir.to_str().unwrap());
@@ -59,20 +93,241 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     None => PREFERRED_LANGUAGE.set(Languages::default()).unwrap(),
                 }
             },
+            cli::Commands::Library { command } => match command {
+                cli::LibraryCommands::Export {
+                    format,
+                    category,
+                    output,
+                } => {
+                    if let Err(e) = build_data_dir() {
+                        eprintln!("Data directory could not be found: {e}");
+                        return Ok(());
+                    }
+
+                    let reading_list = export_reading_list(category, format)?;
+
+                    match output {
+                        Some(path) => std::fs::write(path, reading_list)?,
+                        None => print!("{reading_list}"),
+                    }
+
+                    return Ok(());
+                },
+                cli::LibraryCommands::Import { file, category } => {
+                    let links = std::fs::read_to_string(file)?;
+
+                    if let Err(e) = build_data_dir() {
+                        eprintln!("Data directory could not be found: {e}");
+                        return Ok(());
+                    }
+
+                    MANGADEX_CLIENT_INSTANCE.set(new_mangadex_client()).unwrap();
+
+                    let summary = import_mangas(&links, category).await;
+
+                    for title in &summary.imported {
+                        println!("Added {title}");
+                    }
+
+                    for link in &summary.failed {
+                        eprintln!("Could not import {link}");
+                    }
+
+                    println!("{} mangas imported, {} failed", summary.imported.len(), summary.failed.len());
+
+                    return Ok(());
+                },
+                cli::LibraryCommands::Cover {
+                    manga,
+                    file,
+                    volume,
+                    reset,
+                } => {
+                    let Some(manga_id) = parse_manga_id(&manga) else {
+                        eprintln!("`{manga}` is not the id of a manga or a mangadex link to one");
+                        return Ok(());
+                    };
+
+                    if let Err(e) = build_data_dir() {
+                        eprintln!("Data directory could not be found: {e}");
+                        return Ok(());
+                    }
+
+                    let cover = match (file, volume) {
+                        (Some(file), _) => match file.canonicalize() {
+                            Ok(path) => Some(CoverOverride::LocalFile(path)),
+                            Err(e) => {
+                                eprintln!("Could not find the image {}: {e}", file.display());
+                                return Ok(());
+                            },
+                        },
+                        (None, Some(volume)) => {
+                            MANGADEX_CLIENT_INSTANCE.set(new_mangadex_client()).unwrap();
+
+                            let covers = MangadexClient::global().get_volume_covers(&manga_id).await?;
+
+                            match covers.data.into_iter().find(|cover| cover.attributes.volume.as_deref() == Some(volume.as_str())) {
+                                Some(cover) => Some(CoverOverride::Volume {
+                                    volume,
+                                    file_name: cover.attributes.file_name,
+                                }),
+                                None => {
+                                    eprintln!("Volume {volume} of the manga has no cover");
+                                    return Ok(());
+                                },
+                            }
+                        },
+                        (None, None) => None,
+                    };
+
+                    match cover {
+                        Some(cover) => {
+                            save_cover_override(&manga_id, &cover)?;
+                            println!("The cover of the manga was changed");
+                        },
+                        None if reset => {
+                            if remove_cover_override(&manga_id)? {
+                                println!("The manga is back to the cover mangadex has");
+                            }
+                        },
+                        None => match get_cover_override(&manga_id)? {
+                            Some(CoverOverride::LocalFile(path)) => println!("The cover of the manga is {}", path.display()),
+                            Some(CoverOverride::Volume { volume, .. }) => println!("The cover of the manga is the one of volume {volume}"),
+                            None => println!("The manga has the cover mangadex has"),
+                        },
+                    }
+
+                    return Ok(());
+                },
+            },
+            cli::Commands::Doctor => {
+                MANGADEX_CLIENT_INSTANCE.set(new_mangadex_client()).unwrap();
+
+                if let Err(e) = run_doctor().await {
+                    eprintln!("{e}");
+                }
+
+                return Ok(());
+            },
+            cli::Commands::SelfUpdate => {
+                if let Err(e) = build_data_dir() {
+                    eprintln!("Data directory could not be found: {e}");
+                    return Ok(());
+                }
+
+                if !MangaTuiConfig::get().allow_self_update {
+                    println!("Self update is turned off in the config file, update manga-tui the way you installed it");
+                    return Ok(());
+                }
+
+                println!("Checking the latest release...");
+
+                match self_update(&new_release_client()).await {
+                    Ok(UpdateOutcome::UpToDate { version }) => println!("manga-tui {version} is already the latest version"),
+                    Ok(UpdateOutcome::Updated { from, to }) => println!("Updated manga-tui from {from} to {to}"),
+                    Err(e) => eprintln!("Could not update manga-tui: {e}"),
+                }
+
+                return Ok(());
+            },
+            cli::Commands::Lock { max_content_rating } => {
+                if let Err(e) = build_data_dir() {
+                    eprintln!("Data directory could not be found: {e}");
+                    return Ok(());
+                }
+
+                if let Some(lock) = ParentalLock::get() {
+                    if !lock.matches(&read_pin("Current PIN: ")?) {
+                        eprintln!("Wrong PIN");
+                        return Ok(());
+                    }
+                }
+
+                let pin = read_pin("New PIN, 4 to 8 digits: ")?;
+                if !is_valid_pin(&pin) {
+                    eprintln!("The PIN has to be 4 to 8 digits");
+                    return Ok(());
+                }
+                if read_pin("Repeat the PIN: ")? != pin {
+                    eprintln!("The PINs don't match, the profile was not locked");
+                    return Ok(());
+                }
+
+                let max_content_rating = ContentRating::from(max_content_rating.as_str());
+                save_parental_lock(&ParentalLock::new(&pin, max_content_rating))?;
+                println!("The profile was locked, mangas rated above {max_content_rating} are hidden");
+
+                return Ok(());
+            },
+            cli::Commands::Unlock => {
+                if let Err(e) = build_data_dir() {
+                    eprintln!("Data directory could not be found: {e}");
+                    return Ok(());
+                }
+
+                let Some(lock) = ParentalLock::get() else {
+                    println!("This profile is not locked");
+                    return Ok(());
+                };
+
+                if lock.matches(&read_pin("PIN: ")?) {
+                    remove_parental_lock()?;
+                    println!("The profile was unlocked");
+                } else {
+                    eprintln!("Wrong PIN");
+                }
+
+                return Ok(());
+            },
+            cli::Commands::Watch {
+                manga,
+                interval,
+                lang,
+                notify,
+                download,
+                ignore_quiet_hours,
+            } => {
+                let Some(manga_id) = parse_manga_id(&manga) else {
+                    eprintln!("`{manga}` is not the id of a manga or a mangadex link to one");
+                    return Ok(());
+                };
+
+                let Some(language) = Languages::try_from_iso_code(&lang) else {
+                    eprintln!(
+                        "`{lang}` is not a valid ISO language code, run `{} lang --print` to list available languages and their ISO codes",
+                        env!("CARGO_BIN_NAME")
+                    );
+                    return Ok(());
+                };
+
+                if let Err(e) = build_data_dir() {
+                    eprintln!("Data directory could not be found: {e}");
+                    return Ok(());
+                }
+
+                PREFERRED_LANGUAGE.set(language).unwrap();
+                MANGADEX_CLIENT_INSTANCE.set(new_mangadex_client()).unwrap();
+
+                let options = WatchOptions {
+                    manga_id,
+                    language,
+                    interval: Duration::from_secs(interval * 60),
+                    notify,
+                    download,
+                    quiet_hours: if ignore_quiet_hours { None } else { MangaTuiConfig::get().quiet_hours() },
+                };
+
+                if let Err(e) = watch_manga(options).await {
+                    eprintln!("Could not watch the manga: {e}");
+                }
+
+                return Ok(());
+            },
         },
         None => PREFERRED_LANGUAGE.set(Languages::default()).unwrap(),
     }
 
-    let user_agent = format!(
-        "manga-tui/{} ({}/{}/{})",
-        env!("CARGO_PKG_VERSION"),
-        std::env::consts::FAMILY,
-        std::env::consts::OS,
-        std::env::consts::ARCH
-    );
-
-    let mangadex_client =
-        MangadexClient::new(Client::builder().timeout(Duration::from_secs(10)).user_agent(user_agent).build().unwrap());
+    let mangadex_client = new_mangadex_client();
 
     println!("Checking mangadex status...");
 
@@ -106,9 +361,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         },
     }
 
+    write_to_safe_mode_log(&format!(
+        "starting manga-tui {} in safe mode on {} {}, TERM={} COLORTERM={} TERM_PROGRAM={}",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::var("TERM").unwrap_or_default(),
+        std::env::var("COLORTERM").unwrap_or_default(),
+        std::env::var("TERM_PROGRAM").unwrap_or_default()
+    ));
+
+    if let Some(cache) = PageCache::global().filter(|_| !cli_args.safe_mode) {
+        tokio::task::spawn_blocking(|| {
+            if let Err(e) = cache.prune(MAX_PAGE_CACHE_BYTES) {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+            }
+        });
+    }
+
+    let resume = if cli_args.resume {
+        match get_last_chapter_read() {
+            Ok(Some(chapter)) => Some(chapter),
+            Ok(None) => {
+                println!("There is no chapter to resume, read one first");
+                return Ok(());
+            },
+            Err(e) => {
+                eprintln!("Could not get the last chapter read from the history database: {e}");
+                return Ok(());
+            },
+        }
+    } else {
+        None
+    };
+
     init_error_hooks()?;
     init()?;
-    run_app(CrosstermBackend::new(std::io::stdout())).await?;
+    let start_tab = cli_args.tab.unwrap_or(MangaTuiConfig::get().start_tab);
+
+    run_app(CrosstermBackend::new(std::io::stdout()), start_tab, resume).await?;
     restore()?;
     Ok(())
 }
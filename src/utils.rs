@@ -1,10 +1,11 @@
 use std::io::Cursor;
 
 use image::io::Reader;
+use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Paragraph, Widget};
+use ratatui::widgets::{Block, Clear, Paragraph, Widget};
 use ratatui::Frame;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::JoinSet;
@@ -13,7 +14,8 @@ use tui_input::Input;
 use crate::backend::fetch::MangadexClient;
 use crate::backend::filter::Languages;
 use crate::backend::Data;
-use crate::common::{Artist, Author, Manga};
+use crate::common::{Artist, Author, Manga, MangaTag};
+use crate::config::CONFIG;
 use crate::view::widgets::filter_widget::state::{TagListItem, TagListItemState};
 use crate::view::widgets::ImageHandler;
 
@@ -73,20 +75,8 @@ pub fn search_manga_cover<IM: ImageHandler>(
 pub fn from_manga_response(value: Data) -> Manga {
     let id = value.id;
 
-    // Todo! maybe there is a better way to do this
-    let title = value.attributes.title.en.unwrap_or(
-        value.attributes.title.ja_ro.unwrap_or(
-            value.attributes.title.ja.unwrap_or(
-                value.attributes.title.jp.unwrap_or(
-                    value
-                        .attributes
-                        .title
-                        .zh
-                        .unwrap_or(value.attributes.title.ko.unwrap_or(value.attributes.title.ko_ro.unwrap_or_default())),
-                ),
-            ),
-        ),
-    );
+    let title_languages = CONFIG.get().map(|config| config.title_languages.as_slice()).unwrap_or_default();
+    let title = value.attributes.preferred_title(title_languages);
 
     let description = match value.attributes.description {
         Some(description) => description.en.unwrap_or("No description".to_string()),
@@ -95,7 +85,15 @@ pub fn from_manga_response(value: Data) -> Manga {
 
     let content_rating = value.attributes.content_rating;
 
-    let tags: Vec<String> = value.attributes.tags.iter().map(|tag| tag.attributes.name.en.to_string()).collect();
+    let tags: Vec<MangaTag> = value
+        .attributes
+        .tags
+        .iter()
+        .map(|tag| MangaTag {
+            id: tag.id.clone(),
+            name: tag.attributes.name.en.to_string(),
+        })
+        .collect();
 
     let mut img_url: Option<String> = Option::default();
     let mut author = Author::default();
@@ -136,6 +134,8 @@ pub fn from_manga_response(value: Data) -> Manga {
 
     let created_at = value.attributes.created_at;
 
+    let last_chapter = value.attributes.last_chapter.filter(|chapter| !chapter.is_empty());
+
     Manga {
         id,
         title,
@@ -149,6 +149,7 @@ pub fn from_manga_response(value: Data) -> Manga {
         publication_demographic,
         available_languages: languages,
         created_at,
+        last_chapter,
     }
 }
 
@@ -201,6 +202,25 @@ pub fn render_search_bar(is_typing: bool, input_help: Line<'_>, input: &Input, f
     }
 }
 
+/// Small notice drawn on the top right corner of `area` which doesn't block the rest of the page
+pub fn render_notice(message: &str, color: Color, area: Rect, buf: &mut Buffer) {
+    let width = (message.chars().count() as u16 + 4).min(area.width);
+    let height = 3.min(area.height);
+
+    let notice_area = Rect::new(area.x + area.width.saturating_sub(width), area.y, width, height);
+
+    Clear.render(notice_area, buf);
+    Paragraph::new(message)
+        .style(Style::default().fg(color))
+        .block(Block::bordered().border_style(Style::default().fg(color)))
+        .render(notice_area, buf);
+}
+
+/// Shown when a request is taking longer than `SLOW_REQUEST_THRESHOLD`
+pub fn render_slow_request_notice(area: Rect, buf: &mut Buffer) {
+    render_notice("MangaDex is responding slowly…", Color::Yellow, area, buf);
+}
+
 /// Remove special characteres that may cause errors
 pub fn to_filename(title: &str) -> String {
     let invalid_chars = ['\\', '/', ':', '*', '?', '"', '<', '>', '|'];
@@ -1,7 +1,10 @@
+use std::time::{Duration, Instant};
+
 use ::crossterm::event::KeyCode;
 use crossterm::event::{KeyEvent, KeyModifiers};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
 use ratatui::widgets::{Block, Borders, Tabs, Widget};
 use ratatui::Frame;
 use ratatui_image::picker::{Picker, ProtocolType};
@@ -10,15 +13,57 @@ use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use self::feed::Feed;
 use self::home::Home;
 use self::manga::MangaPage;
-use self::reader::MangaReader;
-use self::search::{InputMode, SearchPage};
+use self::popular::Popular;
+use self::reader::{ChapterToRead, MangaReader, ReadChapterInfo, READER_HINT};
+use self::search::SearchPage;
+use self::settings::Settings;
+use self::updates::Updates;
 use super::widgets::search::MangaItem;
 use super::widgets::Component;
-use crate::backend::tui::{Action, Events};
+use crate::backend::database::{hint_was_seen, LastChapterRead};
+use crate::backend::error_log::{write_to_error_log, ErrorType};
+use crate::backend::fetch::MangadexClient;
+use crate::backend::filter::Languages;
+use crate::backend::tui::{Action, Events, LongTask, Route};
 use crate::backend::ChapterPagesResponse;
-use crate::global::INSTRUCTIONS_STYLE;
+use crate::common::PageType;
+use crate::config::{ImageQuality, MangaTuiConfig, StartTab, TaskAlert, CONFIG};
+use crate::global::{is_safe_mode, INSTRUCTIONS_STYLE};
+use crate::utils::{from_manga_response, render_notice};
 use crate::view::pages::*;
 
+/// How long the tabs flash after a long task finished
+static FLASH_DURATION: Duration = Duration::from_secs(3);
+
+/// What is left to show of the last long task which finished while another tab was open
+#[derive(Default)]
+struct TaskFinishedAlert {
+    ring_bell: bool,
+    flash: Option<(String, Instant)>,
+}
+
+impl TaskFinishedAlert {
+    fn notify(&mut self, message: String, alert: TaskAlert) {
+        self.ring_bell |= alert.rings_bell();
+        if alert.flashes() {
+            self.flash = Some((message, Instant::now()));
+        }
+    }
+
+    /// The bell is rung only once
+    fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.ring_bell)
+    }
+
+    /// The notice shown while the tabs are flashing
+    fn flash_message(&mut self) -> Option<&str> {
+        if self.flash.as_ref().is_some_and(|(_, since)| since.elapsed() >= FLASH_DURATION) {
+            self.flash = None;
+        }
+        self.flash.as_ref().map(|(message, _)| message.as_str())
+    }
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
 pub enum AppState {
     Runnning,
@@ -37,6 +82,10 @@ pub struct App {
     pub search_page: SearchPage,
     pub home_page: Home,
     pub feed_page: Feed,
+    pub updates_page: Updates,
+    pub popular_page: Popular,
+    pub settings_page: Settings,
+    task_finished_alert: TaskFinishedAlert,
     // The picker is what decides how big a image needs to be rendered depending on the user's
     // terminal font size and the graphics it supports
     // if the terminal doesn't support any graphics protocol the picker is `None`
@@ -54,31 +103,34 @@ impl Component for App {
 
             let [top_tabs_area, page_area] = main_layout.areas(area);
 
-            self.render_top_tabs(top_tabs_area, frame.buffer_mut());
+            render_top_tabs(self.current_tab, top_tabs_area, frame.buffer_mut());
 
             self.render_pages(page_area, frame);
+
+            if self.task_finished_alert.flash_message().is_some() {
+                frame.buffer_mut().set_style(top_tabs_area, Style::default().fg(Color::Black).bg(Color::Yellow));
+            }
+        }
+
+        if let Some(message) = self.task_finished_alert.flash_message() {
+            render_notice(message, Color::Yellow, area, frame.buffer_mut());
+        }
+
+        if let Some(cooldown) = MangadexClient::global().circuit_breaker_cooldown() {
+            render_notice(
+                &format!("MangaDex keeps failing, requests are paused for {}s", cooldown.as_secs() + 1),
+                Color::Red,
+                area,
+                frame.buffer_mut(),
+            );
         }
     }
 
     fn handle_events(&mut self, events: Events) {
         match events {
             Events::Key(key_event) => self.handle_key_events(key_event),
-            Events::GoToMangaPage(manga) => self.go_to_manga_page(manga),
-            Events::ReadChapter(chapter_response) => self.go_to_read_chapter(chapter_response),
-            Events::GoSearchPage => {
-                self.go_search_page();
-            },
-            Events::GoToHome => self.go_to_home(),
-            Events::GoFeedPage => self.go_feed_page(),
-
-            Events::GoSearchMangasAuthor(author) => {
-                self.go_search_page();
-                self.search_page.search_mangas_of_author(author);
-            },
-            Events::GoSearchMangasArtist(artist) => {
-                self.go_search_page();
-                self.search_page.search_mangas_of_artist(artist);
-            },
+            Events::Navigate(route) => self.navigate(route),
+            Events::TaskFinished(task) => self.notify_task_finished(task),
             _ => {},
         }
     }
@@ -95,19 +147,53 @@ impl Component for App {
 }
 
 impl App {
-    pub fn new() -> Self {
+    fn navigate(&mut self, route: Route) {
+        match route {
+            Route::Home => self.go_to_tab(SelectedPage::Home),
+            Route::Search => self.go_to_tab(SelectedPage::Search),
+            Route::SearchMangasOfAuthor(author) => {
+                self.go_to_tab(SelectedPage::Search);
+                self.search_page.search_mangas_of_author(author);
+            },
+            Route::SearchMangasOfArtist(artist) => {
+                self.go_to_tab(SelectedPage::Search);
+                self.search_page.search_mangas_of_artist(artist);
+            },
+            Route::Feed => self.go_to_tab(SelectedPage::Feed),
+            Route::Updates => self.go_to_tab(SelectedPage::Updates),
+            Route::Popular => self.go_to_tab(SelectedPage::Popular),
+            Route::Settings => self.go_to_tab(SelectedPage::Settings),
+            Route::Manga(manga) => self.go_to_manga_page(manga),
+            Route::Reader { pages, chapter } => self.go_to_read_chapter(pages, chapter),
+            Route::ResumeChapter(chapter) => self.resume_chapter(chapter),
+        }
+    }
+
+    pub fn new(start_tab: StartTab) -> Self {
         let (global_action_tx, global_action_rx) = unbounded_channel::<Action>();
         let (global_event_tx, global_event_rx) = unbounded_channel::<Events>();
 
-        global_event_tx.send(Events::GoToHome).ok();
+        let go_to_start_tab = match start_tab {
+            StartTab::Home => Events::Navigate(Route::Home),
+            StartTab::Search => Events::Navigate(Route::Search),
+            StartTab::Feed => Events::Navigate(Route::Feed),
+            StartTab::Updates => Events::Navigate(Route::Updates),
+            StartTab::Popular => Events::Navigate(Route::Popular),
+        };
+
+        global_event_tx.send(go_to_start_tab).ok();
 
-        let picker = get_picker();
+        let picker = if is_safe_mode() { None } else { get_picker() };
 
         App {
             picker,
             current_tab: SelectedPage::default(),
             search_page: SearchPage::init(global_event_tx.clone(), picker),
             feed_page: Feed::new(global_event_tx.clone()),
+            updates_page: Updates::new(global_event_tx.clone()),
+            popular_page: Popular::new(global_event_tx.clone(), picker),
+            settings_page: Settings::new(),
+            task_finished_alert: TaskFinishedAlert::default(),
             home_page: Home::new(global_event_tx.clone(), picker),
             manga_page: None,
             manga_reader_page: None,
@@ -119,29 +205,34 @@ impl App {
         }
     }
 
-    pub fn render_top_tabs(&self, area: Rect, buf: &mut Buffer) {
-        let mut titles: Vec<&str> = vec!["Home <F1>/<u>", "Search <F2>/<i>", "Feed <F3>/<o>"];
+    /// Tasks which finish on the tab being looked at already show it there
+    fn notify_task_finished(&mut self, task: LongTask) {
+        if task.page() == self.current_tab {
+            return;
+        }
+        let alert = CONFIG.get().map(|config| task.alert(config)).unwrap_or_default();
+        self.task_finished_alert.notify(task.finished_message(), alert);
+    }
 
-        let tabs_block = Block::default().borders(Borders::BOTTOM);
+    pub fn take_bell(&mut self) -> bool {
+        self.task_finished_alert.take_bell()
+    }
 
-        let index_current_tab = match self.current_tab {
-            SelectedPage::Home => 0,
-            SelectedPage::Search => 1,
-            SelectedPage::Feed => 2,
-            SelectedPage::MangaTab => {
-                titles.push(" 📖 Manga page");
-                3
-            },
-            _ => 0,
-        };
+    /// The manga being read or looked at and the chapter, if any, shown in the title of the terminal window
+    pub fn terminal_title(&self) -> String {
+        let mut title = vec!["manga-tui".to_string()];
+
+        if matches!(self.current_tab, SelectedPage::MangaTab | SelectedPage::ReaderTab) {
+            if let Some(manga_page) = self.manga_page.as_ref() {
+                title.push(manga_page.manga.title.clone());
+            }
+        }
 
-        Tabs::new(titles)
-            .block(tabs_block)
-            .highlight_style(*INSTRUCTIONS_STYLE)
-            .select(index_current_tab)
-            .padding("", "")
-            .divider(" | ")
-            .render(area, buf);
+        if let (SelectedPage::ReaderTab, Some(reader)) = (self.current_tab, self.manga_reader_page.as_ref()) {
+            title.push(format!("Ch. {}", reader.chapter().number));
+        }
+
+        title.join(" - ")
     }
 
     pub fn render_pages(&mut self, area: Rect, frame: &mut Frame<'_>) {
@@ -150,6 +241,9 @@ impl App {
             SelectedPage::MangaTab => self.render_manga_page(area, frame),
             SelectedPage::Home => self.render_home_page(area, frame),
             SelectedPage::Feed => self.render_feed_page(area, frame),
+            SelectedPage::Updates => self.updates_page.render(area, frame),
+            SelectedPage::Popular => self.popular_page.render(area, frame),
+            SelectedPage::Settings => self.settings_page.render(area, frame),
             // Reader tab should be on full screen
             SelectedPage::ReaderTab => {},
         }
@@ -174,114 +268,271 @@ impl App {
     }
 
     fn handle_key_events(&mut self, key_event: KeyEvent) {
-        if self.manga_page.as_ref().is_some_and(|page| page.is_downloading_all_chapters()) {
+        if self.current_page_wants_input() {
             return;
         }
 
-        if self.search_page.input_mode != InputMode::Typing && !self.search_page.is_typing_filter() && !self.feed_page.is_typing() {
-            match key_event.code {
-                KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
-                    self.global_action_tx.send(Action::Quit).ok();
-                },
-                KeyCode::Char('u') | KeyCode::F(1) => {
-                    if self.current_tab != SelectedPage::ReaderTab {
-                        self.global_event_tx.send(Events::GoToHome).ok();
-                    }
-                },
-                KeyCode::Char('i') | KeyCode::F(2) => {
-                    if self.current_tab != SelectedPage::ReaderTab {
-                        self.global_event_tx.send(Events::GoSearchPage).ok();
-                    }
-                },
-                KeyCode::Char('o') | KeyCode::F(3) => {
-                    if self.current_tab != SelectedPage::ReaderTab {
-                        self.global_event_tx.send(Events::GoFeedPage).ok();
-                    }
-                },
-                KeyCode::Backspace => {
-                    if self.current_tab == SelectedPage::ReaderTab && self.manga_reader_page.is_some() {
-                        self.manga_reader_page.as_mut().unwrap().clean_up();
-                        self.current_tab = SelectedPage::MangaTab;
-                    }
-                },
+        match key_event.code {
+            KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.global_action_tx.send(Action::Quit).ok();
+            },
+            KeyCode::Char('u') | KeyCode::F(1) => {
+                if self.current_tab != SelectedPage::ReaderTab {
+                    self.global_event_tx.send(Events::Navigate(Route::Home)).ok();
+                }
+            },
+            KeyCode::Char('i') | KeyCode::F(2) => {
+                if self.current_tab != SelectedPage::ReaderTab {
+                    self.global_event_tx.send(Events::Navigate(Route::Search)).ok();
+                }
+            },
+            KeyCode::Char('o') | KeyCode::F(3) => {
+                if self.current_tab != SelectedPage::ReaderTab {
+                    self.global_event_tx.send(Events::Navigate(Route::Feed)).ok();
+                }
+            },
+            KeyCode::Char('z') | KeyCode::F(4) => {
+                if self.current_tab != SelectedPage::ReaderTab {
+                    self.global_event_tx.send(Events::Navigate(Route::Updates)).ok();
+                }
+            },
+            KeyCode::Char('e') | KeyCode::F(5) => {
+                if self.current_tab != SelectedPage::ReaderTab {
+                    self.global_event_tx.send(Events::Navigate(Route::Popular)).ok();
+                }
+            },
+            KeyCode::Char('S') | KeyCode::F(6) => {
+                if self.current_tab != SelectedPage::ReaderTab {
+                    self.global_event_tx.send(Events::Navigate(Route::Settings)).ok();
+                }
+            },
+            KeyCode::Backspace => {
+                if self.current_tab == SelectedPage::ReaderTab && self.manga_page.is_some() {
+                    self.show_page(SelectedPage::MangaTab);
+                    self.unmount_reader();
+                }
+            },
 
-                _ => {},
-            }
+            _ => {},
         }
     }
 
-    fn go_search_page(&mut self) {
-        if self.manga_page.is_some() {
-            self.manga_page.as_mut().unwrap().clean_up();
-            self.manga_page = None;
+    fn current_page_wants_input(&self) -> bool {
+        match self.current_tab {
+            SelectedPage::Home => self.home_page.wants_input(),
+            SelectedPage::Search => self.search_page.wants_input(),
+            SelectedPage::Feed => self.feed_page.wants_input(),
+            SelectedPage::Updates => self.updates_page.wants_input(),
+            SelectedPage::Popular => self.popular_page.wants_input(),
+            SelectedPage::Settings => self.settings_page.wants_input(),
+            SelectedPage::MangaTab => self.manga_page.as_ref().is_some_and(|page| page.wants_input()),
+            SelectedPage::ReaderTab => self.manga_reader_page.as_ref().is_some_and(|page| page.wants_input()),
         }
-        self.feed_page.clean_up();
-        self.current_tab = SelectedPage::Search;
     }
 
-    fn go_to_manga_page(&mut self, manga: MangaItem) {
-        if self.manga_reader_page.is_some() {
-            self.manga_reader_page.as_mut().unwrap().clean_up();
-            self.manga_reader_page = None;
+    /// Every page change goes through here so that the page left is blurred and the new one focused
+    fn show_page(&mut self, page: SelectedPage) {
+        match self.current_tab {
+            SelectedPage::Home => self.home_page.on_blur(),
+            SelectedPage::Search => self.search_page.on_blur(),
+            SelectedPage::Feed => self.feed_page.on_blur(),
+            SelectedPage::Updates => self.updates_page.on_blur(),
+            SelectedPage::Popular => self.popular_page.on_blur(),
+            SelectedPage::Settings => self.settings_page.on_blur(),
+            SelectedPage::MangaTab => {
+                if let Some(manga_page) = self.manga_page.as_mut() {
+                    manga_page.on_blur();
+                }
+            },
+            SelectedPage::ReaderTab => {
+                if let Some(reader) = self.manga_reader_page.as_mut() {
+                    reader.on_blur();
+                }
+            },
         }
 
-        self.feed_page.clean_up();
+        self.current_tab = page;
 
-        self.current_tab = SelectedPage::MangaTab;
-        self.manga_page = Some(MangaPage::new(manga.manga, self.global_event_tx.clone(), self.picker));
+        match self.current_tab {
+            SelectedPage::Home => self.home_page.on_focus(),
+            SelectedPage::Search => self.search_page.on_focus(),
+            SelectedPage::Feed => self.feed_page.on_focus(),
+            SelectedPage::Updates => self.updates_page.on_focus(),
+            SelectedPage::Popular => self.popular_page.on_focus(),
+            SelectedPage::Settings => self.settings_page.on_focus(),
+            SelectedPage::MangaTab => {
+                if let Some(manga_page) = self.manga_page.as_mut() {
+                    manga_page.on_focus();
+                }
+            },
+            SelectedPage::ReaderTab => {
+                if let Some(reader) = self.manga_reader_page.as_mut() {
+                    reader.on_focus();
+                }
+            },
+        }
     }
 
-    fn go_to_read_chapter(&mut self, chapter_response: ChapterPagesResponse) {
-        self.home_page.clean_up();
-        self.feed_page.clean_up();
-        self.current_tab = SelectedPage::ReaderTab;
-        self.manga_reader_page = Some(MangaReader::new(
-            self.global_event_tx.clone(),
-            chapter_response.chapter.hash,
-            chapter_response.base_url,
-            chapter_response.chapter.data_saver,
-            chapter_response.chapter.data,
-            self.picker.as_ref().cloned().unwrap(),
-        ));
+    fn unmount_manga_page(&mut self) {
+        if let Some(mut manga_page) = self.manga_page.take() {
+            manga_page.on_unmount();
+        }
     }
 
-    fn go_to_home(&mut self) {
-        if self.manga_page.is_some() {
-            self.manga_page.as_mut().unwrap().clean_up();
-            self.manga_page = None;
+    fn unmount_reader(&mut self) {
+        if let Some(mut reader) = self.manga_reader_page.take() {
+            reader.on_unmount();
         }
+    }
 
-        self.feed_page.clean_up();
+    /// The manga page and the reader only exist while they are being used, the other pages are kept
+    fn go_to_tab(&mut self, page: SelectedPage) {
+        self.show_page(page);
+        self.unmount_reader();
+        self.unmount_manga_page();
+    }
 
-        if self.home_page.require_search() {
-            self.home_page.init_search();
-        }
+    fn go_to_manga_page(&mut self, manga: MangaItem) {
+        self.unmount_reader();
+        self.unmount_manga_page();
 
-        self.current_tab = SelectedPage::Home;
+        self.manga_page = Some(MangaPage::new(manga.manga, self.global_event_tx.clone(), self.picker));
+        self.show_page(SelectedPage::MangaTab);
     }
 
-    fn go_feed_page(&mut self) {
-        if self.manga_page.is_some() {
-            self.manga_page.as_mut().unwrap().clean_up();
-            self.manga_page = None;
-        }
-        self.feed_page.init_search();
-        self.current_tab = SelectedPage::Feed;
+    fn go_to_read_chapter(&mut self, chapter_response: ChapterPagesResponse, chapter: ReadChapterInfo) {
+        self.unmount_reader();
+
+        let config = MangaTuiConfig::get();
+
+        let show_hint = match hint_was_seen(READER_HINT) {
+            Ok(seen) => !seen,
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                false
+            },
+        };
+
+        let page_type = match config.image_quality {
+            ImageQuality::Low => PageType::LowQuality,
+            ImageQuality::High => PageType::HighQuality,
+        };
+
+        self.manga_reader_page = Some(
+            MangaReader::new(
+                self.global_event_tx.clone(),
+                ChapterToRead {
+                    chapter,
+                    hash: chapter_response.chapter.hash,
+                    base_url: chapter_response.base_url,
+                    data: chapter_response.chapter.data,
+                    data_saver: chapter_response.chapter.data_saver,
+                    page_type,
+                },
+                config.reader_prefetch_pages(),
+                config.reader_memory_budget(),
+                config.page_render_quality.filter(),
+                config.reader_background,
+                self.picker.as_ref().cloned().unwrap(),
+            )
+            .with_ui_hidden_after(config.hide_reader_ui_after())
+            .with_repeated_pages_skipped(config.skip_repeated_pages)
+            .with_compare_language(config.compare_language())
+            .with_first_time_hint(show_hint),
+        );
+        self.show_page(SelectedPage::ReaderTab);
+    }
+
+    /// Opens the manga page of the chapter and then the chapter itself, so that going back from the
+    /// reader lands on the manga as if it had been opened by the user
+    pub fn resume_chapter(&mut self, chapter: LastChapterRead) {
+        let tx = self.global_event_tx.clone();
+        let can_read = self.picker.is_some();
+
+        tokio::spawn(async move {
+            match MangadexClient::global().get_one_manga(&chapter.manga_id).await {
+                Ok(manga) => {
+                    tx.send(Events::Navigate(Route::Manga(MangaItem::new(from_manga_response(manga.data)))))
+                        .ok();
+                },
+                Err(e) => return write_to_error_log(ErrorType::FromError(Box::new(e))),
+            }
+
+            if !can_read {
+                return;
+            }
+
+            match MangadexClient::global().get_chapter_pages(&chapter.chapter_id).await {
+                Ok(response) => {
+                    tx.send(Events::Navigate(Route::Reader {
+                        pages: response,
+                        chapter: ReadChapterInfo {
+                            manga_id: chapter.manga_id,
+                            chapter_id: chapter.chapter_id,
+                            number: chapter.chapter_number,
+                            title: chapter.chapter_title,
+                            language: *Languages::get_preferred_lang(),
+                        },
+                    }))
+                    .ok();
+                },
+                Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
+            }
+        });
     }
 }
 
-#[cfg(unix)]
+pub fn render_top_tabs(current_tab: SelectedPage, area: Rect, buf: &mut Buffer) {
+    let mut titles: Vec<&str> = vec![
+        "Home <F1>/<u>",
+        "Search <F2>/<i>",
+        "Feed <F3>/<o>",
+        "Updates <F4>/<z>",
+        "Popular <F5>/<e>",
+        "Settings <F6>/<S>",
+    ];
+
+    let tabs_block = Block::default().borders(Borders::BOTTOM);
+
+    let index_current_tab = match current_tab {
+        SelectedPage::Home => 0,
+        SelectedPage::Search => 1,
+        SelectedPage::Feed => 2,
+        SelectedPage::Updates => 3,
+        SelectedPage::Popular => 4,
+        SelectedPage::Settings => 5,
+        SelectedPage::MangaTab => {
+            titles.push(" 📖 Manga page");
+            6
+        },
+        _ => 0,
+    };
+
+    Tabs::new(titles)
+        .block(tabs_block)
+        .highlight_style(*INSTRUCTIONS_STYLE)
+        .select(index_current_tab)
+        .padding("", "")
+        .divider(" | ")
+        .render(area, buf);
+}
+
+/// Images are only shown in terminals which support a graphics protocol, halfblocks look too bad to read manga
 fn get_picker() -> Option<Picker> {
-    Picker::from_termios()
-        .ok()
-        .map(|mut picker| {
-            picker.guess_protocol();
-            picker
-        })
-        .filter(|picker| picker.protocol_type != ProtocolType::Halfblocks)
+    detect_picker().filter(|picker| picker.protocol_type != ProtocolType::Halfblocks)
+}
+
+/// The font size of the terminal and the graphics protocol it most likely supports, `None` if the font size
+/// can't be found
+#[cfg(unix)]
+pub fn detect_picker() -> Option<Picker> {
+    Picker::from_termios().ok().map(|mut picker| {
+        picker.guess_protocol();
+        picker
+    })
 }
 #[cfg(target_os = "windows")]
-fn get_picker() -> Option<Picker> {
+pub fn detect_picker() -> Option<Picker> {
     use windows_sys::Win32::System::Console::GetConsoleWindow;
     use windows_sys::Win32::UI::HiDpi::GetDpiForWindow;
 
@@ -316,10 +567,64 @@ fn get_picker() -> Option<Picker> {
 
     let mut picker = Picker::new((size.width, size.height));
 
-    let protocol = picker.guess_protocol();
+    picker.guess_protocol();
 
-    if protocol == ProtocolType::Halfblocks {
-        return None;
-    }
     Some(picker)
 }
+
+#[cfg(test)]
+mod test {
+    use insta::assert_snapshot;
+
+    use super::*;
+    use crate::common::Manga;
+    use crate::view::pages::manga::MangaPageActions;
+    use crate::view::widgets::render_to_lines;
+
+    fn render_tabs(current_tab: SelectedPage, width: u16) -> Vec<String> {
+        render_to_lines(width, 2, |frame| render_top_tabs(current_tab, frame.size(), frame.buffer_mut()))
+    }
+
+    #[test]
+    fn finished_tasks_ring_the_bell_once_and_flash_for_a_moment() {
+        let mut alert = TaskFinishedAlert::default();
+
+        alert.notify("import".to_string(), TaskAlert::Off);
+        assert!(!alert.take_bell());
+        assert_eq!(None, alert.flash_message());
+
+        alert.notify("download".to_string(), TaskAlert::Both);
+        assert!(alert.take_bell());
+        assert!(!alert.take_bell());
+        assert_eq!(Some("download"), alert.flash_message());
+
+        alert.flash = Some(("download".to_string(), Instant::now() - FLASH_DURATION));
+        assert_eq!(None, alert.flash_message());
+    }
+
+    #[test]
+    fn top_tabs_layout() {
+        assert_snapshot!("top_tabs_home", render_tabs(SelectedPage::Home, 110).join("\n"));
+        assert_snapshot!("top_tabs_manga_page", render_tabs(SelectedPage::MangaTab, 125).join("\n"));
+        assert_snapshot!("top_tabs_narrow", render_tabs(SelectedPage::Search, 30).join("\n"));
+    }
+
+    #[tokio::test]
+    async fn reading_order_key_stays_on_the_manga_page() {
+        let mut app = App::new(StartTab::Home);
+        while app.global_event_rx.try_recv().is_ok() {}
+
+        app.manga_page = Some(MangaPage::new(Manga::default(), app.global_event_tx.clone(), None));
+        app.current_tab = SelectedPage::MangaTab;
+
+        let key = Events::Key(KeyEvent::from(KeyCode::Char('n')));
+        app.handle_events(key.clone());
+        app.manga_page.as_mut().unwrap().handle_events(key);
+
+        assert!(app.global_event_rx.try_recv().is_err());
+        assert!(app.current_tab == SelectedPage::MangaTab);
+
+        let action = app.manga_page.as_mut().unwrap().local_action_rx.recv().await.unwrap();
+        assert_eq!(MangaPageActions::ToggleReadingOrder, action);
+    }
+}
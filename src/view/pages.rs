@@ -3,8 +3,11 @@ use strum::{Display, EnumCount, EnumIter, FromRepr};
 pub mod feed;
 pub mod home;
 pub mod manga;
+pub mod popular;
 pub mod reader;
 pub mod search;
+pub mod settings;
+pub mod updates;
 
 #[derive(Clone, Copy, Default, FromRepr, Display, EnumIter, EnumCount, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SelectedPage {
@@ -14,4 +17,7 @@ pub enum SelectedPage {
     Home,
     Search,
     Feed,
+    Updates,
+    Popular,
+    Settings,
 }
@@ -1,9 +1,11 @@
+use std::time::{Duration, Instant};
+
 use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Margin, Rect};
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span, ToSpan};
-use ratatui::widgets::{Block, Paragraph, StatefulWidget, Tabs, Widget};
+use ratatui::widgets::{Block, Clear, Paragraph, StatefulWidget, Tabs, Widget};
 use ratatui::Frame;
 use throbber_widgets_tui::{Throbber, ThrobberState};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
@@ -11,14 +13,20 @@ use tokio::task::JoinSet;
 use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
 
-use crate::backend::database::{get_history, MangaHistoryResponse, MangaHistoryType};
+use crate::backend::database::{
+    get_chapters_read, get_history, get_library_filters, get_read_chapter_numbers, merge_duplicate_mangas,
+    save_library_filters, ChapterRead, LastChapterRead, MangaHistoryResponse, MangaHistoryType,
+};
 use crate::backend::error_log::{write_to_error_log, ErrorType};
-use crate::backend::fetch::MangadexClient;
-use crate::backend::tui::Events;
+use crate::backend::fetch::{MangadexClient, SLOW_REQUEST_THRESHOLD};
+use crate::backend::filter::Languages;
+use crate::backend::import::{import_mangas, ImportCategory, ImportSummary};
+use crate::backend::tui::{Events, LongTask, Route};
 use crate::backend::ChapterResponse;
+use crate::common::LibraryFilters;
 use crate::global::{ERROR_STYLE, INSTRUCTIONS_STYLE};
-use crate::utils::{from_manga_response, render_search_bar};
-use crate::view::widgets::feed::{FeedTabs, HistoryWidget, MangasRead};
+use crate::utils::{from_manga_response, render_notice, render_search_bar, render_slow_request_notice};
+use crate::view::widgets::feed::{group_into_sessions, FeedTabs, HistoryWidget, MangasRead, SessionsWidget};
 use crate::view::widgets::search::MangaItem;
 use crate::view::widgets::Component;
 
@@ -39,6 +47,12 @@ pub enum FeedActions {
     PreviousPage,
     ChangeTab,
     GoToMangaPage,
+    ToggleImportDialog,
+    ImportMangas,
+    CycleSort,
+    ToggleDownloadedOnly,
+    MergeDuplicates,
+    ContinueReading,
 }
 
 pub enum FeedEvents {
@@ -49,13 +63,23 @@ pub enum FeedEvents {
     ErrorSearchingMangaData,
     /// page , (history_data, total_results)
     LoadHistory(Option<MangaHistoryResponse>),
+    LoadSessions(Option<Vec<ChapterRead>>),
+    FinishedImport(ImportSummary),
+    /// Every chapter of the selected manga was read
+    NothingToContinue,
 }
 
+/// How long the result of an import or a merge stays on screen
+static NOTICE_DURATION: Duration = Duration::from_secs(5);
+
 pub struct Feed {
     pub tabs: FeedTabs,
     state: FeedState,
     pub history: Option<HistoryWidget>,
+    pub sessions: Option<SessionsWidget>,
     pub loading_state: Option<ThrobberState>,
+    /// When the request for the manga page started, used to let the user know mangadex is taking too long
+    searching_manga_since: Option<Instant>,
     pub global_event_tx: UnboundedSender<Events>,
     pub local_action_tx: UnboundedSender<FeedActions>,
     pub local_action_rx: UnboundedReceiver<FeedActions>,
@@ -63,6 +87,15 @@ pub struct Feed {
     pub local_event_rx: UnboundedReceiver<FeedEvents>,
     search_bar: Input,
     is_typing: bool,
+    /// Input where mangadex links are pasted to be imported, `Some` while the dialog is open
+    import_input: Option<Input>,
+    is_importing: bool,
+    import_finished: Option<(ImportSummary, Instant)>,
+    /// How many entries were merged into the selected manga
+    merge_finished: Option<(usize, Instant)>,
+    /// When the selected manga turned out to have no chapter left to read
+    nothing_to_continue: Option<Instant>,
+    filters: LibraryFilters,
     tasks: JoinSet<()>,
 }
 
@@ -70,10 +103,21 @@ impl Feed {
     pub fn new(global_event_tx: UnboundedSender<Events>) -> Self {
         let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<FeedActions>();
         let (local_event_tx, local_event_rx) = mpsc::unbounded_channel::<FeedEvents>();
+
+        let filters = match get_library_filters() {
+            Ok(filters) => filters.unwrap_or_default(),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                LibraryFilters::default()
+            },
+        };
+
         Self {
             tabs: FeedTabs::History,
             loading_state: None,
+            searching_manga_since: None,
             history: None,
+            sessions: None,
             state: FeedState::DisplayingHistory,
             global_event_tx,
             local_action_tx,
@@ -83,14 +127,29 @@ impl Feed {
             tasks: JoinSet::new(),
             search_bar: Input::default(),
             is_typing: false,
+            import_input: None,
+            is_importing: false,
+            import_finished: None,
+            merge_finished: None,
+            nothing_to_continue: None,
+            filters,
         }
     }
 
     pub fn is_typing(&self) -> bool {
-        self.is_typing
+        self.is_typing || self.import_input.is_some()
     }
 
     fn render_history(&mut self, area: Rect, buf: &mut Buffer) {
+        if let Some(sessions) = self.sessions.as_mut() {
+            if sessions.sessions.is_empty() {
+                Paragraph::new("It seems you have not read any chapters yet").render(area, buf);
+            } else {
+                StatefulWidget::render(sessions.clone(), area, buf, &mut sessions.state);
+            }
+            return;
+        }
+
         match self.history.as_mut() {
             Some(history) => {
                 if history.mangas.is_empty() {
@@ -118,13 +177,28 @@ impl Feed {
         let selected_tab = match self.tabs {
             FeedTabs::History => 0,
             FeedTabs::PlantToRead => 1,
+            FeedTabs::Sessions => 2,
         };
 
-        let tabs_instructions = Line::from(vec!["Switch tab: ".into(), Span::raw("<tab>").style(*INSTRUCTIONS_STYLE)]);
-
-        Tabs::new(vec!["Reading history", "Plan to Read"])
+        let tabs_instructions = Line::from(vec![
+            "Switch tab: ".into(),
+            Span::raw("<tab>").style(*INSTRUCTIONS_STYLE),
+            " Import links: ".into(),
+            Span::raw("<p>").style(*INSTRUCTIONS_STYLE),
+            " Continue reading: ".into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        let filters = Line::from(vec![
+            format!("Sort: {} ", self.filters.sort.as_human_readable()).into(),
+            Span::raw("<c>").style(*INSTRUCTIONS_STYLE),
+            if self.filters.downloaded_only { " Downloaded only " } else { " All mangas " }.into(),
+            Span::raw("<d>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        Tabs::new(vec!["Reading history", "Plan to Read", "Reading sessions"])
             .select(selected_tab)
-            .block(Block::bordered().title(tabs_instructions))
+            .block(Block::bordered().title(tabs_instructions).title_bottom(filters))
             .highlight_style(Style::default().fg(Color::Yellow))
             .render(tabs_area, frame.buffer_mut());
 
@@ -154,6 +228,10 @@ impl Feed {
                 buf,
                 state,
             );
+
+            if self.searching_manga_since.is_some_and(|since| since.elapsed() >= SLOW_REQUEST_THRESHOLD) {
+                render_slow_request_notice(area, buf);
+            }
         }
         if self.state == FeedState::MangaPageNotFound {
             Paragraph::new(
@@ -173,12 +251,87 @@ impl Feed {
         self.render_searching_status(searching_area, frame.buffer_mut());
     }
 
+    fn render_import_dialog(&mut self, area: Rect, frame: &mut Frame) {
+        let Some(input) = self.import_input.as_ref() else {
+            return;
+        };
+
+        let width = area.width * 8 / 10;
+        let dialog_area =
+            Rect::new(area.x + (area.width - width) / 2, area.y + area.height.saturating_sub(3) / 2, width, 3.min(area.height));
+
+        let category = match self.tabs {
+            FeedTabs::History | FeedTabs::Sessions => "reading history",
+            FeedTabs::PlantToRead => "plan to read",
+        };
+
+        let input_help = Line::from(vec![
+            format!("Paste mangadex links or ids to add to {category}, ").into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+            " import ".into(),
+            Span::raw("<Esc>").style(*INSTRUCTIONS_STYLE),
+            " cancel".into(),
+        ]);
+
+        Clear.render(dialog_area, frame.buffer_mut());
+        render_search_bar(true, input_help, input, frame, dialog_area);
+    }
+
+    fn render_import_status(&mut self, area: Rect, buf: &mut Buffer) {
+        if self.is_importing {
+            render_notice("Importing mangas...", Color::Yellow, area, buf);
+        } else if let Some((summary, finished_at)) = self.import_finished.as_ref() {
+            if finished_at.elapsed() >= NOTICE_DURATION {
+                self.import_finished = None;
+                return;
+            }
+
+            let message = format!("{} mangas imported, {} failed", summary.imported.len(), summary.failed.len());
+            let color = if summary.failed.is_empty() { Color::Green } else { Color::Red };
+            render_notice(&message, color, area, buf);
+        }
+    }
+
+    fn render_merge_status(&mut self, area: Rect, buf: &mut Buffer) {
+        if let Some((merged, finished_at)) = self.merge_finished {
+            if finished_at.elapsed() >= NOTICE_DURATION {
+                self.merge_finished = None;
+                return;
+            }
+
+            render_notice(&format!("{merged} entries merged"), Color::Green, area, buf);
+        }
+    }
+
+    fn render_continue_status(&mut self, area: Rect, buf: &mut Buffer) {
+        if let Some(searched_at) = self.nothing_to_continue {
+            if searched_at.elapsed() >= NOTICE_DURATION {
+                self.nothing_to_continue = None;
+                return;
+            }
+
+            render_notice("You are caught up, every chapter available was read", Color::Yellow, area, buf);
+        }
+    }
+
     pub fn init_search(&mut self) {
         self.local_event_tx.send(FeedEvents::SearchHistory).ok();
     }
 
     fn handle_key_events(&mut self, key_event: KeyEvent) {
-        if self.is_typing && self.state != FeedState::SearchingMangaPage {
+        if let Some(input) = self.import_input.as_mut() {
+            match key_event.code {
+                KeyCode::Enter => {
+                    self.local_action_tx.send(FeedActions::ImportMangas).ok();
+                },
+                KeyCode::Esc => {
+                    self.local_action_tx.send(FeedActions::ToggleImportDialog).ok();
+                },
+                _ => {
+                    input.handle_event(&crossterm::event::Event::Key(key_event));
+                },
+            };
+        } else if self.is_typing && self.state != FeedState::SearchingMangaPage {
             match key_event.code {
                 KeyCode::Enter => {
                     self.local_event_tx.send(FeedEvents::SearchHistory).ok();
@@ -214,6 +367,21 @@ impl Feed {
                 KeyCode::Char('s') => {
                     self.local_action_tx.send(FeedActions::ToggleSearchBar).ok();
                 },
+                KeyCode::Char('p') => {
+                    self.local_action_tx.send(FeedActions::ToggleImportDialog).ok();
+                },
+                KeyCode::Char('c') => {
+                    self.local_action_tx.send(FeedActions::CycleSort).ok();
+                },
+                KeyCode::Char('d') => {
+                    self.local_action_tx.send(FeedActions::ToggleDownloadedOnly).ok();
+                },
+                KeyCode::Char('m') => {
+                    self.local_action_tx.send(FeedActions::MergeDuplicates).ok();
+                },
+                KeyCode::Enter => {
+                    self.local_action_tx.send(FeedActions::ContinueReading).ok();
+                },
                 _ => {},
             }
         }
@@ -229,10 +397,18 @@ impl Feed {
                 FeedEvents::ErrorSearchingMangaData => self.display_error_searching_manga(),
                 FeedEvents::SearchHistory => self.search_history(),
                 FeedEvents::LoadHistory(maybe_history) => self.load_history(maybe_history),
+                FeedEvents::LoadSessions(maybe_chapters) => self.load_sessions(maybe_chapters),
                 FeedEvents::SearchRecentChapters => self.search_latest_chapters(),
                 FeedEvents::LoadRecentChapters(manga_id, maybe_chapters) => {
                     self.load_recent_chapters(manga_id, maybe_chapters);
                 },
+                FeedEvents::FinishedImport(summary) => self.finish_import(summary),
+                FeedEvents::NothingToContinue => {
+                    self.loading_state = None;
+                    self.searching_manga_since = None;
+                    self.state = FeedState::DisplayingHistory;
+                    self.nothing_to_continue = Some(Instant::now());
+                },
             }
         }
     }
@@ -268,6 +444,7 @@ impl Feed {
 
     fn display_error_searching_manga(&mut self) {
         self.loading_state = None;
+        self.searching_manga_since = None;
         self.state = FeedState::MangaPageNotFound;
     }
 
@@ -276,6 +453,7 @@ impl Feed {
         let tx = self.local_event_tx.clone();
         self.tasks.abort_all();
         let search_term = self.search_bar.value().to_string();
+        let filters = self.filters;
 
         let page = match &self.history {
             Some(history) => history.page,
@@ -285,10 +463,24 @@ impl Feed {
         let history_type = match self.tabs {
             FeedTabs::History => MangaHistoryType::ReadingHistory,
             FeedTabs::PlantToRead => MangaHistoryType::PlanToRead,
+            FeedTabs::Sessions => {
+                self.tasks.spawn(async move {
+                    match get_chapters_read(&search_term) {
+                        Ok(chapters) => {
+                            tx.send(FeedEvents::LoadSessions(Some(chapters))).ok();
+                        },
+                        Err(e) => {
+                            write_to_error_log(ErrorType::FromError(Box::new(e)));
+                            tx.send(FeedEvents::LoadSessions(None)).ok();
+                        },
+                    }
+                });
+                return;
+            },
         };
 
         self.tasks.spawn(async move {
-            let maybe_reading_history = get_history(history_type, page, &search_term);
+            let maybe_reading_history = get_history(history_type, page, &search_term, filters);
 
             match maybe_reading_history {
                 Ok(history) => {
@@ -316,7 +508,22 @@ impl Feed {
         }
     }
 
+    fn load_sessions(&mut self, maybe_chapters: Option<Vec<ChapterRead>>) {
+        self.history = None;
+        match maybe_chapters {
+            Some(chapters) => {
+                self.sessions = Some(SessionsWidget::new(group_into_sessions(chapters)));
+                self.state = FeedState::DisplayingHistory;
+            },
+            None => {
+                self.state = FeedState::ErrorSearchingHistory;
+                self.sessions = None;
+            },
+        }
+    }
+
     fn load_history(&mut self, maybe_history: Option<MangaHistoryResponse>) {
+        self.sessions = None;
         match maybe_history {
             Some(history) => {
                 self.history = Some(HistoryWidget {
@@ -330,6 +537,7 @@ impl Feed {
                             title: history.title.clone(),
                             recent_chapters: vec![],
                             style: Style::default(),
+                            has_duplicates: history.has_duplicates,
                         })
                         .collect(),
                     state: tui_widget_list::ListState::default(),
@@ -348,45 +556,174 @@ impl Feed {
         if let Some(mangas) = self.history.as_mut() {
             mangas.select_next();
         }
+        if let Some(sessions) = self.sessions.as_mut() {
+            sessions.select_next();
+        }
     }
 
     fn select_previous_manga(&mut self) {
         if let Some(mangas) = self.history.as_mut() {
             mangas.select_previous();
         }
+        if let Some(sessions) = self.sessions.as_mut() {
+            sessions.select_previous();
+        }
     }
 
     fn change_tab(&mut self) {
         match self.tabs {
             FeedTabs::History => self.tabs = FeedTabs::PlantToRead,
-            FeedTabs::PlantToRead => self.tabs = FeedTabs::History,
+            FeedTabs::PlantToRead => self.tabs = FeedTabs::Sessions,
+            FeedTabs::Sessions => self.tabs = FeedTabs::History,
         }
     }
 
+    fn get_current_manga_selected(&self) -> Option<String> {
+        if let Some(sessions) = self.sessions.as_ref() {
+            return sessions.get_current_manga_selected().map(String::from);
+        }
+        self.history.as_ref()?.get_current_manga_selected().map(|manga| manga.id.clone())
+    }
+
     fn go_to_manga_page(&mut self) {
-        if let Some(history) = self.history.as_mut() {
-            if let Some(currently_selected_manga) = history.get_current_manga_selected() {
-                self.state = FeedState::SearchingMangaPage;
-                let tx = self.global_event_tx.clone();
-                let loca_tx = self.local_event_tx.clone();
-                let manga_id = currently_selected_manga.id.clone();
+        if let Some(manga_id) = self.get_current_manga_selected() {
+            self.state = FeedState::SearchingMangaPage;
+            let tx = self.global_event_tx.clone();
+            let loca_tx = self.local_event_tx.clone();
+
+            self.loading_state = Some(ThrobberState::default());
+            self.searching_manga_since = Some(Instant::now());
+            self.tasks.spawn(async move {
+                let response = MangadexClient::global().get_one_manga(&manga_id).await;
+                match response {
+                    Ok(manga) => {
+                        let manga_found = from_manga_response(manga.data);
+                        tx.send(Events::Navigate(Route::Manga(MangaItem::new(manga_found)))).ok();
+                    },
+                    Err(e) => {
+                        write_to_error_log(ErrorType::FromError(Box::new(e)));
+                        loca_tx.send(FeedEvents::ErrorSearchingMangaData).ok();
+                    },
+                }
+            });
+        }
+    }
 
-                self.loading_state = Some(ThrobberState::default());
-                self.tasks.spawn(async move {
-                    let response = MangadexClient::global().get_one_manga(&manga_id).await;
-                    match response {
-                        Ok(manga) => {
-                            let manga_found = from_manga_response(manga.data);
-                            tx.send(Events::GoToMangaPage(MangaItem::new(manga_found))).ok();
-                        },
-                        Err(e) => {
-                            write_to_error_log(ErrorType::FromError(Box::new(e)));
-                            loca_tx.send(FeedEvents::ErrorSearchingMangaData).ok();
-                        },
-                    }
-                });
+    /// Opens the first chapter after the highest one read, in the preferred language, going through
+    /// the manga page so that leaving the reader lands on it
+    fn continue_reading(&mut self) {
+        let Some(manga_id) = self.get_current_manga_selected() else {
+            return;
+        };
+
+        self.state = FeedState::SearchingMangaPage;
+        self.nothing_to_continue = None;
+        let tx = self.global_event_tx.clone();
+        let local_tx = self.local_event_tx.clone();
+
+        self.loading_state = Some(ThrobberState::default());
+        self.searching_manga_since = Some(Instant::now());
+        self.tasks.spawn(async move {
+            let read_numbers = get_read_chapter_numbers(&manga_id).unwrap_or_else(|e| {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                vec![]
+            });
+
+            let language = *Languages::get_preferred_lang();
+            match MangadexClient::global().get_next_unread_chapter(&manga_id, &read_numbers, language).await {
+                Ok(Some(chapter)) => {
+                    tx.send(Events::Navigate(Route::ResumeChapter(LastChapterRead {
+                        chapter_id: chapter.id,
+                        manga_id,
+                        chapter_number: chapter.attributes.chapter.unwrap_or_default(),
+                        chapter_title: chapter.attributes.title.unwrap_or_default(),
+                    })))
+                    .ok();
+                },
+                Ok(None) => {
+                    local_tx.send(FeedEvents::NothingToContinue).ok();
+                },
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    local_tx.send(FeedEvents::ErrorSearchingMangaData).ok();
+                },
             }
+        });
+    }
+
+    fn change_filters(&mut self, filters: LibraryFilters) {
+        self.filters = filters;
+
+        if let Err(e) = save_library_filters(filters) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+
+        if let Some(history) = self.history.as_mut() {
+            history.page = 1;
         }
+        self.search_history();
+    }
+
+    /// The selected manga is the one kept
+    fn merge_duplicates(&mut self) {
+        let Some(manga) = self.history.as_ref().and_then(|history| history.get_current_manga_selected()) else {
+            return;
+        };
+
+        if !manga.has_duplicates {
+            return;
+        }
+
+        match merge_duplicate_mangas(&manga.id) {
+            Ok(merged) => {
+                self.merge_finished = Some((merged, Instant::now()));
+                self.search_history();
+            },
+            Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
+        }
+    }
+
+    fn toggle_import_dialog(&mut self) {
+        self.import_input = match self.import_input {
+            Some(_) => None,
+            None => Some(Input::default()),
+        };
+    }
+
+    fn import_mangas(&mut self) {
+        let Some(input) = self.import_input.take() else {
+            return;
+        };
+
+        let links = input.value().to_string();
+
+        if links.trim().is_empty() || self.is_importing {
+            return;
+        }
+
+        let category = match self.tabs {
+            FeedTabs::History | FeedTabs::Sessions => ImportCategory::Reading,
+            FeedTabs::PlantToRead => ImportCategory::PlanToRead,
+        };
+
+        self.is_importing = true;
+        self.import_finished = None;
+
+        let tx = self.local_event_tx.clone();
+        let global_tx = self.global_event_tx.clone();
+
+        // Not spawned on `self.tasks` since searching the history aborts them
+        tokio::spawn(async move {
+            let summary = import_mangas(&links, category).await;
+            tx.send(FeedEvents::FinishedImport(summary)).ok();
+            global_tx.send(Events::TaskFinished(LongTask::ImportMangas)).ok();
+        });
+    }
+
+    fn finish_import(&mut self, summary: ImportSummary) {
+        self.is_importing = false;
+        self.import_finished = Some((summary, Instant::now()));
+        self.search_history();
     }
 
     fn toggle_focus_search_bar(&mut self) {
@@ -417,6 +754,14 @@ impl Component for Feed {
         self.render_top_area(tabs_area, frame);
 
         self.render_history(history_area, frame.buffer_mut());
+
+        self.render_import_status(history_area, frame.buffer_mut());
+
+        self.render_merge_status(history_area, frame.buffer_mut());
+
+        self.render_continue_status(history_area, frame.buffer_mut());
+
+        self.render_import_dialog(history_area, frame);
     }
 
     fn update(&mut self, action: Self::Actions) {
@@ -428,6 +773,18 @@ impl Component for Feed {
                 FeedActions::GoToMangaPage => self.go_to_manga_page(),
                 FeedActions::ScrollHistoryUp => self.select_previous_manga(),
                 FeedActions::ScrollHistoryDown => self.select_next_manga(),
+                FeedActions::ToggleImportDialog => self.toggle_import_dialog(),
+                FeedActions::ImportMangas => self.import_mangas(),
+                FeedActions::CycleSort => self.change_filters(LibraryFilters {
+                    sort: self.filters.sort.next(),
+                    ..self.filters
+                }),
+                FeedActions::ToggleDownloadedOnly => self.change_filters(LibraryFilters {
+                    downloaded_only: !self.filters.downloaded_only,
+                    ..self.filters
+                }),
+                FeedActions::MergeDuplicates => self.merge_duplicates(),
+                FeedActions::ContinueReading => self.continue_reading(),
                 FeedActions::ChangeTab => {
                     if let Some(history) = self.history.as_mut() {
                         history.page = 1;
@@ -439,10 +796,25 @@ impl Component for Feed {
         }
     }
 
+    fn on_focus(&mut self) {
+        self.init_search();
+    }
+
+    fn on_blur(&mut self) {
+        self.clean_up();
+    }
+
+    fn wants_input(&self) -> bool {
+        self.is_typing()
+    }
+
     fn clean_up(&mut self) {
         self.search_bar.reset();
+        self.import_input = None;
         self.history = None;
+        self.sessions = None;
         self.loading_state = None;
+        self.searching_manga_since = None;
     }
 
     fn handle_events(&mut self, events: crate::backend::tui::Events) {
@@ -19,10 +19,12 @@ use tokio::task::JoinSet;
 
 use crate::backend::error_log::{write_to_error_log, ErrorType};
 use crate::backend::fetch::MangadexClient;
-use crate::backend::tui::Events;
+use crate::backend::tui::{Events, Route};
+use crate::backend::update::{check_for_new_release, dismiss_release, new_release_client};
 use crate::backend::SearchMangaResponse;
 use crate::common::ImageState;
-use crate::global::INSTRUCTIONS_STYLE;
+use crate::config::MangaTuiConfig;
+use crate::global::{is_safe_mode, INSTRUCTIONS_STYLE};
 use crate::utils::search_manga_cover;
 use crate::view::widgets::home::{CarrouselItem, CarrouselState, PopularMangaCarrousel, RecentlyAddedCarrousel};
 use crate::view::widgets::search::MangaItem;
@@ -44,6 +46,8 @@ pub enum HomeEvents {
     LoadRecentlyAddedMangas(Option<SearchMangaResponse>),
     LoadCover(Option<DynamicImage>, String),
     LoadRecentlyAddedMangasCover(Option<DynamicImage>, String),
+    CheckForNewRelease,
+    LoadNewRelease(String),
 }
 
 impl ImageHandler for HomeEvents {
@@ -65,6 +69,7 @@ pub enum HomeActions {
     SelectPreviousRecentlyAddedManga,
     SupportMangadex,
     SupportProject,
+    DismissReleaseNotice,
 }
 
 pub struct Home {
@@ -82,15 +87,25 @@ pub struct Home {
     recently_added_manga_state: ImageState,
     picker: Option<Picker>,
     tasks: JoinSet<()>,
+    /// Version of a newer release, shown until the user dismisses it
+    new_release: Option<String>,
 }
 
 impl Component for Home {
     type Actions = HomeActions;
 
     fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
-        let layout = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).margin(1);
         let buf = frame.buffer_mut();
 
+        let notice_height = if self.new_release.is_some() { 1 } else { 0 };
+        let [notice_area, area] = Layout::vertical([Constraint::Length(notice_height), Constraint::Fill(1)]).areas(area);
+
+        if let Some(version) = self.new_release.as_ref() {
+            render_release_notice(version, notice_area, buf);
+        }
+
+        let layout = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).margin(1);
+
         let [carrousel_popular_mangas_area, latest_updates_area] = layout.areas(area);
 
         self.render_popular_mangas_carrousel(carrousel_popular_mangas_area, buf);
@@ -109,11 +124,20 @@ impl Component for Home {
             HomeActions::SelectPreviousRecentlyAddedManga => self.carrousel_recently_added.select_previous(),
             HomeActions::GoToRecentlyAddedMangaPage => {
                 if let Some(item) = self.carrousel_recently_added.get_current_selected_manga() {
-                    self.global_event_tx.send(Events::GoToMangaPage(MangaItem::new(item.manga.clone()))).ok();
+                    self.global_event_tx
+                        .send(Events::Navigate(Route::Manga(MangaItem::new(item.manga.clone()))))
+                        .ok();
                 }
             },
             HomeActions::SupportProject => self.support_project(),
             HomeActions::SupportMangadex => self.support_mangadex(),
+            HomeActions::DismissReleaseNotice => self.dismiss_release_notice(),
+        }
+    }
+
+    fn on_focus(&mut self) {
+        if self.require_search() {
+            self.init_search();
         }
     }
 
@@ -141,6 +165,10 @@ impl Home {
         let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<HomeActions>();
         let (local_event_tx, local_event_rx) = mpsc::unbounded_channel::<HomeEvents>();
 
+        if MangaTuiConfig::get().check_for_updates && !is_safe_mode() {
+            local_event_tx.send(HomeEvents::CheckForNewRelease).ok();
+        }
+
         Self {
             carrousel_popular_mangas: PopularMangaCarrousel::default(),
             carrousel_recently_added: RecentlyAddedCarrousel::new(picker.is_some()),
@@ -156,6 +184,29 @@ impl Home {
             popular_manga_carrousel_state: ImageState::default(),
             recently_added_manga_state: ImageState::default(),
             tasks: JoinSet::new(),
+            new_release: None,
+        }
+    }
+
+    /// Not part of `tasks` so leaving the home page before github answers does not cancel it
+    fn check_for_new_release(&mut self) {
+        let tx = self.local_event_tx.clone();
+        tokio::spawn(async move {
+            match check_for_new_release(&new_release_client()).await {
+                Ok(Some(version)) => {
+                    tx.send(HomeEvents::LoadNewRelease(version)).ok();
+                },
+                Ok(None) => {},
+                Err(e) => write_to_error_log(ErrorType::FromError(e)),
+            }
+        });
+    }
+
+    fn dismiss_release_notice(&mut self) {
+        if let Some(version) = self.new_release.take() {
+            if let Err(e) = dismiss_release(&version) {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+            }
         }
     }
 
@@ -190,7 +241,9 @@ impl Home {
 
     pub fn go_to_manga_page_popular(&self) {
         if let Some(item) = self.get_current_popular_manga() {
-            self.global_event_tx.send(Events::GoToMangaPage(MangaItem::new(item.manga.clone()))).ok();
+            self.global_event_tx
+                .send(Events::Navigate(Route::Manga(MangaItem::new(item.manga.clone()))))
+                .ok();
         }
     }
 
@@ -228,7 +281,11 @@ impl Home {
 
     fn load_support_image(&mut self, img: DynamicImage) {
         if let Some(picker) = self.picker.as_mut() {
-            if let Ok(protocol) = picker.new_protocol(img, self.image_support_area, Resize::Fit(None)) {
+            if let Ok(protocol) = picker.new_protocol(
+                img,
+                self.image_support_area,
+                Resize::Fit(Some(MangaTuiConfig::get().cover_render_quality.filter())),
+            ) {
                 self.support_image = Some(protocol);
             }
         }
@@ -259,6 +316,8 @@ impl Home {
                 },
                 HomeEvents::SearchSupportImage => self.search_support_image(),
                 HomeEvents::LoadSupportImage(image) => self.load_support_image(image),
+                HomeEvents::CheckForNewRelease => self.check_for_new_release(),
+                HomeEvents::LoadNewRelease(version) => self.new_release = Some(version),
             }
         }
     }
@@ -280,8 +339,11 @@ impl Home {
     fn load_popular_manga_cover(&mut self, maybe_cover: Option<DynamicImage>, id: String) {
         if let Some(cover) = maybe_cover {
             if let Some(picker) = self.picker.as_mut() {
-                let fixed_protocol =
-                    picker.new_protocol(cover, self.popular_manga_carrousel_state.get_img_area(), Resize::Fit(None));
+                let fixed_protocol = picker.new_protocol(
+                    cover,
+                    self.popular_manga_carrousel_state.get_img_area(),
+                    Resize::Fit(Some(MangaTuiConfig::get().cover_render_quality.filter())),
+                );
                 if let Ok(protocol) = fixed_protocol {
                     self.popular_manga_carrousel_state.insert_manga(protocol, id);
                 }
@@ -319,7 +381,11 @@ impl Home {
                 Some(file_name) => {
                     let file_name = file_name.clone();
                     self.tasks.spawn(async move {
-                        let response = MangadexClient::global().get_cover_for_manga(&manga_id, &file_name).await;
+                        let response = if MangaTuiConfig::get().low_memory_mode {
+                            MangadexClient::global().get_cover_for_manga_lower_quality(&manga_id, &file_name).await
+                        } else {
+                            MangadexClient::global().get_cover_for_manga(&manga_id, &file_name).await
+                        };
                         if let Ok(bytes) = response {
                             let dyn_img = Reader::new(Cursor::new(bytes)).with_guessed_format().unwrap();
 
@@ -389,7 +455,11 @@ impl Home {
     fn load_recently_added_mangas_cover(&mut self, maybe_cover: Option<DynamicImage>, id: String) {
         if let Some(cover) = maybe_cover {
             if let Some(picker) = self.picker.as_mut() {
-                let fixed_protocol = picker.new_protocol(cover, self.recently_added_manga_state.get_img_area(), Resize::Fit(None));
+                let fixed_protocol = picker.new_protocol(
+                    cover,
+                    self.recently_added_manga_state.get_img_area(),
+                    Resize::Fit(Some(MangaTuiConfig::get().cover_render_quality.filter())),
+                );
 
                 if let Ok(protocol) = fixed_protocol {
                     self.recently_added_manga_state.insert_manga(protocol, id);
@@ -486,7 +556,19 @@ impl Home {
             KeyCode::Char('g') => {
                 self.local_action_tx.send(HomeActions::SupportProject).ok();
             },
+            KeyCode::Char('x') => {
+                self.local_action_tx.send(HomeActions::DismissReleaseNotice).ok();
+            },
             _ => {},
         }
     }
 }
+
+fn render_release_notice(version: &str, area: Rect, buf: &mut Buffer) {
+    Line::from(vec![
+        format!(" manga-tui {version} is available, run `manga-tui self-update` to install it ").into(),
+        "Dismiss: ".into(),
+        "<x>".to_span().style(*INSTRUCTIONS_STYLE),
+    ])
+    .render(area, buf);
+}
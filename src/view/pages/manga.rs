@@ -16,23 +16,34 @@ use strum::Display;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinSet;
 
+use crate::backend::aggregate::VolumeCompleteness;
+use crate::backend::covers::CoverData;
 use crate::backend::database::{
-    get_chapters_history_status, save_history, set_chapter_downloaded, MangaReadingHistorySave, SetChapterDownloaded,
+    get_chapters_history_status, get_cover_override, remove_cover_override, save_cover_override, save_history,
+    set_chapter_downloaded, CoverOverride, MangaReadingHistorySave, SetChapterDownloaded,
 };
-use crate::backend::download::{download_chapter_cbz, download_chapter_epub, download_chapter_raw_images, DownloadChapter};
+use crate::backend::download::{download_chapter, DownloadChapter};
 use crate::backend::error_log::{self, write_to_error_log};
-use crate::backend::fetch::{MangadexClient, ITEMS_PER_PAGE_CHAPTERS};
-use crate::backend::filter::Languages;
-use crate::backend::tui::Events;
+use crate::backend::fetch::{FetchError, MangadexClient, ITEMS_PER_PAGE_CHAPTERS};
+use crate::backend::filter::{ContentRating, Languages};
+use crate::backend::hyperlink::{add_hyperlink, manga_url, tag_url};
+use crate::backend::reading_order::{combined_progress, SeriesPart};
+use crate::backend::tui::{Events, LongTask, Route};
 use crate::backend::{AppDirectories, ChapterResponse, MangaStatisticsResponse, Statistics};
 use crate::common::{Manga, PageType};
-use crate::config::{DownloadType, ImageQuality, MangaTuiConfig};
+use crate::config::{ImageQuality, MangaTuiConfig, CONFIG};
 use crate::global::{ERROR_STYLE, INSTRUCTIONS_STYLE};
-use crate::utils::{set_status_style, set_tags_style, to_filename};
-use crate::view::tasks::manga::{download_all_chapters_task, search_chapters_operation, DownloadAllChaptersData};
+use crate::utils::{from_manga_response, set_status_style, set_tags_style, to_filename};
+use crate::view::pages::reader::ReadChapterInfo;
+use crate::view::tasks::manga::{
+    download_all_chapters_task, estimate_download_size_task, search_chapters_operation, DownloadAllChaptersData,
+    EstimateDownloadSizeData,
+};
 use crate::view::widgets::manga::{
-    ChapterItem, ChaptersListWidget, DownloadAllChaptersState, DownloadAllChaptersWidget, DownloadPhase,
+    ChapterChange, ChapterItem, ChaptersListWidget, DownloadAllChaptersState, DownloadAllChaptersWidget, DownloadPhase,
+    DownloadSizeEstimate, DownloadStart,
 };
+use crate::view::widgets::search::MangaItem;
 use crate::view::widgets::Component;
 
 #[derive(PartialEq, Eq, Debug)]
@@ -47,9 +58,11 @@ pub enum PageState {
 #[derive(Debug, PartialEq, Eq)]
 pub enum MangaPageActions {
     DownloadChapter,
+    DownloadChapterInBothLanguages,
     ConfirmDownloadAll,
     CancelDownloadAll,
     AskDownloadAllChapters,
+    AskDownloadFromChapter,
     AskAbortProcces,
     AbortDownloadAllChapters,
     ScrollChapterDown,
@@ -64,6 +77,18 @@ pub enum MangaPageActions {
     GoMangasArtist,
     SearchNextChapterPage,
     SearchPreviousChapterPage,
+    RefreshChapters,
+    ScrollDownOtherVersions,
+    ScrollUpOtherVersions,
+    ReadOtherVersion,
+    CloseOtherVersions,
+    ToggleReadingOrder,
+    ScrollDownReadingOrder,
+    ScrollUpReadingOrder,
+    GoToSeriesPart,
+    NextCover,
+    ToggleGroupFilter,
+    ToggleSmartList,
 }
 
 #[derive(Debug, PartialEq)]
@@ -72,6 +97,7 @@ pub enum MangaPageEvents {
     SearchCover,
     LoadCover(DynamicImage),
     FethStatistics,
+    FetchVolumes,
     CheckChapterStatus,
     ChapterFinishedDownloading(String),
     DownloadAllChaptersError,
@@ -85,9 +111,17 @@ pub enum MangaPageEvents {
     /// id_chapter
     DownloadError(String),
     ReadError(String),
+    /// id_chapter
+    ChapterUnavailable(String),
+    /// id of the chapter which is not available, other versions of it
+    LoadOtherVersions(String, Option<ChapterResponse>),
     ReadSuccesful,
     LoadChapters(Option<ChapterResponse>),
     LoadStatistics(Option<MangaStatisticsResponse>),
+    LoadVolumes(Option<Vec<VolumeCompleteness>>),
+    LoadSeriesParts(Option<Vec<SeriesPart>>),
+    LoadVolumeCovers(Option<Vec<CoverData>>),
+    LoadDownloadSizeEstimate(DownloadSizeEstimate),
 }
 
 #[derive(Display, Default, Clone, Copy, Debug, PartialEq, Eq)]
@@ -122,11 +156,42 @@ pub struct MangaPage {
     chapter_language: Languages,
     state: PageState,
     statistics: Option<MangaStatistics>,
+    /// How many chapters of each volume are in `chapter_language`
+    volumes: Option<Vec<VolumeCompleteness>>,
     tasks: JoinSet<()>,
     picker: Option<Picker>,
     available_languages_state: ListState,
     is_list_languages_open: bool,
     download_all_chapters_state: DownloadAllChaptersState,
+    /// The next chapters loaded are compared to the current ones to show what changed
+    is_refreshing_chapters: bool,
+    other_versions: Option<OtherVersions>,
+    reading_order: Option<ReadingOrder>,
+    /// Searched the first time the user changes the cover
+    volume_covers: Option<Vec<CoverData>>,
+    /// The manga is rated above what the profile can see, nothing of it is searched nor shown
+    above_content_ceiling: bool,
+    /// Only the chapters of this scanlation group are listed, as its id and name. With the smart list its
+    /// uploads are the ones shown for the chapters it has
+    group: Option<(String, String)>,
+    /// A single row for each chapter number instead of one for each upload
+    smart_list: bool,
+}
+
+/// Uploads from other scanlation groups of a chapter which is no longer available
+#[derive(Clone, Debug)]
+struct OtherVersions {
+    chapter_number: String,
+    state: tui_widget_list::ListState,
+    widget: ChaptersListWidget,
+}
+
+/// The sequels and prequels of the manga together with it, searched when the reading order is first opened
+#[derive(Clone, Debug, Default)]
+struct ReadingOrder {
+    parts: Option<Vec<SeriesPart>>,
+    is_open: bool,
+    state: ListState,
 }
 
 struct MangaStatistics {
@@ -140,6 +205,15 @@ impl MangaStatistics {
     }
 }
 
+/// What a chapter is downloaded with, taken from the list before the download task is spawned
+struct ChapterToDownload {
+    id: String,
+    title: String,
+    number: String,
+    scanlator: String,
+    language: Languages,
+}
+
 #[derive(Clone, Debug)]
 struct ChaptersData {
     state: tui_widget_list::ListState,
@@ -148,14 +222,25 @@ struct ChaptersData {
     total_result: u32,
 }
 
+impl ChaptersData {
+    /// A manga without chapters in the language still has the page the empty list is shown in
+    fn total_pages(&self) -> u32 {
+        self.total_result.div_ceil(ITEMS_PER_PAGE_CHAPTERS).max(1)
+    }
+}
+
 impl MangaPage {
     pub fn new(manga: Manga, global_event_tx: UnboundedSender<Events>, picker: Option<Picker>) -> Self {
         let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<MangaPageActions>();
         let (local_event_tx, local_event_rx) = mpsc::unbounded_channel::<MangaPageEvents>();
 
-        local_event_tx.send(MangaPageEvents::SearchChapters).ok();
-        local_event_tx.send(MangaPageEvents::FethStatistics).ok();
-        local_event_tx.send(MangaPageEvents::SearchCover).ok();
+        let above_content_ceiling = !ContentRating::is_allowed(&manga.content_rating);
+        if !above_content_ceiling {
+            local_event_tx.send(MangaPageEvents::SearchChapters).ok();
+            local_event_tx.send(MangaPageEvents::FethStatistics).ok();
+            local_event_tx.send(MangaPageEvents::FetchVolumes).ok();
+            local_event_tx.send(MangaPageEvents::SearchCover).ok();
+        }
         let cover_area = Rect::default();
 
         let chapter_language = manga
@@ -177,12 +262,20 @@ impl MangaPage {
             chapter_order: ChapterOrder::default(),
             state: PageState::SearchingChapters,
             statistics: None,
+            volumes: None,
             tasks: JoinSet::new(),
             available_languages_state: ListState::default(),
             is_list_languages_open: false,
             download_all_chapters_state: DownloadAllChaptersState::new(local_event_tx),
             chapter_language: chapter_language.unwrap_or(Languages::default()),
             cover_area,
+            is_refreshing_chapters: false,
+            other_versions: None,
+            reading_order: None,
+            volume_covers: None,
+            above_content_ceiling,
+            group: None,
+            smart_list: false,
         }
     }
 
@@ -190,7 +283,24 @@ impl MangaPage {
         let [cover_area, more_details_area] =
             Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
 
-        Paragraph::new(format!(" \n Publication date : \n {}", self.manga.created_at)).render(more_details_area, buf);
+        let mut more_details =
+            vec![Line::from(" "), Line::from(" Publication date : "), Line::from(format!(" {}", self.manga.created_at))];
+
+        if self.picker.is_some() {
+            more_details.push(Line::from(vec![" Change cover ".into(), Span::raw("<p>").style(*INSTRUCTIONS_STYLE)]));
+        }
+
+        if let Some(volumes) = self.volumes.as_ref().filter(|volumes| !volumes.is_empty()) {
+            more_details.push(Line::from(" "));
+            more_details.push(Line::from(format!(" Chapters in {} : ", self.chapter_language.as_human_readable())));
+            more_details.extend(volumes.iter().map(|volume| {
+                let name = if volume.volume == "none" { "No volume".to_string() } else { format!("Vol {}", volume.volume) };
+                let line = Line::from(format!(" {name}: {}/{} ", volume.translated, volume.total));
+                if volume.is_complete() { line } else { line.yellow() }
+            }));
+        }
+
+        Paragraph::new(more_details).render(more_details_area, buf);
 
         match self.image_state.as_ref() {
             Some(state) => {
@@ -228,27 +338,116 @@ impl MangaPage {
                 author_and_artist,
                 " | More about author/artist ".into(),
                 go_to_author_artist_instructions,
+                " | Reading order ".into(),
+                Span::raw("<n>").style(*INSTRUCTIONS_STYLE),
             ]))
             .render(manga_information_area, buf);
 
+        let title_width = (Span::raw(&self.manga.title).width() as u16).min(manga_information_area.width.saturating_sub(2));
+        add_hyperlink(Rect::new(manga_information_area.x + 1, manga_information_area.y, title_width, 1), manga_url(&self.manga.id));
+
         self.render_details(manga_information_area, frame.buffer_mut());
 
         self.render_chapters_area(manga_chapters_area, frame.buffer_mut());
+
+        self.render_reading_order(manga_chapters_area, frame.buffer_mut());
+    }
+
+    fn render_reading_order(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(reading_order) = self.reading_order.as_mut().filter(|reading_order| reading_order.is_open) else {
+            return;
+        };
+
+        let mut instructions: Vec<Span<'_>> = vec!["Reading order ".into()];
+
+        let items: Vec<Line<'_>> = match reading_order.parts.as_ref() {
+            Some(parts) => {
+                let (read, total) = combined_progress(parts);
+                instructions
+                    .push(format!("| {read}/{total} chapters read in {} ", self.chapter_language.as_human_readable()).into());
+
+                parts
+                    .iter()
+                    .enumerate()
+                    .map(|(index, part)| {
+                        let year = part.year.map(|year| format!(" ({year})")).unwrap_or_default();
+                        let line = Line::from(format!(
+                            "{}. {}{year} {}/{}",
+                            index + 1,
+                            part.title,
+                            part.chapters_read.min(part.total_chapters),
+                            part.total_chapters
+                        ));
+
+                        if part.id == self.manga.id {
+                            line.bold()
+                        } else if part.is_finished() {
+                            line.green()
+                        } else {
+                            line
+                        }
+                    })
+                    .collect()
+            },
+            None => vec![Line::from("Searching sequels and prequels")],
+        };
+
+        instructions.extend([
+            "Go to".into(),
+            Span::raw(" <Enter> ").style(*INSTRUCTIONS_STYLE),
+            "Close".into(),
+            Span::raw(" <Esc> ").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        Clear.render(area, buf);
+        StatefulWidget::render(
+            List::new(items)
+                .block(Block::bordered().title(Line::from(instructions)))
+                .highlight_style(Style::default().on_blue()),
+            area,
+            buf,
+            &mut reading_order.state,
+        );
     }
 
     fn render_details(&mut self, area: Rect, buf: &mut Buffer) {
         let layout = Layout::vertical([Constraint::Percentage(20), Constraint::Percentage(80)]).margin(1);
         let [tags_area, description_area] = layout.areas(area);
 
-        let mut tags: Vec<Span<'_>> = self.manga.tags.iter().map(|tag| set_tags_style(tag)).collect();
+        let mut tags: Vec<(Span<'_>, Option<String>)> = self
+            .manga
+            .tags
+            .iter()
+            .map(|tag| (set_tags_style(&tag.name), Some(tag_url(&tag.id))))
+            .collect();
 
-        tags.push(set_status_style(&self.manga.publication_demographic));
+        tags.push((set_status_style(&self.manga.publication_demographic), None));
 
-        tags.push(set_tags_style(&self.manga.content_rating));
+        tags.push((set_tags_style(&self.manga.content_rating), None));
 
-        tags.push(set_status_style(&self.manga.status));
+        tags.push((set_status_style(&self.manga.status), None));
 
-        Paragraph::new(Line::from(tags)).wrap(Wrap { trim: true }).render(tags_area, buf);
+        // Laid out one by one instead of with a `Paragraph` so the area of each tag is known and it can be a link
+        let (mut x, mut y) = (tags_area.x, tags_area.y);
+        for (tag, url) in tags {
+            let width = tag.width() as u16;
+            if x + width > tags_area.right() && x > tags_area.x {
+                x = tags_area.x;
+                y += 1;
+            }
+            if y >= tags_area.bottom() {
+                break;
+            }
+
+            let (x_end, _) = buf.set_span(x, y, &tag, tags_area.right() - x);
+
+            if let Some(url) = url {
+                let link_width = (Span::raw(tag.content.trim_end()).width() as u16).min(x_end - x);
+                add_hyperlink(Rect::new(x, y, link_width, 1), url);
+            }
+
+            x = x_end;
+        }
 
         Paragraph::new(self.manga.description.clone())
             .wrap(Wrap { trim: true })
@@ -267,8 +466,7 @@ impl MangaPage {
 
         match self.chapters.as_mut() {
             Some(chapters) => {
-                let tota_pages = chapters.total_result as f64 / 16_f64;
-                let page = format!("Page {} of : {}", chapters.page, tota_pages.ceil());
+                let page = format!("Page {} of {}", chapters.page, chapters.total_pages());
                 let total = format!("Total chapters {}", chapters.total_result);
 
                 let mut chapter_instructions = vec![
@@ -278,14 +476,23 @@ impl MangaPage {
                     Span::raw(" <d> ").style(*INSTRUCTIONS_STYLE),
                     " Download all chapters ".into(),
                     Span::raw(" <a> ").style(*INSTRUCTIONS_STYLE),
+                    " Download from here ".into(),
+                    Span::raw(" <A> ").style(*INSTRUCTIONS_STYLE),
+                    " Refresh ".into(),
+                    Span::raw(" <f> ").style(*INSTRUCTIONS_STYLE),
                 ];
 
+                if let Some(compare_language) = CONFIG.get().and_then(|config| config.compare_language()) {
+                    chapter_instructions.push(format!(" Download with {} ", compare_language.as_human_readable()).into());
+                    chapter_instructions.push(Span::raw(" <D> ").style(*INSTRUCTIONS_STYLE));
+                }
+
                 if self.picker.is_some() {
                     chapter_instructions.push(" Read chapter ".into());
                     chapter_instructions.push(Span::raw(" <r> ").style(*INSTRUCTIONS_STYLE));
                 }
 
-                let pagination_instructions: Vec<Span<'_>> = vec![
+                let mut pagination_instructions: Vec<Span<'_>> = vec![
                     page.into(),
                     " | ".into(),
                     total.into(),
@@ -295,6 +502,24 @@ impl MangaPage {
                     Span::raw("<b>").style(*INSTRUCTIONS_STYLE),
                 ];
 
+                let count_changes =
+                    |change: ChapterChange| chapters.widget.chapters.iter().filter(|chapter| chapter.change == change).count();
+                let (new_chapters, removed_chapters) = (count_changes(ChapterChange::New), count_changes(ChapterChange::Removed));
+
+                if new_chapters > 0 || removed_chapters > 0 {
+                    pagination_instructions.push(format!(" | {} new, {} removed ", new_chapters, removed_chapters).into());
+                }
+
+                let group_filter = if self.smart_list { "Prefer" } else { "Only" };
+                pagination_instructions.push(match &self.group {
+                    Some((_, name)) => format!(" | {group_filter} {name} ").into(),
+                    None => format!(" | {group_filter} this group ").into(),
+                });
+                pagination_instructions.push(Span::raw("<g>").style(*INSTRUCTIONS_STYLE));
+                let smart_list = if self.smart_list { " All uploads " } else { " One per chapter " };
+                pagination_instructions.push(smart_list.into());
+                pagination_instructions.push(Span::raw("<m>").style(*INSTRUCTIONS_STYLE));
+
                 Block::bordered()
                     .title_top(Line::from(chapter_instructions))
                     .title_bottom(Line::from(pagination_instructions))
@@ -302,6 +527,23 @@ impl MangaPage {
 
                 StatefulWidget::render(chapters.widget.clone(), chapters_area, buf, &mut chapters.state);
 
+                if let Some(other_versions) = self.other_versions.as_mut() {
+                    let instructions = Line::from(vec![
+                        format!("Chapter {} is no longer available, other versions: ", other_versions.chapter_number).into(),
+                        "Read".into(),
+                        Span::raw(" <r> ").style(*INSTRUCTIONS_STYLE),
+                        "Close".into(),
+                        Span::raw(" <Esc> ").style(*INSTRUCTIONS_STYLE),
+                    ]);
+
+                    let block = Block::bordered().title(instructions);
+                    let other_versions_area = block.inner(chapters_area);
+
+                    Clear.render(chapters_area, buf);
+                    block.render(chapters_area, buf);
+                    StatefulWidget::render(other_versions.widget.clone(), other_versions_area, buf, &mut other_versions.state);
+                }
+
                 self.render_sorting_buttons(sorting_buttons_area, buf);
             },
 
@@ -350,12 +592,7 @@ impl MangaPage {
                 Span::raw("<s>").style(*INSTRUCTIONS_STYLE),
             ]);
 
-            let available_language_list = List::new(
-                self.manga
-                    .available_languages
-                    .iter()
-                    .map(|lang| format!("{} {}", lang.as_emoji(), lang.as_human_readable())),
-            )
+            let available_language_list = List::new(self.available_language_items())
             .block(Block::bordered().title(instructions))
             .highlight_style(Style::default().on_blue());
 
@@ -364,14 +601,28 @@ impl MangaPage {
             Paragraph::new(Line::from(vec![
                 "Language: ".into(),
                 self.chapter_language.as_emoji().into(),
+                " ".into(),
+                self.chapter_language.as_human_readable().into(),
                 " | ".into(),
-                "Available languages: ".into(),
+                format!("Available languages ({}): ", self.manga.available_languages.len()).into(),
                 "<l>".bold().yellow(),
             ]))
             .render(language_area, buf);
         }
     }
 
+    /// The languages the manga was translated to, the one the chapters are listed in is marked
+    fn available_language_items(&self) -> Vec<String> {
+        self.manga
+            .available_languages
+            .iter()
+            .map(|lang| {
+                let current = if *lang == self.chapter_language { " (current)" } else { "" };
+                format!("{} {}{current}", lang.as_emoji(), lang.as_human_readable())
+            })
+            .collect()
+    }
+
     fn download_process_started(&self) -> bool {
         self.download_all_chapters_state.process_started()
     }
@@ -379,7 +630,9 @@ impl MangaPage {
     fn search_by_language(&mut self) {
         self.chapters = None;
         self.chapter_language = self.get_current_selected_language();
+        self.reading_order = None;
         self.search_chapters();
+        self.fetch_volumes();
     }
 
     fn handle_key_events(&mut self, key_event: KeyEvent) {
@@ -399,6 +652,38 @@ impl MangaPage {
                 },
                 _ => {},
             }
+        } else if self.reading_order.as_ref().is_some_and(|reading_order| reading_order.is_open) {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.local_action_tx.send(MangaPageActions::ScrollDownReadingOrder).ok();
+                },
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.local_action_tx.send(MangaPageActions::ScrollUpReadingOrder).ok();
+                },
+                KeyCode::Enter => {
+                    self.local_action_tx.send(MangaPageActions::GoToSeriesPart).ok();
+                },
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.local_action_tx.send(MangaPageActions::ToggleReadingOrder).ok();
+                },
+                _ => {},
+            }
+        } else if self.other_versions.is_some() {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.local_action_tx.send(MangaPageActions::ScrollDownOtherVersions).ok();
+                },
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.local_action_tx.send(MangaPageActions::ScrollUpOtherVersions).ok();
+                },
+                KeyCode::Char('r') | KeyCode::Enter => {
+                    self.local_action_tx.send(MangaPageActions::ReadOtherVersion).ok();
+                },
+                KeyCode::Esc => {
+                    self.local_action_tx.send(MangaPageActions::CloseOtherVersions).ok();
+                },
+                _ => {},
+            }
         } else if self.state != PageState::SearchingChapterData {
             if self.download_process_started() {
                 match key_event.code {
@@ -439,9 +724,15 @@ impl MangaPage {
                     KeyCode::Char('d') => {
                         self.local_action_tx.send(MangaPageActions::DownloadChapter).ok();
                     },
+                    KeyCode::Char('D') => {
+                        self.local_action_tx.send(MangaPageActions::DownloadChapterInBothLanguages).ok();
+                    },
                     KeyCode::Char('a') => {
                         self.local_action_tx.send(MangaPageActions::AskDownloadAllChapters).ok();
                     },
+                    KeyCode::Char('A') => {
+                        self.local_action_tx.send(MangaPageActions::AskDownloadFromChapter).ok();
+                    },
                     KeyCode::Char('c') => {
                         self.local_action_tx.send(MangaPageActions::GoMangasAuthor).ok();
                     },
@@ -457,6 +748,21 @@ impl MangaPage {
                     KeyCode::Char('b') => {
                         self.local_action_tx.send(MangaPageActions::SearchPreviousChapterPage).ok();
                     },
+                    KeyCode::Char('f') => {
+                        self.local_action_tx.send(MangaPageActions::RefreshChapters).ok();
+                    },
+                    KeyCode::Char('n') => {
+                        self.local_action_tx.send(MangaPageActions::ToggleReadingOrder).ok();
+                    },
+                    KeyCode::Char('p') => {
+                        self.local_action_tx.send(MangaPageActions::NextCover).ok();
+                    },
+                    KeyCode::Char('g') => {
+                        self.local_action_tx.send(MangaPageActions::ToggleGroupFilter).ok();
+                    },
+                    KeyCode::Char('m') => {
+                        self.local_action_tx.send(MangaPageActions::ToggleSmartList).ok();
+                    },
 
                     _ => {},
                 }
@@ -485,6 +791,37 @@ impl MangaPage {
         self.search_chapters();
     }
 
+    /// Lists only the chapters of the scanlation group of the chapter selected, or all of them again
+    fn toggle_group_filter(&mut self) {
+        if self.state == PageState::SearchingChapters {
+            return;
+        }
+
+        self.group = match self.group.take() {
+            Some(_) => None,
+            None => match self.get_current_selected_chapter() {
+                Some(chapter) if !chapter.scanlator_id.is_empty() => {
+                    Some((chapter.scanlator_id.clone(), chapter.scanlator.clone()))
+                },
+                _ => return,
+            },
+        };
+
+        self.chapters = None;
+        self.search_chapters();
+    }
+
+    /// Switches between a row for each upload and a single row for each chapter number
+    fn toggle_smart_list(&mut self) {
+        if self.state == PageState::SearchingChapters {
+            return;
+        }
+
+        self.smart_list = !self.smart_list;
+        self.chapters = None;
+        self.search_chapters();
+    }
+
     fn scroll_language_down(&mut self) {
         self.available_languages_state.select_next();
     }
@@ -507,7 +844,7 @@ impl MangaPage {
         }
     }
 
-    fn _get_current_selected_chapter(&self) -> Option<&ChapterItem> {
+    fn get_current_selected_chapter(&self) -> Option<&ChapterItem> {
         match self.chapters.as_ref() {
             Some(chapters_data) => match chapters_data.state.selected {
                 Some(selected_chapter_index) => return chapters_data.widget.chapters.get(selected_chapter_index),
@@ -520,50 +857,148 @@ impl MangaPage {
     fn read_chapter(&mut self) {
         self.state = PageState::SearchingChapterData;
         match self.get_current_selected_chapter_mut() {
+            Some(chapter_selected) if chapter_selected.change == ChapterChange::Removed => {
+                self.state = PageState::DisplayingChapters;
+            },
             Some(chapter_selected) => {
                 chapter_selected.set_normal_state();
-                let id_chapter = chapter_selected.id.clone();
-                let chapter_title = chapter_selected.title.clone();
-                let is_read = chapter_selected.is_read;
-                let manga_id = self.manga.id.clone();
-                let title = self.manga.title.clone();
-                let img_url = self.manga.img_url.clone();
-                let tx = self.global_event_tx.clone();
-                let local_tx = self.local_event_tx.clone();
-
-                tokio::spawn(async move {
-                    let chapter_response = MangadexClient::global().get_chapter_pages(&id_chapter).await;
-                    match chapter_response {
-                        Ok(response) => {
-                            if !is_read {
-                                let save_response = save_history(MangaReadingHistorySave {
-                                    id: &manga_id,
-                                    title: &title,
-                                    img_url: img_url.as_deref(),
-                                    chapter_id: &id_chapter,
-                                    chapter_title: &chapter_title,
-                                });
-
-                                if let Err(e) = save_response {
-                                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
-                                }
-                            }
-
-                            tx.send(Events::ReadChapter(response)).ok();
-                            local_tx.send(MangaPageEvents::CheckChapterStatus).ok();
-                            local_tx.send(MangaPageEvents::ReadSuccesful).ok();
-                        },
-                        Err(e) => {
-                            write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
-                            local_tx.send(MangaPageEvents::ReadError(id_chapter)).ok();
-                        },
-                    }
-                });
+                let chapter = chapter_selected.clone();
+                self.spawn_read_chapter(chapter);
             },
             None => self.state = PageState::DisplayingChapters,
         }
     }
 
+    fn read_other_version(&mut self) {
+        let Some(other_versions) = self.other_versions.take() else {
+            return;
+        };
+
+        if let Some(chapter) = other_versions.state.selected.and_then(|index| other_versions.widget.chapters.get(index)) {
+            self.state = PageState::SearchingChapterData;
+            self.spawn_read_chapter(chapter.clone());
+        }
+    }
+
+    fn spawn_read_chapter(&mut self, chapter: ChapterItem) {
+        let id_chapter = chapter.id;
+        let chapter_title = chapter.title;
+        let chapter_number = chapter.chapter_number;
+        let is_read = chapter.is_read;
+        let language = chapter.translated_language;
+        let manga_id = self.manga.id.clone();
+        let title = self.manga.title.clone();
+        let img_url = self.manga.img_url.clone();
+        let tx = self.global_event_tx.clone();
+        let local_tx = self.local_event_tx.clone();
+
+        tokio::spawn(async move {
+            let chapter_response = MangadexClient::global().get_chapter_pages(&id_chapter).await;
+            match chapter_response {
+                Ok(response) => {
+                    if !is_read {
+                        let save_response = save_history(MangaReadingHistorySave {
+                            id: &manga_id,
+                            title: &title,
+                            img_url: img_url.as_deref(),
+                            chapter_id: &id_chapter,
+                            chapter_title: &chapter_title,
+                        });
+
+                        if let Err(e) = save_response {
+                            write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                        }
+                    }
+
+                    tx.send(Events::Navigate(Route::Reader {
+                        pages: response,
+                        chapter: ReadChapterInfo {
+                            manga_id,
+                            chapter_id: id_chapter,
+                            number: chapter_number,
+                            title: chapter_title,
+                            language,
+                        },
+                    }))
+                    .ok();
+                    local_tx.send(MangaPageEvents::CheckChapterStatus).ok();
+                    local_tx.send(MangaPageEvents::ReadSuccesful).ok();
+                },
+                Err(FetchError::ChapterUnavailable) => {
+                    local_tx.send(MangaPageEvents::ChapterUnavailable(id_chapter)).ok();
+                },
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    local_tx.send(MangaPageEvents::ReadError(id_chapter)).ok();
+                },
+            }
+        });
+    }
+
+    /// Marks the chapter as unavailable and looks for the same chapter uploaded by other groups
+    fn set_chapter_unavailable(&mut self, chapter_id: String) {
+        self.state = PageState::DisplayingChapters;
+        let Some(chapter) = self
+            .chapters
+            .as_mut()
+            .and_then(|chapters| chapters.widget.chapters.iter_mut().find(|chap| chap.id == chapter_id))
+        else {
+            return;
+        };
+
+        chapter.set_unavailable();
+
+        let manga_id = self.manga.id.clone();
+        let chapter_number = chapter.chapter_number.clone();
+        let language = chapter.translated_language;
+        let tx = self.local_event_tx.clone();
+
+        self.tasks.spawn(async move {
+            let response = MangadexClient::global()
+                .get_other_versions_of_chapter(&manga_id, &chapter_number, language)
+                .await;
+            match response {
+                Ok(response) => {
+                    tx.send(MangaPageEvents::LoadOtherVersions(chapter_id, Some(response))).ok();
+                },
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    tx.send(MangaPageEvents::LoadOtherVersions(chapter_id, None)).ok();
+                },
+            }
+        });
+    }
+
+    fn load_other_versions(&mut self, chapter_id: String, response: Option<ChapterResponse>) {
+        let Some(mut response) = response else {
+            return;
+        };
+
+        response.data.retain(|chapter| chapter.id != chapter_id);
+
+        if response.data.is_empty() {
+            return;
+        }
+
+        let mut widget = ChaptersListWidget::from_response(&response);
+        let chapter_number = widget.chapters[0].chapter_number.clone();
+
+        if let Some(chapters) = self.chapters.as_ref() {
+            for version in widget.chapters.iter_mut() {
+                version.is_read = chapters.widget.chapters.iter().any(|chapter| chapter.id == version.id && chapter.is_read);
+            }
+        }
+
+        let mut state = tui_widget_list::ListState::default();
+        state.select(Some(0));
+
+        self.other_versions = Some(OtherVersions {
+            chapter_number,
+            state,
+            widget,
+        });
+    }
+
     fn get_current_selected_language(&mut self) -> Languages {
         match self.available_languages_state.selected() {
             Some(index) => self.manga.available_languages[index],
@@ -573,7 +1008,7 @@ impl MangaPage {
 
     fn search_next_chapters(&mut self) {
         if let Some(chapters) = self.chapters.as_mut() {
-            if chapters.page * ITEMS_PER_PAGE_CHAPTERS < chapters.total_result {
+            if chapters.page < chapters.total_pages() {
                 chapters.page += 1;
                 self.search_chapters();
             }
@@ -589,16 +1024,26 @@ impl MangaPage {
         }
     }
 
+    fn refresh_chapters(&mut self) {
+        if self.state == PageState::SearchingChapters {
+            return;
+        }
+        self.is_refreshing_chapters = true;
+        self.search_chapters();
+    }
+
     fn search_chapters(&mut self) {
         self.state = PageState::SearchingChapters;
         let manga_id = self.manga.id.clone();
         let tx = self.local_event_tx.clone();
         let language = self.chapter_language;
         let chapter_order = self.chapter_order;
+        let group = self.group.as_ref().map(|(id, _)| id.clone());
 
         let page = if let Some(chapters) = self.chapters.as_ref() { chapters.page } else { 1 };
 
-        self.tasks.spawn(search_chapters_operation(manga_id, page, language, chapter_order, tx));
+        let search = search_chapters_operation(manga_id, page, language, chapter_order, group, self.smart_list, tx);
+        self.tasks.spawn(search);
     }
 
     fn fetch_statistics(&mut self) {
@@ -619,6 +1064,111 @@ impl MangaPage {
         });
     }
 
+    fn fetch_volumes(&mut self) {
+        self.volumes = None;
+
+        let manga_id = self.manga.id.clone();
+        let language = self.chapter_language;
+        let tx = self.local_event_tx.clone();
+        self.tasks.spawn(async move {
+            match MangadexClient::global().get_volume_completeness(&manga_id, language).await {
+                Ok(volumes) => tx.send(MangaPageEvents::LoadVolumes(Some(volumes))).ok(),
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    tx.send(MangaPageEvents::LoadVolumes(None)).ok()
+                },
+            };
+        });
+    }
+
+    fn toggle_reading_order(&mut self) {
+        match self.reading_order.as_mut() {
+            Some(reading_order) => reading_order.is_open = !reading_order.is_open,
+            None => {
+                self.reading_order = Some(ReadingOrder {
+                    is_open: true,
+                    ..Default::default()
+                });
+                self.fetch_series_parts();
+            },
+        }
+    }
+
+    fn fetch_series_parts(&mut self) {
+        let manga_id = self.manga.id.clone();
+        let language = self.chapter_language;
+        let tx = self.local_event_tx.clone();
+        self.tasks.spawn(async move {
+            match MangadexClient::global().get_series_parts(&manga_id, language).await {
+                Ok(mut parts) => {
+                    for part in parts.iter_mut() {
+                        part.chapters_read = get_chapters_history_status(&part.id)
+                            .map(|chapters| chapters.iter().filter(|chapter| chapter.is_read).count())
+                            .unwrap_or_default();
+                    }
+                    tx.send(MangaPageEvents::LoadSeriesParts(Some(parts))).ok()
+                },
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    tx.send(MangaPageEvents::LoadSeriesParts(None)).ok()
+                },
+            };
+        });
+    }
+
+    fn load_series_parts(&mut self, parts: Option<Vec<SeriesPart>>) {
+        let Some(parts) = parts else {
+            self.reading_order = None;
+            return;
+        };
+
+        if let Some(reading_order) = self.reading_order.as_mut() {
+            let current = parts.iter().position(|part| part.id == self.manga.id);
+            reading_order.state.select(current);
+            reading_order.parts = Some(parts);
+        }
+    }
+
+    fn scroll_reading_order(&mut self, down: bool) {
+        if let Some(reading_order) = self.reading_order.as_mut() {
+            let total = reading_order.parts.as_ref().map(|parts| parts.len()).unwrap_or_default();
+            if total == 0 {
+                return;
+            }
+            let selected = reading_order.state.selected().unwrap_or_default();
+            let next = if down { (selected + 1).min(total - 1) } else { selected.saturating_sub(1) };
+            reading_order.state.select(Some(next));
+        }
+    }
+
+    fn go_to_series_part(&mut self) {
+        let Some(reading_order) = self.reading_order.as_mut() else {
+            return;
+        };
+
+        let part = reading_order
+            .state
+            .selected()
+            .and_then(|index| reading_order.parts.as_ref().and_then(|parts| parts.get(index)));
+
+        match part {
+            Some(part) if part.id != self.manga.id => {
+                let manga_id = part.id.clone();
+                let tx = self.global_event_tx.clone();
+                self.tasks.spawn(async move {
+                    match MangadexClient::global().get_one_manga(&manga_id).await {
+                        Ok(manga) => {
+                            tx.send(Events::Navigate(Route::Manga(MangaItem::new(from_manga_response(manga.data)))))
+                                .ok();
+                        },
+                        Err(e) => write_to_error_log(error_log::ErrorType::FromError(Box::new(e))),
+                    }
+                });
+            },
+            _ => reading_order.is_open = false,
+        }
+    }
+
     fn check_chapters_read(&mut self) {
         let history = get_chapters_history_status(&self.manga.id);
         match history {
@@ -640,68 +1190,68 @@ impl MangaPage {
     }
 
     fn download_chapter_selected(&mut self) {
+        self.download_selected_chapter_in(None);
+    }
+
+    /// Downloads the selected chapter and the same chapter in the `compare_language` of the config,
+    /// each one goes to the directory of its language so they end up side by side
+    fn download_chapter_in_both_languages(&mut self) {
+        let Some(compare_language) = CONFIG.get().and_then(|config| config.compare_language()) else {
+            return;
+        };
+        self.download_selected_chapter_in(Some(compare_language));
+    }
+
+    fn download_selected_chapter_in(&mut self, compare_language: Option<Languages>) {
         let manga_id = self.manga.id.clone();
         let manga_title = self.manga.title.clone();
         let tx = self.local_event_tx.clone();
 
         self.state = PageState::DownloadingChapters;
         if let Some(chapter) = self.get_current_selected_chapter_mut() {
-            if chapter.download_loading_state.is_some() {
+            if chapter.download_loading_state.is_some() || chapter.change == ChapterChange::Removed {
                 return;
             }
             chapter.set_normal_state();
-            let title = chapter.title.clone();
-            let number = chapter.chapter_number.clone();
-            let scanlator = chapter.scanlator.clone();
-            let chapter_id = chapter.id.clone();
-            let lang = chapter.translated_language.as_human_readable().to_string();
+            let chapter_to_download = ChapterToDownload {
+                id: chapter.id.clone(),
+                title: chapter.title.clone(),
+                number: chapter.chapter_number.clone(),
+                scanlator: chapter.scanlator.clone(),
+                language: chapter.translated_language,
+            };
 
             chapter.download_loading_state = Some(0.001);
 
+            let compare_language = compare_language.filter(|language| *language != chapter.translated_language);
+
             self.tasks.spawn(async move {
-                let manga_response = MangadexClient::global().get_chapter_pages(&chapter_id).await;
-                match manga_response {
-                    Ok(response) => {
-                        let config = MangaTuiConfig::get();
-
-                        let (files, quality) = match config.image_quality {
-                            ImageQuality::Low => (response.chapter.data_saver, PageType::LowQuality),
-                            ImageQuality::High => (response.chapter.data, PageType::HighQuality),
-                        };
-
-                        let endpoint = format!("{}/{}/{}", response.base_url, quality, response.chapter.hash);
-                        let manga_title = to_filename(&manga_title);
-                        let chapter_title = to_filename(&title);
-                        let scanlator = to_filename(&scanlator);
-
-                        let chapter = DownloadChapter {
-                            id_chapter: &chapter_id,
-                            manga_id: &manga_id,
-                            manga_title: &manga_title,
-                            chapter_title: &chapter_title,
-                            number: &number,
-                            scanlator: &scanlator,
-                            lang: &lang,
-                        };
-
-                        let download_chapter_task = match config.download_type {
-                            DownloadType::Raw => download_chapter_raw_images(false, chapter, files, endpoint, tx.clone()),
-                            DownloadType::Cbz => download_chapter_cbz(false, chapter, files, endpoint, tx.clone()),
-                            DownloadType::Epub => download_chapter_epub(false, chapter, files, endpoint, tx.clone()),
-                        };
-
-                        if let Err(e) = download_chapter_task {
-                            write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
-                            tx.send(MangaPageEvents::DownloadError(chapter_id)).ok();
-                            return;
-                        }
+                let number = chapter_to_download.number.clone();
+                download_single_chapter(&manga_id, &manga_title, chapter_to_download, tx.clone()).await;
 
-                        tx.send(MangaPageEvents::SaveChapterDownloadStatus(chapter_id, title)).ok();
-                    },
-                    Err(e) => {
-                        write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
-                        tx.send(MangaPageEvents::DownloadError(chapter_id)).ok();
+                let Some(language) = compare_language else {
+                    return;
+                };
+
+                let versions = MangadexClient::global().get_other_versions_of_chapter(&manga_id, &number, language).await;
+                match versions {
+                    Ok(versions) => match ChaptersListWidget::from_response(&versions).chapters.into_iter().next() {
+                        Some(version) => {
+                            let other_version = ChapterToDownload {
+                                id: version.id,
+                                title: version.title,
+                                number: version.chapter_number,
+                                scanlator: version.scanlator,
+                                language,
+                            };
+                            download_single_chapter(&manga_id, &manga_title, other_version, tx).await;
+                        },
+                        None => write_to_error_log(error_log::ErrorType::FromError(
+                            format!("chapter {number} of {manga_title} is not translated to {}", language.as_human_readable())
+                                .into(),
+                        )),
                     },
+                    Err(e) => write_to_error_log(error_log::ErrorType::FromError(Box::new(e))),
                 }
             });
         }
@@ -731,11 +1281,15 @@ impl MangaPage {
     }
 
     fn go_mangas_author(&mut self) {
-        self.global_event_tx.send(Events::GoSearchMangasAuthor(self.manga.author.clone())).ok();
+        self.global_event_tx
+            .send(Events::Navigate(Route::SearchMangasOfAuthor(self.manga.author.clone())))
+            .ok();
     }
 
     fn go_mangas_artist(&mut self) {
-        self.global_event_tx.send(Events::GoSearchMangasArtist(self.manga.artist.clone())).ok();
+        self.global_event_tx
+            .send(Events::Navigate(Route::SearchMangasOfArtist(self.manga.artist.clone())))
+            .ok();
     }
 
     fn set_download_progress_for_chapter(&mut self, progress: f64, id_chapter: String) {
@@ -765,16 +1319,32 @@ impl MangaPage {
 
     fn load_chapters(&mut self, response: Option<ChapterResponse>) {
         self.state = PageState::DisplayingChapters;
+        let is_refreshing = std::mem::take(&mut self.is_refreshing_chapters);
         match response {
             Some(response) => {
                 let mut list_state = tui_widget_list::ListState::default();
 
                 list_state.select(Some(0));
 
-                let chapter_widget = ChaptersListWidget::from_response(&response);
+                let mut chapter_widget = ChaptersListWidget::from_response(&response);
+                // Without the group filter every upload is listed on purpose so that the user can pick the group
+                // to read a chapter from, and the smart list already has a single row for each chapter which its
+                // total is counted from. Only the reuploads a group made of its own chapters are left to drop
+                if self.group.is_some() && !self.smart_list {
+                    chapter_widget.dedupe();
+                }
 
                 let page = if let Some(previous) = self.chapters.as_ref() { previous.page } else { 1 };
 
+                if is_refreshing {
+                    if let Some(previous) = self.chapters.as_ref() {
+                        let is_last_page = page * ITEMS_PER_PAGE_CHAPTERS >= response.total as u32;
+                        chapter_widget.mark_changes(&previous.widget.chapters, is_last_page);
+                        let last_index = chapter_widget.chapters.len().saturating_sub(1);
+                        list_state.select(previous.state.selected.map(|selected| selected.min(last_index)));
+                    }
+                }
+
                 self.chapters = Some(ChaptersData {
                     state: list_state,
                     widget: chapter_widget,
@@ -796,7 +1366,37 @@ impl MangaPage {
     }
 
     fn ask_download_all_chapters(&mut self) {
+        if self.download_all_chapters_state.is_downloading() {
+            return;
+        }
         self.download_all_chapters_state.ask_for_confirmation();
+        self.estimate_download_size();
+    }
+
+    /// Asks to download the selected chapter and the ones after it in reading order, no matter how the list is sorted
+    fn ask_download_from_chapter(&mut self) {
+        if self.download_all_chapters_state.is_downloading() {
+            return;
+        }
+        let Some(chapter) = self.get_current_selected_chapter_mut() else {
+            return;
+        };
+        let from_chapter = DownloadStart {
+            id: chapter.id.clone(),
+            number: chapter.chapter_number.clone(),
+        };
+        self.download_all_chapters_state.ask_to_download_from(from_chapter);
+        self.estimate_download_size();
+    }
+
+    fn estimate_download_size(&mut self) {
+        let lang = self.get_current_selected_language();
+        self.tasks.spawn(estimate_download_size_task(EstimateDownloadSizeData {
+            tx: self.local_event_tx.clone(),
+            manga_id: self.manga.id.clone(),
+            lang,
+            from_chapter: self.download_all_chapters_state.from_chapter.as_ref().map(|chapter| chapter.id.clone()),
+        }));
     }
 
     fn confirm_download_all_chapters(&mut self) {
@@ -805,12 +1405,20 @@ impl MangaPage {
         let manga_title = self.manga.title.clone();
         let lang = self.get_current_selected_language();
         let tx = self.local_event_tx.clone();
-        self.tasks.spawn(download_all_chapters_task(DownloadAllChaptersData {
+        let global_tx = self.global_event_tx.clone();
+        let download = download_all_chapters_task(DownloadAllChaptersData {
             tx,
             manga_id,
-            manga_title,
+            manga_title: manga_title.clone(),
             lang,
-        }));
+            from_chapter: self.download_all_chapters_state.from_chapter.as_ref().map(|chapter| chapter.id.clone()),
+        });
+        self.tasks.spawn(async move {
+            // only a download which went through every chapter is announced, a failed one is not worth a bell
+            if download.await {
+                global_tx.send(Events::TaskFinished(LongTask::DownloadAllChapters { manga_title })).ok();
+            }
+        });
     }
 
     fn cancel_download_all_chapters(&mut self) {
@@ -857,19 +1465,89 @@ impl MangaPage {
         }
         let tx = self.local_event_tx.clone();
         let manga_id = self.manga.id.clone();
-        let file_name = self.manga.img_url.as_ref().cloned().unwrap_or_default();
+        let mut file_name = self.manga.img_url.as_ref().cloned().unwrap_or_default();
+        let mut local_path = None;
+
+        match get_cover_override(&manga_id) {
+            Ok(Some(CoverOverride::LocalFile(path))) => local_path = Some(path),
+            Ok(Some(CoverOverride::Volume { file_name: volume_file_name, .. })) => file_name = volume_file_name,
+            Ok(None) => {},
+            Err(e) => write_to_error_log(error_log::ErrorType::FromError(Box::new(e))),
+        }
+
         self.tasks.spawn(async move {
+            // If the local image can't be read the cover mangadex has is shown instead
+            if let Some(path) = local_path {
+                match tokio::fs::read(&path).await {
+                    Ok(bytes) => {
+                        if let Some(img) = decode_cover(&bytes) {
+                            tx.send(MangaPageEvents::LoadCover(img)).ok();
+                            return;
+                        }
+                    },
+                    Err(e) => write_to_error_log(error_log::ErrorType::FromError(Box::new(e))),
+                }
+            }
+
             let cover_image_response = MangadexClient::global().get_cover_for_manga_lower_quality(&manga_id, &file_name).await;
 
-            if let Ok(response) = cover_image_response {
-                let img = Reader::new(Cursor::new(response)).with_guessed_format().unwrap().decode().unwrap();
+            if let Some(img) = cover_image_response.ok().and_then(|response| decode_cover(&response)) {
                 tx.send(MangaPageEvents::LoadCover(img)).ok();
             }
         });
     }
 
+    fn change_cover(&mut self) {
+        if self.picker.is_none() {
+            return;
+        }
+
+        match self.volume_covers.as_ref() {
+            Some(covers) => {
+                let current = get_cover_override(&self.manga.id).ok().flatten();
+                let saved = match next_cover(current.as_ref(), covers) {
+                    Some(cover) => save_cover_override(&self.manga.id, &cover),
+                    None => remove_cover_override(&self.manga.id).map(|_| ()),
+                };
+
+                if let Err(e) = saved {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    return;
+                }
+
+                self.search_cover();
+            },
+            None => self.fetch_volume_covers(),
+        }
+    }
+
+    fn fetch_volume_covers(&mut self) {
+        let manga_id = self.manga.id.clone();
+        let tx = self.local_event_tx.clone();
+        self.tasks.spawn(async move {
+            match MangadexClient::global().get_volume_covers(&manga_id).await {
+                Ok(response) => tx.send(MangaPageEvents::LoadVolumeCovers(Some(response.data))).ok(),
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    tx.send(MangaPageEvents::LoadVolumeCovers(None)).ok()
+                },
+            };
+        });
+    }
+
+    fn load_volume_covers(&mut self, covers: Option<Vec<CoverData>>) {
+        if covers.is_some() {
+            self.volume_covers = covers;
+            self.change_cover();
+        }
+    }
+
     fn load_cover(&mut self, img: DynamicImage) {
-        let fixed_protocol = self.picker.as_mut().unwrap().new_protocol(img, self.cover_area, Resize::Fit(None));
+        let fixed_protocol = self.picker.as_mut().unwrap().new_protocol(
+            img,
+            self.cover_area,
+            Resize::Fit(Some(MangaTuiConfig::get().cover_render_quality.filter())),
+        );
         if let Ok(protocol) = fixed_protocol {
             self.image_state = Some(protocol);
         }
@@ -914,6 +1592,8 @@ impl MangaPage {
                 MangaPageEvents::ReadError(chapter_id) => {
                     self.set_chapter_read_error(chapter_id);
                 },
+                MangaPageEvents::ChapterUnavailable(chapter_id) => self.set_chapter_unavailable(chapter_id),
+                MangaPageEvents::LoadOtherVersions(chapter_id, response) => self.load_other_versions(chapter_id, response),
                 MangaPageEvents::DownloadError(chapter_id) => self.set_chapter_download_error(chapter_id),
                 MangaPageEvents::SetDownloadProgress(progress, id_chapter) => {
                     self.set_download_progress_for_chapter(progress, id_chapter)
@@ -921,6 +1601,10 @@ impl MangaPage {
                 MangaPageEvents::SaveChapterDownloadStatus(id_chapter, title) => self.save_download_status(id_chapter, title),
                 MangaPageEvents::ChapterFinishedDownloading(id_chapter) => self.stop_loader_for_chapter(id_chapter),
                 MangaPageEvents::FethStatistics => self.fetch_statistics(),
+                MangaPageEvents::FetchVolumes => self.fetch_volumes(),
+                MangaPageEvents::LoadVolumes(volumes) => self.volumes = volumes,
+                MangaPageEvents::LoadSeriesParts(parts) => self.load_series_parts(parts),
+                MangaPageEvents::LoadVolumeCovers(covers) => self.load_volume_covers(covers),
                 MangaPageEvents::SearchChapters => self.search_chapters(),
                 MangaPageEvents::LoadChapters(response) => self.load_chapters(response),
                 MangaPageEvents::CheckChapterStatus => {
@@ -936,6 +1620,7 @@ impl MangaPage {
                     }
                 },
                 MangaPageEvents::ReadSuccesful => self.state = PageState::DisplayingChapters,
+                MangaPageEvents::LoadDownloadSizeEstimate(estimate) => self.download_all_chapters_state.set_size_estimate(estimate),
             }
         }
     }
@@ -955,6 +1640,18 @@ impl Component for MangaPage {
     type Actions = MangaPageActions;
 
     fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        if self.above_content_ceiling {
+            Paragraph::new(format!(
+                "This manga is rated {}, this profile can only see mangas rated up to {}",
+                self.manga.content_rating,
+                ContentRating::ceiling()
+            ))
+            .block(Block::bordered().title(self.manga.title.clone()))
+            .wrap(Wrap { trim: true })
+            .render(area, frame.buffer_mut());
+            return;
+        }
+
         let layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(15), Constraint::Percentage(85)]);
@@ -972,6 +1669,7 @@ impl Component for MangaPage {
             MangaPageActions::SearchByLanguage => self.search_by_language(),
             MangaPageActions::CancelDownloadAll => self.cancel_download_all_chapters(),
             MangaPageActions::AskDownloadAllChapters => self.ask_download_all_chapters(),
+            MangaPageActions::AskDownloadFromChapter => self.ask_download_from_chapter(),
             MangaPageActions::ConfirmDownloadAll => self.confirm_download_all_chapters(),
             MangaPageActions::SearchPreviousChapterPage => self.search_previous_chapters(),
             MangaPageActions::SearchNextChapterPage => self.search_next_chapters(),
@@ -987,6 +1685,8 @@ impl Component for MangaPage {
                     self.toggle_chapter_order()
                 }
             },
+            MangaPageActions::ToggleGroupFilter => self.toggle_group_filter(),
+            MangaPageActions::ToggleSmartList => self.toggle_smart_list(),
             MangaPageActions::ReadChapter => {
                 if self.state != PageState::SearchingChapterData {
                     if self.picker.is_none() {
@@ -997,17 +1697,50 @@ impl Component for MangaPage {
             },
 
             MangaPageActions::DownloadChapter => self.download_chapter_selected(),
+            MangaPageActions::DownloadChapterInBothLanguages => self.download_chapter_in_both_languages(),
+            MangaPageActions::RefreshChapters => self.refresh_chapters(),
+            MangaPageActions::ScrollDownOtherVersions => {
+                if let Some(other_versions) = self.other_versions.as_mut() {
+                    other_versions.state.next();
+                }
+            },
+            MangaPageActions::ScrollUpOtherVersions => {
+                if let Some(other_versions) = self.other_versions.as_mut() {
+                    other_versions.state.previous();
+                }
+            },
+            MangaPageActions::ReadOtherVersion => {
+                if self.state != PageState::SearchingChapterData && self.picker.is_some() {
+                    self.read_other_version();
+                }
+            },
+            MangaPageActions::CloseOtherVersions => self.other_versions = None,
+            MangaPageActions::ToggleReadingOrder => self.toggle_reading_order(),
+            MangaPageActions::ScrollDownReadingOrder => self.scroll_reading_order(true),
+            MangaPageActions::ScrollUpReadingOrder => self.scroll_reading_order(false),
+            MangaPageActions::GoToSeriesPart => self.go_to_series_part(),
+            MangaPageActions::NextCover => self.change_cover(),
         }
     }
 
     fn handle_events(&mut self, events: Events) {
         match events {
+            Events::Key(_) | Events::Mouse(_) if self.above_content_ceiling => {},
             Events::Key(key_event) => self.handle_key_events(key_event),
             Events::Mouse(mouse_event) => self.handle_mouse_events(mouse_event),
             _ => self.tick(),
         }
     }
 
+    /// Coming back from the reader, the chapters read may have changed
+    fn on_focus(&mut self) {
+        self.local_event_tx.send(MangaPageEvents::CheckChapterStatus).ok();
+    }
+
+    fn wants_input(&self) -> bool {
+        self.is_downloading_all_chapters()
+    }
+
     fn clean_up(&mut self) {
         self.abort_tasks();
         self.manga.tags = vec![];
@@ -1015,11 +1748,81 @@ impl Component for MangaPage {
     }
 }
 
+async fn download_single_chapter(
+    manga_id: &str,
+    manga_title: &str,
+    chapter: ChapterToDownload,
+    tx: UnboundedSender<MangaPageEvents>,
+) {
+    let manga_response = MangadexClient::global().get_chapter_pages(&chapter.id).await;
+    match manga_response {
+        Ok(response) => {
+            let config = MangaTuiConfig::get();
+
+            let (files, quality) = match config.image_quality {
+                ImageQuality::Low => (response.chapter.data_saver, PageType::LowQuality),
+                ImageQuality::High => (response.chapter.data, PageType::HighQuality),
+            };
+
+            let endpoint = format!("{}/{}/{}", response.base_url, quality, response.chapter.hash);
+            let manga_title = to_filename(manga_title);
+            let chapter_title = to_filename(&chapter.title);
+            let scanlator = to_filename(&chapter.scanlator);
+            let lang = chapter.language.as_human_readable().to_string();
+
+            let download = DownloadChapter {
+                id_chapter: &chapter.id,
+                manga_id,
+                manga_title: &manga_title,
+                chapter_title: &chapter_title,
+                number: &chapter.number,
+                scanlator: &scanlator,
+                lang: &lang,
+            };
+
+            let download_chapter_task = download_chapter(&config.download_type, false, download, files, endpoint, tx.clone());
+
+            if let Err(e) = download_chapter_task {
+                write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                tx.send(MangaPageEvents::DownloadError(chapter.id)).ok();
+                return;
+            }
+
+            tx.send(MangaPageEvents::SaveChapterDownloadStatus(chapter.id, chapter.title)).ok();
+        },
+        Err(e) => {
+            write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+            tx.send(MangaPageEvents::DownloadError(chapter.id)).ok();
+        },
+    }
+}
+
+fn decode_cover(bytes: &[u8]) -> Option<DynamicImage> {
+    Reader::new(Cursor::new(bytes)).with_guessed_format().ok()?.decode().ok()
+}
+
+/// The volume cover after the one shown, after the last one it goes back to the cover mangadex has
+fn next_cover(current: Option<&CoverOverride>, covers: &[CoverData]) -> Option<CoverOverride> {
+    let next = match current {
+        Some(CoverOverride::Volume { file_name, .. }) => covers
+            .iter()
+            .position(|cover| cover.attributes.file_name == *file_name)
+            .map(|index| index + 1)
+            .unwrap_or_default(),
+        _ => 0,
+    };
+
+    covers.get(next).map(|cover| CoverOverride::Volume {
+        volume: cover.attributes.volume.clone().unwrap_or_else(|| "none".to_string()),
+        file_name: cover.attributes.file_name.clone(),
+    })
+}
+
 #[cfg(test)]
 mod test {
 
     use super::*;
-    use crate::backend::ChapterData;
+    use crate::backend::{ChapterAttribute, ChapterData, ChapterRelationshipAttribute, Relationship};
     use crate::view::widgets::press_key;
 
     fn get_manga_page() -> MangaPage {
@@ -1052,6 +1855,21 @@ mod test {
         StatefulWidget::render(list, area, &mut buf, &mut manga_page.available_languages_state);
     }
 
+    #[test]
+    fn chapter_pages_are_counted_from_the_total() {
+        let chapters = |total_result| ChaptersData {
+            state: tui_widget_list::ListState::default(),
+            widget: ChaptersListWidget { chapters: vec![] },
+            page: 1,
+            total_result,
+        };
+
+        assert_eq!(1, chapters(0).total_pages());
+        assert_eq!(1, chapters(ITEMS_PER_PAGE_CHAPTERS).total_pages());
+        assert_eq!(2, chapters(ITEMS_PER_PAGE_CHAPTERS + 1).total_pages());
+        assert_eq!(2, chapters(30).total_pages());
+    }
+
     #[tokio::test]
     async fn key_events_trigger_expected_actions() {
         let mut manga_page = get_manga_page();
@@ -1086,6 +1904,18 @@ mod test {
 
         assert_eq!(MangaPageActions::SearchPreviousChapterPage, action);
 
+        // Refresh the chapters list
+        press_key(&mut manga_page, KeyCode::Char('f'));
+        let action = manga_page.local_action_rx.recv().await.unwrap();
+
+        assert_eq!(MangaPageActions::RefreshChapters, action);
+
+        // Download the chapter in its language and the compare language
+        press_key(&mut manga_page, KeyCode::Char('D'));
+        let action = manga_page.local_action_rx.recv().await.unwrap();
+
+        assert_eq!(MangaPageActions::DownloadChapterInBothLanguages, action);
+
         // Open available_languages list
         press_key(&mut manga_page, KeyCode::Char('l'));
         let action = manga_page.local_action_rx.recv().await.unwrap();
@@ -1132,6 +1962,11 @@ mod test {
 
         assert_eq!(MangaPageActions::AskDownloadAllChapters, action);
 
+        press_key(&mut manga_page, KeyCode::Char('A'));
+        let action = manga_page.local_action_rx.recv().await.unwrap();
+
+        assert_eq!(MangaPageActions::AskDownloadFromChapter, action);
+
         manga_page.ask_download_all_chapters();
 
         // confirm download all chapters
@@ -1212,12 +2047,198 @@ mod test {
         assert!(second_event == MangaPageEvents::FethStatistics || second_event == MangaPageEvents::SearchChapters);
     }
 
+    #[tokio::test]
+    async fn the_reading_order_starts_at_the_current_part() {
+        let mut manga_page = get_manga_page();
+        manga_page.manga.id = "second".to_string();
+
+        press_key(&mut manga_page, KeyCode::Char('n'));
+        let action = manga_page.local_action_rx.recv().await.unwrap();
+        assert_eq!(MangaPageActions::ToggleReadingOrder, action);
+
+        manga_page.update(action);
+
+        let part = |id: &str| SeriesPart {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        manga_page.load_series_parts(Some(vec![part("first"), part("second"), part("third")]));
+
+        manga_page.update(MangaPageActions::ScrollDownReadingOrder);
+        manga_page.update(MangaPageActions::ScrollDownReadingOrder);
+
+        assert_eq!(Some(2), manga_page.reading_order.as_ref().unwrap().state.selected());
+
+        press_key(&mut manga_page, KeyCode::Esc);
+        let action = manga_page.local_action_rx.recv().await.unwrap();
+        assert_eq!(MangaPageActions::ToggleReadingOrder, action);
+    }
+
+    #[test]
+    fn other_versions_of_an_unavailable_chapter_are_listed() {
+        let mut manga_page = get_manga_page();
+
+        let response = ChapterResponse {
+            data: vec![
+                ChapterData {
+                    id: "unavailable".to_string(),
+                    ..Default::default()
+                },
+                ChapterData {
+                    id: "other_group".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        manga_page.load_other_versions("unavailable".to_string(), Some(response));
+
+        let other_versions = manga_page.other_versions.as_ref().expect("other versions were not listed");
+        assert_eq!(1, other_versions.widget.chapters.len());
+        assert_eq!("other_group", other_versions.widget.chapters[0].id);
+
+        manga_page.update(MangaPageActions::CloseOtherVersions);
+        assert!(manga_page.other_versions.is_none());
+
+        // No other group uploaded the chapter
+        manga_page.load_other_versions("unavailable".to_string(), Some(ChapterResponse::default()));
+        assert!(manga_page.other_versions.is_none());
+    }
+
+    /// A chapter the same group uploaded twice
+    fn reuploaded_chapter_response() -> ChapterResponse {
+        ChapterResponse {
+            data: ["first", "reupload"]
+                .map(|id| ChapterData {
+                    id: id.to_string(),
+                    attributes: ChapterAttribute {
+                        chapter: Some("1".to_string()),
+                        ..Default::default()
+                    },
+                    relationships: vec![Relationship {
+                        id: "group_id".to_string(),
+                        type_field: "scanlation_group".to_string(),
+                        attributes: Some(ChapterRelationshipAttribute {
+                            name: "group".to_string(),
+                        }),
+                    }],
+                    ..Default::default()
+                })
+                .to_vec(),
+            total: 2,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn only_the_chapters_of_the_group_selected_are_listed() {
+        let mut manga_page = get_manga_page();
+        let response = reuploaded_chapter_response();
+        manga_page.load_chapters(Some(response.clone()));
+        render_chapters(&mut manga_page);
+
+        manga_page.update(MangaPageActions::ToggleGroupFilter);
+
+        assert_eq!(Some(("group_id".to_string(), "group".to_string())), manga_page.group);
+        assert_eq!(PageState::SearchingChapters, manga_page.state);
+
+        manga_page.load_chapters(Some(response));
+
+        let chapters = &manga_page.chapters.as_ref().unwrap().widget.chapters;
+        assert_eq!(1, chapters.len());
+        assert_eq!("first", chapters[0].id);
+
+        manga_page.update(MangaPageActions::ToggleGroupFilter);
+        assert!(manga_page.group.is_none());
+    }
+
+    #[test]
+    fn reuploads_are_only_dropped_when_filtering_by_group_without_the_smart_list() {
+        let mut manga_page = get_manga_page();
+        let listed = |manga_page: &mut MangaPage| {
+            manga_page.load_chapters(Some(reuploaded_chapter_response()));
+            manga_page.chapters.as_ref().unwrap().widget.chapters.len()
+        };
+
+        assert_eq!(2, listed(&mut manga_page));
+
+        manga_page.group = Some(("group_id".to_string(), "group".to_string()));
+        assert_eq!(1, listed(&mut manga_page));
+
+        manga_page.smart_list = true;
+        assert_eq!(2, listed(&mut manga_page));
+
+        manga_page.group = None;
+        assert_eq!(2, listed(&mut manga_page));
+    }
+
+    #[tokio::test]
+    async fn smart_list_is_searched_from_the_first_page() {
+        let mut manga_page = get_manga_page();
+        manga_page.load_chapters(Some(get_chapters_response()));
+        manga_page.chapters.as_mut().unwrap().page = 2;
+
+        press_key(&mut manga_page, KeyCode::Char('m'));
+        let action = manga_page.local_action_rx.recv().await.unwrap();
+        assert_eq!(MangaPageActions::ToggleSmartList, action);
+
+        manga_page.update(action);
+
+        assert!(manga_page.smart_list);
+        assert!(manga_page.chapters.is_none());
+        assert_eq!(PageState::SearchingChapters, manga_page.state);
+
+        // Nothing changes until the chapters are found
+        manga_page.update(MangaPageActions::ToggleSmartList);
+        assert!(manga_page.smart_list);
+    }
+
+    #[tokio::test]
+    async fn chapters_are_downloaded_from_the_selected_one() {
+        let mut manga_page = get_manga_page();
+        manga_page.load_chapters(Some(ChapterResponse {
+            data: ["first", "second"]
+                .map(|id| ChapterData {
+                    id: id.to_string(),
+                    ..Default::default()
+                })
+                .to_vec(),
+            total: 2,
+            ..Default::default()
+        }));
+        render_chapters(&mut manga_page);
+
+        manga_page.update(MangaPageActions::ScrollChapterDown);
+        manga_page.update(MangaPageActions::AskDownloadFromChapter);
+
+        assert_eq!(DownloadPhase::Asking, manga_page.download_all_chapters_state.phase);
+        assert_eq!(Some("second"), manga_page.download_all_chapters_state.from_chapter.as_ref().map(|chapter| chapter.id.as_str()));
+
+        // Downloading every chapter afterwards doesn't keep where the previous download started
+        manga_page.update(MangaPageActions::CancelDownloadAll);
+        manga_page.update(MangaPageActions::AskDownloadAllChapters);
+        assert!(manga_page.download_all_chapters_state.from_chapter.is_none());
+    }
+
     #[tokio::test]
     async fn handle_events() {
         let mut manga_page = get_manga_page();
         manga_page_initialized_correctly(&mut manga_page).await;
     }
 
+    #[tokio::test]
+    async fn language_the_chapters_are_listed_in_is_marked() {
+        let mut manga_page = get_manga_page();
+        manga_page.manga.available_languages = vec![Languages::English, Languages::Spanish];
+        manga_page.chapter_language = Languages::Spanish;
+
+        let items = manga_page.available_language_items();
+
+        assert!(!items[0].ends_with("(current)"));
+        assert!(items[1].ends_with(&format!("{} (current)", Languages::Spanish.as_human_readable())));
+    }
+
     #[tokio::test]
     async fn handle_key_events() {
         let mut manga_page = get_manga_page();
@@ -1307,4 +2328,42 @@ mod test {
 
         assert!(!manga_page.download_process_started());
     }
+
+    #[test]
+    fn covers_cycle_through_volumes_and_back_to_the_default() {
+        let covers: Vec<CoverData> = ["vol1.jpg", "vol2.jpg"]
+            .iter()
+            .enumerate()
+            .map(|(index, file_name)| CoverData {
+                id: index.to_string(),
+                attributes: crate::backend::covers::Attributes {
+                    volume: Some((index + 1).to_string()),
+                    file_name: file_name.to_string(),
+                },
+            })
+            .collect();
+
+        let first = next_cover(None, &covers);
+        assert_eq!(
+            Some(CoverOverride::Volume {
+                volume: "1".to_string(),
+                file_name: "vol1.jpg".to_string()
+            }),
+            first
+        );
+
+        let second = next_cover(first.as_ref(), &covers);
+        assert_eq!(
+            Some(CoverOverride::Volume {
+                volume: "2".to_string(),
+                file_name: "vol2.jpg".to_string()
+            }),
+            second
+        );
+
+        assert_eq!(None, next_cover(second.as_ref(), &covers));
+
+        // A local image is replaced by the first volume cover
+        assert_eq!(first, next_cover(Some(&CoverOverride::LocalFile("cover.png".into())), &covers));
+    }
 }
@@ -0,0 +1,465 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use image::DynamicImage;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Margin, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span, ToSpan};
+use ratatui::widgets::{Block, Paragraph, StatefulWidget, StatefulWidgetRef, Widget};
+use ratatui::Frame;
+use ratatui_image::picker::Picker;
+use ratatui_image::Resize;
+use throbber_widgets_tui::{Throbber, ThrobberState};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinSet;
+use tui_widget_list::ListState;
+
+use crate::backend::error_log::{write_to_error_log, ErrorType};
+use crate::backend::fetch::{MangadexClient, ITEMS_PER_PAGE_SEARCH, MAX_SEARCH_RESULTS};
+use crate::backend::filter::Ranking;
+use crate::backend::tui::{Events, Route};
+use crate::backend::SearchMangaResponse;
+use crate::common::ImageState;
+use crate::config::MangaTuiConfig;
+use crate::global::{ERROR_STYLE, INSTRUCTIONS_STYLE};
+use crate::utils::search_manga_cover;
+use crate::view::widgets::search::{ListMangasFoundWidget, MangaItem, MangaPreview};
+use crate::view::widgets::{Component, ImageHandler};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PopularActions {
+    ScrollDown,
+    ScrollUp,
+    NextPage,
+    PreviousPage,
+    NextRanking,
+    GoToMangaPage,
+}
+
+pub enum PopularEvents {
+    SearchCovers,
+    LoadCover(Option<DynamicImage>, String),
+    LoadMangasFound(Option<SearchMangaResponse>),
+}
+
+impl ImageHandler for PopularEvents {
+    fn load(image: DynamicImage, id: String) -> Self {
+        Self::LoadCover(Some(image), id)
+    }
+
+    fn not_found(id: String) -> Self {
+        Self::LoadCover(None, id)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PopularState {
+    Searching,
+    Displaying,
+    ErrorSearching,
+}
+
+/// The most followed, best rated and newest mangas, shown with the same list and preview as the search page
+pub struct Popular {
+    state: PopularState,
+    ranking: Ranking,
+    mangas_found: ListMangasFoundWidget,
+    list_state: ListState,
+    page: u32,
+    total_result: u32,
+    picker: Option<Picker>,
+    manga_cover_state: ImageState,
+    loader_state: ThrobberState,
+    pub global_event_tx: UnboundedSender<Events>,
+    pub local_action_tx: UnboundedSender<PopularActions>,
+    pub local_action_rx: UnboundedReceiver<PopularActions>,
+    pub local_event_tx: UnboundedSender<PopularEvents>,
+    pub local_event_rx: UnboundedReceiver<PopularEvents>,
+    /// When the mangas found were loaded, `Some` while their covers wait for `img_area` to be set
+    pending_covers_search: Option<Instant>,
+    tasks: JoinSet<()>,
+}
+
+/// How long the covers wait before being searched, so that `img_area` is set to the area for covers
+static COVERS_SEARCH_DELAY: Duration = Duration::from_millis(500);
+
+impl Popular {
+    pub fn new(global_event_tx: UnboundedSender<Events>, picker: Option<Picker>) -> Self {
+        let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<PopularActions>();
+        let (local_event_tx, local_event_rx) = mpsc::unbounded_channel::<PopularEvents>();
+
+        Self {
+            state: PopularState::Displaying,
+            ranking: Ranking::default(),
+            mangas_found: ListMangasFoundWidget::default(),
+            list_state: ListState::default(),
+            page: 1,
+            total_result: 0,
+            picker,
+            manga_cover_state: ImageState::default(),
+            loader_state: ThrobberState::default(),
+            global_event_tx,
+            local_action_tx,
+            local_action_rx,
+            local_event_tx,
+            local_event_rx,
+            pending_covers_search: None,
+            tasks: JoinSet::new(),
+        }
+    }
+
+    /// Only the pages mangadex can return, the results after `MAX_SEARCH_RESULTS` can't be reached
+    fn total_pages(&self) -> u32 {
+        self.total_result.min(MAX_SEARCH_RESULTS).div_ceil(ITEMS_PER_PAGE_SEARCH).max(1)
+    }
+
+    fn search_mangas(&mut self) {
+        self.tasks.abort_all();
+        self.manga_cover_state = ImageState::default();
+        self.list_state = ListState::default();
+        self.state = PopularState::Searching;
+
+        let tx = self.local_event_tx.clone();
+        let ranking = self.ranking;
+        let page = self.page;
+
+        self.tasks.spawn(async move {
+            match MangadexClient::global().get_ranked_mangas(ranking, page).await {
+                Ok(response) => {
+                    tx.send(PopularEvents::LoadMangasFound(Some(response))).ok();
+                },
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    tx.send(PopularEvents::LoadMangasFound(None)).ok();
+                },
+            }
+        });
+    }
+
+    /// Pages before the first or after the last one are not searched
+    fn search_page(&mut self, page: u32) {
+        if self.state == PopularState::Displaying && (1..=self.total_pages()).contains(&page) && page != self.page {
+            self.page = page;
+            self.search_mangas();
+        }
+    }
+
+    fn next_ranking(&mut self) {
+        self.ranking = self.ranking.next();
+        self.page = 1;
+        self.search_mangas();
+    }
+
+    fn load_mangas_found(&mut self, response: Option<SearchMangaResponse>) {
+        match response {
+            Some(response) => {
+                self.total_result = response.total;
+                self.mangas_found = ListMangasFoundWidget::from_response(response.data);
+                if !self.mangas_found.mangas.is_empty() {
+                    self.list_state.select(Some(0));
+                }
+                self.state = PopularState::Displaying;
+                self.local_event_tx.send(PopularEvents::SearchCovers).ok();
+            },
+            None => self.state = PopularState::ErrorSearching,
+        }
+    }
+
+    fn search_covers(&mut self) {
+        for item in self.mangas_found.mangas.iter() {
+            let manga_id = item.manga.id.clone();
+            let tx = self.local_event_tx.clone();
+
+            match item.manga.img_url.as_ref() {
+                Some(file_name) => search_manga_cover(file_name.clone(), manga_id, &mut self.tasks, tx),
+                None => {
+                    tx.send(PopularEvents::LoadCover(None, manga_id)).ok();
+                },
+            };
+        }
+    }
+
+    fn search_covers_once_laid_out(&mut self) {
+        if !self.pending_covers_search.is_some_and(|loaded_at| loaded_at.elapsed() >= COVERS_SEARCH_DELAY) {
+            return;
+        }
+
+        self.pending_covers_search = None;
+        self.search_covers();
+    }
+
+    fn load_cover(&mut self, maybe_cover: Option<DynamicImage>, manga_id: String) {
+        if let (Some(cover), Some(picker)) = (maybe_cover, self.picker.as_mut()) {
+            if let Ok(protocol) = picker.new_protocol(
+                cover,
+                self.manga_cover_state.get_img_area(),
+                Resize::Fit(Some(MangaTuiConfig::get().cover_render_quality.filter())),
+            ) {
+                self.manga_cover_state.insert_manga(protocol, manga_id);
+            }
+        }
+    }
+
+    fn get_current_manga_selected(&self) -> Option<&MangaItem> {
+        self.list_state.selected.and_then(|index| self.mangas_found.mangas.get(index))
+    }
+
+    fn go_to_manga_page(&mut self) {
+        if let Some(item) = self.get_current_manga_selected() {
+            self.global_event_tx.send(Events::Navigate(Route::Manga(item.clone()))).ok();
+        }
+    }
+
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+        let area = area.inner(Margin {
+            horizontal: 2,
+            vertical: 2,
+        });
+
+        match self.state {
+            PopularState::Searching => {
+                let loader = Throbber::default()
+                    .label(format!("Searching {}, please wait ", self.ranking.to_string().to_lowercase()))
+                    .style(Style::default().fg(Color::Yellow))
+                    .throbber_set(throbber_widgets_tui::BRAILLE_SIX)
+                    .use_type(throbber_widgets_tui::WhichUse::Spin);
+
+                StatefulWidget::render(loader, area, buf, &mut self.loader_state);
+            },
+            PopularState::ErrorSearching => {
+                let error = "Error, could not get the popular mangas, please try again another time";
+                Paragraph::new(error.to_span().style(*ERROR_STYLE)).render(area, buf);
+            },
+            PopularState::Displaying => {
+                Paragraph::new("No mangas were found").render(area, buf);
+            },
+        }
+    }
+
+    fn handle_key_events(&mut self, key_event: KeyEvent) {
+        let action = match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => PopularActions::ScrollDown,
+            KeyCode::Char('k') | KeyCode::Up => PopularActions::ScrollUp,
+            KeyCode::Char('w') => PopularActions::NextPage,
+            KeyCode::Char('b') => PopularActions::PreviousPage,
+            KeyCode::Char('t') => PopularActions::NextRanking,
+            KeyCode::Char('r') | KeyCode::Enter => PopularActions::GoToMangaPage,
+            _ => return,
+        };
+        self.local_action_tx.send(action).ok();
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::ScrollUp => {
+                self.local_action_tx.send(PopularActions::ScrollUp).ok();
+            },
+            MouseEventKind::ScrollDown => {
+                self.local_action_tx.send(PopularActions::ScrollDown).ok();
+            },
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.local_action_tx.send(PopularActions::GoToMangaPage).ok();
+            },
+            _ => {},
+        }
+    }
+
+    pub fn tick(&mut self) {
+        self.loader_state.calc_next();
+        if let Ok(local_event) = self.local_event_rx.try_recv() {
+            match local_event {
+                PopularEvents::LoadMangasFound(response) => self.load_mangas_found(response),
+                PopularEvents::SearchCovers => {
+                    if self.picker.is_some() {
+                        self.pending_covers_search = Some(Instant::now());
+                    }
+                },
+                PopularEvents::LoadCover(maybe_image, manga_id) => self.load_cover(maybe_image, manga_id),
+            }
+        }
+        self.search_covers_once_laid_out();
+    }
+}
+
+impl Component for Popular {
+    type Actions = PopularActions;
+
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        let buf = frame.buffer_mut();
+        let [manga_list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(area);
+
+        let list_instructions = Line::from(vec![
+            "Go down ".into(),
+            Span::raw("<j>").style(*INSTRUCTIONS_STYLE),
+            " Go up ".into(),
+            Span::raw("<k>").style(*INSTRUCTIONS_STYLE),
+            " Read ".into(),
+            Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        let ranking_instructions = Line::from(vec![
+            format!("{} ", self.ranking).into(),
+            "Change ".into(),
+            Span::raw("<t>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        let pagination_instructions = Line::from(vec![
+            format!("Page {} of {} ", self.page, self.total_pages()).into(),
+            "Next ".into(),
+            Span::raw("<w>").style(*INSTRUCTIONS_STYLE),
+            " Previous ".into(),
+            Span::raw("<b>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        Block::bordered()
+            .title_top(ranking_instructions)
+            .title_top(list_instructions.right_aligned())
+            .title_bottom(pagination_instructions)
+            .render(manga_list_area, buf);
+
+        if self.state != PopularState::Displaying || self.mangas_found.mangas.is_empty() {
+            self.render_status(manga_list_area, buf);
+            return;
+        }
+
+        let inner_list_area = manga_list_area.inner(Margin {
+            horizontal: 1,
+            vertical: 1,
+        });
+
+        StatefulWidgetRef::render_ref(&self.mangas_found, inner_list_area, buf, &mut self.list_state);
+
+        if let Some(item) = self.get_current_manga_selected() {
+            let manga = item.manga.clone();
+            StatefulWidget::render(
+                MangaPreview::new(
+                    &manga.id,
+                    &manga.title,
+                    &manga.description,
+                    &manga.tags,
+                    &manga.content_rating,
+                    &manga.status,
+                    self.picker.is_some(),
+                    self.loader_state.clone(),
+                ),
+                preview_area,
+                buf,
+                &mut self.manga_cover_state,
+            );
+        }
+    }
+
+    fn update(&mut self, action: Self::Actions) {
+        match action {
+            PopularActions::ScrollDown => self.list_state.next(),
+            PopularActions::ScrollUp => self.list_state.previous(),
+            PopularActions::NextPage => self.search_page(self.page + 1),
+            PopularActions::PreviousPage => self.search_page(self.page.saturating_sub(1)),
+            PopularActions::NextRanking => self.next_ranking(),
+            PopularActions::GoToMangaPage => self.go_to_manga_page(),
+        }
+    }
+
+    fn on_focus(&mut self) {
+        if self.mangas_found.mangas.is_empty() && self.state != PopularState::Searching {
+            self.search_mangas();
+        }
+    }
+
+    fn clean_up(&mut self) {
+        self.tasks.abort_all();
+        self.mangas_found = ListMangasFoundWidget::default();
+        self.list_state = ListState::default();
+        self.manga_cover_state = ImageState::default();
+        self.state = PopularState::Displaying;
+    }
+
+    fn handle_events(&mut self, events: Events) {
+        match events {
+            Events::Key(key_event) => self.handle_key_events(key_event),
+            Events::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
+            Events::Tick => self.tick(),
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::Data;
+    use crate::view::widgets::press_key;
+
+    fn mangas_found(ids: &[&str], total: u32) -> SearchMangaResponse {
+        SearchMangaResponse {
+            data: ids
+                .iter()
+                .map(|id| Data {
+                    id: id.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            total,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn selected_manga_is_opened_and_pages_past_the_last_are_not_searched() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Events>();
+        let mut page = Popular::new(tx, None);
+        page.load_mangas_found(Some(mangas_found(&["first", "second"], 2)));
+
+        press_key(&mut page, KeyCode::Char('j'));
+        let action = page.local_action_rx.recv().await.unwrap();
+        page.update(action);
+        press_key(&mut page, KeyCode::Enter);
+        let action = page.local_action_rx.recv().await.unwrap();
+        assert_eq!(PopularActions::GoToMangaPage, action);
+        page.update(action);
+
+        let Events::Navigate(Route::Manga(manga)) = rx.recv().await.unwrap() else {
+            panic!("the manga page should be opened");
+        };
+        assert_eq!("second", manga.manga.id);
+
+        page.update(PopularActions::NextPage);
+        assert_eq!((1, PopularState::Displaying), (page.page, page.state));
+    }
+
+    #[tokio::test]
+    async fn covers_are_searched_after_a_delay_without_blocking_the_tick() {
+        let (tx, _) = mpsc::unbounded_channel::<Events>();
+        let mut page = Popular::new(tx, Some(Picker::new((8, 16))));
+        page.load_mangas_found(Some(mangas_found(&["first"], 1)));
+
+        let started = Instant::now();
+        page.tick();
+        assert!(started.elapsed() < COVERS_SEARCH_DELAY);
+        assert!(page.pending_covers_search.is_some());
+
+        page.pending_covers_search = page.pending_covers_search.map(|loaded_at| loaded_at - COVERS_SEARCH_DELAY);
+        page.tick();
+        assert!(page.pending_covers_search.is_none());
+
+        // The manga has no cover, so it's loaded right away as missing
+        let PopularEvents::LoadCover(None, manga_id) = page.local_event_rx.recv().await.unwrap() else {
+            panic!("the cover of the manga should be searched");
+        };
+        assert_eq!("first", manga_id);
+    }
+
+    #[test]
+    fn rankings_go_back_to_the_first_after_the_last() {
+        let rankings: Vec<Ranking> = std::iter::successors(Some(Ranking::default()), |ranking| Some(ranking.next()))
+            .take(5)
+            .collect();
+
+        assert_eq!(
+            vec![Ranking::PopularNew, Ranking::MostFollowed, Ranking::TopRated, Ranking::RecentlyAdded, Ranking::PopularNew],
+            rankings
+        );
+    }
+}
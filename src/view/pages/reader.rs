@@ -1,28 +1,100 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::future::Future;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
 use crossterm::event::KeyCode;
-use image::io::Reader;
-use image::{DynamicImage, GenericImageView};
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::imageops::FilterType;
+use image::{AnimationDecoder, DynamicImage, GenericImageView, ImageFormat};
+use manga_tui::image_pipeline::decode_page;
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Margin, Rect};
+use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Paragraph, StatefulWidget, Widget};
+use ratatui::widgets::{Block, Clear, Paragraph, StatefulWidget, Widget};
 use ratatui::Frame;
-use ratatui_image::picker::Picker;
+use ratatui_image::picker::{Picker, ProtocolType};
 use ratatui_image::protocol::StatefulProtocol;
-use ratatui_image::{Resize, StatefulImage};
+use ratatui_image::{CropOptions, Resize, StatefulImage};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
 
+use crate::backend::cache::PageCache;
+use crate::backend::database::{
+    get_fit_mode, get_last_page_read, get_read_chapter_numbers, get_reader_layout, get_reading_direction, mark_chapter_as_read,
+    save_fit_mode, save_hint_seen, save_last_page_read, save_reader_setting, save_reading_direction, ChapterProgressSave,
+    ReaderLayout, ReaderSetting,
+};
 use crate::backend::error_log::{write_to_error_log, ErrorType};
 use crate::backend::fetch::MangadexClient;
+#[cfg(feature = "ocr")]
+use crate::backend::ocr;
+use crate::backend::filter::Languages;
+use crate::backend::repeated_pages::{page_hash, repeated_page};
 use crate::backend::tui::Events;
-use crate::common::PageType;
+use crate::backend::ChapterPagesResponse;
+use crate::common::{FitMode, ImageFilter, PageType, ReadingDirection};
+use crate::config::{MangaTuiConfig, ReaderBackground};
 use crate::global::INSTRUCTIONS_STYLE;
-use crate::view::widgets::reader::{PageItemState, PagesItem, PagesList};
+use crate::utils::{render_notice, render_search_bar};
+use crate::view::widgets::reader::{PageItemState, PagesItem, PagesList, ReaderStatusBar};
 use crate::view::widgets::Component;
 
 pub enum MangaReaderActions {
     NextPage,
     PreviousPage,
+    ToggleGoToPrompt,
+    SubmitGoToPrompt,
+    ToggleReadingDirection,
+    ToggleDoublePage,
+    ToggleSpreadOffset,
+    ToggleWebtoon,
+    CycleFitMode,
+    ToggleImageQuality,
+    ToggleStatusBar,
+    RetryPage,
+    GoToNextChapter,
+    GoToPreviousChapter,
+    GoToNextUnreadChapter,
+    SwitchLanguage,
+    /// Clockwise when true
+    RotatePage(bool),
+    CycleImageFilter,
+    ToggleCropMargins,
+    ToggleOverview,
+    MoveOverviewSelection(GridMove),
+    OpenOverviewSelection,
+    ToggleHelp,
+    DismissFirstTimeHint,
+    ExportPage(PageExport),
+    ToggleAnimations,
+    /// Forward when true
+    StepFrame(bool),
+    ToggleSkipRepeatedPages,
+    #[cfg(feature = "ocr")]
+    ToggleTextPane,
+    #[cfg(feature = "ocr")]
+    ToggleTranslation,
+}
+
+/// What can be done with the current page besides reading it, for terminals which can't show images well
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageExport {
+    /// Written to `saved_pages_directory`
+    Save,
+    /// Written to a temporary directory and opened with `external_viewer`
+    OpenInViewer,
 }
 
 pub enum State {
@@ -33,42 +105,967 @@ pub struct PageData {
     pub img: DynamicImage,
     pub index: usize,
     pub dimensions: (u32, u32),
+    /// Every frame of the page if it's animated, `img` is the first one
+    pub animation: Option<Animation>,
+    /// Used to find the pages repeated in the chapter
+    pub hash: u64,
+}
+
+/// Frames which ask to be shown for less than this are shown for `DEFAULT_FRAME_DELAY` instead, as browsers do
+static MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+static DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// The frames of an animated gif or png and how long each one is shown
+pub struct Animation {
+    pub frames: Vec<(Arc<DynamicImage>, Duration)>,
+}
+
+impl Animation {
+    pub fn delay(&self, frame: usize) -> Duration {
+        match self.frames.get(frame) {
+            Some((_, delay)) if *delay >= MIN_FRAME_DELAY => *delay,
+            _ => DEFAULT_FRAME_DELAY,
+        }
+    }
 }
 
 pub enum MangaReaderEvents {
     FetchPages,
     LoadPage(Option<PageData>),
+    /// index of the page
+    FailedPage(usize),
+    LoadNextChapter(Option<Box<(ReadChapterInfo, ChapterPagesResponse)>>),
+    LoadNextChapterPage(PageData),
+    LoadPreviousChapter(Option<Box<(ReadChapterInfo, ChapterPagesResponse)>>),
+    LoadNextUnreadChapter(Option<Box<(ReadChapterInfo, ChapterPagesResponse)>>),
+    /// The chapter being read in the other language, which was searched from this page
+    LoadChapterInOtherLanguage(usize, Option<Box<(ReadChapterInfo, ChapterPagesResponse)>>),
+    LoadThumbnail(PageData),
+    /// index of the page
+    FailedThumbnail(usize),
+    PreparedPage(PreparedPage),
+    /// index of the page
+    FittedPage(usize, FittedPage),
+    /// index of the page
+    WebtoonPage(usize, WebtoonPage),
+    /// index of the page, id of the `EncodedImage` and its protocol encoded for the area it's drawn in
+    EncodedImage(usize, u64, Box<dyn StatefulProtocol>),
+    /// Where the page was written to, `None` if it could not be
+    PageExported(PageExport, Option<PathBuf>),
+    /// index of the page and its text, `None` if tesseract failed
+    #[cfg(feature = "ocr")]
+    PageText(usize, Option<String>),
+    /// index of the page and the translation of its text, `None` if the translation command failed
+    #[cfg(feature = "ocr")]
+    PageTranslation(usize, Option<String>),
+}
+
+/// A chapter fetched ahead of time together with its first pages
+pub struct PreloadedChapter {
+    pub chapter: ReadChapterInfo,
+    pub response: ChapterPagesResponse,
+    pub page_type: PageType,
+    pub pages: Vec<PageData>,
+}
+
+/// The search of the chapter before or after the one being read
+#[derive(Default)]
+pub enum ChapterSearch {
+    #[default]
+    NotSearched,
+    Searching,
+    NotFound,
+    Found(Box<PreloadedChapter>),
+}
+
+impl PartialEq for ChapterSearch {
+    fn eq(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+pub struct ChapterEnd {
+    pub marked_as_read: bool,
+}
+
+/// The thumbnails are made from the data saver pages and downscaled to this size in pixels
+static THUMBNAIL_SIZE: (u32, u32) = (160, 240);
+
+/// Each thumbnail takes this many columns and rows, including its border
+static THUMBNAIL_CELL: (u16, u16) = (14, 12);
+
+#[derive(Clone, Copy)]
+pub enum GridMove {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Default)]
+pub enum ThumbnailState {
+    #[default]
+    Waiting,
+    Loading,
+    Loaded(Box<dyn StatefulProtocol>),
+    Failed,
+}
+
+/// A grid with a thumbnail of every page of the chapter to jump to any of them
+#[derive(Default)]
+pub struct Overview {
+    pub thumbnails: Vec<ThumbnailState>,
+    pub selected: usize,
+    /// How many thumbnails fit in a row, known once the grid is rendered
+    pub columns: usize,
+    /// The first row shown
+    pub scroll: usize,
+    /// The thumbnails on screen, only these are fetched
+    pub visible: std::ops::Range<usize>,
+}
+
+impl Overview {
+    pub fn new(total_pages: usize, selected: usize) -> Self {
+        Self {
+            thumbnails: (0..total_pages).map(|_| ThumbnailState::default()).collect(),
+            selected: selected.min(total_pages.saturating_sub(1)),
+            columns: 1,
+            ..Default::default()
+        }
+    }
+
+    pub fn move_selection(&mut self, direction: GridMove) {
+        let last = self.thumbnails.len().saturating_sub(1);
+        self.selected = match direction {
+            GridMove::Left => self.selected.saturating_sub(1),
+            GridMove::Right => (self.selected + 1).min(last),
+            GridMove::Up => self.selected.checked_sub(self.columns).unwrap_or(self.selected),
+            GridMove::Down => (self.selected + self.columns).min(last),
+        };
+    }
+
+    /// Scrolls just enough for the selected thumbnail to be in one of the `rows` shown
+    fn scroll_to_selected(&mut self, rows: usize) {
+        let selected_row = self.selected / self.columns;
+        if selected_row < self.scroll {
+            self.scroll = selected_row;
+        } else if selected_row >= self.scroll + rows {
+            self.scroll = selected_row + 1 - rows;
+        }
+    }
+}
+
+/// How many pages before the end of the chapter the next one starts being preloaded
+static NEXT_CHAPTER_PRELOAD_PAGES: usize = 3;
+
+/// Which chapter is searched from the one being read
+#[derive(Clone)]
+enum ChapterStep {
+    Next,
+    Previous,
+    /// The first chapter after the highest of these chapter numbers, which were read
+    NextUnread(Vec<String>),
+    /// The same chapter translated to this language
+    InLanguage(Languages),
+}
+
+/// Finds the chapter after or before `chapter`, or the same one in another language, and the servers its
+/// pages are fetched from
+async fn fetch_adjacent_chapter(
+    chapter: &ReadChapterInfo,
+    step: ChapterStep,
+) -> Option<Box<(ReadChapterInfo, ChapterPagesResponse)>> {
+    let client = MangadexClient::global();
+    let language = match &step {
+        ChapterStep::InLanguage(language) => *language,
+        _ => chapter.language,
+    };
+    let adjacent_chapter = match step {
+        ChapterStep::Next => client.get_next_chapter(&chapter.manga_id, &chapter.number, chapter.language).await,
+        ChapterStep::Previous => client.get_previous_chapter(&chapter.manga_id, &chapter.number, chapter.language).await,
+        ChapterStep::NextUnread(read_numbers) => {
+            client.get_next_unread_chapter(&chapter.manga_id, &read_numbers, chapter.language).await
+        },
+        ChapterStep::InLanguage(language) => client
+            .get_other_versions_of_chapter(&chapter.manga_id, &chapter.number, language)
+            .await
+            .map(|versions| versions.data.into_iter().next()),
+    };
+
+    let adjacent_chapter = match adjacent_chapter {
+        Ok(adjacent_chapter) => adjacent_chapter?,
+        Err(e) => {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+            return None;
+        },
+    };
+
+    match client.get_chapter_pages(&adjacent_chapter.id).await {
+        Ok(response) => {
+            let info = ReadChapterInfo {
+                manga_id: chapter.manga_id.clone(),
+                chapter_id: adjacent_chapter.id,
+                number: adjacent_chapter.attributes.chapter.unwrap_or_default(),
+                title: adjacent_chapter.attributes.title.unwrap_or_default(),
+                language,
+            };
+            Some(Box::new((info, response)))
+        },
+        Err(e) => {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+            None
+        },
+    }
+}
+
+/// Runs `task` on `tasks` until it finishes or `cancellation` is cancelled, whichever comes first
+fn spawn_cancellable(tasks: &mut JoinSet<()>, cancellation: &CancellationToken, task: impl Future<Output = ()> + Send + 'static) {
+    let cancellation = cancellation.clone();
+    tasks.spawn(async move {
+        tokio::select! {
+            _ = cancellation.cancelled() => {},
+            _ = task => {},
+        }
+    });
+}
+
+/// Fetches and decodes a page, `None` if either of them failed, pages read before are taken from the page cache
+async fn fetch_page_data(endpoint: &str, file_name: &str, index: usize) -> Option<PageData> {
+    let bytes = fetch_page_bytes(endpoint, file_name).await?;
+
+    let decoded = tokio::task::spawn_blocking(move || {
+        decode_page(&bytes).map(|decoded| {
+            let hash = page_hash(&decoded);
+            (decoded, decode_animation(&bytes), hash)
+        })
+    });
+
+    match decoded.await.ok()? {
+        Ok((decoded, animation, hash)) => Some(PageData {
+            dimensions: decoded.dimensions(),
+            img: decoded,
+            index,
+            animation,
+            hash,
+        }),
+        Err(e) => {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+            None
+        },
+    }
+}
+
+/// The page as it's served by mangadex, from the disk cache if it was fetched before
+async fn fetch_page_bytes(endpoint: &str, file_name: &str) -> Option<Bytes> {
+    let cache = PageCache::global();
+
+    let cached = match cache {
+        Some(cache) => cache.get(endpoint, file_name).await,
+        None => None,
+    };
+
+    let bytes = match cached {
+        Some(bytes) => bytes,
+        None => match MangadexClient::global().get_chapter_page(endpoint, file_name).await {
+            Ok(bytes) => {
+                if let Some(cache) = cache {
+                    if let Err(e) = cache.insert(endpoint, file_name, &bytes).await {
+                        write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    }
+                }
+                bytes
+            },
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                return None;
+            },
+        },
+    };
+
+    Some(bytes)
+}
+
+/// Writes the page to `directory` as it was downloaded and opens it with the external viewer if that's what it was
+/// exported for
+async fn export_page(
+    endpoint: String,
+    file_name: String,
+    path: PathBuf,
+    export: PageExport,
+    viewer: Option<String>,
+) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+    let bytes = fetch_page_bytes(&endpoint, &file_name).await.ok_or("the page could not be fetched")?;
+
+    if let Some(directory) = path.parent() {
+        tokio::fs::create_dir_all(directory).await?;
+    }
+    tokio::fs::write(&path, bytes).await?;
+
+    if export == PageExport::OpenInViewer {
+        match viewer {
+            Some(viewer) => {
+                let command = shell_words::split(&viewer)?;
+                let (program, args) = command.split_first().ok_or("external_viewer is empty")?;
+                std::process::Command::new(program)
+                    .args(args)
+                    .arg(&path)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()?;
+            },
+            None => open::that_detached(&path)?,
+        }
+    }
+
+    Ok(path)
+}
+
+/// The frames of animated gifs and pngs, `None` for every other page
+fn decode_animation(bytes: &[u8]) -> Option<Animation> {
+    let frames = match image::guess_format(bytes).ok()? {
+        ImageFormat::Gif => GifDecoder::new(Cursor::new(bytes)).ok()?.into_frames().collect_frames().ok()?,
+        ImageFormat::Png => {
+            let decoder = PngDecoder::new(Cursor::new(bytes)).ok()?;
+            if !decoder.is_apng().ok()? {
+                return None;
+            }
+            decoder.apng().ok()?.into_frames().collect_frames().ok()?
+        },
+        _ => return None,
+    };
+
+    if frames.len() < 2 {
+        return None;
+    }
+
+    let frames = frames
+        .into_iter()
+        .map(|frame| {
+            let delay = Duration::from(frame.delay());
+            (Arc::new(DynamicImage::ImageRgba8(frame.into_buffer())), delay)
+        })
+        .collect();
+
+    Some(Animation { frames })
+}
+
+/// How far from pure white or black a pixel can be to still be part of a margin
+static MARGIN_TOLERANCE: u8 = 24;
+
+/// Scans have dust and page numbers on the margins, a line with less than this fraction of pixels
+/// which are not margin is still a margin
+static MARGIN_NOISE: f32 = 0.01;
+
+/// Removes the white or black borders around a page, the color of the margin is the one in the
+/// top left corner. Blank pages are left as they are
+pub fn crop_margins(image: &DynamicImage) -> DynamicImage {
+    let luma = image.to_luma8();
+    let (width, height) = luma.dimensions();
+    if width == 0 || height == 0 {
+        return image.clone();
+    }
+
+    let corner = luma.get_pixel(0, 0)[0];
+    let is_margin = |value: u8| {
+        if corner >= u8::MAX - MARGIN_TOLERANCE {
+            value >= u8::MAX - MARGIN_TOLERANCE
+        } else if corner <= MARGIN_TOLERANCE {
+            value <= MARGIN_TOLERANCE
+        } else {
+            false
+        }
+    };
+
+    let is_margin_row =
+        |y: u32| (0..width).filter(|x| !is_margin(luma.get_pixel(*x, y)[0])).count() as f32 <= width as f32 * MARGIN_NOISE;
+
+    let (Some(top), Some(bottom)) = ((0..height).find(|y| !is_margin_row(*y)), (0..height).rfind(|y| !is_margin_row(*y))) else {
+        return image.clone();
+    };
+
+    let rows = bottom - top + 1;
+    let is_margin_column =
+        |x: u32| (top..=bottom).filter(|y| !is_margin(luma.get_pixel(x, *y)[0])).count() as f32 <= rows as f32 * MARGIN_NOISE;
+
+    let (Some(left), Some(right)) = ((0..width).find(|x| !is_margin_column(*x)), (0..width).rfind(|x| !is_margin_column(*x)))
+    else {
+        return image.clone();
+    };
+
+    image.crop_imm(left, top, right - left + 1, rows)
+}
+
+/// What is done to a page after it's decoded and before it's drawn
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImageOptions {
+    pub filter: ImageFilter,
+    pub crop_margins: bool,
+}
+
+impl ImageOptions {
+    pub fn process(&self, decoded: &DynamicImage) -> DynamicImage {
+        if self.crop_margins { self.filter.apply(&crop_margins(decoded)) } else { self.filter.apply(decoded) }
+    }
+}
+
+static NEXT_IMAGE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A protocol which is resized and encoded for the area it's drawn in on a blocking thread, so drawing never waits
+/// for it, nothing is drawn in its place until it comes back
+pub struct EncodedImage {
+    id: u64,
+    protocol: Option<Box<dyn StatefulProtocol>>,
+}
+
+/// A protocol sent away to be encoded for `area`
+pub struct EncodeJob {
+    pub id: u64,
+    pub protocol: Box<dyn StatefulProtocol>,
+    pub resize: Resize,
+    pub area: Rect,
+}
+
+impl EncodedImage {
+    pub fn new(protocol: Box<dyn StatefulProtocol>) -> Self {
+        Self {
+            id: NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed),
+            protocol: Some(protocol),
+        }
+    }
+
+    /// Draws the image if it's encoded for `area`, otherwise it's taken out to be encoded
+    pub fn render(&mut self, resize: Resize, area: Rect, buf: &mut Buffer) -> Option<EncodeJob> {
+        if area.width == 0 || area.height == 0 {
+            return None;
+        }
+
+        let mut protocol = self.protocol.take()?;
+
+        match protocol.needs_resize(&resize, area) {
+            Some(area) => Some(EncodeJob {
+                id: self.id,
+                protocol,
+                resize,
+                area,
+            }),
+            None => {
+                protocol.render(area, buf);
+                self.protocol = Some(protocol);
+                None
+            },
+        }
+    }
+
+    /// Puts back the protocol once it's encoded, unless the image was replaced in the meantime
+    pub fn restore(&mut self, id: u64, protocol: Box<dyn StatefulProtocol>) {
+        if self.id == id {
+            self.protocol = Some(protocol);
+        }
+    }
+}
+
+/// Makes the protocol in a copy of the picker for a worker thread, the kitty id the copy gives it is skipped here so
+/// that no other image is given the same one
+fn picker_for_worker(picker: &mut Picker) -> Picker {
+    let worker_picker = *picker;
+    if picker.protocol_type == ProtocolType::Kitty {
+        picker.new_resize_protocol(DynamicImage::new_rgb8(1, 1));
+    }
+    worker_picker
+}
+
+fn rotate_image(image: &DynamicImage, rotation: u16) -> DynamicImage {
+    match rotation {
+        90 => image.rotate90(),
+        180 => image.rotate180(),
+        270 => image.rotate270(),
+        _ => image.clone(),
+    }
+}
+
+/// A page with its rotation and `ImageOptions` applied, made on a blocking thread
+pub struct PreparedPage {
+    pub index: usize,
+    /// Identifies the page, the quality of the chapter could have been changed while it was made
+    pub url: String,
+    pub rotation: u16,
+    pub options: ImageOptions,
+    pub decoded: Arc<DynamicImage>,
+    pub image: DynamicImage,
+    pub image_state: Box<dyn StatefulProtocol>,
+}
+
+impl PreparedPage {
+    pub fn new(
+        index: usize,
+        url: String,
+        rotation: u16,
+        options: ImageOptions,
+        decoded: Arc<DynamicImage>,
+        mut picker: Picker,
+    ) -> Self {
+        let image = options.process(&rotate_image(&decoded, rotation));
+        let image_state = picker.new_resize_protocol(image.clone());
+
+        Self {
+            index,
+            url,
+            rotation,
+            options,
+            decoded,
+            image,
+            image_state,
+        }
+    }
+}
+
+fn fitted_page_resize(fit_mode: FitMode, filter: FilterType) -> Resize {
+    match fit_mode {
+        FitMode::FitHeight => Resize::Fit(Some(filter)),
+        _ => Resize::Crop(None),
+    }
+}
+
+/// What a fitted page was made for, it's made again once any of it changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FitKey {
+    pub fit_mode: FitMode,
+    pub area: (u16, u16),
+    pub scroll: u32,
+    /// `Page::image_id` of the image it was made from
+    pub image_id: u64,
+}
+
+/// The part of a page that is visible with the current fit mode
+pub struct FittedPage {
+    pub key: FitKey,
+    pub image_state: EncodedImage,
+}
+
+/// A page resized to the width of the reader for the webtoon mode
+pub struct WebtoonPage {
+    pub width: u16,
+    /// `Page::image_id` of the image it was made from
+    pub image_id: u64,
+    pub image_state: EncodedImage,
 }
 
 pub struct Page {
-    pub image_state: Option<Box<dyn StatefulProtocol>>,
+    pub image_state: Option<EncodedImage>,
+    /// The page as it was decoded, its rotation and the `ImageOptions` are applied on top of it
+    pub decoded: Option<Arc<DynamicImage>>,
+    /// The image that is drawn, it's kept so that it can be resized for the webtoon mode
+    pub image: Option<Arc<DynamicImage>>,
+    /// Changes every time the image is replaced, the fitted and webtoon pages made from the previous image are
+    /// drawn until they are made again so that animated pages don't flicker
+    pub image_id: u64,
+    pub webtoon_page: Option<WebtoonPage>,
+    /// The width and image the page is being resized for the webtoon mode
+    pub webtoon_resizing: Option<(u16, u64)>,
+    pub fitted_page: Option<FittedPage>,
+    pub fitting: Option<FitKey>,
+    /// The frames of the page if it's animated
+    pub animation: Option<Arc<Animation>>,
+    /// The frame of `animation` which is shown
+    pub frame: usize,
+    /// When the frame shown was drawn for the first time, `None` while it's being prepared
+    pub frame_shown_at: Option<Instant>,
     pub url: String,
     pub page_type: PageType,
     pub dimensions: Option<(u32, u32)>,
+    pub state: PageItemState,
+    /// Degrees the page was rotated clockwise, for pages scanned sideways
+    pub rotation: u16,
+    /// Kept when the page is unloaded, so a repeated page is known without fetching it again
+    pub hash: Option<u64>,
+    /// The earlier page of the chapter this one is the same as
+    pub repeats: Option<usize>,
 }
 
 impl Page {
+    /// The image itself is rotated so that it's drawn the same way by every protocol, the page has to be prepared
+    /// again afterwards, returns false if it has not loaded
+    pub fn rotate(&mut self, clockwise: bool) -> bool {
+        if self.decoded.is_none() {
+            return false;
+        }
+
+        self.rotation = if clockwise { (self.rotation + 90) % 360 } else { (self.rotation + 270) % 360 };
+        true
+    }
+
+    pub fn set_image(&mut self, prepared: PreparedPage) {
+        let image_state = EncodedImage::new(prepared.image_state);
+        self.image_id = image_state.id;
+        self.dimensions = Some(prepared.image.dimensions());
+        self.image_state = Some(image_state);
+        self.image = Some(Arc::new(prepared.image));
+        self.decoded = Some(prepared.decoded);
+        self.frame_shown_at = self.animation.is_some().then(Instant::now);
+    }
+
+    /// Makes `frame` the image of an animated page, it has to be prepared again afterwards. Returns false if the page
+    /// is not animated
+    pub fn show_frame(&mut self, frame: usize) -> bool {
+        let Some(animation) = self.animation.as_ref() else {
+            return false;
+        };
+
+        self.frame = frame % animation.frames.len();
+        self.decoded = Some(Arc::clone(&animation.frames[self.frame].0));
+        self.frame_shown_at = None;
+        true
+    }
+
+    /// The frame shown has been on screen for as long as it should
+    pub fn frame_is_due(&self) -> bool {
+        match (self.animation.as_ref(), self.frame_shown_at) {
+            (Some(animation), Some(shown_at)) => shown_at.elapsed() >= animation.delay(self.frame),
+            _ => false,
+        }
+    }
+
+    /// Puts back a protocol of this page once it's encoded
+    pub fn restore_encoded_image(&mut self, id: u64, protocol: Box<dyn StatefulProtocol>) {
+        let images = [
+            self.image_state.as_mut(),
+            self.fitted_page.as_mut().map(|fitted| &mut fitted.image_state),
+            self.webtoon_page.as_mut().map(|webtoon| &mut webtoon.image_state),
+        ];
+
+        if let Some(image) = images.into_iter().flatten().find(|image| image.id == id) {
+            image.restore(id, protocol);
+        }
+    }
+
+    /// Bytes taken by the images kept for this page
+    pub fn memory_usage(&self) -> usize {
+        let frames: usize = match self.animation.as_ref() {
+            Some(animation) => animation.frames.iter().map(|(frame, _)| frame.as_bytes().len()).sum(),
+            None => self.decoded.as_ref().map(|decoded| decoded.as_bytes().len()).unwrap_or(0),
+        };
+        frames + self.image.as_ref().map(|image| image.as_bytes().len()).unwrap_or(0)
+    }
+
+    /// Drops the images of the page so it has to be fetched again, its dimensions are kept so the layout doesn't change
+    pub fn unload(&mut self) {
+        self.animation = None;
+        self.frame = 0;
+        self.frame_shown_at = None;
+        self.decoded = None;
+        self.image = None;
+        self.image_state = None;
+        self.fitted_page = None;
+        self.fitting = None;
+        self.webtoon_page = None;
+        self.webtoon_resizing = None;
+    }
+
+    /// Some chapters are served as a few very tall images, these are read a screen at a time
+    pub fn is_tall(&self) -> bool {
+        self.dimensions.is_some_and(|(width, height)| height > width.max(1) * TALL_PAGE_RATIO)
+    }
+
     pub fn new(url: String, page_type: PageType) -> Self {
         Self {
             image_state: None,
+            decoded: None,
+            image: None,
+            image_id: 0,
+            webtoon_page: None,
+            webtoon_resizing: None,
+            fitted_page: None,
+            fitting: None,
+            animation: None,
+            frame: 0,
+            frame_shown_at: None,
             dimensions: None,
+            state: PageItemState::Waiting,
+            rotation: 0,
+            hash: None,
+            repeats: None,
             url,
             page_type,
         }
     }
 }
 
+/// Once the pages used the longest time ago don't fit in the memory budget they are unloaded and fetched again, from
+/// the page cache, if the user goes back to them
+pub struct DecodedPages {
+    /// Indexes of the pages loaded, the least recently used first
+    recent: VecDeque<usize>,
+    /// In bytes
+    budget: usize,
+}
+
+impl DecodedPages {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            recent: VecDeque::new(),
+            budget,
+        }
+    }
+
+    pub fn touch(&mut self, index: usize) {
+        if self.recent.back() != Some(&index) {
+            self.recent.retain(|recent| *recent != index);
+            self.recent.push_back(index);
+        }
+    }
+
+    /// The pages to unload so the rest fit in the budget, the last `KEEP_RECENT_PAGES` used and the ones `keep` returns
+    /// true for stay loaded even if they don't fit
+    pub fn over_budget(&mut self, memory_usage: impl Fn(usize) -> usize, keep: impl Fn(usize) -> bool) -> Vec<usize> {
+        let mut total: usize = self.recent.iter().map(|index| memory_usage(*index)).sum();
+        let evictable = self.recent.len().saturating_sub(KEEP_RECENT_PAGES);
+
+        let mut unload = vec![];
+        for index in self.recent.iter().take(evictable) {
+            if total <= self.budget {
+                break;
+            }
+            if !keep(*index) {
+                total = total.saturating_sub(memory_usage(*index));
+                unload.push(*index);
+            }
+        }
+
+        self.recent.retain(|index| !unload.contains(index));
+        unload
+    }
+}
+
+/// Enough for the pages of a spread and the ones next to it
+static KEEP_RECENT_PAGES: usize = 4;
+
+/// Which chapter is going to be read
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadChapterInfo {
+    pub manga_id: String,
+    pub chapter_id: String,
+    pub number: String,
+    pub title: String,
+    /// The next chapter is searched in the same language
+    pub language: Languages,
+}
+
+/// What the reader needs to know about the chapter that will be read
+pub struct ChapterToRead {
+    pub chapter: ReadChapterInfo,
+    /// Used to build the url of each page
+    pub hash: String,
+    pub base_url: String,
+    /// The file names of the pages in high quality
+    pub data: Vec<String>,
+    /// The file names of the pages in low quality
+    pub data_saver: Vec<String>,
+    /// The quality the chapter starts being read with
+    pub page_type: PageType,
+}
+
+/// How long the notice telling the user the chapter was resumed or the page was saved stays on screen
+static RESUME_NOTICE_DURATION: Duration = Duration::from_secs(3);
+
+/// Pages which are this many times taller than they are wide are split in screens, always fitting
+/// the width of the reader
+static TALL_PAGE_RATIO: u32 = 3;
+
+/// Every key of the reader grouped by what it does, shown with `?`
+static HELP: &[(&str, &[(&str, &str)])] = &[
+    ("Navigation", &[
+        ("j / <Down>", "Next page"),
+        ("k / <Up>", "Previous page"),
+        ("h l <Left> <Right>", "Previous / next page in the reading direction"),
+        ("g", "Go to page"),
+        ("a", "Page overview"),
+        ("n / p", "Next / previous chapter, at the end of the chapter"),
+        ("u", "Next unread chapter, at the end of a chapter read again"),
+        ("L", "Same chapter in the compare language of the config"),
+        ("<Backspace>", "Back to the manga page"),
+    ]),
+    ("Layout", &[
+        ("d", "Reading direction"),
+        ("m", "Single / double page"),
+        ("o", "Shift spreads"),
+        ("w", "Webtoon mode"),
+        ("f", "Fit mode"),
+        ("s", "Status bar"),
+    ]),
+    ("Pages", &[
+        ("q", "Image quality"),
+        ("v", "Image filter"),
+        ("c", "Crop margins"),
+        ("t / T", "Rotate clockwise / counterclockwise"),
+        ("r", "Retry pages which failed to load"),
+        ("<Space>", "Play / pause animated pages"),
+        (", / .", "Previous / next frame of an animated page"),
+        ("x", "Save the page"),
+        ("e", "Open the page in an external viewer"),
+        ("R", "Skip pages repeated in the chapter"),
+    ]),
+];
+
+/// The name the first time hint is saved with once it's dismissed
+pub static READER_HINT: &str = "reader_navigation";
+
+/// The text tesseract found in a page, shown in place of the instructions
+#[cfg(feature = "ocr")]
+enum TextPane {
+    Extracting(usize),
+    Extracted(usize, String),
+    Failed(usize),
+}
+
+#[cfg(feature = "ocr")]
+impl TextPane {
+    fn page(&self) -> usize {
+        match self {
+            Self::Extracting(index) | Self::Extracted(index, _) | Self::Failed(index) => *index,
+        }
+    }
+}
+
+/// How many rows the webtoon mode moves with each scroll
+static WEBTOON_SCROLL_ROWS: i64 = 3;
+
+/// In double page mode the first page is shown alone and the rest in pairs (1, 2), (3, 4) ... as
+/// mangadex orders pages, with `offset` the pairs start from the first page instead (0, 1), (2, 3) ...
+fn spread_start(index: usize, offset: bool) -> usize {
+    let shift = if offset { 0 } else { 1 };
+    if index < shift {
+        return index;
+    }
+    index - (index - shift) % 2
+}
+
+/// Draws the keys around the edges they move towards without clearing the page below, only the boxes
+/// and their text are drawn
+fn render_first_time_hint(reading_direction: ReadingDirection, area: Rect, buf: &mut Buffer) {
+    let key_hint = |key: &'static str, description: &'static str| {
+        Line::from(vec![Span::raw(key).style(*INSTRUCTIONS_STYLE), " ".into(), description.into()])
+    };
+    let hint_box = |lines: Vec<Line<'static>>, title: &'static str, area: Rect, buf: &mut Buffer| {
+        let width = (lines.iter().map(|line| line.width()).max().unwrap_or(0).max(title.len()) as u16 + 4).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let box_area = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+        Paragraph::new(lines).block(Block::bordered().title(title)).render(box_area, buf);
+    };
+
+    let (left, right) = match reading_direction {
+        ReadingDirection::LeftToRight => ("Previous page", "Next page"),
+        ReadingDirection::RightToLeft => ("Next page", "Previous page"),
+    };
+
+    let [top, middle, bottom] = Layout::vertical([Constraint::Length(3), Constraint::Fill(1), Constraint::Length(3)]).areas(area);
+    let [left_edge, center, right_edge] =
+        Layout::horizontal([Constraint::Length(20), Constraint::Fill(1), Constraint::Length(20)]).areas(middle);
+
+    hint_box(vec![key_hint("▲ k <Up>", "Previous page")], "", top, buf);
+    hint_box(vec![key_hint("▼ j <Down>", "Next page")], "", bottom, buf);
+    hint_box(vec![key_hint("◀ h <Left>", ""), Line::from(left)], "", left_edge, buf);
+    hint_box(vec![key_hint("l <Right> ▶", ""), Line::from(right)], "", right_edge, buf);
+    hint_box(
+        vec![
+            key_hint("n / p", "Next / previous chapter, at the end of a chapter"),
+            key_hint("f", "Fit to the height / width or the original size"),
+            key_hint("<Backspace>", "Back to the manga page"),
+            key_hint("<C-c>", "Quit"),
+            key_hint("?", "All the keybindings"),
+            Line::default(),
+            Line::from("Press any key to start reading").centered(),
+        ],
+        " First time reading ",
+        center,
+        buf,
+    );
+}
+
 pub struct MangaReader {
-    chapter_id: String,
+    chapter: ReadChapterInfo,
+    hash: String,
     base_url: String,
+    data: Vec<String>,
+    data_saver: Vec<String>,
+    /// The quality of the pages that are shown, can be changed while reading
+    page_type: PageType,
     pages: Vec<Page>,
     pages_list: PagesList,
     current_page_size: u16,
+    /// How many pages after the current one are fetched ahead of time
+    prefetch_window: usize,
+    decoded_pages: DecodedPages,
+    /// Decides which of the left / right keys goes to the next page
+    reading_direction: ReadingDirection,
+    /// Show two pages side by side
+    double_page: bool,
+    /// By default the first page is shown alone and then the rest in pairs, some chapters need the
+    /// pairs to start from the first page for spreads to line up
+    spread_offset: bool,
+    /// Pages are stitched vertically and scrolled through, for webtoons which are one long image
+    /// split in chunks
+    webtoon: bool,
+    /// How many rows from the top of the first page the webtoon mode is scrolled
+    webtoon_scroll: u32,
+    /// The width of the area pages are drawn in, used to know how tall they are in webtoon mode
+    webtoon_width: u16,
+    webtoon_height: u16,
+    /// How the page is sized in single page mode
+    fit_mode: FitMode,
+    image_options: ImageOptions,
+    /// How many rows the current page is scrolled when it doesn't fit the reader
+    page_scroll: u32,
+    /// How many rows of the current page don't fit the reader
+    page_overflow: u32,
+    page_height: u16,
+    /// The last page stored in the database for this chapter, the page the chapter is opened at is
+    /// also stored so that `--resume` knows which chapter was read last
+    last_saved_page: Option<usize>,
+    /// The page the chapter was resumed at and when
+    resumed_at: Option<(usize, Instant)>,
+    /// The result of saving or opening the page, or of switching the language, and when it finished
+    notice: Option<(String, Color, Instant)>,
+    show_status_bar: bool,
+    skip_repeated_pages: bool,
+    /// The status bar, page list and instructions are hidden when no key is pressed for this long
+    hide_ui_after: Option<Duration>,
+    last_key_press: Instant,
+    next_chapter: ChapterSearch,
+    /// Only searched when a chapter after the one being read was already read, otherwise it's the next chapter
+    next_unread_chapter: ChapterSearch,
+    /// The language `L` switches to, the one the chapter was switched from after switching
+    other_language: Option<Languages>,
+    switching_language: bool,
+    previous_chapter: ChapterSearch,
+    /// `Some` once the last page is passed
+    chapter_end: Option<ChapterEnd>,
+    /// `Some` while the grid of thumbnails is shown
+    overview: Option<Overview>,
+    show_help: bool,
+    /// Shown over the first chapter read, until any key is pressed
+    first_time_hint: bool,
+    /// `Some` while the text of the current page is shown
+    #[cfg(feature = "ocr")]
+    text_pane: Option<TextPane>,
+    /// `Some` while the translation of the text pane is shown over the page, with the same states as the text pane
+    #[cfg(feature = "ocr")]
+    translation: Option<TextPane>,
     page_list_state: tui_widget_list::ListState,
     _state: State,
     /// Handle fetching the images
     image_tasks: JoinSet<()>,
+    /// Cancelled once the reader is left, so the pages still being fetched or prepared are dropped right away
+    cancellation: CancellationToken,
     picker: Picker,
+    /// Used to size the pages to the reader, set with `page_render_quality`
+    resize_filter: FilterType,
+    background: ReaderBackground,
+    /// Animated pages are played, they are stepped through a frame at a time otherwise
+    animations_playing: bool,
+    /// Where the user types the number of the page to jump to
+    go_to_page_input: Input,
+    is_typing_page: bool,
     pub _global_event_tx: UnboundedSender<Events>,
     pub local_action_tx: UnboundedSender<MangaReaderActions>,
     pub local_action_rx: UnboundedReceiver<MangaReaderActions>,
@@ -80,60 +1077,294 @@ impl Component for MangaReader {
     type Actions = MangaReaderActions;
 
     fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
-        let buf = frame.buffer_mut();
+        let ui_hidden = self.ui_hidden();
 
-        let layout =
-            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(self.current_page_size), Constraint::Fill(1)]).spacing(1);
+        let area = if self.show_status_bar && !ui_hidden {
+            let [area, status_bar_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+            self.render_status_bar(status_bar_area, frame.buffer_mut());
+            area
+        } else {
+            area
+        };
 
-        let [left, center, right] = layout.areas(area);
+        let center = if ui_hidden {
+            area
+        } else {
+            let layout =
+                Layout::horizontal([Constraint::Fill(1), Constraint::Fill(self.current_page_size), Constraint::Fill(1)]).spacing(1);
 
-        Block::bordered().render(left, buf);
-        self.render_page_list(left, buf);
+            let [left, center, right] = layout.areas(area);
 
-        Paragraph::new(Line::from(vec!["Go back: ".into(), Span::raw("<Backspace>").style(*INSTRUCTIONS_STYLE)]))
-            .render(right, buf);
+            self.render_instructions(right, frame);
+            #[cfg(feature = "ocr")]
+            self.render_text_pane(right, frame.buffer_mut());
 
-        match self.pages.get_mut(self.page_list_state.selected.unwrap_or(0)) {
-            Some(page) => match page.image_state.as_mut() {
-                Some(img_state) => {
-                    let (width, height) = page.dimensions.unwrap();
-                    if width > height {
-                        if width - height > 250 {
-                            self.current_page_size = 5;
-                        }
-                    } else {
-                        self.current_page_size = 2;
-                    }
-                    let image = StatefulImage::new(None).resize(Resize::Fit(None));
-                    StatefulWidget::render(image, center, buf, img_state);
-                },
-                None => {
-                    Block::bordered().title("Loading page").render(center, frame.buffer_mut());
-                },
-            },
-            None => Block::bordered().title("Loading page").render(center, frame.buffer_mut()),
+            Block::bordered().render(left, frame.buffer_mut());
+            self.render_page_list(left, frame.buffer_mut());
+
+            center
         };
+
+        let buf = frame.buffer_mut();
+
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+
+        if let Some(color) = self.background.color() {
+            Block::default().style(Style::default().bg(color)).render(center, buf);
+        }
+
+        if self.overview.is_some() {
+            self.render_overview(center, buf);
+        } else if self.chapter_end.is_some() {
+            self.render_chapter_end(center, buf);
+        } else if self.webtoon {
+            self.render_webtoon(center, buf);
+        } else if self.double_page {
+            let (first, second) = self.current_spread(current_page);
+            let [left_page, right_page] = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(center);
+
+            match second {
+                Some(second) => {
+                    // The earlier page goes on the side the reader starts from
+                    let (left_index, right_index) = match self.reading_direction {
+                        ReadingDirection::LeftToRight => (first, second),
+                        ReadingDirection::RightToLeft => (second, first),
+                    };
+                    self.render_page(left_index, left_page, buf);
+                    self.render_page(right_index, right_page, buf);
+                },
+                None => self.render_page(first, center, buf),
+            }
+        } else {
+            if let Some((width, height)) = self.pages.get(current_page).and_then(|page| page.dimensions) {
+                if width > height {
+                    if width - height > 250 {
+                        self.current_page_size = 5;
+                    }
+                } else {
+                    self.current_page_size = 2;
+                }
+            }
+            self.render_fitted_page(current_page, center, buf);
+        }
+
+        #[cfg(feature = "ocr")]
+        self.render_translation(center, buf);
+
+        if self.show_help {
+            self.render_help(area, buf);
+        } else if self.first_time_hint {
+            render_first_time_hint(self.reading_direction, center, buf);
+        }
+
+        if let Some((page, resumed_at)) = self.resumed_at {
+            if resumed_at.elapsed() < RESUME_NOTICE_DURATION {
+                render_notice(&format!("Resuming at page {}", page + 1), Color::Yellow, center, buf);
+            } else {
+                self.resumed_at = None;
+            }
+        }
+
+        let repeats = self.pages.get(current_page).and_then(|page| page.repeats);
+        if let Some(repeats) = repeats.filter(|_| !self.skip_repeated_pages && !self.webtoon && self.overview.is_none()) {
+            render_notice(&format!("Same as page {}, skip repeated pages <R>", repeats + 1), Color::Yellow, center, buf);
+        }
+
+        if let Some((message, color, shown_at)) = self.notice.as_ref() {
+            if shown_at.elapsed() < RESUME_NOTICE_DURATION {
+                render_notice(message, *color, center, buf);
+            } else {
+                self.notice = None;
+            }
+        }
     }
 
     fn update(&mut self, action: Self::Actions) {
         match action {
             MangaReaderActions::NextPage => self.next_page(),
             MangaReaderActions::PreviousPage => self.previous_page(),
+            MangaReaderActions::ToggleGoToPrompt => self.toggle_go_to_page(),
+            MangaReaderActions::SubmitGoToPrompt => self.go_to_page(),
+            MangaReaderActions::ToggleReadingDirection => self.toggle_reading_direction(),
+            MangaReaderActions::ToggleDoublePage => self.toggle_double_page(),
+            MangaReaderActions::ToggleSpreadOffset => self.toggle_spread_offset(),
+            MangaReaderActions::ToggleWebtoon => self.toggle_webtoon(),
+            MangaReaderActions::CycleFitMode => self.cycle_fit_mode(),
+            MangaReaderActions::ToggleImageQuality => self.toggle_image_quality(),
+            MangaReaderActions::ToggleStatusBar => self.show_status_bar = !self.show_status_bar,
+            MangaReaderActions::RetryPage => self.retry_failed_pages(),
+            MangaReaderActions::GoToNextChapter => self.go_to_next_chapter(),
+            MangaReaderActions::GoToPreviousChapter => self.search_previous_chapter(),
+            MangaReaderActions::GoToNextUnreadChapter => self.go_to_next_unread_chapter(),
+            MangaReaderActions::SwitchLanguage => self.switch_language(),
+            MangaReaderActions::RotatePage(clockwise) => self.rotate_current_page(clockwise),
+            MangaReaderActions::CycleImageFilter => self.cycle_image_filter(),
+            MangaReaderActions::ToggleCropMargins => self.toggle_crop_margins(),
+            MangaReaderActions::ToggleOverview => self.toggle_overview(),
+            MangaReaderActions::MoveOverviewSelection(direction) => {
+                if let Some(overview) = self.overview.as_mut() {
+                    overview.move_selection(direction);
+                }
+            },
+            MangaReaderActions::OpenOverviewSelection => self.open_overview_selection(),
+            MangaReaderActions::ToggleHelp => self.show_help = !self.show_help,
+            MangaReaderActions::DismissFirstTimeHint => self.dismiss_first_time_hint(),
+            MangaReaderActions::ExportPage(export) => self.export_current_page(export),
+            MangaReaderActions::ToggleSkipRepeatedPages => self.toggle_skip_repeated_pages(),
+            MangaReaderActions::ToggleAnimations => self.animations_playing = !self.animations_playing,
+            MangaReaderActions::StepFrame(forward) => self.step_frame(forward),
+            #[cfg(feature = "ocr")]
+            MangaReaderActions::ToggleTextPane => self.toggle_text_pane(),
+            #[cfg(feature = "ocr")]
+            MangaReaderActions::ToggleTranslation => self.toggle_translation(),
         }
     }
 
     fn handle_events(&mut self, events: crate::backend::tui::Events) {
+        if let Events::Key(_) = events {
+            self.last_key_press = Instant::now();
+        }
+
         match events {
+            Events::Key(key_event) if self.is_typing_page => match key_event.code {
+                KeyCode::Enter => {
+                    self.local_action_tx.send(MangaReaderActions::SubmitGoToPrompt).ok();
+                },
+                KeyCode::Esc => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleGoToPrompt).ok();
+                },
+                KeyCode::Char(character) if !character.is_ascii_digit() => {},
+                _ => {
+                    self.go_to_page_input.handle_event(&crossterm::event::Event::Key(key_event));
+                },
+            },
+            Events::Key(_) if self.first_time_hint => {
+                self.local_action_tx.send(MangaReaderActions::DismissFirstTimeHint).ok();
+            },
+            Events::Key(key_event) if self.show_help => {
+                if matches!(key_event.code, KeyCode::Char('?') | KeyCode::Esc) {
+                    self.local_action_tx.send(MangaReaderActions::ToggleHelp).ok();
+                }
+            },
+            Events::Key(key_event) if self.overview.is_some() => {
+                let action = match key_event.code {
+                    KeyCode::Esc | KeyCode::Char('a') => MangaReaderActions::ToggleOverview,
+                    KeyCode::Enter => MangaReaderActions::OpenOverviewSelection,
+                    KeyCode::Left | KeyCode::Char('h') => MangaReaderActions::MoveOverviewSelection(GridMove::Left),
+                    KeyCode::Right | KeyCode::Char('l') => MangaReaderActions::MoveOverviewSelection(GridMove::Right),
+                    KeyCode::Up | KeyCode::Char('k') => MangaReaderActions::MoveOverviewSelection(GridMove::Up),
+                    KeyCode::Down | KeyCode::Char('j') => MangaReaderActions::MoveOverviewSelection(GridMove::Down),
+                    _ => return,
+                };
+                self.local_action_tx.send(action).ok();
+            },
             Events::Key(key_event) => match key_event.code {
+                KeyCode::Char('g') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleGoToPrompt).ok();
+                },
                 KeyCode::Down | KeyCode::Char('j') => {
                     self.local_action_tx.send(MangaReaderActions::NextPage).ok();
                 },
                 KeyCode::Up | KeyCode::Char('k') => {
                     self.local_action_tx.send(MangaReaderActions::PreviousPage).ok();
                 },
-
-                _ => {},
-            },
+                KeyCode::Right | KeyCode::Char('l') => {
+                    let action = match self.reading_direction {
+                        ReadingDirection::LeftToRight => MangaReaderActions::NextPage,
+                        ReadingDirection::RightToLeft => MangaReaderActions::PreviousPage,
+                    };
+                    self.local_action_tx.send(action).ok();
+                },
+                KeyCode::Left | KeyCode::Char('h') => {
+                    let action = match self.reading_direction {
+                        ReadingDirection::LeftToRight => MangaReaderActions::PreviousPage,
+                        ReadingDirection::RightToLeft => MangaReaderActions::NextPage,
+                    };
+                    self.local_action_tx.send(action).ok();
+                },
+                KeyCode::Char('d') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleReadingDirection).ok();
+                },
+                KeyCode::Char('m') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleDoublePage).ok();
+                },
+                KeyCode::Char('o') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleSpreadOffset).ok();
+                },
+                KeyCode::Char('w') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleWebtoon).ok();
+                },
+                KeyCode::Char('f') => {
+                    self.local_action_tx.send(MangaReaderActions::CycleFitMode).ok();
+                },
+                KeyCode::Char('q') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleImageQuality).ok();
+                },
+                KeyCode::Char('s') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleStatusBar).ok();
+                },
+                KeyCode::Char('r') => {
+                    self.local_action_tx.send(MangaReaderActions::RetryPage).ok();
+                },
+                KeyCode::Char('t') => {
+                    self.local_action_tx.send(MangaReaderActions::RotatePage(true)).ok();
+                },
+                KeyCode::Char('T') => {
+                    self.local_action_tx.send(MangaReaderActions::RotatePage(false)).ok();
+                },
+                KeyCode::Char('v') => {
+                    self.local_action_tx.send(MangaReaderActions::CycleImageFilter).ok();
+                },
+                KeyCode::Char('c') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleCropMargins).ok();
+                },
+                KeyCode::Char('a') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleOverview).ok();
+                },
+                KeyCode::Char(' ') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleAnimations).ok();
+                },
+                KeyCode::Char('.') => {
+                    self.local_action_tx.send(MangaReaderActions::StepFrame(true)).ok();
+                },
+                KeyCode::Char(',') => {
+                    self.local_action_tx.send(MangaReaderActions::StepFrame(false)).ok();
+                },
+                KeyCode::Char('x') => {
+                    self.local_action_tx.send(MangaReaderActions::ExportPage(PageExport::Save)).ok();
+                },
+                KeyCode::Char('e') => {
+                    self.local_action_tx.send(MangaReaderActions::ExportPage(PageExport::OpenInViewer)).ok();
+                },
+                KeyCode::Char('R') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleSkipRepeatedPages).ok();
+                },
+                KeyCode::Char('?') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleHelp).ok();
+                },
+                #[cfg(feature = "ocr")]
+                KeyCode::Char('i') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleTextPane).ok();
+                },
+                #[cfg(feature = "ocr")]
+                KeyCode::Char('I') => {
+                    self.local_action_tx.send(MangaReaderActions::ToggleTranslation).ok();
+                },
+                KeyCode::Char('n') if self.chapter_end.is_some() => {
+                    self.local_action_tx.send(MangaReaderActions::GoToNextChapter).ok();
+                },
+                KeyCode::Char('p') if self.chapter_end.is_some() => {
+                    self.local_action_tx.send(MangaReaderActions::GoToPreviousChapter).ok();
+                },
+                KeyCode::Char('u') if self.chapter_end.is_some() => {
+                    self.local_action_tx.send(MangaReaderActions::GoToNextUnreadChapter).ok();
+                },
+                KeyCode::Char('L') => {
+                    self.local_action_tx.send(MangaReaderActions::SwitchLanguage).ok();
+                },
+
+                _ => {},
+            },
             Events::Mouse(mouse_event) => match mouse_event.kind {
                 crossterm::event::MouseEventKind::ScrollUp => {
                     self.local_action_tx.send(MangaReaderActions::PreviousPage).ok();
@@ -144,139 +1375,2018 @@ impl Component for MangaReader {
                 _ => {},
             },
             Events::Tick => self.tick(),
+            Events::Redraw => self.handle_background_events(),
             _ => {},
         }
     }
 
+    fn wants_input(&self) -> bool {
+        self.is_typing_page
+    }
+
     fn clean_up(&mut self) {
+        self.cancellation.cancel();
         self.image_tasks.abort_all();
         self.pages = vec![];
         self.pages_list.pages = vec![];
+        self.overview = None;
+    }
+}
+
+/// Also covers the reader being replaced by the one of the next or previous chapter
+impl Drop for MangaReader {
+    fn drop(&mut self) {
+        self.cancellation.cancel();
     }
 }
 
 impl MangaReader {
     pub fn new(
         global_event_tx: UnboundedSender<Events>,
-        chapter_id: String,
-        base_url: String,
-        url_imgs: Vec<String>,
-        url_imgs_high_quality: Vec<String>,
+        chapter_to_read: ChapterToRead,
+        prefetch_window: usize,
+        memory_budget: usize,
+        resize_filter: FilterType,
+        background: ReaderBackground,
         picker: Picker,
     ) -> Self {
         let set: JoinSet<()> = JoinSet::new();
         let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<MangaReaderActions>();
         let (local_event_tx, local_event_rx) = mpsc::unbounded_channel::<MangaReaderEvents>();
 
-        let mut pages: Vec<Page> = vec![];
-
-        for url in url_imgs.iter().take(5) {
-            pages.push(Page::new(url.to_string(), PageType::LowQuality));
-        }
+        let layout = match get_reader_layout(&chapter_to_read.chapter.manga_id) {
+            Ok(layout) => layout,
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                ReaderLayout::default()
+            },
+        };
 
-        for url in url_imgs_high_quality.iter().skip(5) {
-            pages.push(Page::new(url.to_string(), PageType::HighQuality));
-        }
+        // Not every chapter has both qualities
+        let page_type = match layout.page_type.unwrap_or(chapter_to_read.page_type) {
+            PageType::LowQuality if chapter_to_read.data_saver.is_empty() => PageType::HighQuality,
+            PageType::HighQuality if chapter_to_read.data.is_empty() => PageType::LowQuality,
+            page_type => page_type,
+        };
+        let url_imgs = if page_type == PageType::LowQuality { &chapter_to_read.data_saver } else { &chapter_to_read.data };
+        let pages: Vec<Page> = url_imgs.iter().map(|url| Page::new(url.clone(), page_type)).collect();
 
         local_event_tx.send(MangaReaderEvents::FetchPages).ok();
 
+        let last_page_read = match get_last_page_read(&chapter_to_read.chapter.chapter_id) {
+            Ok(page) => page.filter(|page| *page > 0 && *page < pages.len()),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                None
+            },
+        };
+
+        let mut page_list_state = tui_widget_list::ListState::default();
+        page_list_state.select(last_page_read);
+
+        let reading_direction = match get_reading_direction(&chapter_to_read.chapter.manga_id) {
+            Ok(direction) => direction.unwrap_or_default(),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                ReadingDirection::default()
+            },
+        };
+
+        let fit_mode = match get_fit_mode(&chapter_to_read.chapter.manga_id) {
+            Ok(fit_mode) => fit_mode.unwrap_or_default(),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                FitMode::default()
+            },
+        };
+
+        let webtoon = layout.webtoon.unwrap_or(false);
+        let double_page = layout.double_page.unwrap_or(false) && !webtoon;
+
         Self {
             _global_event_tx: global_event_tx,
-            chapter_id,
-            base_url,
+            chapter: chapter_to_read.chapter,
+            hash: chapter_to_read.hash,
+            base_url: chapter_to_read.base_url,
+            data: chapter_to_read.data,
+            data_saver: chapter_to_read.data_saver,
+            page_type,
             pages,
-            page_list_state: tui_widget_list::ListState::default(),
+            page_list_state,
             image_tasks: set,
+            cancellation: CancellationToken::new(),
             local_action_tx,
             local_action_rx,
             local_event_tx,
             local_event_rx,
             _state: State::SearchingPages,
-            current_page_size: 2,
+            current_page_size: if double_page { 4 } else { 2 },
+            prefetch_window,
+            decoded_pages: DecodedPages::new(memory_budget),
+            reading_direction,
+            double_page,
+            spread_offset: layout.spread_offset.unwrap_or(false),
+            webtoon,
+            webtoon_scroll: 0,
+            webtoon_width: 0,
+            webtoon_height: 0,
+            fit_mode,
+            image_options: ImageOptions::default(),
+            page_scroll: 0,
+            page_overflow: 0,
+            page_height: 0,
+            last_saved_page: None,
+            resumed_at: last_page_read.map(|page| (page, Instant::now())),
+            notice: None,
+            show_status_bar: true,
+            skip_repeated_pages: false,
+            hide_ui_after: None,
+            last_key_press: Instant::now(),
+            next_chapter: ChapterSearch::default(),
+            next_unread_chapter: ChapterSearch::default(),
+            other_language: None,
+            switching_language: false,
+            previous_chapter: ChapterSearch::default(),
+            chapter_end: None,
+            overview: None,
+            show_help: false,
+            first_time_hint: false,
+            #[cfg(feature = "ocr")]
+            text_pane: None,
+            #[cfg(feature = "ocr")]
+            translation: None,
             pages_list: PagesList::default(),
             picker,
+            resize_filter,
+            background,
+            animations_playing: true,
+            go_to_page_input: Input::default(),
+            is_typing_page: false,
         }
     }
 
-    fn next_page(&mut self) {
-        self.page_list_state.next()
+    pub fn with_ui_hidden_after(mut self, hide_ui_after: Option<Duration>) -> Self {
+        self.hide_ui_after = hide_ui_after;
+        self
     }
 
-    fn previous_page(&mut self) {
-        self.page_list_state.previous();
+    pub fn with_repeated_pages_skipped(mut self, skip_repeated_pages: bool) -> Self {
+        self.skip_repeated_pages = skip_repeated_pages;
+        self
     }
 
-    fn render_page_list(&mut self, area: Rect, buf: &mut Buffer) {
-        let inner_area = area.inner(Margin {
-            horizontal: 1,
-            vertical: 1,
+    pub fn with_compare_language(mut self, compare_language: Option<Languages>) -> Self {
+        self.other_language = compare_language.filter(|language| *language != self.chapter.language);
+        self
+    }
+
+    pub fn with_first_time_hint(mut self, show: bool) -> Self {
+        self.first_time_hint = show;
+        self
+    }
+
+    fn dismiss_first_time_hint(&mut self) {
+        self.first_time_hint = false;
+        if let Err(e) = save_hint_seen(READER_HINT) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+    }
+
+    fn is_skipped(&self, index: usize) -> bool {
+        self.skip_repeated_pages && self.pages.get(index).is_some_and(|page| page.repeats.is_some())
+    }
+
+    fn find_repeated_pages(&mut self) {
+        let hashes: Vec<Option<u64>> = self.pages.iter().map(|page| page.hash).collect();
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            page.repeats = repeated_page(&hashes, index);
+        }
+    }
+
+    fn toggle_skip_repeated_pages(&mut self) {
+        self.skip_repeated_pages = !self.skip_repeated_pages;
+        if self.webtoon {
+            self.scroll_webtoon_to_page(self.page_list_state.selected.unwrap_or(0));
+        }
+    }
+
+    /// Only the pages are drawn so they can take the whole screen
+    fn ui_hidden(&self) -> bool {
+        let inactive = self.hide_ui_after.is_some_and(|hide_after| self.last_key_press.elapsed() >= hide_after);
+        inactive
+            && !self.show_help
+            && !self.first_time_hint
+            && !self.is_typing_page
+            && self.overview.is_none()
+            && !self.text_pane_open()
+    }
+
+    #[cfg(feature = "ocr")]
+    fn text_pane_open(&self) -> bool {
+        self.text_pane.is_some()
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    fn text_pane_open(&self) -> bool {
+        false
+    }
+
+    pub fn is_typing_page(&self) -> bool {
+        self.is_typing_page
+    }
+
+    pub fn chapter(&self) -> &ReadChapterInfo {
+        &self.chapter
+    }
+
+    fn render_instructions(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        let [instructions_area, go_to_page_area] = Layout::vertical([Constraint::Length(14), Constraint::Length(3)]).areas(area);
+
+        Paragraph::new(vec![
+            Line::from(vec!["All keybindings: ".into(), Span::raw("<?>").style(*INSTRUCTIONS_STYLE)]),
+            Line::from(vec!["Go back: ".into(), Span::raw("<Backspace>").style(*INSTRUCTIONS_STYLE)]),
+            Line::from(vec!["Go to page: ".into(), Span::raw("<g>").style(*INSTRUCTIONS_STYLE)]),
+            Line::from(vec!["Page overview: ".into(), Span::raw("<a>").style(*INSTRUCTIONS_STYLE)]),
+            Line::from(vec![
+                format!("Reading direction: {} ", self.reading_direction.as_human_readable()).into(),
+                Span::raw("<d>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                if self.double_page { "Single page: " } else { "Double page: " }.into(),
+                Span::raw("<m>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec!["Shift spreads: ".into(), Span::raw("<o>").style(*INSTRUCTIONS_STYLE)]),
+            Line::from(vec![
+                if self.webtoon { "Page mode: " } else { "Webtoon mode: " }.into(),
+                Span::raw("<w>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![format!("{} ", self.fit_mode.as_human_readable()).into(), Span::raw("<f>").style(*INSTRUCTIONS_STYLE)]),
+            Line::from(vec![
+                format!("{} ", self.page_type.as_human_readable()).into(),
+                Span::raw("<q>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                if self.show_status_bar { "Hide status bar: " } else { "Show status bar: " }.into(),
+                Span::raw("<s>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                format!("{} ", self.image_options.filter.as_human_readable()).into(),
+                Span::raw("<v>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                if self.image_options.crop_margins { "Keep margins: " } else { "Crop margins: " }.into(),
+                Span::raw("<c>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                "Rotate page: ".into(),
+                Span::raw("<t>").style(*INSTRUCTIONS_STYLE),
+                " ".into(),
+                Span::raw("<T>").style(*INSTRUCTIONS_STYLE),
+            ]),
+        ])
+        .render(instructions_area, frame.buffer_mut());
+
+        if self.is_typing_page {
+            let input_help = Line::from(vec![
+                "Go ".into(),
+                Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+                " Cancel ".into(),
+                Span::raw("<Esc>").style(*INSTRUCTIONS_STYLE),
+            ]);
+            render_search_bar(true, input_help, &self.go_to_page_input, frame, go_to_page_area);
+        }
+    }
+
+    fn toggle_reading_direction(&mut self) {
+        self.reading_direction = self.reading_direction.toggle();
+        if let Err(e) = save_reading_direction(&self.chapter.manga_id, self.reading_direction) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+    }
+
+    fn render_status_bar(&mut self, area: Rect, buf: &mut Buffer) {
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+
+        ReaderStatusBar {
+            chapter_number: &self.chapter.number,
+            chapter_title: &self.chapter.title,
+            current_page: current_page + 1,
+            total_pages: self.pages.len(),
+            loaded_pages: self.pages.iter().filter(|page| page.image.is_some()).count(),
+            pending_pages: self.pages.iter().filter(|page| page.state == PageItemState::Loading).count(),
+            failed_pages: self.pages.iter().filter(|page| page.state == PageItemState::Failed).count(),
+            page_type: self.page_type,
+            rotation: self.pages.get(current_page).map(|page| page.rotation).unwrap_or(0),
+            screen: self.current_screen(),
+            next_chapter_ready: matches!(self.next_chapter, ChapterSearch::Found(_)),
+        }
+        .render(area, buf);
+    }
+
+    /// Which screen of a tall page is shown and how many it's split in
+    fn current_screen(&self) -> Option<(u32, u32)> {
+        let current_page = self.pages.get(self.page_list_state.selected.unwrap_or(0))?;
+        if self.webtoon || self.double_page || self.chapter_end.is_some() || !current_page.is_tall() || self.page_height == 0 {
+            return None;
+        }
+
+        let height = self.page_height as u32;
+        Some((self.page_scroll.min(self.page_overflow).div_ceil(height) + 1, self.page_overflow.div_ceil(height) + 1))
+    }
+
+    fn rotate_current_page(&mut self, clockwise: bool) {
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+        if self.pages.get_mut(current_page).is_some_and(|page| page.rotate(clockwise)) {
+            self.prepare_page(current_page);
+            self.page_scroll = 0;
+        }
+    }
+
+    fn cycle_image_filter(&mut self) {
+        self.image_options.filter = self.image_options.filter.next();
+        self.process_loaded_pages();
+    }
+
+    fn toggle_crop_margins(&mut self) {
+        self.image_options.crop_margins = !self.image_options.crop_margins;
+        self.page_scroll = 0;
+        self.process_loaded_pages();
+    }
+
+    /// Makes the pages which have loaded again with the current `ImageOptions`, they are drawn as they were until then
+    fn process_loaded_pages(&mut self) {
+        for index in 0..self.pages.len() {
+            self.prepare_page(index);
+        }
+    }
+
+    /// Applies the rotation and `ImageOptions` to the decoded page on a blocking thread
+    fn prepare_page(&mut self, index: usize) {
+        let Some(page) = self.pages.get(index) else {
+            return;
+        };
+        let Some(decoded) = page.decoded.clone() else {
+            return;
+        };
+
+        let (url, rotation, options) = (page.url.clone(), page.rotation, self.image_options);
+        let picker = picker_for_worker(&mut self.picker);
+        let tx = self.local_event_tx.clone();
+        let redraw_tx = self._global_event_tx.clone();
+        let cancellation = self.cancellation.clone();
+
+        tokio::task::spawn_blocking(move || {
+            if cancellation.is_cancelled() {
+                return;
+            }
+            tx.send(MangaReaderEvents::PreparedPage(PreparedPage::new(index, url, rotation, options, decoded, picker)))
+                .ok();
+            redraw_tx.send(Events::Redraw).ok();
         });
-        StatefulWidget::render(self.pages_list.clone(), inner_area, buf, &mut self.page_list_state);
     }
 
-    fn load_page(&mut self, maybe_data: Option<PageData>) {
-        if let Some(data) = maybe_data {
-            match self.pages.get_mut(data.index) {
-                Some(page) => {
-                    let protocol = self.picker.new_resize_protocol(data.img);
-                    page.image_state = Some(protocol);
-                    page.dimensions = Some(data.dimensions);
-                },
-                None => {
-                    // Todo! indicate that the page couldnot be loaded
-                },
+    fn load_prepared_page(&mut self, prepared: PreparedPage) {
+        let index = prepared.index;
+        let Some(page) = self.pages.get_mut(index) else {
+            return;
+        };
+
+        // The page was rotated, the options changed or another frame was shown while it was prepared, it's being
+        // prepared again with them
+        let is_same_image = page.decoded.as_ref().is_some_and(|decoded| Arc::ptr_eq(decoded, &prepared.decoded));
+        if !is_same_image
+            || page.url != prepared.url
+            || page.rotation != prepared.rotation
+            || prepared.options != self.image_options
+        {
+            return;
+        }
+
+        page.set_image(prepared);
+
+        // Redraws once the next frame is due so that it's shown on time
+        if let Some(animation) = page.animation.as_ref() {
+            let delay = animation.delay(page.frame);
+            let redraw_tx = self._global_event_tx.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                redraw_tx.send(Events::Redraw).ok();
+            });
+        }
+
+        self.set_page_state(index, PageItemState::FinishedLoad);
+        self.decoded_pages.touch(index);
+        self.unload_pages_over_budget();
+    }
+
+    fn load_fitted_page(&mut self, index: usize, fitted_page: FittedPage) {
+        if let Some(page) = self.pages.get_mut(index) {
+            if page.fitting == Some(fitted_page.key) {
+                page.fitting = None;
+                page.fitted_page = Some(fitted_page);
+            }
+        }
+    }
+
+    fn load_webtoon_page(&mut self, index: usize, webtoon_page: WebtoonPage) {
+        if let Some(page) = self.pages.get_mut(index) {
+            if page.webtoon_resizing == Some((webtoon_page.width, webtoon_page.image_id)) {
+                page.webtoon_resizing = None;
+                page.webtoon_page = Some(webtoon_page);
+            }
+        }
+    }
+
+    fn encode_in_background(&self, index: usize, job: EncodeJob) {
+        let background = self.background.rgb();
+        let tx = self.local_event_tx.clone();
+        let redraw_tx = self._global_event_tx.clone();
+        let cancellation = self.cancellation.clone();
+
+        tokio::task::spawn_blocking(move || {
+            if cancellation.is_cancelled() {
+                return;
+            }
+            let EncodeJob {
+                id,
+                mut protocol,
+                resize,
+                area,
+            } = job;
+            protocol.resize_encode(&resize, background, area);
+            tx.send(MangaReaderEvents::EncodedImage(index, id, protocol)).ok();
+            redraw_tx.send(Events::Redraw).ok();
+        });
+    }
+
+    fn cycle_fit_mode(&mut self) {
+        self.fit_mode = self.fit_mode.next();
+        self.page_scroll = 0;
+        if let Err(e) = save_fit_mode(&self.chapter.manga_id, self.fit_mode) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+    }
+
+    /// Replaces the pages with the ones in the other quality, the pages already loaded are fetched
+    /// again
+    fn toggle_image_quality(&mut self) {
+        let page_type = self.page_type.toggle();
+        let url_imgs = if page_type == PageType::LowQuality { &self.data_saver } else { &self.data };
+
+        if url_imgs.is_empty() {
+            return;
+        }
+
+        self.image_tasks.abort_all();
+        self.page_type = page_type;
+        self.save_reader_setting(ReaderSetting::PageType(page_type));
+        self.pages = url_imgs.iter().map(|url| Page::new(url.clone(), page_type)).collect();
+        self.pages_list = PagesList::new((1..=self.pages.len()).map(PagesItem::new).collect());
+
+        if self.page_list_state.selected.is_some_and(|selected| selected >= self.pages.len()) {
+            self.page_list_state.select(Some(self.pages.len() - 1));
+        }
+
+        self.prefetch_pages();
+    }
+
+    fn toggle_go_to_page(&mut self) {
+        self.is_typing_page = !self.is_typing_page;
+        self.go_to_page_input.reset();
+    }
+
+    /// Jumps to the page the user typed, pages start from 1
+    fn go_to_page(&mut self) {
+        if let Ok(page_number) = self.go_to_page_input.value().parse::<usize>() {
+            if !self.pages.is_empty() {
+                self.jump_to_page(page_number.clamp(1, self.pages.len()) - 1);
+            }
+        }
+        self.toggle_go_to_page();
+    }
+
+    fn jump_to_page(&mut self, index: usize) {
+        self.page_list_state.select(Some(index));
+        self.page_scroll = 0;
+        self.chapter_end = None;
+        if self.webtoon {
+            self.scroll_webtoon_to_page(index);
+        }
+        self.prefetch_pages();
+    }
+
+    fn toggle_overview(&mut self) {
+        self.overview = match self.overview {
+            Some(_) => None,
+            None => Some(Overview::new(self.pages.len(), self.page_list_state.selected.unwrap_or(0))),
+        };
+    }
+
+    fn open_overview_selection(&mut self) {
+        if let Some(overview) = self.overview.take() {
+            if !self.pages.is_empty() {
+                self.jump_to_page(overview.selected);
+            }
+        }
+    }
+
+    /// Fetches the thumbnails on screen which have not been requested yet
+    fn fetch_visible_thumbnails(&mut self) {
+        let Some(overview) = self.overview.as_mut() else {
+            return;
+        };
+
+        // The data saver pages are smaller and enough for a thumbnail
+        let (page_type, file_names) =
+            if self.data_saver.is_empty() { (PageType::HighQuality, &self.data) } else { (PageType::LowQuality, &self.data_saver) };
+        let endpoint = format!("{}/{}/{}", self.base_url, page_type, self.hash);
+
+        for index in overview.visible.clone() {
+            let (Some(thumbnail), Some(file_name)) = (overview.thumbnails.get_mut(index), file_names.get(index)) else {
+                continue;
             };
-            match self.pages_list.pages.get_mut(data.index) {
-                Some(page_item) => page_item.state = PageItemState::FinishedLoad,
-                None => {
-                    // Todo! indicate with an x that some page didnt load
-                },
+            if !matches!(thumbnail, ThumbnailState::Waiting) {
+                continue;
             }
+
+            *thumbnail = ThumbnailState::Loading;
+
+            let endpoint = endpoint.clone();
+            let file_name = file_name.clone();
+            let tx = self.local_event_tx.clone();
+
+            spawn_cancellable(&mut self.image_tasks, &self.cancellation, async move {
+                match fetch_page_data(&endpoint, &file_name, index).await {
+                    Some(page_data) => {
+                        let img = page_data.img.thumbnail(THUMBNAIL_SIZE.0, THUMBNAIL_SIZE.1);
+                        tx.send(MangaReaderEvents::LoadThumbnail(PageData {
+                            dimensions: img.dimensions(),
+                            img,
+                            index,
+                            animation: None,
+                            hash: page_data.hash,
+                        }))
+                        .ok()
+                    },
+                    None => tx.send(MangaReaderEvents::FailedThumbnail(index)).ok(),
+                };
+            });
         }
     }
 
-    fn tick(&mut self) {
-        self.pages_list.on_tick();
-        if let Ok(background_event) = self.local_event_rx.try_recv() {
-            match background_event {
-                MangaReaderEvents::FetchPages => {
-                    let mut pages_list: Vec<PagesItem> = vec![];
-                    for (index, page) in self.pages.iter().enumerate() {
-                        let file_name = page.url.clone();
-                        let endpoint = format!("{}/{}/{}", self.base_url, page.page_type, self.chapter_id);
-                        let tx = self.local_event_tx.clone();
-                        pages_list.push(PagesItem::new(index));
-                        self.image_tasks.spawn(async move {
-                            let image_response = MangadexClient::global().get_chapter_page(&endpoint, &file_name).await;
-                            match image_response {
-                                Ok(bytes) => {
-                                    let dyn_img = Reader::new(std::io::Cursor::new(bytes)).with_guessed_format();
-
-                                    if let Err(err) = dyn_img {
-                                        return write_to_error_log(ErrorType::FromError(Box::new(err)));
-                                    }
-
-                                    let maybe_decoded = dyn_img.unwrap().decode();
-
-                                    if let Ok(decoded) = maybe_decoded {
-                                        let page_data = PageData {
-                                            dimensions: decoded.dimensions(),
-                                            img: decoded,
-                                            index,
-                                        };
-                                        tx.send(MangaReaderEvents::LoadPage(Some(page_data))).ok();
-                                    }
-                                },
-                                Err(e) => {
-                                    write_to_error_log(ErrorType::FromError(Box::new(e)));
-                                },
-                            };
-                        });
-                    }
-                    self.pages_list = PagesList::new(pages_list);
+    fn set_thumbnail(&mut self, index: usize, state: ThumbnailState) {
+        if let Some(thumbnail) = self.overview.as_mut().and_then(|overview| overview.thumbnails.get_mut(index)) {
+            *thumbnail = state;
+        }
+    }
+
+    fn render_overview(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(overview) = self.overview.as_mut() else {
+            return;
+        };
+
+        let block = Block::bordered().title(Line::from(vec![
+            "Go to page ".into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+            " Close ".into(),
+            Span::raw("<Esc>").style(*INSTRUCTIONS_STYLE),
+        ]));
+        let grid_area = block.inner(area);
+        block.render(area, buf);
+
+        let (cell_width, cell_height) = THUMBNAIL_CELL;
+        let columns = (grid_area.width / cell_width).max(1) as usize;
+        let rows = (grid_area.height / cell_height).max(1) as usize;
+
+        overview.columns = columns;
+        overview.scroll_to_selected(rows);
+
+        let first = overview.scroll * columns;
+        let last = (first + rows * columns).min(overview.thumbnails.len());
+        overview.visible = first..last;
+
+        for index in first..last {
+            let position = index - first;
+            let cell = Rect::new(
+                grid_area.x + (position % columns) as u16 * cell_width,
+                grid_area.y + (position / columns) as u16 * cell_height,
+                cell_width,
+                cell_height,
+            )
+            .intersection(grid_area);
+
+            let border_style = if index == overview.selected { Style::default().fg(Color::Yellow) } else { Style::default() };
+            let cell_block = Block::bordered().border_style(border_style).title(format!("{}", index + 1));
+            let thumbnail_area = cell_block.inner(cell);
+            cell_block.render(cell, buf);
+
+            match &mut overview.thumbnails[index] {
+                ThumbnailState::Loaded(image_state) => {
+                    let image = StatefulImage::new(None).resize(Resize::Fit(None));
+                    StatefulWidget::render(image, thumbnail_area, buf, image_state);
                 },
-                MangaReaderEvents::LoadPage(maybe_data) => self.load_page(maybe_data),
+                ThumbnailState::Failed => Paragraph::new("Failed").red().render(thumbnail_area, buf),
+                ThumbnailState::Waiting | ThumbnailState::Loading => Paragraph::new("Loading").render(thumbnail_area, buf),
             }
         }
     }
+
+    fn render_page(&mut self, index: usize, area: Rect, buf: &mut Buffer) {
+        self.decoded_pages.touch(index);
+        match self.pages.get_mut(index).and_then(|page| page.image_state.as_mut()) {
+            Some(image) => {
+                if let Some(job) = image.render(Resize::Fit(Some(self.resize_filter)), area, buf) {
+                    self.encode_in_background(index, job);
+                }
+            },
+            None => self.render_page_placeholder(index, area, buf),
+        }
+    }
+
+    /// Shown in place of a page which has not loaded
+    fn render_page_placeholder(&self, index: usize, area: Rect, buf: &mut Buffer) {
+        match self.pages.get(index).map(|page| &page.state) {
+            Some(PageItemState::Failed) => Block::bordered()
+                .border_style(Style::default().fg(Color::Red))
+                .title(Line::from(vec!["Could not load this page, retry ".into(), Span::raw("<r>").style(*INSTRUCTIONS_STYLE)]))
+                .render(area, buf),
+            _ => Block::bordered().title("Loading page").render(area, buf),
+        }
+    }
+
+    /// Renders a page sized according to `fit_mode`, pages which are bigger than the reader only show
+    /// the rows starting from `page_scroll`
+    fn render_fitted_page(&mut self, index: usize, area: Rect, buf: &mut Buffer) {
+        let (font_width, font_height) = self.picker.font_size;
+        let area_size = (area.width, area.height);
+        self.page_height = area.height;
+        self.decoded_pages.touch(index);
+
+        let Some(page) = self.pages.get_mut(index) else {
+            return;
+        };
+
+        let fit_mode = if page.is_tall() { FitMode::FitWidth } else { self.fit_mode };
+
+        let Some(image) = page.image.as_ref() else {
+            self.page_overflow = 0;
+            return self.render_page_placeholder(index, area, buf);
+        };
+
+        let area_width_in_pixels = (area.width as u32 * font_width as u32).max(1);
+        let area_height_in_pixels = (area.height as u32 * font_height as u32).max(1);
+
+        let (width, height) = match fit_mode {
+            FitMode::FitHeight => {
+                (image.width() as u64 * area_height_in_pixels as u64 / image.height().max(1) as u64, area_height_in_pixels as u64)
+            },
+            FitMode::FitWidth => {
+                (area_width_in_pixels as u64, image.height() as u64 * area_width_in_pixels as u64 / image.width().max(1) as u64)
+            },
+            FitMode::Original => (image.width() as u64, image.height() as u64),
+        };
+        let (width, height) = (width.max(1) as u32, height.max(1) as u32);
+
+        // With fit height the page is shrinked if it's too wide so the whole page is always visible
+        self.page_overflow = match fit_mode {
+            FitMode::FitHeight => 0,
+            _ => (height.div_ceil(font_height as u32)).saturating_sub(area.height as u32),
+        };
+        self.page_scroll = self.page_scroll.min(self.page_overflow);
+        let scroll = self.page_scroll;
+
+        let key = FitKey {
+            fit_mode,
+            area: area_size,
+            scroll,
+            image_id: page.image_id,
+        };
+        let is_up_to_date = page.fitted_page.as_ref().is_some_and(|fitted| fitted.key == key);
+
+        if !is_up_to_date && page.fitting != Some(key) {
+            page.fitting = Some(key);
+
+            let image = Arc::clone(image);
+            let resize_filter = self.resize_filter;
+            let background = self.background.rgb();
+            let mut picker = picker_for_worker(&mut self.picker);
+            let tx = self.local_event_tx.clone();
+            let redraw_tx = self._global_event_tx.clone();
+            let cancellation = self.cancellation.clone();
+
+            tokio::task::spawn_blocking(move || {
+                if cancellation.is_cancelled() {
+                    return;
+                }
+                let resized =
+                    if fit_mode == FitMode::Original { (*image).clone() } else { image.resize_exact(width, height, resize_filter) };
+                let visible = match fit_mode {
+                    FitMode::FitHeight => resized,
+                    _ => resized.crop_imm(0, scroll * font_height as u32, area_width_in_pixels, area_height_in_pixels),
+                };
+
+                let mut protocol = picker.new_resize_protocol(visible);
+                let resize = fitted_page_resize(fit_mode, resize_filter);
+                if let Some(encode_area) = protocol.needs_resize(&resize, area) {
+                    protocol.resize_encode(&resize, background, encode_area);
+                }
+
+                let fitted_page = FittedPage {
+                    key,
+                    image_state: EncodedImage::new(protocol),
+                };
+                tx.send(MangaReaderEvents::FittedPage(index, fitted_page)).ok();
+                redraw_tx.send(Events::Redraw).ok();
+            });
+        }
+
+        // Until the page is fitted again it's drawn as it was
+        let job = match page.fitted_page.as_mut() {
+            Some(fitted_page) => {
+                fitted_page
+                    .image_state
+                    .render(fitted_page_resize(fitted_page.key.fit_mode, self.resize_filter), area, buf)
+            },
+            None => return self.render_page_placeholder(index, area, buf),
+        };
+
+        if let Some(job) = job {
+            self.encode_in_background(index, job);
+        }
+    }
+
+    /// Scrolls through the current page if it doesn't fit the reader, returns false if there is
+    /// nothing left to scroll in that direction
+    fn scroll_page(&mut self, down: bool) -> bool {
+        if self.double_page || self.page_overflow == 0 {
+            return false;
+        }
+
+        let current_page_is_tall = self
+            .pages
+            .get(self.page_list_state.selected.unwrap_or(0))
+            .is_some_and(|page| page.is_tall());
+        let step = if current_page_is_tall { self.page_height.max(1) as u32 } else { (self.page_height.max(2) / 2) as u32 };
+
+        if down && self.page_scroll < self.page_overflow {
+            self.page_scroll = (self.page_scroll + step).min(self.page_overflow);
+            return true;
+        }
+
+        if !down && self.page_scroll > 0 {
+            self.page_scroll = self.page_scroll.saturating_sub(step);
+            return true;
+        }
+
+        false
+    }
+
+    /// The pages shown together with the page at `index` in double page mode
+    fn current_spread(&self, index: usize) -> (usize, Option<usize>) {
+        let first = spread_start(index, self.spread_offset);
+        if first == 0 && !self.spread_offset {
+            return (first, None);
+        }
+        let second = first + 1;
+        (first, if second < self.pages.len() { Some(second) } else { None })
+    }
+
+    /// How many rows a page takes in webtoon mode, pages which have not loaded take the whole area
+    fn webtoon_page_height(&self, index: usize) -> u32 {
+        if self.is_skipped(index) {
+            return 0;
+        }
+
+        match self.pages.get(index).and_then(|page| page.dimensions) {
+            Some((width, height)) => {
+                let (font_width, font_height) = self.picker.font_size;
+                let width_in_pixels = self.webtoon_width as f64 * font_width as f64;
+                let height_in_pixels = height as f64 * width_in_pixels / width as f64;
+                (height_in_pixels / font_height as f64).ceil() as u32
+            },
+            None => self.webtoon_height.max(1) as u32,
+        }
+    }
+
+    fn render_webtoon(&mut self, area: Rect, buf: &mut Buffer) {
+        self.webtoon_width = area.width;
+        self.webtoon_height = area.height;
+
+        let view_top = self.webtoon_scroll;
+        let view_bottom = view_top + area.height as u32;
+        let mut page_top: u32 = 0;
+
+        for index in 0..self.pages.len() {
+            if page_top >= view_bottom {
+                break;
+            }
+
+            let page_bottom = page_top + self.webtoon_page_height(index);
+
+            if page_bottom > view_top && page_bottom > page_top {
+                let visible_top = page_top.max(view_top);
+                let visible_bottom = page_bottom.min(view_bottom);
+                let page_area =
+                    Rect::new(area.x, area.y + (visible_top - view_top) as u16, area.width, (visible_bottom - visible_top) as u16);
+
+                self.render_webtoon_page(index, page_area, page_top < view_top, buf);
+            }
+
+            page_top = page_bottom;
+        }
+    }
+
+    /// Renders the visible part of a page, if it's cut at the top then `clip_top` shows its bottom part
+    fn render_webtoon_page(&mut self, index: usize, area: Rect, clip_top: bool, buf: &mut Buffer) {
+        let width = self.webtoon_width;
+        let (font_width, _) = self.picker.font_size;
+        self.decoded_pages.touch(index);
+
+        let Some(page) = self.pages.get_mut(index) else {
+            return;
+        };
+
+        let image_id = page.image_id;
+        let is_up_to_date = page
+            .webtoon_page
+            .as_ref()
+            .is_some_and(|webtoon_page| webtoon_page.width == width && webtoon_page.image_id == image_id);
+
+        if !is_up_to_date && page.webtoon_resizing != Some((width, image_id)) {
+            if let Some(image) = page.image.as_ref() {
+                page.webtoon_resizing = Some((width, image_id));
+
+                let image = Arc::clone(image);
+                let resize_filter = self.resize_filter;
+                let mut picker = picker_for_worker(&mut self.picker);
+                let tx = self.local_event_tx.clone();
+                let redraw_tx = self._global_event_tx.clone();
+                let cancellation = self.cancellation.clone();
+
+                tokio::task::spawn_blocking(move || {
+                    if cancellation.is_cancelled() {
+                        return;
+                    }
+                    let width_in_pixels = (width as u32 * font_width as u32).max(1);
+                    let height_in_pixels =
+                        (image.height() as u64 * width_in_pixels as u64 / image.width().max(1) as u64).max(1) as u32;
+                    let resized = image.resize_exact(width_in_pixels, height_in_pixels, resize_filter);
+
+                    let webtoon_page = WebtoonPage {
+                        width,
+                        image_id,
+                        image_state: EncodedImage::new(picker.new_resize_protocol(resized)),
+                    };
+                    tx.send(MangaReaderEvents::WebtoonPage(index, webtoon_page)).ok();
+                    redraw_tx.send(Events::Redraw).ok();
+                });
+            }
+        }
+
+        // A page resized to another width would not line up with the pages around it
+        let job = match page.webtoon_page.as_mut().filter(|webtoon_page| webtoon_page.width == width) {
+            Some(webtoon_page) => {
+                let resize = Resize::Crop(Some(CropOptions {
+                    clip_top,
+                    clip_left: false,
+                }));
+                webtoon_page.image_state.render(resize, area, buf)
+            },
+            None => return self.render_page_placeholder(index, area, buf),
+        };
+
+        if let Some(job) = job {
+            self.encode_in_background(index, job);
+        }
+    }
+
+    fn webtoon_total_height(&self) -> u32 {
+        (0..self.pages.len()).map(|index| self.webtoon_page_height(index)).sum()
+    }
+
+    fn scroll_webtoon(&mut self, rows: i64) {
+        let max_scroll = self.webtoon_total_height().saturating_sub(self.webtoon_height as u32);
+        self.webtoon_scroll = (self.webtoon_scroll as i64 + rows).clamp(0, max_scroll as i64) as u32;
+
+        // The page at the top of the screen is the current one
+        let mut page_top: u32 = 0;
+        for index in 0..self.pages.len() {
+            page_top += self.webtoon_page_height(index);
+            if page_top > self.webtoon_scroll {
+                if self.page_list_state.selected != Some(index) {
+                    self.page_list_state.select(Some(index));
+                    self.prefetch_pages();
+                }
+                break;
+            }
+        }
+    }
+
+    fn scroll_webtoon_to_page(&mut self, index: usize) {
+        self.webtoon_scroll = (0..index).map(|index| self.webtoon_page_height(index)).sum();
+    }
+
+    fn toggle_webtoon(&mut self) {
+        self.webtoon = !self.webtoon;
+        if self.webtoon {
+            self.double_page = false;
+            self.current_page_size = 2;
+            self.scroll_webtoon_to_page(self.page_list_state.selected.unwrap_or(0));
+        }
+        self.save_reader_setting(ReaderSetting::Webtoon(self.webtoon));
+    }
+
+    fn save_reader_setting(&self, setting: ReaderSetting) {
+        if let Err(e) = save_reader_setting(&self.chapter.manga_id, setting) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+    }
+
+    fn next_page(&mut self) {
+        if self.chapter_end.is_some() {
+            return self.go_to_next_chapter();
+        }
+
+        if self.webtoon {
+            let max_scroll = self.webtoon_total_height().saturating_sub(self.webtoon_height as u32);
+            let all_pages_fetched = self
+                .pages
+                .iter()
+                .all(|page| page.state == PageItemState::FinishedLoad || page.state == PageItemState::Failed);
+            if self.webtoon_scroll >= max_scroll && all_pages_fetched {
+                return self.show_chapter_end();
+            }
+            return self.scroll_webtoon(WEBTOON_SCROLL_ROWS);
+        }
+
+        if self.scroll_page(true) {
+            return;
+        }
+        self.page_scroll = 0;
+
+        if self.double_page {
+            let (first, second) = self.current_spread(self.page_list_state.selected.unwrap_or(0));
+            let next = second.unwrap_or(first) + 1;
+            if next < self.pages.len() {
+                self.page_list_state.select(Some(next));
+            } else {
+                return self.show_chapter_end();
+            }
+        } else {
+            let start = self.page_list_state.selected.map_or(0, |page| page + 1);
+            match (start..self.pages.len()).find(|index| !self.is_skipped(*index)) {
+                Some(next) => self.page_list_state.select(Some(next)),
+                None => return self.show_chapter_end(),
+            }
+        }
+        self.prefetch_pages();
+    }
+
+    fn previous_page(&mut self) {
+        if self.chapter_end.take().is_some() {
+            return;
+        }
+
+        if self.webtoon {
+            return self.scroll_webtoon(-WEBTOON_SCROLL_ROWS);
+        }
+
+        if self.scroll_page(false) {
+            return;
+        }
+        self.page_scroll = 0;
+
+        if self.double_page {
+            let (first, _) = self.current_spread(self.page_list_state.selected.unwrap_or(0));
+            if first > 0 {
+                let (previous, _) = self.current_spread(first - 1);
+                self.page_list_state.select(Some(previous));
+            }
+        } else {
+            let current = self.page_list_state.selected.unwrap_or(0);
+            if let Some(previous) = (0..current).rev().find(|index| !self.is_skipped(*index)) {
+                self.page_list_state.select(Some(previous));
+            }
+            // Going back to a tall page starts from its last screen, the scroll is limited to the
+            // height of the page once it's drawn
+            if self
+                .pages
+                .get(self.page_list_state.selected.unwrap_or(0))
+                .is_some_and(|page| page.is_tall())
+            {
+                self.page_scroll = u32::MAX;
+            }
+        }
+        self.prefetch_pages();
+    }
+
+    fn toggle_double_page(&mut self) {
+        self.double_page = !self.double_page;
+        self.current_page_size = if self.double_page { 4 } else { 2 };
+        self.align_to_spread();
+        self.save_reader_setting(ReaderSetting::DoublePage(self.double_page));
+    }
+
+    fn toggle_spread_offset(&mut self) {
+        self.spread_offset = !self.spread_offset;
+        self.align_to_spread();
+        self.save_reader_setting(ReaderSetting::SpreadOffset(self.spread_offset));
+    }
+
+    /// Make sure the selected page is the first one of a spread so page turns move a whole spread
+    fn align_to_spread(&mut self) {
+        if self.double_page {
+            if let Some(selected) = self.page_list_state.selected {
+                let (first, _) = self.current_spread(selected);
+                self.page_list_state.select(Some(first));
+            }
+        }
+    }
+
+    fn render_page_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let inner_area = area.inner(Margin {
+            horizontal: 1,
+            vertical: 1,
+        });
+        StatefulWidget::render(self.pages_list.clone(), inner_area, buf, &mut self.page_list_state);
+    }
+
+    /// The page is shown once it's prepared
+    fn load_page(&mut self, maybe_data: Option<PageData>) {
+        if let Some(data) = maybe_data {
+            if let Some(page) = self.pages.get_mut(data.index) {
+                page.decoded = Some(Arc::new(data.img));
+                page.animation = data.animation.map(Arc::new);
+                page.hash = Some(data.hash);
+                page.show_frame(0);
+            }
+            self.find_repeated_pages();
+            self.prepare_page(data.index);
+        }
+    }
+
+    /// The pages around the current one are never unloaded, otherwise they would be fetched again right away
+    fn unload_pages_over_budget(&mut self) {
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+        let window = current_page.saturating_sub(1)..=current_page + self.prefetch_window;
+
+        let pages = &self.pages;
+        let unload = self
+            .decoded_pages
+            .over_budget(|index| pages.get(index).map(Page::memory_usage).unwrap_or(0), |index| window.contains(&index));
+
+        for index in unload {
+            if let Some(page) = self.pages.get_mut(index) {
+                page.unload();
+            }
+            self.set_page_state(index, PageItemState::Waiting);
+        }
+    }
+
+    /// The page and its item on the list of pages always have the same state
+    fn set_page_state(&mut self, index: usize, state: PageItemState) {
+        if let Some(page_item) = self.pages_list.pages.get_mut(index) {
+            page_item.state = state.clone();
+        }
+        if let Some(page) = self.pages.get_mut(index) {
+            page.state = state;
+        }
+    }
+
+    /// Fetches again the pages on screen which failed to load
+    fn retry_failed_pages(&mut self) {
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+        let on_screen = if self.double_page {
+            let (first, second) = self.current_spread(current_page);
+            vec![Some(first), second]
+        } else {
+            vec![Some(current_page)]
+        };
+
+        for index in on_screen.into_iter().flatten() {
+            if self.pages.get(index).is_some_and(|page| page.state == PageItemState::Failed) {
+                self.fetch_page(index);
+            }
+        }
+    }
+
+    /// Spawns the tasks to fetch the current page and the next `prefetch_window` pages which have
+    /// not been requested yet, so that going to the next page doesn't have to wait for it to load
+    fn prefetch_pages(&mut self) {
+        self.preload_next_chapter();
+
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+
+        for index in current_page..=current_page + self.prefetch_window {
+            let Some(page) = self.pages.get(index) else {
+                break;
+            };
+
+            if page.state == PageItemState::Waiting {
+                self.fetch_page(index);
+            }
+        }
+    }
+
+    fn export_current_page(&mut self, export: PageExport) {
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+        let Some(page) = self.pages.get(current_page) else {
+            return;
+        };
+
+        let config = MangaTuiConfig::get();
+        let file_name = page.url.clone();
+        let endpoint = format!("{}/{}/{}", self.base_url, page.page_type, self.hash);
+        let directory = match export {
+            PageExport::Save => config.saved_pages_directory(),
+            PageExport::OpenInViewer => std::env::temp_dir().join("manga-tui"),
+        };
+        let path = directory.join(format!("chapter-{}-{}", self.chapter.number, file_name));
+        let viewer = config.external_viewer.clone();
+        let tx = self.local_event_tx.clone();
+
+        // Not spawned on `image_tasks` so that leaving the reader doesn't leave the page half written
+        tokio::spawn(async move {
+            match export_page(endpoint, file_name, path, export, viewer).await {
+                Ok(path) => tx.send(MangaReaderEvents::PageExported(export, Some(path))).ok(),
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(e));
+                    tx.send(MangaReaderEvents::PageExported(export, None)).ok()
+                },
+            };
+        });
+    }
+
+    #[cfg(feature = "ocr")]
+    fn toggle_text_pane(&mut self) {
+        self.translation = None;
+        if self.text_pane.take().is_none() {
+            self.extract_page_text(self.page_list_state.selected.unwrap_or(0));
+        }
+    }
+
+    /// The pane always shows the text of the current page, so it's extracted again once the page changes
+    #[cfg(feature = "ocr")]
+    fn follow_current_page_text(&mut self) {
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+        if self.text_pane.as_ref().is_some_and(|pane| pane.page() != current_page) {
+            self.extract_page_text(current_page);
+        }
+    }
+
+    #[cfg(feature = "ocr")]
+    fn extract_page_text(&mut self, index: usize) {
+        let Some(page) = self.pages.get(index) else {
+            return;
+        };
+
+        let file_name = page.url.clone();
+        let endpoint = format!("{}/{}/{}", self.base_url, page.page_type, self.hash);
+        let language = self.chapter.language;
+        let tx = self.local_event_tx.clone();
+
+        self.text_pane = Some(TextPane::Extracting(index));
+        self.translation = None;
+
+        spawn_cancellable(&mut self.image_tasks, &self.cancellation, async move {
+            let text = match fetch_page_bytes(&endpoint, &file_name).await {
+                Some(bytes) => match ocr::extract_text(&bytes, language).await {
+                    Ok(text) => Some(text),
+                    Err(e) => {
+                        write_to_error_log(ErrorType::FromError(e));
+                        None
+                    },
+                },
+                None => None,
+            };
+            tx.send(MangaReaderEvents::PageText(index, text)).ok();
+        });
+    }
+
+    #[cfg(feature = "ocr")]
+    fn load_page_text(&mut self, index: usize, text: Option<String>) {
+        // The pane was closed or moved to another page while tesseract was running
+        if !self.text_pane.as_ref().is_some_and(|pane| pane.page() == index) {
+            return;
+        }
+
+        self.text_pane = Some(match text {
+            Some(text) => TextPane::Extracted(index, text),
+            None => TextPane::Failed(index),
+        });
+    }
+
+    /// Only the text of a page that was already extracted can be translated
+    #[cfg(feature = "ocr")]
+    fn toggle_translation(&mut self) {
+        if self.translation.take().is_some() {
+            return;
+        }
+
+        let Some(TextPane::Extracted(index, text)) = self.text_pane.as_ref() else {
+            return;
+        };
+
+        let index = *index;
+        let text = text.clone();
+        let command = MangaTuiConfig::get().translation_command.clone();
+        let tx = self.local_event_tx.clone();
+
+        self.translation = Some(TextPane::Extracting(index));
+
+        spawn_cancellable(&mut self.image_tasks, &self.cancellation, async move {
+            let translation = match ocr::translate(&text, command.as_deref()).await {
+                Ok(translation) => Some(translation),
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(e));
+                    None
+                },
+            };
+            tx.send(MangaReaderEvents::PageTranslation(index, translation)).ok();
+        });
+    }
+
+    #[cfg(feature = "ocr")]
+    fn load_page_translation(&mut self, index: usize, translation: Option<String>) {
+        if !self.translation.as_ref().is_some_and(|pane| pane.page() == index) {
+            return;
+        }
+
+        self.translation = Some(match translation {
+            Some(translation) => TextPane::Extracted(index, translation),
+            None => TextPane::Failed(index),
+        });
+    }
+
+    /// Drawn over the bottom of the page like subtitles, so the original text stays next to it
+    #[cfg(feature = "ocr")]
+    fn render_translation(&self, area: Rect, buf: &mut Buffer) {
+        let Some(translation) = self.translation.as_ref() else {
+            return;
+        };
+
+        let text = match translation {
+            TextPane::Extracting(_) => Line::from("Translating...").into(),
+            TextPane::Extracted(_, translation) => ratatui::text::Text::from(translation.as_str()),
+            TextPane::Failed(_) => Line::from("Could not translate the text, check the error logs").red().into(),
+        };
+
+        let [_, translation_area] = Layout::vertical([Constraint::Fill(2), Constraint::Fill(1)]).areas(area);
+
+        Clear.render(translation_area, buf);
+        Paragraph::new(text)
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .block(Block::bordered().title("Translation").title_bottom(Line::from(vec![
+                " Close ".into(),
+                Span::raw("<I>").style(*INSTRUCTIONS_STYLE),
+                " ".into(),
+            ])))
+            .render(translation_area, buf);
+    }
+
+    #[cfg(feature = "ocr")]
+    fn render_text_pane(&self, area: Rect, buf: &mut Buffer) {
+        let Some(pane) = self.text_pane.as_ref() else {
+            return;
+        };
+
+        let text = match pane {
+            TextPane::Extracting(_) => Line::from("Extracting the text...").into(),
+            TextPane::Extracted(_, text) if text.is_empty() => Line::from("No text was found in this page").into(),
+            TextPane::Extracted(_, text) => ratatui::text::Text::from(text.as_str()),
+            TextPane::Failed(_) => Line::from("Could not extract the text, check the error logs").red().into(),
+        };
+
+        Clear.render(area, buf);
+        Paragraph::new(text)
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .block(Block::bordered().title(format!("Page {} text", pane.page() + 1)).title_bottom(Line::from(vec![
+                " Close ".into(),
+                Span::raw("<i>").style(*INSTRUCTIONS_STYLE),
+                " Translate ".into(),
+                Span::raw("<I>").style(*INSTRUCTIONS_STYLE),
+                " ".into(),
+            ])))
+            .render(area, buf);
+    }
+
+    fn page_exported(&mut self, export: PageExport, path: Option<PathBuf>) {
+        let notice = match (export, path) {
+            (PageExport::Save, Some(path)) => (format!("Page saved to {}", path.display()), Color::Green),
+            (PageExport::Save, None) => ("Could not save the page, check the error logs".to_string(), Color::Red),
+            (PageExport::OpenInViewer, Some(_)) => ("Page opened in the external viewer".to_string(), Color::Green),
+            (PageExport::OpenInViewer, None) => ("Could not open the page, check the error logs".to_string(), Color::Red),
+        };
+        self.notice = Some((notice.0, notice.1, Instant::now()));
+    }
+
+    fn fetch_page(&mut self, index: usize) {
+        let Some(page) = self.pages.get(index) else {
+            return;
+        };
+
+        let file_name = page.url.clone();
+        let endpoint = format!("{}/{}/{}", self.base_url, page.page_type, self.hash);
+        let tx = self.local_event_tx.clone();
+
+        self.set_page_state(index, PageItemState::Loading);
+
+        spawn_cancellable(&mut self.image_tasks, &self.cancellation, async move {
+            match fetch_page_data(&endpoint, &file_name, index).await {
+                Some(page_data) => tx.send(MangaReaderEvents::LoadPage(Some(page_data))).ok(),
+                None => tx.send(MangaReaderEvents::FailedPage(index)).ok(),
+            };
+        });
+    }
+
+    /// Once the last pages are reached the next chapter and its first pages are fetched, so that
+    /// going to it doesn't have to wait for mangadex
+    fn preload_next_chapter(&mut self) {
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+        if self.next_chapter != ChapterSearch::NotSearched || current_page + NEXT_CHAPTER_PRELOAD_PAGES < self.pages.len() {
+            return;
+        }
+
+        self.next_chapter = ChapterSearch::Searching;
+
+        let chapter = self.chapter.clone();
+        let tx = self.local_event_tx.clone();
+
+        spawn_cancellable(&mut self.image_tasks, &self.cancellation, async move {
+            let next_chapter = fetch_adjacent_chapter(&chapter, ChapterStep::Next).await;
+            tx.send(MangaReaderEvents::LoadNextChapter(next_chapter)).ok();
+        });
+    }
+
+    fn search_previous_chapter(&mut self) {
+        if self.previous_chapter != ChapterSearch::NotSearched {
+            return;
+        }
+
+        self.previous_chapter = ChapterSearch::Searching;
+
+        let chapter = self.chapter.clone();
+        let tx = self.local_event_tx.clone();
+
+        spawn_cancellable(&mut self.image_tasks, &self.cancellation, async move {
+            let previous_chapter = fetch_adjacent_chapter(&chapter, ChapterStep::Previous).await;
+            tx.send(MangaReaderEvents::LoadPreviousChapter(previous_chapter)).ok();
+        });
+    }
+
+    fn load_previous_chapter(&mut self, maybe_chapter: Option<Box<(ReadChapterInfo, ChapterPagesResponse)>>) {
+        match maybe_chapter {
+            Some(previous_chapter) => {
+                let (chapter, response) = *previous_chapter;
+                let page_type = self.page_type;
+                self.open_chapter(chapter, response, page_type, vec![]);
+            },
+            None => self.previous_chapter = ChapterSearch::NotFound,
+        }
+    }
+
+    /// Shown once the last page is passed, the chapter is marked as read since it may have been
+    /// opened from the previous one instead of the manga page
+    fn show_chapter_end(&mut self) {
+        if self.chapter_end.is_some() {
+            return;
+        }
+
+        let marked_as_read = match mark_chapter_as_read(&self.chapter.manga_id, &self.chapter.chapter_id, &self.chapter.title) {
+            Ok(marked) => marked,
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                false
+            },
+        };
+
+        self.chapter_end = Some(ChapterEnd { marked_as_read });
+        self.search_next_unread_chapter();
+    }
+
+    /// A chapter read again ends offering the chapter the manga was left at besides the next one,
+    /// chapters are compared by number so the ones read from other scanlation groups are skipped
+    fn search_next_unread_chapter(&mut self) {
+        if self.next_unread_chapter != ChapterSearch::NotSearched {
+            return;
+        }
+
+        let Ok(current) = self.chapter.number.parse::<f64>() else {
+            return;
+        };
+
+        let read_numbers = match get_read_chapter_numbers(&self.chapter.manga_id) {
+            Ok(read_numbers) => read_numbers,
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                return;
+            },
+        };
+
+        if !read_numbers.iter().filter_map(|number| number.parse::<f64>().ok()).any(|number| number > current) {
+            return;
+        }
+
+        self.next_unread_chapter = ChapterSearch::Searching;
+
+        let chapter = self.chapter.clone();
+        let tx = self.local_event_tx.clone();
+
+        spawn_cancellable(&mut self.image_tasks, &self.cancellation, async move {
+            let next_unread_chapter = fetch_adjacent_chapter(&chapter, ChapterStep::NextUnread(read_numbers)).await;
+            tx.send(MangaReaderEvents::LoadNextUnreadChapter(next_unread_chapter)).ok();
+        });
+    }
+
+    fn load_next_unread_chapter(&mut self, maybe_chapter: Option<Box<(ReadChapterInfo, ChapterPagesResponse)>>) {
+        self.next_unread_chapter = match maybe_chapter {
+            Some(next_unread_chapter) => {
+                let (chapter, response) = *next_unread_chapter;
+                ChapterSearch::Found(Box::new(PreloadedChapter {
+                    chapter,
+                    response,
+                    page_type: self.page_type,
+                    pages: vec![],
+                }))
+            },
+            None => ChapterSearch::NotFound,
+        };
+    }
+
+    fn go_to_next_unread_chapter(&mut self) {
+        let ChapterSearch::Found(next_unread_chapter) = std::mem::take(&mut self.next_unread_chapter) else {
+            return;
+        };
+        let PreloadedChapter {
+            chapter,
+            response,
+            page_type,
+            pages,
+        } = *next_unread_chapter;
+
+        self.open_chapter(chapter, response, page_type, pages);
+    }
+
+    /// Opens the same chapter in the other language at the page being read, for comparing translations
+    fn switch_language(&mut self) {
+        let Some(language) = self.other_language else {
+            return;
+        };
+        if self.switching_language {
+            return;
+        }
+
+        self.switching_language = true;
+
+        let chapter = self.chapter.clone();
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+        let tx = self.local_event_tx.clone();
+
+        spawn_cancellable(&mut self.image_tasks, &self.cancellation, async move {
+            let other_chapter = fetch_adjacent_chapter(&chapter, ChapterStep::InLanguage(language)).await;
+            tx.send(MangaReaderEvents::LoadChapterInOtherLanguage(current_page, other_chapter)).ok();
+        });
+    }
+
+    fn load_chapter_in_other_language(
+        &mut self,
+        page: usize,
+        maybe_chapter: Option<Box<(ReadChapterInfo, ChapterPagesResponse)>>,
+    ) {
+        self.switching_language = false;
+
+        let Some(other_chapter) = maybe_chapter else {
+            let language = self.other_language.map(|language| language.as_human_readable()).unwrap_or_default();
+            let message = format!("Chapter {} is not translated to {language}", self.chapter.number);
+            self.notice = Some((message, Color::Red, Instant::now()));
+            return;
+        };
+
+        let previous_language = self.chapter.language;
+        let (chapter, response) = *other_chapter;
+        let page_type = self.page_type;
+        self.open_chapter(chapter, response, page_type, vec![]);
+
+        self.other_language = Some(previous_language);
+        if !self.pages.is_empty() {
+            self.jump_to_page(page.min(self.pages.len() - 1));
+        }
+    }
+
+    fn render_help(&self, area: Rect, buf: &mut Buffer) {
+        let key_width = HELP.iter().flat_map(|(_, keys)| keys.iter()).map(|(key, _)| key.len()).max().unwrap_or(0) + 2;
+
+        let mut lines: Vec<Line<'_>> = vec![];
+        #[cfg(feature = "ocr")]
+        let help = HELP
+            .iter()
+            .chain(&[("Text", &[("i", "Show the text of the page"), ("I", "Translate the text of the page")] as &[(&str, &str)])]);
+        #[cfg(not(feature = "ocr"))]
+        let help = HELP.iter();
+        for (group, keys) in help {
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(Line::from(*group).bold());
+            for (key, description) in keys.iter() {
+                lines.push(Line::from(vec![
+                    Span::raw(format!("{key:<key_width$}")).style(*INSTRUCTIONS_STYLE),
+                    Span::raw(*description),
+                ]));
+            }
+        }
+
+        let width = (lines.iter().map(|line| line.width()).max().unwrap_or(0) as u16 + 4).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let help_area = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+
+        Clear.render(help_area, buf);
+        Paragraph::new(lines)
+            .block(Block::bordered().title("Reader keybindings").title_bottom(Line::from(vec![
+                " Close ".into(),
+                Span::raw("<?>").style(*INSTRUCTIONS_STYLE),
+                " ".into(),
+            ])))
+            .render(help_area, buf);
+    }
+
+    fn render_chapter_end(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(chapter_end) = self.chapter_end.as_ref() else {
+            return;
+        };
+
+        let mut finished = format!("Finished chapter {}", self.chapter.number);
+        if !self.chapter.title.is_empty() {
+            finished.push_str(&format!(": {}", self.chapter.title));
+        }
+
+        let read_status = if chapter_end.marked_as_read {
+            Line::from("Marked as read").style(Style::default().fg(Color::Green))
+        } else {
+            Line::from("Could not be marked as read").style(Style::default().fg(Color::Red))
+        };
+
+        let next_chapter = match &self.next_chapter {
+            ChapterSearch::Found(next_chapter) => Line::from(vec![
+                format!("Next chapter {} {} ", next_chapter.chapter.number, next_chapter.chapter.title).into(),
+                Span::raw("<n>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            ChapterSearch::NotFound => Line::from("This is the last chapter available"),
+            ChapterSearch::NotSearched | ChapterSearch::Searching => Line::from("Searching the next chapter..."),
+        };
+
+        let previous_chapter = match &self.previous_chapter {
+            ChapterSearch::NotFound => Line::from("There is no previous chapter"),
+            ChapterSearch::Searching | ChapterSearch::Found(_) => Line::from("Opening the previous chapter..."),
+            ChapterSearch::NotSearched => Line::from(vec!["Previous chapter ".into(), Span::raw("<p>").style(*INSTRUCTIONS_STYLE)]),
+        };
+
+        let mut lines = vec![Line::from(finished), read_status, Line::default(), next_chapter];
+
+        if let ChapterSearch::Found(next_unread_chapter) = &self.next_unread_chapter {
+            lines.push(Line::from(vec![
+                format!("Next unread chapter {} {} ", next_unread_chapter.chapter.number, next_unread_chapter.chapter.title).into(),
+                Span::raw("<u>").style(*INSTRUCTIONS_STYLE),
+            ]));
+        }
+
+        lines.extend([
+            previous_chapter,
+            Line::from(vec!["Back to the manga page ".into(), Span::raw("<Backspace>").style(*INSTRUCTIONS_STYLE)]),
+            Line::from(vec!["Back to the last page ".into(), Span::raw("<k>").style(*INSTRUCTIONS_STYLE)]),
+        ]);
+
+        let height = (lines.len() as u16).min(area.height);
+        let [_, content_area, _] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(height), Constraint::Fill(1)]).areas(area);
+
+        Block::bordered().render(area, buf);
+        Paragraph::new(lines).centered().render(content_area, buf);
+    }
+
+    fn load_next_chapter(&mut self, maybe_chapter: Option<Box<(ReadChapterInfo, ChapterPagesResponse)>>) {
+        let Some(next_chapter) = maybe_chapter else {
+            self.next_chapter = ChapterSearch::NotFound;
+            return;
+        };
+        let (chapter, response) = *next_chapter;
+
+        let (page_type, file_names) = match self.page_type {
+            PageType::LowQuality if !response.chapter.data_saver.is_empty() => (PageType::LowQuality, &response.chapter.data_saver),
+            _ if response.chapter.data.is_empty() => (PageType::LowQuality, &response.chapter.data_saver),
+            _ => (PageType::HighQuality, &response.chapter.data),
+        };
+
+        let endpoint = format!("{}/{}/{}", response.base_url, page_type, response.chapter.hash);
+
+        for (index, file_name) in file_names.iter().take(self.prefetch_window + 1).enumerate() {
+            let endpoint = endpoint.clone();
+            let file_name = file_name.clone();
+            let tx = self.local_event_tx.clone();
+
+            spawn_cancellable(&mut self.image_tasks, &self.cancellation, async move {
+                if let Some(page_data) = fetch_page_data(&endpoint, &file_name, index).await {
+                    tx.send(MangaReaderEvents::LoadNextChapterPage(page_data)).ok();
+                }
+            });
+        }
+
+        self.next_chapter = ChapterSearch::Found(Box::new(PreloadedChapter {
+            chapter,
+            response,
+            page_type,
+            pages: vec![],
+        }));
+    }
+
+    fn load_next_chapter_page(&mut self, page_data: PageData) {
+        if let ChapterSearch::Found(next_chapter) = &mut self.next_chapter {
+            next_chapter.pages.push(page_data);
+        }
+    }
+
+    fn go_to_next_chapter(&mut self) {
+        let ChapterSearch::Found(next_chapter) = std::mem::take(&mut self.next_chapter) else {
+            return;
+        };
+        let PreloadedChapter {
+            chapter,
+            response,
+            page_type,
+            pages,
+        } = *next_chapter;
+
+        self.open_chapter(chapter, response, page_type, pages);
+    }
+
+    /// Replaces the chapter being read, keeping how the reader is set up
+    fn open_chapter(
+        &mut self,
+        chapter: ReadChapterInfo,
+        response: ChapterPagesResponse,
+        page_type: PageType,
+        pages: Vec<PageData>,
+    ) {
+        let mut reader = MangaReader::new(
+            self._global_event_tx.clone(),
+            ChapterToRead {
+                chapter,
+                hash: response.chapter.hash,
+                base_url: response.base_url,
+                data: response.chapter.data,
+                data_saver: response.chapter.data_saver,
+                page_type,
+            },
+            self.prefetch_window,
+            self.decoded_pages.budget,
+            self.resize_filter,
+            self.background,
+            self.picker,
+        );
+
+        for page_data in pages {
+            reader.load_page(Some(page_data));
+        }
+
+        reader.show_status_bar = self.show_status_bar;
+        reader.hide_ui_after = self.hide_ui_after;
+        reader.skip_repeated_pages = self.skip_repeated_pages;
+        reader.other_language = self.other_language.filter(|language| *language != reader.chapter.language);
+        reader.image_options = self.image_options;
+
+        self.save_progress();
+        *self = reader;
+    }
+
+    /// Stores the current page so that the chapter opens on it the next time it's read
+    fn save_progress(&mut self) {
+        let current_page = self.page_list_state.selected;
+        if current_page.is_none() || current_page == self.last_saved_page {
+            return;
+        }
+
+        self.last_saved_page = current_page;
+        let progress = ChapterProgressSave {
+            chapter_id: &self.chapter.chapter_id,
+            manga_id: &self.chapter.manga_id,
+            chapter_number: &self.chapter.number,
+            chapter_title: &self.chapter.title,
+            page: current_page.unwrap_or(0),
+        };
+
+        if let Err(e) = save_last_page_read(progress) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+    }
+
+    fn tick(&mut self) {
+        self.pages_list.on_tick();
+        self.save_progress();
+        self.fetch_visible_thumbnails();
+        self.handle_background_events();
+    }
+
+    fn handle_background_events(&mut self) {
+        while let Ok(background_event) = self.local_event_rx.try_recv() {
+            match background_event {
+                MangaReaderEvents::FetchPages => {
+                    self.pages_list = PagesList::new((1..=self.pages.len()).map(PagesItem::new).collect());
+                    self.prefetch_pages();
+                },
+                MangaReaderEvents::LoadPage(maybe_data) => self.load_page(maybe_data),
+                MangaReaderEvents::FailedPage(index) => self.set_page_state(index, PageItemState::Failed),
+                MangaReaderEvents::LoadNextChapter(maybe_chapter) => self.load_next_chapter(maybe_chapter),
+                MangaReaderEvents::LoadNextChapterPage(page_data) => self.load_next_chapter_page(page_data),
+                MangaReaderEvents::LoadThumbnail(page_data) => {
+                    let protocol = self.picker.new_resize_protocol(page_data.img);
+                    self.set_thumbnail(page_data.index, ThumbnailState::Loaded(protocol));
+                },
+                MangaReaderEvents::FailedThumbnail(index) => self.set_thumbnail(index, ThumbnailState::Failed),
+                MangaReaderEvents::LoadPreviousChapter(maybe_chapter) => self.load_previous_chapter(maybe_chapter),
+                MangaReaderEvents::LoadNextUnreadChapter(maybe_chapter) => self.load_next_unread_chapter(maybe_chapter),
+                MangaReaderEvents::LoadChapterInOtherLanguage(page, maybe_chapter) => {
+                    self.load_chapter_in_other_language(page, maybe_chapter)
+                },
+                MangaReaderEvents::PreparedPage(prepared) => self.load_prepared_page(prepared),
+                MangaReaderEvents::FittedPage(index, fitted_page) => self.load_fitted_page(index, fitted_page),
+                MangaReaderEvents::WebtoonPage(index, webtoon_page) => self.load_webtoon_page(index, webtoon_page),
+                MangaReaderEvents::PageExported(export, path) => self.page_exported(export, path),
+                #[cfg(feature = "ocr")]
+                MangaReaderEvents::PageText(index, text) => self.load_page_text(index, text),
+                #[cfg(feature = "ocr")]
+                MangaReaderEvents::PageTranslation(index, translation) => self.load_page_translation(index, translation),
+                MangaReaderEvents::EncodedImage(index, id, protocol) => {
+                    if let Some(page) = self.pages.get_mut(index) {
+                        page.restore_encoded_image(id, protocol);
+                    }
+                },
+            }
+        }
+
+        #[cfg(feature = "ocr")]
+        self.follow_current_page_text();
+
+        self.advance_animations();
+    }
+
+    /// The pages drawn right now, only these are animated
+    fn visible_pages(&self) -> Vec<usize> {
+        if self.overview.is_some() || self.chapter_end.is_some() {
+            return vec![];
+        }
+
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+        if self.double_page {
+            let (first, second) = self.current_spread(current_page);
+            return [Some(first), second].into_iter().flatten().collect();
+        }
+        vec![current_page]
+    }
+
+    fn advance_animations(&mut self) {
+        if !self.animations_playing {
+            return;
+        }
+
+        for index in self.visible_pages() {
+            let Some(page) = self.pages.get_mut(index) else {
+                continue;
+            };
+            if page.frame_is_due() && page.show_frame(page.frame + 1) {
+                self.prepare_page(index);
+            }
+        }
+    }
+
+    /// Pauses the animation of the current page to show the frame after or before the one shown
+    fn step_frame(&mut self, forward: bool) {
+        let current_page = self.page_list_state.selected.unwrap_or(0);
+        let Some(page) = self.pages.get_mut(current_page) else {
+            return;
+        };
+        let Some(frames) = page.animation.as_ref().map(|animation| animation.frames.len()) else {
+            return;
+        };
+
+        self.animations_playing = false;
+        let frame = if forward { page.frame + 1 } else { page.frame + frames - 1 };
+        if page.show_frame(frame) {
+            self.prepare_page(current_page);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, Frame, ImageFormat, RgbImage, Rgba, RgbaImage};
+
+    use super::*;
+
+    /// A jpg with enough detail for decoding and resizing to do real work
+    fn encoded_page((width, height): (u32, u32)) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, |x, y| image::Rgb([(x % 256) as u8, (y % 256) as u8, ((x * y) % 256) as u8]));
+
+        let mut bytes = std::io::Cursor::new(vec![]);
+        DynamicImage::ImageRgb8(image).write_to(&mut bytes, ImageFormat::Jpeg).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn pages_used_the_longest_time_ago_are_unloaded_first() {
+        let mut decoded_pages = DecodedPages::new(50);
+
+        for index in [0, 1, 2, 3, 4, 5, 6, 7] {
+            decoded_pages.touch(index);
+        }
+        decoded_pages.touch(1);
+
+        // Every page takes 10 bytes, page 2 is kept as if it was next to the current one
+        let unload = decoded_pages.over_budget(|_| 10, |index| index == 2);
+
+        assert_eq!(unload, [0, 3, 4]);
+        assert_eq!(decoded_pages.recent, [2, 5, 6, 7, 1]);
+
+        // The most recent pages stay loaded even if they don't fit
+        let mut tiny_budget = DecodedPages::new(0);
+        for index in 0..KEEP_RECENT_PAGES {
+            tiny_budget.touch(index);
+        }
+        assert!(tiny_budget.over_budget(|_| 10, |_| false).is_empty());
+    }
+
+    #[test]
+    fn first_page_is_alone_and_the_rest_are_paired() {
+        assert_eq!(0, spread_start(0, false));
+        assert_eq!(1, spread_start(1, false));
+        assert_eq!(1, spread_start(2, false));
+        assert_eq!(3, spread_start(3, false));
+        assert_eq!(3, spread_start(4, false));
+    }
+
+    #[test]
+    fn offset_pairs_pages_from_the_first_one() {
+        assert_eq!(0, spread_start(0, true));
+        assert_eq!(0, spread_start(1, true));
+        assert_eq!(2, spread_start(2, true));
+        assert_eq!(2, spread_start(3, true));
+    }
+
+    #[test]
+    fn first_time_hint_follows_the_reading_direction_and_keeps_the_page_visible() {
+        let area = Rect::new(0, 0, 100, 30);
+        let mut buf = Buffer::empty(area);
+        buf.set_string(30, 5, "PAGE", Style::default());
+
+        render_first_time_hint(ReadingDirection::RightToLeft, area, &mut buf);
+
+        let lines: Vec<String> = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect();
+
+        assert!(lines[5].contains("PAGE"));
+        let left_page = lines.iter().find(|line| line.contains("◀")).unwrap();
+        let below = &lines[lines.iter().position(|line| line.contains("◀")).unwrap() + 1];
+        assert!(left_page.contains("▶"));
+        assert!(below.find("Next page").unwrap() < below.find("Previous page").unwrap());
+        assert!(lines.iter().any(|line| line.contains("Press any key to start reading")));
+    }
+
+    #[test]
+    fn only_pages_much_taller_than_wide_are_tall() {
+        let mut page = Page::new("page.jpg".to_string(), PageType::LowQuality);
+        assert!(!page.is_tall());
+
+        page.dimensions = Some((1000, 1500));
+        assert!(!page.is_tall());
+
+        page.dimensions = Some((800, 20000));
+        assert!(page.is_tall());
+    }
+
+    #[test]
+    fn margins_are_cropped_around_the_content() {
+        let mut image = image::RgbImage::from_pixel(10, 10, image::Rgb([255, 255, 255]));
+        for x in 3..7 {
+            for y in 4..6 {
+                image.put_pixel(x, y, image::Rgb([0, 0, 0]));
+            }
+        }
+
+        let cropped = crop_margins(&DynamicImage::ImageRgb8(image));
+        assert_eq!((4, 2), cropped.dimensions());
+
+        let blank = DynamicImage::new_rgb8(10, 10);
+        assert_eq!((10, 10), crop_margins(&blank).dimensions());
+    }
+
+    #[test]
+    fn overview_selection_stays_within_the_pages() {
+        let mut overview = Overview::new(10, 0);
+        overview.columns = 4;
+
+        overview.move_selection(GridMove::Up);
+        overview.move_selection(GridMove::Left);
+        assert_eq!(0, overview.selected);
+
+        overview.move_selection(GridMove::Down);
+        overview.move_selection(GridMove::Right);
+        assert_eq!(5, overview.selected);
+
+        overview.move_selection(GridMove::Down);
+        assert_eq!(9, overview.selected);
+
+        overview.scroll_to_selected(1);
+        assert_eq!(2, overview.scroll);
+    }
+
+    #[test]
+    fn rotating_a_page_swaps_its_dimensions() {
+        let picker = Picker::new((8, 16));
+        let decoded = Arc::new(DynamicImage::new_rgb8(4, 2));
+        let mut page = Page::new("page.png".to_string(), PageType::HighQuality);
+
+        assert!(!page.rotate(true));
+        assert_eq!(0, page.rotation);
+
+        page.set_image(PreparedPage::new(0, page.url.clone(), 0, ImageOptions::default(), Arc::clone(&decoded), picker));
+        assert!(page.rotate(true));
+        assert_eq!(90, page.rotation);
+
+        page.set_image(PreparedPage::new(
+            0,
+            page.url.clone(),
+            page.rotation,
+            ImageOptions::default(),
+            Arc::clone(&decoded),
+            picker,
+        ));
+        assert_eq!(Some((2, 4)), page.dimensions);
+
+        page.rotate(false);
+        page.rotate(false);
+        assert_eq!(270, page.rotation);
+
+        page.set_image(PreparedPage::new(0, page.url.clone(), page.rotation, ImageOptions::default(), decoded, picker));
+        assert_eq!(Some((2, 4)), page.dimensions);
+    }
+
+    #[test]
+    fn only_animated_pages_have_frames() {
+        let frames = [Rgba([0, 0, 0, 255]), Rgba([255, 255, 255, 255])]
+            .map(|color| Frame::from_parts(RgbaImage::from_pixel(4, 4, color), 0, 0, Delay::from_numer_denom_ms(50, 1)));
+
+        let mut gif = vec![];
+        GifEncoder::new(&mut gif).encode_frames(frames.clone()).unwrap();
+
+        let animation = decode_animation(&gif).expect("the gif is animated");
+        assert_eq!(2, animation.frames.len());
+        assert_eq!(Duration::from_millis(50), animation.delay(0));
+
+        let mut single_frame = vec![];
+        GifEncoder::new(&mut single_frame).encode_frames(frames.into_iter().take(1)).unwrap();
+        assert!(decode_animation(&single_frame).is_none());
+
+        assert!(decode_animation(&encoded_page((4, 4))).is_none());
+    }
+
+    #[tokio::test]
+    async fn cancelled_tasks_stop_without_finishing() {
+        let mut tasks = JoinSet::new();
+        let cancellation = CancellationToken::new();
+
+        spawn_cancellable(&mut tasks, &cancellation, std::future::pending());
+        cancellation.cancel();
+
+        let stopped = tokio::time::timeout(Duration::from_secs(1), tasks.join_next()).await;
+        assert!(stopped.is_ok_and(|task| task.is_some_and(|result| result.is_ok())));
+    }
+
+    #[test]
+    fn encoded_image_is_drawn_once_it_comes_back() {
+        let mut picker = Picker::new((8, 16));
+        let mut image = EncodedImage::new(picker.new_resize_protocol(DynamicImage::new_rgb8(16, 32)));
+        let area = Rect::new(0, 0, 4, 4);
+        let mut buf = Buffer::empty(area);
+
+        let EncodeJob {
+            id,
+            mut protocol,
+            resize,
+            area: encode_area,
+        } = image
+            .render(Resize::Fit(None), area, &mut buf)
+            .expect("the image is not encoded for the area yet");
+
+        assert!(image.render(Resize::Fit(None), area, &mut buf).is_none());
+
+        protocol.resize_encode(&resize, None, encode_area);
+        image.restore(id + 1, protocol.clone());
+        assert!(image.render(Resize::Fit(None), area, &mut buf).is_none());
+        assert_eq!(Buffer::empty(area), buf);
+
+        image.restore(id, protocol);
+        assert!(image.render(Resize::Fit(None), area, &mut buf).is_none());
+        assert_ne!(Buffer::empty(area), buf);
+    }
 }
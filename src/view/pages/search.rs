@@ -1,15 +1,18 @@
+use std::collections::HashMap;
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crossterm::event::{self, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use image::DynamicImage;
+use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Margin, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span, ToSpan};
-use ratatui::widgets::{Block, Paragraph, StatefulWidget, StatefulWidgetRef, Widget, Wrap};
+use ratatui::widgets::{Block, Clear, List, Paragraph, StatefulWidget, StatefulWidgetRef, Widget, Wrap};
 use ratatui::Frame;
 use ratatui_image::picker::Picker;
-use ratatui_image::Resize;
+use ratatui_image::protocol::StatefulProtocol;
+use ratatui_image::{Resize, StatefulImage};
 use throbber_widgets_tui::{Throbber, ThrobberState};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinSet;
@@ -17,15 +20,21 @@ use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
 use tui_widget_list::ListState;
 
-use crate::backend::database::{save_plan_to_read, MangaPlanToReadSave};
+use crate::backend::authors::AuthorsResponse;
+use crate::backend::database::{
+    get_mangas_engagement, get_popular_searches, save_plan_to_read, save_search, MangaEngagement, MangaPlanToReadSave,
+};
 use crate::backend::error_log::{write_to_error_log, ErrorType};
-use crate::backend::fetch::MangadexClient;
-use crate::backend::tui::Events;
+use crate::backend::fetch::{MangadexClient, ITEMS_PER_PAGE_SEARCH, MAX_SEARCH_RESULTS, SLOW_REQUEST_THRESHOLD};
+use crate::backend::import::parse_manga_id;
+use crate::backend::tui::{Events, Route};
 use crate::backend::SearchMangaResponse;
 use crate::common::{Artist, Author, ImageState};
-use crate::global::{ERROR_STYLE, INSTRUCTIONS_STYLE};
-use crate::utils::{render_search_bar, search_manga_cover};
-use crate::view::widgets::filter_widget::state::FilterState;
+use crate::config::{MangaTuiConfig, CONFIG};
+use crate::global::{CURRENT_LIST_ITEM_STYLE, ERROR_STYLE, INSTRUCTIONS_STYLE};
+use crate::utils::{render_search_bar, render_slow_request_notice, search_manga_cover};
+use crate::view::widgets::filter_widget::state::{FilterState, TagListItem};
+use crate::view::pages::reader::GridMove;
 use crate::view::widgets::filter_widget::FilterWidget;
 use crate::view::widgets::search::*;
 use crate::view::widgets::{Component, ImageHandler, StatefulWidgetFrame};
@@ -36,6 +45,7 @@ use crate::view::widgets::{Component, ImageHandler, StatefulWidgetFrame};
 enum PageState {
     SearchingMangas,
     DisplayingMangasFound,
+    DisplayingAuthorsFound,
     NotFound,
     ErrorOcurred,
     #[default]
@@ -47,6 +57,9 @@ pub enum SearchPageEvents {
     SearchCovers,
     LoadCover(Option<DynamicImage>, String),
     LoadMangasFound(Option<SearchMangaResponse>),
+    LoadAuthorsFound(Option<AuthorsResponse>),
+    LoadPreviousSearches(Vec<String>),
+    LoadEngagement(HashMap<String, MangaEngagement>),
 }
 
 impl ImageHandler for SearchPageEvents {
@@ -67,11 +80,35 @@ pub enum SearchPageActions {
     Search,
     ScrollUp,
     ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+    ToggleLayout,
     ToggleFilters,
     NextPage,
     PreviousPage,
+    FirstPage,
+    LastPage,
     GoToMangaPage,
     PlanToRead,
+    ToggleSearchMode,
+    SearchWorksOfAuthor,
+    SearchWorksOfArtist,
+}
+
+/// What the search bar looks for, authors are searched to list all of their works
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    #[default]
+    Mangas,
+    Authors,
+}
+
+/// How the mangas found are shown, the grid shows their covers and is meant for terminals which can display images
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ResultsLayout {
+    #[default]
+    List,
+    Grid,
 }
 
 #[derive(Default, PartialEq, Eq, PartialOrd, Ord)]
@@ -90,26 +127,152 @@ pub struct SearchPage {
     local_event_tx: UnboundedSender<SearchPageEvents>,
     pub local_event_rx: UnboundedReceiver<SearchPageEvents>,
     pub input_mode: InputMode,
+    search_mode: SearchMode,
     search_bar: Input,
     state: PageState,
+    /// When the current search started, used to let the user know mangadex is taking too long
+    searching_since: Option<Instant>,
     loader_state: ThrobberState,
     mangas_found_list: MangasFoundList,
+    authors_found: AuthorsFoundList,
     filter_state: FilterState,
     manga_added_to_plan_to_read: Option<String>,
     picker: Option<Picker>,
     manga_cover_state: ImageState,
+    results_layout: ResultsLayout,
+    /// The covers shown in the grid layout, resized to fit its cells, `None` for the mangas without a cover
+    grid_covers: HashMap<String, Option<Box<dyn StatefulProtocol>>>,
+    /// Search while typing, set with `live_search`
+    live_search: bool,
+    /// When the search bar was last typed in, `Some` while a live search waits for typing to stop
+    pending_live_search: Option<Instant>,
+    /// The searches made the most recently, loaded each time the search bar is focused
+    previous_searches: Vec<String>,
+    suggestions: Vec<Suggestion>,
+    selected_suggestion: usize,
     tasks: JoinSet<()>,
 }
 
+/// How long typing has to stop for before a live search starts, so that mangadex isn't asked on every key press
+static LIVE_SEARCH_DELAY: Duration = Duration::from_millis(400);
+
+/// What can be inserted in the search bar with <Tab> while typing
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Suggestion {
+    /// Typed as `tag:` and part of its name, it's added to the tag filters instead of being searched
+    Tag { id: String, name: String },
+    PreviousSearch(String),
+}
+
+static TAG_PREFIX: &str = "tag:";
+
+/// How many suggestions are shown below the search bar
+static MAX_SUGGESTIONS: usize = 6;
+
+/// How many of the previous searches are loaded to be suggested
+static PREVIOUS_SEARCHES_LIMIT: u32 = 50;
+
+/// What was typed after `tag:` when it's the start of the last word of the search bar
+fn typed_tag(search_term: &str) -> Option<&str> {
+    let last_word = search_term.rsplit(' ').next()?;
+    last_word
+        .get(..TAG_PREFIX.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(TAG_PREFIX))
+        .map(|_| &last_word[TAG_PREFIX.len()..])
+}
+
+/// The tags whose name starts with what was typed go first, then the ones which have it anywhere
+fn tag_suggestions(typed: &str, tags: &[TagListItem]) -> Vec<Suggestion> {
+    let typed = typed.to_lowercase();
+    let mut matching: Vec<&TagListItem> = tags.iter().filter(|tag| tag.name.to_lowercase().contains(&typed)).collect();
+    matching.sort_by_key(|tag| !tag.name.to_lowercase().starts_with(&typed));
+
+    matching
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|tag| Suggestion::Tag {
+            id: tag.id.clone(),
+            name: tag.name.clone(),
+        })
+        .collect()
+}
+
+/// The previous searches which start with what was typed, not including the search already typed
+fn previous_search_suggestions(search_term: &str, previous_searches: &[String]) -> Vec<Suggestion> {
+    let typed = search_term.trim_start().to_lowercase();
+
+    previous_searches
+        .iter()
+        .filter(|search| search.starts_with(&typed) && **search != typed.trim_end())
+        .take(MAX_SUGGESTIONS)
+        .map(|search| Suggestion::PreviousSearch(search.clone()))
+        .collect()
+}
+
+/// Each cover of the grid layout takes this many columns and rows, including its border and title
+static GRID_CELL: (u16, u16) = (22, 16);
+
+/// Where the grid layout is scrolled to, known once it's rendered
+#[derive(Debug, Default)]
+struct CoversGrid {
+    /// How many covers fit in a row
+    columns: usize,
+    /// The first row shown
+    scroll: usize,
+}
+
+impl CoversGrid {
+    /// The manga selected after moving in the grid, the first one if none was selected
+    fn move_selection(&self, selected: Option<usize>, total: usize, direction: GridMove) -> Option<usize> {
+        let last = total.checked_sub(1)?;
+        let Some(selected) = selected else {
+            return Some(0);
+        };
+        let columns = self.columns.max(1);
+
+        Some(match direction {
+            GridMove::Left => selected.saturating_sub(1),
+            GridMove::Right => (selected + 1).min(last),
+            GridMove::Up => selected.checked_sub(columns).unwrap_or(selected),
+            GridMove::Down => (selected + columns).min(last),
+        })
+    }
+
+    /// Scrolls just enough for the selected manga to be in one of the `rows` shown
+    fn scroll_to_selected(&mut self, selected: usize, rows: usize) {
+        let selected_row = selected / self.columns.max(1);
+        if selected_row < self.scroll {
+            self.scroll = selected_row;
+        } else if selected_row >= self.scroll + rows {
+            self.scroll = selected_row + 1 - rows;
+        }
+    }
+}
+
 /// This contains the data the application gets when doing a search
 #[derive(Default)]
 struct MangasFoundList {
     widget: ListMangasFoundWidget,
     state: tui_widget_list::ListState,
+    grid: CoversGrid,
     total_result: u32,
     page: u32,
 }
 
+impl MangasFoundList {
+    /// Only the pages mangadex can return, the results after `MAX_SEARCH_RESULTS` can't be reached
+    fn total_pages(&self) -> u32 {
+        self.total_result.min(MAX_SEARCH_RESULTS).div_ceil(ITEMS_PER_PAGE_SEARCH).max(1)
+    }
+}
+
+/// The people found in author search mode, they can be an author, an artist or both
+#[derive(Default)]
+struct AuthorsFoundList {
+    authors: Vec<Author>,
+    state: ratatui::widgets::ListState,
+}
+
 impl Component for SearchPage {
     type Actions = SearchPageActions;
 
@@ -123,6 +286,8 @@ impl Component for SearchPage {
         self.render_input_area(input_area, frame);
 
         self.render_manga_found_area(manga_area, frame);
+
+        self.render_suggestions(manga_area, frame.buffer_mut());
     }
 
     fn update(&mut self, action: SearchPageActions) {
@@ -130,21 +295,47 @@ impl Component for SearchPage {
             SearchPageActions::ToggleFilters => self.open_advanced_filters(),
             SearchPageActions::StartTyping => self.focus_search_bar(),
             SearchPageActions::StopTyping => self.input_mode = InputMode::Idle,
-            SearchPageActions::Search => {
-                self.mangas_found_list.page = 1;
-                self.search_mangas();
+            SearchPageActions::Search => match self.search_mode {
+                SearchMode::Mangas => match parse_manga_id(self.search_bar.value()) {
+                    Some(manga_id) => self.open_manga(manga_id),
+                    None => {
+                        self.save_search();
+                        self.mangas_found_list.page = 1;
+                        self.search_mangas();
+                    },
+                },
+                SearchMode::Authors => self.search_authors(),
             },
             SearchPageActions::ScrollUp => self.scroll_up(),
             SearchPageActions::ScrollDown => self.scroll_down(),
+            SearchPageActions::ScrollLeft => self.move_grid_selection(GridMove::Left),
+            SearchPageActions::ScrollRight => self.move_grid_selection(GridMove::Right),
+            SearchPageActions::ToggleLayout => self.toggle_layout(),
             SearchPageActions::NextPage => self.search_next_page(),
             SearchPageActions::PreviousPage => self.search_previous_page(),
+            SearchPageActions::FirstPage => self.search_page(1),
+            SearchPageActions::LastPage => self.search_page(self.mangas_found_list.total_pages()),
             SearchPageActions::GoToMangaPage => {
                 let manga_selected = self.get_current_manga_selected();
                 if let Some(manga) = manga_selected {
-                    self.global_event_tx.send(Events::GoToMangaPage(manga.clone())).ok();
+                    self.global_event_tx.send(Events::Navigate(Route::Manga(manga.clone()))).ok();
                 }
             },
             SearchPageActions::PlanToRead => self.plan_to_read(),
+            SearchPageActions::ToggleSearchMode => self.toggle_search_mode(),
+            SearchPageActions::SearchWorksOfAuthor => {
+                if let Some(author) = self.get_current_author_selected() {
+                    self.search_mangas_of_author(author);
+                }
+            },
+            SearchPageActions::SearchWorksOfArtist => {
+                if let Some(author) = self.get_current_author_selected() {
+                    self.search_mangas_of_artist(Artist {
+                        id: author.id,
+                        name: author.name,
+                    });
+                }
+            },
         }
     }
 
@@ -163,13 +354,22 @@ impl Component for SearchPage {
         }
     }
 
+    fn wants_input(&self) -> bool {
+        self.input_mode == InputMode::Typing || self.filter_state.is_typing
+    }
+
     fn clean_up(&mut self) {
         self.abort_tasks();
         self.manga_cover_state = ImageState::default();
         self.state = PageState::default();
+        self.searching_since = None;
         self.manga_added_to_plan_to_read = None;
         self.input_mode = InputMode::Idle;
         self.mangas_found_list.state = ListState::default();
+        self.mangas_found_list.grid = CoversGrid::default();
+        self.grid_covers = HashMap::new();
+        self.authors_found = AuthorsFoundList::default();
+        self.suggestions = vec![];
         if !self.mangas_found_list.widget.mangas.is_empty() {
             self.mangas_found_list.widget.mangas = vec![];
         }
@@ -190,28 +390,59 @@ impl SearchPage {
             input_mode: InputMode::default(),
             search_bar: Input::default(),
             state: PageState::default(),
+            searching_since: None,
             mangas_found_list: MangasFoundList::default(),
+            authors_found: AuthorsFoundList::default(),
+            search_mode: SearchMode::default(),
             tasks: JoinSet::new(),
             filter_state: FilterState::new(),
             loader_state: ThrobberState::default(),
             manga_added_to_plan_to_read: None,
             picker,
             manga_cover_state: ImageState::default(),
+            results_layout: ResultsLayout::default(),
+            grid_covers: HashMap::new(),
+            live_search: CONFIG.get().is_some_and(|config| config.live_search),
+            pending_live_search: None,
+            previous_searches: vec![],
+            suggestions: vec![],
+            selected_suggestion: 0,
         }
     }
 
     fn render_input_area(&self, area: Rect, frame: &mut Frame<'_>) {
         let [input_area, information_area] = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
 
-        let input_help = match self.input_mode {
-            InputMode::Idle => Line::from(vec![
+        let input_help = match (&self.input_mode, self.search_mode) {
+            (InputMode::Idle, SearchMode::Mangas) => Line::from(vec![
                 "Press ".into(),
                 "<s>".to_span().style(*INSTRUCTIONS_STYLE),
                 " to search mangas ".into(),
                 "<f>".to_span().style(*INSTRUCTIONS_STYLE),
-                " to open advanced filters".into(),
+                " to open advanced filters ".into(),
+                "<a>".to_span().style(*INSTRUCTIONS_STYLE),
+                " to search authors".into(),
+            ]),
+            (InputMode::Idle, SearchMode::Authors) => Line::from(vec![
+                "Press ".into(),
+                "<s>".to_span().style(*INSTRUCTIONS_STYLE),
+                " to search authors and artists ".into(),
+                "<a>".to_span().style(*INSTRUCTIONS_STYLE),
+                " to search mangas".into(),
+            ]),
+            (InputMode::Typing, _) if self.live_search => Line::from(vec![
+                "Results update as you type, ".into(),
+                "<Esc>".to_span().style(*INSTRUCTIONS_STYLE),
+                " to stop typing".into(),
+            ]),
+            (InputMode::Typing, SearchMode::Mangas) => Line::from(vec![
+                "Press ".into(),
+                "<Enter>".to_span().style(*INSTRUCTIONS_STYLE),
+                " to search or open a mangadex link ".into(),
+                "<Esc>".to_span().style(*INSTRUCTIONS_STYLE),
+                " to stop typing".into(),
             ]),
-            InputMode::Typing => Line::from(vec![
+            (InputMode::Typing, _) => Line::from(vec![
                 "Press ".into(),
                 "<Enter>".to_span().style(*INSTRUCTIONS_STYLE),
                 " to search ".into(),
@@ -252,9 +483,35 @@ impl SearchPage {
                     .use_type(throbber_widgets_tui::WhichUse::Spin);
 
                 StatefulWidget::render(loader, area, buf, &mut self.loader_state);
+
+                if self.searching_since.is_some_and(|since| since.elapsed() >= SLOW_REQUEST_THRESHOLD) {
+                    render_slow_request_notice(area, buf);
+                }
             },
             PageState::NotFound => {
-                Block::bordered().title("No mangas were found").render(area, buf);
+                let title = match self.search_mode {
+                    SearchMode::Mangas => "No mangas were found",
+                    SearchMode::Authors => "No authors or artists were found",
+                };
+                Block::bordered().title(title).render(area, buf);
+            },
+            PageState::DisplayingAuthorsFound => {
+                let instructions = Line::from(vec![
+                    "Go down ".into(),
+                    Span::raw("<j>").style(*INSTRUCTIONS_STYLE),
+                    " Go up ".into(),
+                    Span::raw("<k>").style(*INSTRUCTIONS_STYLE),
+                    " Works as author ".into(),
+                    Span::raw("<c>").style(*INSTRUCTIONS_STYLE),
+                    " Works as artist ".into(),
+                    Span::raw("<v>").style(*INSTRUCTIONS_STYLE),
+                ]);
+
+                let authors = List::new(self.authors_found.authors.iter().map(|author| author.name.clone()))
+                    .block(Block::bordered().title_top(instructions))
+                    .highlight_style(Style::default().on_blue());
+
+                StatefulWidget::render(authors, area, buf, &mut self.authors_found.state);
             },
             PageState::ErrorOcurred => {
                 Block::bordered()
@@ -262,24 +519,36 @@ impl SearchPage {
                     .render(area, buf);
             },
             PageState::DisplayingMangasFound => {
-                let total_pages = self.mangas_found_list.total_result as f64 / 10_f64;
-
-                let list_instructions = Line::from(vec![
-                    "Go down ".into(),
-                    Span::raw("<j>").style(*INSTRUCTIONS_STYLE),
-                    " Go up ".into(),
-                    Span::raw("<k>").style(*INSTRUCTIONS_STYLE),
-                    " Plan to read ".into(),
-                    Span::raw("<p>").style(*INSTRUCTIONS_STYLE),
-                    " Read ".into(),
-                    Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
-                ]);
+                let list_instructions = match self.results_layout {
+                    ResultsLayout::List => Line::from(vec![
+                        "Go down ".into(),
+                        Span::raw("<j>").style(*INSTRUCTIONS_STYLE),
+                        " Go up ".into(),
+                        Span::raw("<k>").style(*INSTRUCTIONS_STYLE),
+                        " Plan to read ".into(),
+                        Span::raw("<p>").style(*INSTRUCTIONS_STYLE),
+                        " Read ".into(),
+                        Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+                        " Covers ".into(),
+                        Span::raw("<v>").style(*INSTRUCTIONS_STYLE),
+                    ]),
+                    ResultsLayout::Grid => Line::from(vec![
+                        "Move ".into(),
+                        Span::raw("<h/j/k/l>").style(*INSTRUCTIONS_STYLE),
+                        " Plan to read ".into(),
+                        Span::raw("<p>").style(*INSTRUCTIONS_STYLE),
+                        " Read ".into(),
+                        Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+                        " List ".into(),
+                        Span::raw("<v>").style(*INSTRUCTIONS_STYLE),
+                    ]),
+                };
 
                 let pagination_instructions = Line::from(vec![
                     format!(
-                        "Page : {} of {}, total : {} ",
+                        "Page {} of {} ({} results) ",
                         self.mangas_found_list.page,
-                        total_pages.ceil(),
+                        self.mangas_found_list.total_pages(),
                         self.mangas_found_list.total_result
                     )
                     .into(),
@@ -287,44 +556,42 @@ impl SearchPage {
                     Span::raw("<w>").style(*INSTRUCTIONS_STYLE),
                     " Previous ".into(),
                     Span::raw("<b>").style(*INSTRUCTIONS_STYLE),
+                    " First ".into(),
+                    Span::raw("<g>").style(*INSTRUCTIONS_STYLE),
+                    " Last ".into(),
+                    Span::raw("<G>").style(*INSTRUCTIONS_STYLE),
                 ]);
 
-                Block::bordered()
-                    .title_top(list_instructions)
-                    .title_bottom(pagination_instructions)
-                    .render(manga_list_area, buf);
-
-                let inner_list_area = manga_list_area.inner(Margin {
-                    horizontal: 1,
-                    vertical: 1,
-                });
+                let (results_area, results_preview_area) = match self.results_layout {
+                    ResultsLayout::List => (manga_list_area, preview_area),
+                    ResultsLayout::Grid => {
+                        let [grid_area, grid_preview_area] =
+                            Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(area);
+                        (grid_area, grid_preview_area)
+                    },
+                };
+                let results_block = Block::bordered().title_top(list_instructions).title_bottom(pagination_instructions);
+                let inner_results_area = results_block.inner(results_area);
+                results_block.render(results_area, buf);
 
                 if !self.filter_state.is_open {
-                    StatefulWidgetRef::render_ref(
-                        &self.mangas_found_list.widget,
-                        inner_list_area,
-                        buf,
-                        &mut self.mangas_found_list.state,
-                    );
-
-                    let loader_state = self.loader_state.clone();
-                    if let Some(index) = self.mangas_found_list.state.selected {
-                        let manga_selected = &self.mangas_found_list.widget.mangas[index];
-                        StatefulWidget::render(
-                            MangaPreview::new(
-                                &manga_selected.manga.id,
-                                &manga_selected.manga.title,
-                                &manga_selected.manga.description,
-                                &manga_selected.manga.tags,
-                                &manga_selected.manga.content_rating,
-                                &manga_selected.manga.status,
-                                self.picker.is_some(),
-                                loader_state,
-                            ),
-                            preview_area,
-                            buf,
-                            &mut self.manga_cover_state,
-                        )
+                    match self.results_layout {
+                        ResultsLayout::List => {
+                            StatefulWidgetRef::render_ref(
+                                &self.mangas_found_list.widget,
+                                inner_results_area,
+                                buf,
+                                &mut self.mangas_found_list.state,
+                            );
+                            self.render_preview(results_preview_area, true, buf);
+                        },
+                        ResultsLayout::Grid => {
+                            // The covers of the preview are made for its size in the list layout, which is known
+                            // without rendering it
+                            self.manga_cover_state.set_area(MangaPreview::cover_area(preview_area));
+                            self.render_covers_grid(inner_results_area, buf);
+                            self.render_preview(results_preview_area, false, buf);
+                        },
                     }
                 }
             },
@@ -334,12 +601,88 @@ impl SearchPage {
         }
     }
 
+    /// The details of the manga selected, the grid already shows the covers so the preview beside it leaves the cover out
+    fn render_preview(&mut self, area: Rect, with_cover: bool, buf: &mut Buffer) {
+        let loader_state = self.loader_state.clone();
+        if let Some(index) = self.mangas_found_list.state.selected {
+            let manga_selected = &self.mangas_found_list.widget.mangas[index];
+            StatefulWidget::render(
+                MangaPreview::new(
+                    &manga_selected.manga.id,
+                    &manga_selected.manga.title,
+                    &manga_selected.manga.description,
+                    &manga_selected.manga.tags,
+                    &manga_selected.manga.content_rating,
+                    &manga_selected.manga.status,
+                    with_cover && self.picker.is_some(),
+                    loader_state,
+                ),
+                area,
+                buf,
+                &mut self.manga_cover_state,
+            )
+        }
+    }
+
+    /// The covers of the mangas found with their title below, the rows are scrolled so the selected one is shown
+    fn render_covers_grid(&mut self, area: Rect, buf: &mut Buffer) {
+        let (cell_width, cell_height) = GRID_CELL;
+        let columns = (area.width / cell_width).max(1) as usize;
+        let rows = (area.height / cell_height).max(1) as usize;
+
+        let selected = self.mangas_found_list.state.selected;
+        let grid = &mut self.mangas_found_list.grid;
+        grid.columns = columns;
+        grid.scroll_to_selected(selected.unwrap_or(0), rows);
+
+        let mangas = &self.mangas_found_list.widget.mangas;
+        let first = grid.scroll * columns;
+        let last = (first + rows * columns).min(mangas.len());
+
+        for (position, index) in (first..last).enumerate() {
+            let cell = Rect::new(
+                area.x + (position % columns) as u16 * cell_width,
+                area.y + (position / columns) as u16 * cell_height,
+                cell_width,
+                cell_height,
+            )
+            .intersection(area);
+
+            let border_style = if selected == Some(index) { Style::default().fg(Color::Yellow) } else { Style::default() };
+            let mut cell_block = Block::bordered().border_style(border_style);
+            if let Some(badge) = mangas[index].engagement.as_ref().and_then(engagement_badge) {
+                cell_block = cell_block.title_bottom(badge.green());
+            }
+            let [cover_area, title_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(cell_block.inner(cell));
+            cell_block.render(cell, buf);
+
+            let manga = &mangas[index].manga;
+            match self.grid_covers.get_mut(&manga.id) {
+                Some(Some(cover)) => {
+                    StatefulWidget::render(StatefulImage::new(None).resize(Resize::Fit(None)), cover_area, buf, cover);
+                },
+                Some(None) => Paragraph::new("No cover").render(cover_area, buf),
+                None if self.picker.is_some() => Paragraph::new("Loading cover").render(cover_area, buf),
+                None => {},
+            }
+
+            Paragraph::new(manga.title.as_str()).wrap(Wrap { trim: true }).bold().render(title_area, buf);
+        }
+    }
+
     fn render_filters(&mut self, area: Rect, frame: &mut Frame<'_>) {
         let filter_instructions = Line::from(vec![
             "Close ".into(),
             Span::raw("<f>").style(*INSTRUCTIONS_STYLE),
             " Reset filters ".into(),
             Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+            if self.filter_state.filters.available_chapters.0 {
+                " Only mangas with chapters to read: on ".into()
+            } else {
+                " Only mangas with chapters to read: off ".into()
+            },
+            Span::raw("<a>").style(*INSTRUCTIONS_STYLE),
         ]);
 
         FilterWidget::new()
@@ -349,14 +692,157 @@ impl SearchPage {
 
     fn focus_search_bar(&mut self) {
         self.input_mode = InputMode::Typing;
+
+        let tx = self.local_event_tx.clone();
+        self.tasks.spawn(async move {
+            match get_popular_searches(PREVIOUS_SEARCHES_LIMIT) {
+                Ok(searches) => tx.send(SearchPageEvents::LoadPreviousSearches(searches)).ok(),
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    None
+                },
+            };
+        });
+    }
+
+    fn save_search(&mut self) {
+        let search_term = self.search_bar.value().trim().to_string();
+        if search_term.is_empty() {
+            return;
+        }
+
+        // Not spawned on `self.tasks` since the search that follows aborts them
+        tokio::spawn(async move {
+            if let Err(e) = save_search(&search_term) {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+            }
+        });
+    }
+
+    fn update_suggestions(&mut self) {
+        self.selected_suggestion = 0;
+
+        if self.search_mode != SearchMode::Mangas {
+            self.suggestions = vec![];
+            return;
+        }
+
+        let search_term = self.search_bar.value();
+        self.suggestions = match typed_tag(search_term) {
+            Some(typed) => tag_suggestions(typed, self.filter_state.tags_state.tags.as_deref().unwrap_or_default()),
+            None => previous_search_suggestions(search_term, &self.previous_searches),
+        };
+    }
+
+    fn select_suggestion(&mut self, next: bool) {
+        if self.suggestions.is_empty() {
+            return;
+        }
+
+        self.selected_suggestion = if next {
+            (self.selected_suggestion + 1) % self.suggestions.len()
+        } else {
+            self.selected_suggestion.checked_sub(1).unwrap_or(self.suggestions.len() - 1)
+        };
+    }
+
+    /// A tag replaces the `tag:` word typed and is included in the filters, a previous search replaces
+    /// everything typed
+    fn insert_suggestion(&mut self) {
+        let Some(suggestion) = self.suggestions.get(self.selected_suggestion).cloned() else {
+            return;
+        };
+
+        let search_term = match suggestion {
+            Suggestion::Tag { id, .. } => {
+                self.filter_state.include_tag(&id);
+                self.search_bar.value().rsplit_once(' ').map(|(before, _)| before.trim_end()).unwrap_or_default().to_string()
+            },
+            Suggestion::PreviousSearch(search) => search,
+        };
+
+        self.search_bar = Input::new(search_term);
+        self.update_suggestions();
+    }
+
+    fn render_suggestions(&self, area: Rect, buf: &mut Buffer) {
+        if self.input_mode != InputMode::Typing || self.suggestions.is_empty() {
+            return;
+        }
+
+        let lines: Vec<Line<'_>> = self
+            .suggestions
+            .iter()
+            .enumerate()
+            .map(|(index, suggestion)| {
+                let line = match suggestion {
+                    Suggestion::Tag { name, .. } => Line::from(vec![TAG_PREFIX.into(), name.as_str().bold()]),
+                    Suggestion::PreviousSearch(search) => Line::from(search.as_str()),
+                };
+                if index == self.selected_suggestion { line.style(*CURRENT_LIST_ITEM_STYLE) } else { line }
+            })
+            .collect();
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let suggestions_area = Rect::new(area.x, area.y, area.width / 2, height);
+
+        Clear.render(suggestions_area, buf);
+        Paragraph::new(lines)
+            .block(Block::bordered().title(Line::from(vec![
+                " Insert ".into(),
+                "<Tab>".to_span().style(*INSTRUCTIONS_STYLE),
+                " Move ".into(),
+                "<Up> <Down>".to_span().style(*INSTRUCTIONS_STYLE),
+                " ".into(),
+            ])))
+            .render(suggestions_area, buf);
     }
 
     fn scroll_down(&mut self) {
-        self.mangas_found_list.state.next();
+        match self.results_layout {
+            ResultsLayout::List => self.mangas_found_list.state.next(),
+            ResultsLayout::Grid => self.move_grid_selection(GridMove::Down),
+        }
     }
 
     fn scroll_up(&mut self) {
-        self.mangas_found_list.state.previous();
+        match self.results_layout {
+            ResultsLayout::List => self.mangas_found_list.state.previous(),
+            ResultsLayout::Grid => self.move_grid_selection(GridMove::Up),
+        }
+    }
+
+    fn move_grid_selection(&mut self, direction: GridMove) {
+        if self.results_layout != ResultsLayout::Grid {
+            return;
+        }
+
+        let list = &mut self.mangas_found_list;
+        let selected = list.grid.move_selection(list.state.selected, list.widget.mangas.len(), direction);
+        list.state.select(selected);
+    }
+
+    fn toggle_layout(&mut self) {
+        self.results_layout = match self.results_layout {
+            ResultsLayout::List => ResultsLayout::Grid,
+            ResultsLayout::Grid => ResultsLayout::List,
+        };
+    }
+
+    fn toggle_search_mode(&mut self) {
+        self.search_mode = match self.search_mode {
+            SearchMode::Mangas => SearchMode::Authors,
+            SearchMode::Authors => SearchMode::Mangas,
+        };
+        self.clean_up();
+    }
+
+    fn get_current_author_selected(&self) -> Option<Author> {
+        self.authors_found
+            .state
+            .selected()
+            .and_then(|index| self.authors_found.authors.get(index))
+            .cloned()
     }
 
     fn open_advanced_filters(&mut self) {
@@ -400,7 +886,27 @@ impl SearchPage {
 
     fn handle_key_events(&mut self, key_event: KeyEvent) {
         match self.input_mode {
+            InputMode::Idle if self.state == PageState::DisplayingAuthorsFound => match key_event.code {
+                KeyCode::Char('s') => {
+                    self.local_action_tx.send(SearchPageActions::StartTyping).ok();
+                },
+                KeyCode::Char('a') => {
+                    self.local_action_tx.send(SearchPageActions::ToggleSearchMode).ok();
+                },
+                KeyCode::Char('j') | KeyCode::Down => self.authors_found.state.select_next(),
+                KeyCode::Char('k') | KeyCode::Up => self.authors_found.state.select_previous(),
+                KeyCode::Char('c') | KeyCode::Enter => {
+                    self.local_action_tx.send(SearchPageActions::SearchWorksOfAuthor).ok();
+                },
+                KeyCode::Char('v') => {
+                    self.local_action_tx.send(SearchPageActions::SearchWorksOfArtist).ok();
+                },
+                _ => {},
+            },
             InputMode::Idle => match key_event.code {
+                KeyCode::Char('a') => {
+                    self.local_action_tx.send(SearchPageActions::ToggleSearchMode).ok();
+                },
                 KeyCode::Char('s') => {
                     self.local_action_tx.send(SearchPageActions::StartTyping).ok();
                 },
@@ -411,6 +917,15 @@ impl SearchPage {
                 KeyCode::Char('k') | KeyCode::Up => {
                     self.local_action_tx.send(SearchPageActions::ScrollUp).ok();
                 },
+                KeyCode::Char('h') | KeyCode::Left => {
+                    self.local_action_tx.send(SearchPageActions::ScrollLeft).ok();
+                },
+                KeyCode::Char('l') | KeyCode::Right => {
+                    self.local_action_tx.send(SearchPageActions::ScrollRight).ok();
+                },
+                KeyCode::Char('v') => {
+                    self.local_action_tx.send(SearchPageActions::ToggleLayout).ok();
+                },
                 KeyCode::Char('w') => {
                     self.local_action_tx.send(SearchPageActions::NextPage).ok();
                 },
@@ -420,6 +935,12 @@ impl SearchPage {
                 KeyCode::Char('b') => {
                     self.local_action_tx.send(SearchPageActions::PreviousPage).ok();
                 },
+                KeyCode::Char('g') => {
+                    self.local_action_tx.send(SearchPageActions::FirstPage).ok();
+                },
+                KeyCode::Char('G') => {
+                    self.local_action_tx.send(SearchPageActions::LastPage).ok();
+                },
                 KeyCode::Char('f') => {
                     self.local_action_tx.send(SearchPageActions::ToggleFilters).ok();
                 },
@@ -431,15 +952,26 @@ impl SearchPage {
             },
             InputMode::Typing => match key_event.code {
                 KeyCode::Enter => {
+                    self.pending_live_search = None;
                     if self.state != PageState::SearchingMangas {
                         self.local_action_tx.send(SearchPageActions::Search).ok();
                     }
                 },
                 KeyCode::Esc => {
+                    self.pending_live_search = None;
                     self.local_action_tx.send(SearchPageActions::StopTyping).ok();
                 },
+                KeyCode::Tab => self.insert_suggestion(),
+                KeyCode::Down => self.select_suggestion(true),
+                KeyCode::Up => self.select_suggestion(false),
                 _ => {
-                    self.search_bar.handle_event(&event::Event::Key(key_event));
+                    let changed = self.search_bar.handle_event(&event::Event::Key(key_event));
+                    if changed.is_some_and(|changed| changed.value) {
+                        self.update_suggestions();
+                        if self.live_search {
+                            self.pending_live_search = Some(Instant::now());
+                        }
+                    }
                 },
             },
         }
@@ -470,6 +1002,7 @@ impl SearchPage {
         self.clean_up();
 
         self.state = PageState::SearchingMangas;
+        self.searching_since = Some(Instant::now());
 
         let page = self.mangas_found_list.page;
 
@@ -494,27 +1027,96 @@ impl SearchPage {
         });
     }
 
+    /// A mangadex link or the id of a manga was typed, it's listed as the only result and its page opened right away
+    fn open_manga(&mut self, manga_id: String) {
+        self.clean_up();
+
+        self.state = PageState::SearchingMangas;
+        self.searching_since = Some(Instant::now());
+
+        let tx = self.local_event_tx.clone();
+        let global_tx = self.global_event_tx.clone();
+
+        self.tasks.spawn(async move {
+            match MangadexClient::global().get_one_manga(&manga_id).await {
+                Ok(manga) => {
+                    tx.send(SearchPageEvents::LoadMangasFound(Some(SearchMangaResponse {
+                        data: vec![manga.data.clone()],
+                        total: 1,
+                        ..Default::default()
+                    })))
+                    .ok();
+                    global_tx.send(Events::Navigate(Route::Manga(MangaItem::from(manga.data)))).ok();
+                },
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    tx.send(SearchPageEvents::LoadMangasFound(None)).ok();
+                },
+            }
+        });
+    }
+
     fn search_next_page(&mut self) {
-        if self.state == PageState::DisplayingMangasFound
-            && self.state != PageState::SearchingMangas
-            && self.mangas_found_list.page * 10 < self.mangas_found_list.total_result
-        {
-            self.mangas_found_list.page += 1;
-            self.search_mangas();
-        }
+        self.search_page(self.mangas_found_list.page + 1);
     }
 
     fn search_previous_page(&mut self) {
+        self.search_page(self.mangas_found_list.page.saturating_sub(1));
+    }
+
+    /// Pages before the first or after the last one, and the page already shown, are not searched
+    fn search_page(&mut self, page: u32) {
         if self.state == PageState::DisplayingMangasFound
-            && self.state != PageState::SearchingMangas
-            && self.mangas_found_list.page != 1
+            && (1..=self.mangas_found_list.total_pages()).contains(&page)
+            && page != self.mangas_found_list.page
         {
-            self.mangas_found_list.page -= 1;
+            self.mangas_found_list.page = page;
             self.search_mangas();
         }
     }
 
+    fn search_authors(&mut self) {
+        self.clean_up();
+
+        self.state = PageState::SearchingMangas;
+        self.searching_since = Some(Instant::now());
+
+        let tx = self.local_event_tx.clone();
+        let name = self.search_bar.value().to_string();
+
+        self.tasks.spawn(async move {
+            match MangadexClient::global().get_authors(&name).await {
+                Ok(authors) => tx.send(SearchPageEvents::LoadAuthorsFound(Some(authors))).ok(),
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    tx.send(SearchPageEvents::LoadAuthorsFound(None)).ok()
+                },
+            };
+        });
+    }
+
+    fn load_authors_found(&mut self, response: Option<AuthorsResponse>) {
+        self.searching_since = None;
+        match response {
+            Some(response) if response.data.is_empty() => self.state = PageState::NotFound,
+            Some(response) => {
+                self.authors_found.authors = response
+                    .data
+                    .into_iter()
+                    .map(|author| Author {
+                        id: author.id,
+                        name: author.attributes.name,
+                    })
+                    .collect();
+                self.authors_found.state.select(Some(0));
+                self.state = PageState::DisplayingAuthorsFound;
+            },
+            None => self.state = PageState::ErrorOcurred,
+        }
+    }
+
     pub fn search_mangas_of_author(&mut self, author: Author) {
+        self.search_mode = SearchMode::Mangas;
         self.filter_state.set_author(author);
         self.search_bar.reset();
         self.mangas_found_list.page = 1;
@@ -522,6 +1124,7 @@ impl SearchPage {
     }
 
     pub fn search_mangas_of_artist(&mut self, artist: Artist) {
+        self.search_mode = SearchMode::Mangas;
         self.filter_state.set_artist(artist);
         self.search_bar.reset();
         self.mangas_found_list.page = 1;
@@ -529,6 +1132,7 @@ impl SearchPage {
     }
 
     fn load_mangas_found(&mut self, response: Option<SearchMangaResponse>) {
+        self.searching_since = None;
         match response {
             Some(response) => {
                 if response.data.is_empty() {
@@ -540,6 +1144,7 @@ impl SearchPage {
                 self.mangas_found_list.total_result = response.total;
                 self.state = PageState::DisplayingMangasFound;
                 self.local_event_tx.send(SearchPageEvents::SearchCovers).ok();
+                self.search_engagement();
             },
             None => {
                 self.state = PageState::ErrorOcurred;
@@ -548,6 +1153,34 @@ impl SearchPage {
         }
     }
 
+    /// Looks up which of the mangas found the user already added or read, to mark them in the results
+    fn search_engagement(&mut self) {
+        let mangas: Vec<(String, Option<String>)> = self
+            .mangas_found_list
+            .widget
+            .mangas
+            .iter()
+            .map(|item| (item.manga.id.clone(), item.manga.last_chapter.clone()))
+            .collect();
+
+        let tx = self.local_event_tx.clone();
+        self.tasks.spawn(async move {
+            match get_mangas_engagement(&mangas) {
+                Ok(engagement) => tx.send(SearchPageEvents::LoadEngagement(engagement)).ok(),
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    None
+                },
+            };
+        });
+    }
+
+    fn load_engagement(&mut self, mut engagement: HashMap<String, MangaEngagement>) {
+        for item in self.mangas_found_list.widget.mangas.iter_mut() {
+            item.engagement = engagement.remove(&item.manga.id);
+        }
+    }
+
     fn search_covers(&mut self) {
         for item in self.mangas_found_list.widget.mangas.iter() {
             let manga_id = item.manga.id.clone();
@@ -566,20 +1199,49 @@ impl SearchPage {
     }
 
     fn load_cover(&mut self, maybe_cover: Option<DynamicImage>, manga_id: String) {
-        if let Some(cover) = maybe_cover {
-            if let Some(picker) = self.picker.as_mut() {
-                if let Ok(protocol) = picker.new_protocol(cover, self.manga_cover_state.get_img_area(), Resize::Fit(None)) {
-                    self.manga_cover_state.insert_manga(protocol, manga_id);
-                }
+        let Some(cover) = maybe_cover else {
+            self.grid_covers.insert(manga_id, None);
+            return;
+        };
+
+        if let Some(picker) = self.picker.as_mut() {
+            let config = MangaTuiConfig::get();
+            // The covers of the grid keep the whole image so they can be drawn at any size
+            if config.low_memory_mode {
+                self.grid_covers.insert(manga_id.clone(), None);
+            } else {
+                self.grid_covers.insert(manga_id.clone(), Some(picker.new_resize_protocol(cover.clone())));
+            }
+            if let Ok(protocol) = picker.new_protocol(
+                cover,
+                self.manga_cover_state.get_img_area(),
+                Resize::Fit(Some(config.cover_render_quality.filter())),
+            ) {
+                self.manga_cover_state.insert_manga(protocol, manga_id);
             }
         }
     }
 
+    /// Searches what was typed once typing stopped for `LIVE_SEARCH_DELAY`, the previous search is
+    /// aborted if it didn't finish and the search bar stays focused
+    fn search_as_typed(&mut self) {
+        if !self.pending_live_search.is_some_and(|typed_at| typed_at.elapsed() >= LIVE_SEARCH_DELAY) {
+            return;
+        }
+
+        self.pending_live_search = None;
+        self.update(SearchPageActions::Search);
+        self.input_mode = InputMode::Typing;
+    }
+
     pub fn tick(&mut self) {
         self.loader_state.calc_next();
+        self.search_as_typed();
+        self.filter_state.tick();
         if let Ok(event) = self.local_event_rx.try_recv() {
             match event {
                 SearchPageEvents::LoadMangasFound(response) => self.load_mangas_found(response),
+                SearchPageEvents::LoadAuthorsFound(response) => self.load_authors_found(response),
                 SearchPageEvents::SearchCovers => {
                     if self.picker.is_some() {
                         // wait a bit so that `img_area` is set to the area for covers
@@ -588,6 +1250,13 @@ impl SearchPage {
                     }
                 },
                 SearchPageEvents::LoadCover(maybe_image, manga_id) => self.load_cover(maybe_image, manga_id),
+                SearchPageEvents::LoadEngagement(engagement) => self.load_engagement(engagement),
+                SearchPageEvents::LoadPreviousSearches(searches) => {
+                    self.previous_searches = searches;
+                    if self.input_mode == InputMode::Typing {
+                        self.update_suggestions();
+                    }
+                },
             }
         }
     }
@@ -598,7 +1267,7 @@ mod test {
     use ratatui::buffer::Buffer;
 
     use super::*;
-    use crate::view::widgets::press_key;
+    use crate::view::widgets::{press_key, render_to_lines};
 
     #[tokio::test]
     async fn search_page_key_events() {
@@ -717,4 +1386,189 @@ mod test {
             panic!("The action `go to manga page` is not working");
         }
     }
+
+    #[tokio::test]
+    async fn covers_grid_is_moved_through_by_rows_and_columns() {
+        let (tx, _) = mpsc::unbounded_channel::<Events>();
+        let mut search_page = SearchPage::init(tx, None);
+        search_page.state = PageState::DisplayingMangasFound;
+        search_page.mangas_found_list.widget.mangas = (1..=7)
+            .map(|number| {
+                MangaItem::new(crate::common::Manga {
+                    title: format!("Manga {number}"),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        press_key(&mut search_page, KeyCode::Char('v'));
+        let action = search_page.local_action_rx.recv().await.unwrap();
+        search_page.update(action);
+        assert_eq!(ResultsLayout::Grid, search_page.results_layout);
+
+        let (cell_width, cell_height) = GRID_CELL;
+        let lines = render_to_lines(cell_width * 3, cell_height * 2, |frame| {
+            search_page.render_covers_grid(frame.area(), frame.buffer_mut())
+        });
+        assert_eq!(3, search_page.mangas_found_list.grid.columns);
+        assert!(lines.iter().any(|line| line.contains("Manga 6")));
+        assert!(!lines.iter().any(|line| line.contains("Manga 7")));
+
+        let mut selected = vec![];
+        for key in ['j', 'j', 'l', 'j', 'k', 'h'] {
+            press_key(&mut search_page, KeyCode::Char(key));
+            let action = search_page.local_action_rx.recv().await.unwrap();
+            search_page.update(action);
+            selected.push(search_page.mangas_found_list.state.selected.unwrap());
+        }
+        assert_eq!(vec![0, 3, 4, 6, 3, 2], selected);
+
+        search_page.update(SearchPageActions::ToggleLayout);
+        assert_eq!(ResultsLayout::List, search_page.results_layout);
+        assert_eq!(Some(2), search_page.mangas_found_list.state.selected);
+    }
+
+    #[tokio::test]
+    async fn works_of_the_author_selected_are_searched() {
+        let (tx, _) = mpsc::unbounded_channel::<Events>();
+        let mut search_page = SearchPage::init(tx, None);
+
+        press_key(&mut search_page, KeyCode::Char('a'));
+        let action = search_page.local_action_rx.recv().await.unwrap();
+        search_page.update(action);
+
+        assert_eq!(SearchMode::Authors, search_page.search_mode);
+
+        let author = |id: &str, name: &str| crate::backend::authors::Data {
+            id: id.to_string(),
+            attributes: crate::backend::authors::Attributes {
+                name: name.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        search_page.load_authors_found(Some(AuthorsResponse {
+            data: vec![author("1", "Oda Eiichiro"), author("2", "Fujimoto Tatsuki")],
+            ..Default::default()
+        }));
+
+        assert!(search_page.state == PageState::DisplayingAuthorsFound);
+
+        press_key(&mut search_page, KeyCode::Char('j'));
+        assert_eq!("2", search_page.get_current_author_selected().unwrap().id);
+
+        press_key(&mut search_page, KeyCode::Char('v'));
+        assert_eq!(Some(SearchPageActions::SearchWorksOfArtist), search_page.local_action_rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn pages_past_the_ones_mangadex_returns_are_not_searched() {
+        let (tx, _) = mpsc::unbounded_channel::<Events>();
+        let mut search_page = SearchPage::init(tx, None);
+
+        search_page.state = PageState::DisplayingMangasFound;
+        search_page.mangas_found_list.total_result = 25;
+        search_page.mangas_found_list.page = 1;
+
+        assert_eq!(3, search_page.mangas_found_list.total_pages());
+
+        search_page.update(SearchPageActions::LastPage);
+        assert_eq!(3, search_page.mangas_found_list.page);
+
+        search_page.state = PageState::DisplayingMangasFound;
+        search_page.update(SearchPageActions::NextPage);
+        assert_eq!(3, search_page.mangas_found_list.page);
+
+        search_page.update(SearchPageActions::FirstPage);
+        assert_eq!(1, search_page.mangas_found_list.page);
+
+        search_page.state = PageState::DisplayingMangasFound;
+        search_page.update(SearchPageActions::PreviousPage);
+        assert_eq!(1, search_page.mangas_found_list.page);
+
+        search_page.mangas_found_list.total_result = 123_456;
+        assert_eq!(1000, search_page.mangas_found_list.total_pages());
+    }
+
+    #[tokio::test]
+    async fn live_search_waits_for_typing_to_stop() {
+        let (tx, _) = mpsc::unbounded_channel::<Events>();
+        let mut search_page = SearchPage::init(tx, None);
+        search_page.live_search = true;
+        search_page.input_mode = InputMode::Typing;
+
+        press_key(&mut search_page, KeyCode::Char('o'));
+        press_key(&mut search_page, KeyCode::Char('n'));
+        assert!(search_page.pending_live_search.is_some());
+
+        search_page.tick();
+        assert!(search_page.state == PageState::Normal);
+
+        search_page.pending_live_search = search_page.pending_live_search.map(|typed_at| typed_at - LIVE_SEARCH_DELAY);
+        search_page.tick();
+
+        assert!(search_page.state == PageState::SearchingMangas);
+        assert!(search_page.input_mode == InputMode::Typing);
+        assert!(search_page.pending_live_search.is_none());
+
+        // Moving the cursor doesn't change what is searched
+        press_key(&mut search_page, KeyCode::Left);
+        assert!(search_page.pending_live_search.is_none());
+    }
+
+    #[test]
+    fn tags_are_suggested_after_the_tag_prefix() {
+        let tags: Vec<TagListItem> = ["Action", "Martial Arts", "Magic"]
+            .into_iter()
+            .map(|name| TagListItem {
+                id: name.to_lowercase(),
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        assert_eq!(Some("ma"), typed_tag("one piece TAG:ma"));
+        assert_eq!(None, typed_tag("tag:ma one piece"));
+
+        let names: Vec<String> = tag_suggestions("ma", &tags)
+            .into_iter()
+            .map(|suggestion| match suggestion {
+                Suggestion::Tag { name, .. } => name,
+                Suggestion::PreviousSearch(search) => search,
+            })
+            .collect();
+        assert_eq!(vec!["Martial Arts", "Magic"], names);
+
+        let previous_searches = vec!["one piece".to_string(), "one punch man".to_string(), "naruto".to_string()];
+        assert_eq!(
+            vec![Suggestion::PreviousSearch("one piece".to_string()), Suggestion::PreviousSearch("one punch man".to_string())],
+            previous_search_suggestions("One p", &previous_searches)
+        );
+        assert!(previous_search_suggestions("naruto", &previous_searches).is_empty());
+    }
+
+    #[tokio::test]
+    async fn tab_inserts_the_suggested_tag_as_a_filter() {
+        let (tx, _) = mpsc::unbounded_channel::<Events>();
+        let mut search_page = SearchPage::init(tx, None);
+        search_page.input_mode = InputMode::Typing;
+        search_page.filter_state.tags_state.tags = Some(vec![TagListItem {
+            id: "romance".to_string(),
+            name: "Romance".to_string(),
+            ..Default::default()
+        }]);
+
+        for character in "love tag:rom".chars() {
+            press_key(&mut search_page, KeyCode::Char(character));
+        }
+
+        assert_eq!(1, search_page.suggestions.len());
+
+        press_key(&mut search_page, KeyCode::Tab);
+
+        assert_eq!("love", search_page.search_bar.value());
+        assert_eq!(1, search_page.filter_state.tags_state.num_filters_active());
+        assert!(search_page.suggestions.is_empty());
+    }
 }
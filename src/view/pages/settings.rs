@@ -0,0 +1,587 @@
+use crossterm::event::{self, KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, StatefulWidget, Table, TableState, Widget, Wrap};
+use ratatui::Frame;
+use serde::Serialize;
+use strum::IntoEnumIterator;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
+
+use crate::backend::error_log::{write_to_error_log, ErrorType};
+use crate::backend::filter::{ContentRating, Languages};
+use crate::backend::parental_lock::ParentalLock;
+use crate::backend::tui::Events;
+use crate::backend::APP_DATA_DIR;
+use crate::config::{
+    DownloadType, ImageQuality, MangaTuiConfig, ReaderBackground, RenderQuality, StartTab, TaskAlert, CONFIG, CONFIG_FILE,
+};
+use crate::global::{CURRENT_LIST_ITEM_STYLE, ERROR_STYLE, INSTRUCTIONS_STYLE};
+use crate::utils::render_search_bar;
+use crate::view::widgets::Component;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SettingsActions {
+    ScrollDown,
+    ScrollUp,
+    NextValue,
+    PreviousValue,
+    StartEditing,
+    StopEditing,
+    SaveEditing,
+    SubmitPin,
+}
+
+/// How a setting is changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SettingValues {
+    /// Cycled through with <h> and <l>, written to the config file as they are
+    Choices(Vec<String>),
+    /// Typed in, like a path
+    Text,
+}
+
+/// An option of the config file which can be changed from the settings tab
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting {
+    pub key: &'static str,
+    pub description: &'static str,
+    values: SettingValues,
+    /// As it's written in the config file, `None` for the options which are not set
+    pub value: Option<String>,
+}
+
+impl Setting {
+    fn new(key: &'static str, description: &'static str, values: SettingValues, config: &toml::Table) -> Self {
+        Self {
+            key,
+            description,
+            values,
+            value: config.get(key).map(toml::Value::to_string),
+        }
+    }
+
+    /// Changes to the next or previous choice, the first one when the value is not among them. Returns
+    /// whether the value changed, text settings have no choices
+    pub fn cycle(&mut self, forward: bool) -> bool {
+        let SettingValues::Choices(choices) = &self.values else {
+            return false;
+        };
+
+        let position = self.value.as_ref().and_then(|value| choices.iter().position(|choice| choice == value));
+        let next = match position {
+            Some(position) if forward => (position + 1) % choices.len(),
+            Some(position) => position.checked_sub(1).unwrap_or(choices.len() - 1),
+            None => 0,
+        };
+
+        self.value = choices.get(next).cloned();
+        position != Some(next)
+    }
+
+    /// The value without the quotes strings have in the config file
+    pub fn display_value(&self) -> String {
+        match self.value.as_deref() {
+            Some(value) => value.trim_matches('"').to_string(),
+            None => "not set".to_string(),
+        }
+    }
+}
+
+fn toml_literal(value: impl Serialize) -> String {
+    toml::Value::try_from(value).map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn enum_choices<T: IntoEnumIterator + Serialize>() -> SettingValues {
+    SettingValues::Choices(T::iter().map(toml_literal).collect())
+}
+
+/// The options users change the most, with the values they have in `config`
+pub fn settings_from_config(config: &MangaTuiConfig) -> Vec<Setting> {
+    let config = match toml::Value::try_from(config) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => toml::Table::new(),
+    };
+
+    let languages = Languages::iter()
+        .filter(|language| *language != Languages::Unkown)
+        .map(|language| toml_literal(language.as_iso_code()))
+        .collect();
+
+    // Each choice searches one more content rating than the previous one
+    let content_ratings = (1..=ContentRating::iter().count())
+        .map(|ratings| toml_literal(ContentRating::iter().take(ratings).collect::<Vec<ContentRating>>()))
+        .collect();
+
+    let toggle = || SettingValues::Choices(vec![toml_literal(false), toml_literal(true)]);
+
+    let title_languages = [
+        vec![],
+        vec!["en", "ja-ro", "original"],
+        vec!["ja-ro", "en", "original"],
+        vec!["original", "ja-ro", "en"],
+    ]
+    .into_iter()
+    .map(toml_literal)
+    .collect();
+
+    vec![
+        Setting::new(
+            "compare_language",
+            "The second language chapters are downloaded and read in",
+            SettingValues::Choices(languages),
+            &config,
+        ),
+        Setting::new(
+            "image_quality",
+            "Quality of the pages read and downloaded, low is better on slow connections",
+            enum_choices::<ImageQuality>(),
+            &config,
+        ),
+        Setting::new(
+            "content_ratings",
+            "The content ratings searched by default",
+            SettingValues::Choices(content_ratings),
+            &config,
+        ),
+        Setting::new("reader_background", "The color around the pages in the reader", enum_choices::<ReaderBackground>(), &config),
+        Setting::new(
+            "cover_render_quality",
+            "How covers are scaled, high is the sharpest and the slowest",
+            enum_choices::<RenderQuality>(),
+            &config,
+        ),
+        Setting::new(
+            "page_render_quality",
+            "How pages are scaled, high is the sharpest and the slowest",
+            enum_choices::<RenderQuality>(),
+            &config,
+        ),
+        Setting::new("download_type", "The format chapters are downloaded as", enum_choices::<DownloadType>(), &config),
+        Setting::new("saved_pages_directory", "Where pages are saved with <x> in the reader", SettingValues::Text, &config),
+        Setting::new(
+            "reader_prefetch_pages",
+            "How many pages after the one being read are loaded at the same time in the background",
+            SettingValues::Choices([0, 1, 2, 3, 5, 10, 20].into_iter().map(toml_literal).collect()),
+            &config,
+        ),
+        Setting::new("start_tab", "The page manga-tui starts on", enum_choices::<StartTab>(), &config),
+        Setting::new(
+            "title_languages",
+            "The languages manga titles are shown in, the first one a manga has a title in is used",
+            SettingValues::Choices(title_languages),
+            &config,
+        ),
+        Setting::new("live_search", "Search while typing in the search bar", toggle(), &config),
+        Setting::new("skip_repeated_pages", "Skip pages which repeat an earlier one, like credit pages", toggle(), &config),
+        Setting::new("check_for_updates", "Look for a newer release on startup", toggle(), &config),
+        Setting::new("set_terminal_title", "Show the manga being read in the title of the terminal", toggle(), &config),
+        Setting::new("hyperlinks", "Make titles and tags links to MangaDex", toggle(), &config),
+        Setting::new("low_memory_mode", "Keep as few images in memory as possible, for small machines", toggle(), &config),
+        Setting::new(
+            "download_finished_alert",
+            "What happens when downloading all the chapters of a manga finishes on another tab",
+            enum_choices::<TaskAlert>(),
+            &config,
+        ),
+        Setting::new(
+            "import_finished_alert",
+            "What happens when importing mangas to the library finishes on another tab",
+            enum_choices::<TaskAlert>(),
+            &config,
+        ),
+    ]
+}
+
+/// The most important options of the config file, each change is written to it right away and takes effect the
+/// next time manga-tui starts
+pub struct Settings {
+    settings: Vec<Setting>,
+    state: TableState,
+    /// `Some` while the value of a text setting is being typed
+    editing: Option<Input>,
+    /// What happened with the last change, an error is shown in red
+    status: Option<Result<String, String>>,
+    /// The parental lock of the profile, `None` once its PIN was entered until the tab is left
+    lock: Option<ParentalLock>,
+    /// `Some` while the PIN of the lock is being typed
+    pin_input: Option<Input>,
+    pub local_action_tx: UnboundedSender<SettingsActions>,
+    pub local_action_rx: UnboundedReceiver<SettingsActions>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<SettingsActions>();
+
+        let settings = match CONFIG.get() {
+            Some(config) => settings_from_config(config),
+            None => settings_from_config(&MangaTuiConfig::default()),
+        };
+
+        Self {
+            settings,
+            state: TableState::default().with_selected(0),
+            editing: None,
+            status: None,
+            lock: ParentalLock::get().cloned(),
+            pin_input: None,
+            local_action_tx,
+            local_action_rx,
+        }
+    }
+
+    fn selected_setting(&mut self) -> Option<&mut Setting> {
+        self.state.selected().and_then(|index| self.settings.get_mut(index))
+    }
+
+    /// Asks for the PIN instead when the profile is locked
+    fn ask_pin_if_locked(&mut self) -> bool {
+        if self.lock.is_some() {
+            self.pin_input = Some(Input::default());
+        }
+        self.lock.is_some()
+    }
+
+    fn submit_pin(&mut self) {
+        let (Some(input), Some(lock)) = (self.pin_input.take(), self.lock.as_ref()) else {
+            return;
+        };
+
+        if lock.matches(input.value()) {
+            self.lock = None;
+            self.status = Some(Ok("Unlocked, the settings can be changed until you leave this tab".to_string()));
+        } else {
+            self.status = Some(Err("Wrong PIN".to_string()));
+        }
+    }
+
+    fn change_value(&mut self, forward: bool) {
+        if self.ask_pin_if_locked() {
+            return;
+        }
+        if self.selected_setting().is_some_and(|setting| setting.cycle(forward)) {
+            self.save_selected();
+        }
+    }
+
+    fn start_editing(&mut self) {
+        if self.ask_pin_if_locked() {
+            return;
+        }
+        match self.selected_setting() {
+            Some(setting) if setting.values == SettingValues::Text => {
+                let value = setting.value.as_ref().map(|_| setting.display_value()).unwrap_or_default();
+                self.editing = Some(Input::new(value));
+            },
+            Some(_) => self.change_value(true),
+            None => {},
+        }
+    }
+
+    /// Nothing is saved when nothing was typed, the options which are not set can't be unset from here
+    fn save_editing(&mut self) {
+        let Some(input) = self.editing.take() else {
+            return;
+        };
+
+        let value = input.value().trim().to_string();
+        if value.is_empty() {
+            return;
+        }
+
+        if let Some(setting) = self.selected_setting() {
+            setting.value = Some(toml_literal(value));
+            self.save_selected();
+        }
+    }
+
+    fn save_selected(&mut self) {
+        let Some(setting) = self.selected_setting() else {
+            return;
+        };
+        let (key, value) = (setting.key, setting.value.clone().unwrap_or_default());
+
+        let saved = match APP_DATA_DIR.as_ref() {
+            Some(data_dir) => MangaTuiConfig::save_value(data_dir, key, &value),
+            None => Err(std::io::Error::other("data dir could not be found")),
+        };
+
+        self.status = Some(match saved {
+            Ok(()) => Ok(format!("Saved {key}, restart manga-tui for the change to take effect")),
+            Err(e) => {
+                let status = Err(format!("Could not save {key} to {CONFIG_FILE}: {e}"));
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                status
+            },
+        });
+    }
+
+    fn render_settings(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        let key_width = self.settings.iter().map(|setting| setting.key.len()).max().unwrap_or_default() as u16;
+
+        let rows: Vec<Row<'_>> = self
+            .settings
+            .iter()
+            .map(|setting| Row::new(vec![Span::raw(setting.key).bold(), Span::raw(setting.display_value()).yellow()]))
+            .collect();
+
+        let table = Table::new(rows, [Constraint::Length(key_width + 2), Constraint::Fill(1)])
+            .block(Block::bordered().title(format!("Settings saved to {CONFIG_FILE}")))
+            .highlight_style(*CURRENT_LIST_ITEM_STYLE);
+
+        StatefulWidget::render(table, area, frame.buffer_mut(), &mut self.state);
+    }
+
+    fn render_details(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        let [description_area, status_area] = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(area);
+
+        match (self.pin_input.as_ref(), self.editing.as_ref()) {
+            (Some(pin), _) => {
+                let help = Line::from(vec![
+                    "This profile is locked, type its PIN to change the settings ".into(),
+                    Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+                    " Cancel ".into(),
+                    Span::raw("<Esc>").style(*INSTRUCTIONS_STYLE),
+                ]);
+                let hidden_pin = Input::new("*".repeat(pin.value().chars().count()));
+                render_search_bar(true, help, &hidden_pin, frame, description_area);
+            },
+            (None, Some(input)) => {
+                let help = Line::from(vec![
+                    "Save ".into(),
+                    Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+                    " Cancel ".into(),
+                    Span::raw("<Esc>").style(*INSTRUCTIONS_STYLE),
+                ]);
+                render_search_bar(true, help, input, frame, description_area);
+            },
+            (None, None) => {
+                let description = self.selected_setting().map(|setting| setting.description).unwrap_or_default();
+                Paragraph::new(description)
+                    .wrap(Wrap { trim: true })
+                    .block(Block::bordered())
+                    .render(description_area, frame.buffer_mut());
+            },
+        }
+
+        let status = match self.status.as_ref() {
+            Some(Ok(status)) => Span::raw(status.as_str()).style(Style::default().fg(Color::Green)),
+            Some(Err(error)) => Span::raw(error.as_str()).style(*ERROR_STYLE),
+            None => Span::raw("The language of the chapters is chosen with `manga-tui lang --set`"),
+        };
+        Paragraph::new(status).wrap(Wrap { trim: true }).render(status_area, frame.buffer_mut());
+    }
+
+    fn handle_key_events(&mut self, key_event: KeyEvent) {
+        if let Some(pin) = self.pin_input.as_mut() {
+            match key_event.code {
+                KeyCode::Enter => {
+                    self.local_action_tx.send(SettingsActions::SubmitPin).ok();
+                },
+                KeyCode::Esc => {
+                    self.local_action_tx.send(SettingsActions::StopEditing).ok();
+                },
+                _ => {
+                    pin.handle_event(&event::Event::Key(key_event));
+                },
+            }
+            return;
+        }
+
+        if let Some(input) = self.editing.as_mut() {
+            match key_event.code {
+                KeyCode::Enter => {
+                    self.local_action_tx.send(SettingsActions::SaveEditing).ok();
+                },
+                KeyCode::Esc => {
+                    self.local_action_tx.send(SettingsActions::StopEditing).ok();
+                },
+                _ => {
+                    input.handle_event(&event::Event::Key(key_event));
+                },
+            }
+            return;
+        }
+
+        let action = match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => SettingsActions::ScrollDown,
+            KeyCode::Char('k') | KeyCode::Up => SettingsActions::ScrollUp,
+            KeyCode::Char('l') | KeyCode::Right => SettingsActions::NextValue,
+            KeyCode::Char('h') | KeyCode::Left => SettingsActions::PreviousValue,
+            KeyCode::Enter => SettingsActions::StartEditing,
+            _ => return,
+        };
+        self.local_action_tx.send(action).ok();
+    }
+}
+
+impl Component for Settings {
+    type Actions = SettingsActions;
+
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        let [settings_area, details_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(6)]).margin(1).areas(area);
+
+        let instructions = Line::from(vec![
+            "Move ".into(),
+            Span::raw("<j>/<k>").style(*INSTRUCTIONS_STYLE),
+            " Change ".into(),
+            Span::raw("<h>/<l>").style(*INSTRUCTIONS_STYLE),
+            " Edit ".into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        self.render_settings(settings_area, frame);
+        self.render_details(details_area, frame);
+
+        Block::default().title_bottom(instructions).render(area, frame.buffer_mut());
+    }
+
+    fn update(&mut self, action: Self::Actions) {
+        match action {
+            SettingsActions::ScrollDown => {
+                let last = self.settings.len().saturating_sub(1);
+                self.state.select(Some(self.state.selected().map_or(0, |selected| (selected + 1).min(last))));
+            },
+            SettingsActions::ScrollUp => {
+                self.state.select(Some(self.state.selected().map_or(0, |selected| selected.saturating_sub(1))));
+            },
+            SettingsActions::NextValue => self.change_value(true),
+            SettingsActions::PreviousValue => self.change_value(false),
+            SettingsActions::StartEditing => self.start_editing(),
+            SettingsActions::StopEditing => {
+                self.editing = None;
+                self.pin_input = None;
+            },
+            SettingsActions::SaveEditing => self.save_editing(),
+            SettingsActions::SubmitPin => self.submit_pin(),
+        }
+    }
+
+    fn handle_events(&mut self, events: Events) {
+        if let Events::Key(key_event) = events {
+            self.handle_key_events(key_event);
+        }
+    }
+
+    fn wants_input(&self) -> bool {
+        self.editing.is_some() || self.pin_input.is_some()
+    }
+
+    fn clean_up(&mut self) {
+        self.editing = None;
+        self.status = None;
+        self.pin_input = None;
+        self.lock = ParentalLock::get().cloned();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::view::widgets::press_key;
+
+    fn setting(settings: &[Setting], key: &str) -> Setting {
+        settings.iter().find(|setting| setting.key == key).cloned().unwrap()
+    }
+
+    #[test]
+    fn settings_have_the_values_of_the_config() {
+        let settings = settings_from_config(&MangaTuiConfig::default());
+
+        assert_eq!("low", setting(&settings, "image_quality").display_value());
+        assert_eq!("[\"safe\", \"suggestive\"]", setting(&settings, "content_ratings").display_value());
+        assert_eq!("5", setting(&settings, "reader_prefetch_pages").display_value());
+        assert_eq!("not set", setting(&settings, "saved_pages_directory").display_value());
+
+        // Every value which can be chosen is one the config file can be read with
+        for setting in settings {
+            if let SettingValues::Choices(choices) = setting.values {
+                for choice in choices {
+                    let config = format!("{} = {choice}", setting.key);
+                    assert!(toml::from_str::<MangaTuiConfig>(&config).is_ok(), "{config}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn choices_are_cycled_through() {
+        let settings = settings_from_config(&MangaTuiConfig::default());
+
+        let mut quality = setting(&settings, "image_quality");
+        assert!(quality.cycle(true));
+        assert_eq!("high", quality.display_value());
+        quality.cycle(true);
+        assert_eq!("low", quality.display_value());
+        quality.cycle(false);
+        assert_eq!("high", quality.display_value());
+
+        let mut language = setting(&settings, "compare_language");
+        assert!(language.cycle(true));
+        assert!(language.value.is_some());
+
+        assert!(!setting(&settings, "saved_pages_directory").cycle(true));
+    }
+
+    #[tokio::test]
+    async fn text_settings_are_typed_in() {
+        let mut page = Settings::new();
+        let index = page.settings.iter().position(|setting| setting.key == "saved_pages_directory").unwrap();
+        page.state.select(Some(index));
+
+        press_key(&mut page, KeyCode::Enter);
+        let action = page.local_action_rx.recv().await.unwrap();
+        assert_eq!(SettingsActions::StartEditing, action);
+        page.update(action);
+        assert!(page.wants_input());
+
+        press_key(&mut page, KeyCode::Char('j'));
+        assert_eq!("j", page.editing.as_ref().unwrap().value());
+
+        press_key(&mut page, KeyCode::Esc);
+        let action = page.local_action_rx.recv().await.unwrap();
+        page.update(action);
+        assert!(!page.wants_input());
+        assert_eq!("not set", page.settings[index].display_value());
+    }
+
+    #[tokio::test]
+    async fn settings_of_a_locked_profile_are_changed_only_after_the_pin() {
+        let mut page = Settings::new();
+        page.lock = Some(ParentalLock::new("1234", ContentRating::Safe));
+        let value = page.settings[0].value.clone();
+
+        page.update(SettingsActions::NextValue);
+        assert!(page.wants_input());
+        assert_eq!(value, page.settings[0].value);
+
+        for key in ['4', '3', '2', '1'] {
+            press_key(&mut page, KeyCode::Char(key));
+        }
+        page.update(SettingsActions::SubmitPin);
+        assert!(page.lock.is_some());
+        assert_eq!(Some(Err("Wrong PIN".to_string())), page.status);
+
+        page.update(SettingsActions::StartEditing);
+        for key in ['1', '2', '3', '4'] {
+            press_key(&mut page, KeyCode::Char(key));
+        }
+        press_key(&mut page, KeyCode::Enter);
+        let action = page.local_action_rx.recv().await.unwrap();
+        assert_eq!(SettingsActions::SubmitPin, action);
+        page.update(action);
+
+        assert!(page.lock.is_none());
+        assert!(!page.wants_input());
+    }
+}
@@ -0,0 +1,352 @@
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Margin, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span, ToSpan};
+use ratatui::widgets::{Block, Paragraph, StatefulWidget, Widget};
+use ratatui::Frame;
+use throbber_widgets_tui::{Throbber, ThrobberState};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinSet;
+
+use crate::backend::database::LastChapterRead;
+use crate::backend::error_log::{write_to_error_log, ErrorType};
+use crate::backend::fetch::MangadexClient;
+use crate::backend::tui::{Events, Route};
+use crate::global::{ERROR_STYLE, INSTRUCTIONS_STYLE};
+use crate::view::widgets::search::MangaItem;
+use crate::view::widgets::updates::{group_by_manga, LatestUpdatesWidget, MangaUpdates};
+use crate::view::widgets::Component;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum UpdatesActions {
+    ScrollDown,
+    ScrollUp,
+    OpenSelected,
+    Refresh,
+    ToggleGroupFilter,
+}
+
+pub enum UpdatesEvents {
+    LoadLatestUpdates(Option<Vec<MangaUpdates>>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum UpdatesState {
+    Searching,
+    Displaying,
+    ErrorSearching,
+}
+
+/// The latest chapters uploaded to mangadex in the preferred language, grouped by manga
+pub struct Updates {
+    state: UpdatesState,
+    latest_updates: LatestUpdatesWidget,
+    /// The id and name of the scanlation group whose uploads are the only ones searched
+    group: Option<(String, String)>,
+    loading_state: ThrobberState,
+    pub global_event_tx: UnboundedSender<Events>,
+    pub local_action_tx: UnboundedSender<UpdatesActions>,
+    pub local_action_rx: UnboundedReceiver<UpdatesActions>,
+    pub local_event_tx: UnboundedSender<UpdatesEvents>,
+    pub local_event_rx: UnboundedReceiver<UpdatesEvents>,
+    tasks: JoinSet<()>,
+}
+
+impl Updates {
+    pub fn new(global_event_tx: UnboundedSender<Events>) -> Self {
+        let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<UpdatesActions>();
+        let (local_event_tx, local_event_rx) = mpsc::unbounded_channel::<UpdatesEvents>();
+
+        Self {
+            state: UpdatesState::Displaying,
+            latest_updates: LatestUpdatesWidget::default(),
+            group: None,
+            loading_state: ThrobberState::default(),
+            global_event_tx,
+            local_action_tx,
+            local_action_rx,
+            local_event_tx,
+            local_event_rx,
+            tasks: JoinSet::new(),
+        }
+    }
+
+    fn search_latest_updates(&mut self) {
+        self.state = UpdatesState::Searching;
+        self.tasks.abort_all();
+
+        let tx = self.local_event_tx.clone();
+        let id_group = self.group.as_ref().map(|(id_group, _)| id_group.clone());
+        self.tasks.spawn(async move {
+            match MangadexClient::global().get_latest_updates(id_group.as_deref()).await {
+                Ok((chapters, mangas)) => {
+                    let updates = group_by_manga(chapters.data, mangas.data, chrono::Utc::now());
+                    tx.send(UpdatesEvents::LoadLatestUpdates(Some(updates))).ok();
+                },
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    tx.send(UpdatesEvents::LoadLatestUpdates(None)).ok();
+                },
+            }
+        });
+    }
+
+    fn load_latest_updates(&mut self, maybe_updates: Option<Vec<MangaUpdates>>) {
+        match maybe_updates {
+            Some(updates) => {
+                self.latest_updates =
+                    LatestUpdatesWidget::new(updates).with_group(self.group.as_ref().map(|(_, name)| name.clone()));
+                self.state = UpdatesState::Displaying;
+            },
+            None => self.state = UpdatesState::ErrorSearching,
+        }
+    }
+
+    /// A manga opens its page and a chapter is read right away, going through the manga page so that
+    /// leaving the reader lands on it
+    fn open_selected(&mut self) {
+        let Some((manga, chapter)) = self.latest_updates.get_current_selected() else {
+            return;
+        };
+
+        let route = match chapter {
+            Some(chapter) => Route::ResumeChapter(LastChapterRead {
+                chapter_id: chapter.id.clone(),
+                manga_id: manga.id.clone(),
+                chapter_number: chapter.number.clone(),
+                chapter_title: chapter.title.clone(),
+            }),
+            None => Route::Manga(MangaItem::new(manga.clone())),
+        };
+
+        self.global_event_tx.send(Events::Navigate(route)).ok();
+    }
+
+    /// Only the uploads of the group of the chapter selected are searched, or all of them again if a group
+    /// was already chosen
+    fn toggle_group_filter(&mut self) {
+        if self.group.take().is_none() {
+            let Some((_, Some(chapter))) = self.latest_updates.get_current_selected() else {
+                return;
+            };
+            if chapter.scanlator_id.is_empty() {
+                return;
+            }
+            self.group = Some((chapter.scanlator_id.clone(), chapter.scanlator.clone()));
+        }
+        self.search_latest_updates();
+    }
+
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+        let area = area.inner(Margin {
+            horizontal: 2,
+            vertical: 2,
+        });
+
+        match self.state {
+            UpdatesState::Searching => {
+                let loader = Throbber::default()
+                    .label("Searching the latest updates, please wait ")
+                    .style(Style::default().fg(Color::Yellow))
+                    .throbber_set(throbber_widgets_tui::BRAILLE_SIX)
+                    .use_type(throbber_widgets_tui::WhichUse::Spin);
+
+                StatefulWidget::render(loader, area, buf, &mut self.loading_state);
+            },
+            UpdatesState::ErrorSearching => {
+                let error = "Error, could not get the latest updates, please try again another time";
+                Paragraph::new(error.to_span().style(*ERROR_STYLE)).render(area, buf);
+            },
+            UpdatesState::Displaying if self.latest_updates.updates.is_empty() => {
+                Paragraph::new("No chapters were uploaded in your language lately").render(area, buf);
+            },
+            UpdatesState::Displaying => {},
+        }
+    }
+
+    fn handle_key_events(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.local_action_tx.send(UpdatesActions::ScrollDown).ok();
+            },
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.local_action_tx.send(UpdatesActions::ScrollUp).ok();
+            },
+            KeyCode::Enter | KeyCode::Char('r') => {
+                self.local_action_tx.send(UpdatesActions::OpenSelected).ok();
+            },
+            KeyCode::Char('f') => {
+                self.local_action_tx.send(UpdatesActions::Refresh).ok();
+            },
+            KeyCode::Char('g') => {
+                self.local_action_tx.send(UpdatesActions::ToggleGroupFilter).ok();
+            },
+            _ => {},
+        }
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::ScrollUp => {
+                self.local_action_tx.send(UpdatesActions::ScrollUp).ok();
+            },
+            MouseEventKind::ScrollDown => {
+                self.local_action_tx.send(UpdatesActions::ScrollDown).ok();
+            },
+            _ => {},
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self.state == UpdatesState::Searching {
+            self.loading_state.calc_next();
+        }
+        if let Ok(local_event) = self.local_event_rx.try_recv() {
+            match local_event {
+                UpdatesEvents::LoadLatestUpdates(maybe_updates) => self.load_latest_updates(maybe_updates),
+            }
+        }
+    }
+}
+
+impl Component for Updates {
+    type Actions = UpdatesActions;
+
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        let buf = frame.buffer_mut();
+        let [list_area] = Layout::vertical([Constraint::Fill(1)]).margin(1).areas(area);
+
+        let instructions = Line::from(vec![
+            "Scroll Down/Up ".into(),
+            Span::raw("<j>/<k>").style(*INSTRUCTIONS_STYLE),
+            " Open the manga or read the chapter ".into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+            " Refresh ".into(),
+            Span::raw("<f>").style(*INSTRUCTIONS_STYLE),
+            if self.group.is_some() { " Show every group ".into() } else { " Only this group ".into() },
+            Span::raw("<g>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        if self.state == UpdatesState::Displaying && !self.latest_updates.updates.is_empty() {
+            StatefulWidget::render(self.latest_updates.clone(), list_area, buf, &mut self.latest_updates.state);
+        } else {
+            Block::bordered().title(self.latest_updates.title()).render(list_area, buf);
+            self.render_status(list_area, buf);
+        }
+
+        Block::default().title_bottom(instructions).render(area, buf);
+    }
+
+    fn update(&mut self, action: Self::Actions) {
+        if self.state != UpdatesState::Displaying && action != UpdatesActions::Refresh {
+            return;
+        }
+        match action {
+            UpdatesActions::ScrollDown => self.latest_updates.select_next(),
+            UpdatesActions::ScrollUp => self.latest_updates.select_previous(),
+            UpdatesActions::OpenSelected => self.open_selected(),
+            UpdatesActions::Refresh => self.search_latest_updates(),
+            UpdatesActions::ToggleGroupFilter => self.toggle_group_filter(),
+        }
+    }
+
+    fn on_focus(&mut self) {
+        if self.latest_updates.updates.is_empty() && self.state != UpdatesState::Searching {
+            self.search_latest_updates();
+        }
+    }
+
+    fn clean_up(&mut self) {
+        self.tasks.abort_all();
+        self.latest_updates = LatestUpdatesWidget::default();
+        self.group = None;
+        self.state = UpdatesState::Displaying;
+    }
+
+    fn handle_events(&mut self, events: Events) {
+        match events {
+            Events::Key(key_event) => self.handle_key_events(key_event),
+            Events::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
+            Events::Tick => self.tick(),
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::Manga;
+    use crate::view::widgets::updates::UpdatedChapter;
+
+    fn press_key(page: &mut Updates, key: KeyCode) {
+        page.handle_events(Events::Key(key.into()));
+    }
+
+    fn updates() -> Vec<MangaUpdates> {
+        vec![MangaUpdates {
+            manga: Manga {
+                id: "manga".to_string(),
+                ..Default::default()
+            },
+            chapters: vec![UpdatedChapter {
+                id: "chapter".to_string(),
+                number: "12".to_string(),
+                title: String::new(),
+                scanlator: "group".to_string(),
+                scanlator_id: "group_id".to_string(),
+                readable_at: String::new(),
+            }],
+        }]
+    }
+
+    #[tokio::test]
+    async fn enter_opens_the_manga_or_reads_the_chapter() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Events>();
+        let mut page = Updates::new(tx);
+        page.load_latest_updates(Some(updates()));
+
+        press_key(&mut page, KeyCode::Enter);
+        let action = page.local_action_rx.recv().await.unwrap();
+        assert_eq!(UpdatesActions::OpenSelected, action);
+        page.update(action);
+
+        let Events::Navigate(Route::Manga(manga)) = rx.recv().await.unwrap() else {
+            panic!("the manga page should be opened");
+        };
+        assert_eq!("manga", manga.manga.id);
+
+        press_key(&mut page, KeyCode::Char('j'));
+        let action = page.local_action_rx.recv().await.unwrap();
+        page.update(action);
+        page.update(UpdatesActions::OpenSelected);
+
+        let Events::Navigate(Route::ResumeChapter(chapter)) = rx.recv().await.unwrap() else {
+            panic!("the chapter should be read");
+        };
+        assert_eq!(("manga", "chapter"), (chapter.manga_id.as_str(), chapter.chapter_id.as_str()));
+    }
+
+    #[tokio::test]
+    async fn only_the_uploads_of_the_group_selected_are_searched() {
+        let (tx, _) = mpsc::unbounded_channel::<Events>();
+        let mut page = Updates::new(tx);
+        page.load_latest_updates(Some(updates()));
+
+        page.update(UpdatesActions::ToggleGroupFilter);
+        assert!(page.group.is_none(), "a manga row has no group");
+
+        page.update(UpdatesActions::ScrollDown);
+        page.update(UpdatesActions::ToggleGroupFilter);
+        assert_eq!(Some(("group_id".to_string(), "group".to_string())), page.group);
+        assert_eq!(UpdatesState::Searching, page.state);
+
+        page.tasks.abort_all();
+        page.load_latest_updates(Some(updates()));
+        assert_eq!("Latest chapters uploaded by group", page.latest_updates.title());
+
+        page.update(UpdatesActions::ToggleGroupFilter);
+        assert!(page.group.is_none());
+    }
+}
@@ -1,7 +1,10 @@
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::backend::filter::Languages;
+use crate::backend::ChapterData;
 use crate::view::pages::manga::{ChapterOrder, MangaPageEvents};
+#[cfg(not(test))]
+use crate::view::widgets::manga::DownloadSizeEstimate;
 
 #[cfg(not(test))]
 pub async fn search_chapters_operation(
@@ -9,12 +12,23 @@ pub async fn search_chapters_operation(
     page: u32,
     language: Languages,
     chapter_order: ChapterOrder,
+    group: Option<String>,
+    smart_list: bool,
     tx: UnboundedSender<MangaPageEvents>,
 ) {
     use crate::backend::error_log::{write_to_error_log, ErrorType};
     use crate::backend::fetch::MangadexClient;
 
-    let response = MangadexClient::global().get_manga_chapters(manga_id, page, language, chapter_order).await;
+    let client = MangadexClient::global();
+    let response = if smart_list {
+        client
+            .get_smart_chapter_list(&manga_id, page, language, chapter_order, group.as_deref())
+            .await
+    } else {
+        client
+            .get_manga_chapters(manga_id, page, language, chapter_order, group.as_deref())
+            .await
+    };
 
     match response {
         Ok(chapters_response) => {
@@ -33,129 +47,335 @@ pub async fn search_chapters_operation(
     _page: u32,
     _language: Languages,
     _chapter_order: ChapterOrder,
+    _group: Option<String>,
+    _smart_list: bool,
     tx: UnboundedSender<MangaPageEvents>,
 ) {
     tx.send(MangaPageEvents::LoadChapters(None)).ok();
 }
 
+/// Leaves out the chapters of a batch of the feed which come before `from_chapter`, the feed is in reading
+/// order so once it's found `from_chapter` is cleared and the next batches are kept whole
+pub fn skip_chapters_before(chapters: Vec<ChapterData>, from_chapter: &mut Option<String>) -> Vec<ChapterData> {
+    let Some(id) = from_chapter.as_deref() else {
+        return chapters;
+    };
+
+    match chapters.iter().position(|chapter| chapter.id == id) {
+        Some(start) => {
+            *from_chapter = None;
+            chapters.into_iter().skip(start).collect()
+        },
+        None => vec![],
+    }
+}
+
 pub struct DownloadAllChaptersData {
     pub tx: UnboundedSender<MangaPageEvents>,
     pub manga_id: String,
     pub manga_title: String,
     pub lang: Languages,
+    /// The id of the chapter the download starts from, `None` to download every chapter
+    pub from_chapter: Option<String>,
 }
 
+/// `false` when the download stopped before going through every chapter
 #[cfg(not(test))]
-pub async fn download_all_chapters_task(data: DownloadAllChaptersData) {
+pub async fn download_all_chapters_task(data: DownloadAllChaptersData) -> bool {
     use std::time::{Duration, Instant};
 
-    use crate::backend::download::{download_chapter_cbz, download_chapter_epub, download_chapter_raw_images, DownloadChapter};
+    use crate::backend::download::{download_chapter, DownloadChapter};
     use crate::backend::error_log::{self, write_to_error_log, ErrorType};
     use crate::backend::fetch::MangadexClient;
     use crate::common::PageType;
-    use crate::config::{DownloadType, ImageQuality, MangaTuiConfig};
+    use crate::config::{ImageQuality, MangaTuiConfig};
+    use crate::view::widgets::manga::{AVERAGE_PAGE_SIZE_HIGH_QUALITY, AVERAGE_PAGE_SIZE_LOW_QUALITY};
     use crate::utils::to_filename;
 
-    let chapter_response = MangadexClient::global().get_all_chapters_for_manga(&data.manga_id, data.lang).await;
-    match chapter_response {
-        Ok(response) => {
-            let total_chapters = response.data.len();
-            data.tx.send(MangaPageEvents::StartDownloadProgress(total_chapters as f64)).ok();
-
-            let download_chapter_delay = if total_chapters < 40 {
-                1
-            } else if (40..100).contains(&total_chapters) {
-                3
-            } else if (100..200).contains(&total_chapters) {
-                6
-            } else {
-                8
-            };
+    // The chapters are fetched in batches so that downloading can start before the whole feed is retrieved
+    let mut offset: u32 = 0;
+    let mut total_chapters: Option<u32> = None;
+    let mut download_chapter_delay = 1;
+    let mut from_chapter = data.from_chapter.clone();
 
-            let config = MangaTuiConfig::get();
+    let config = MangaTuiConfig::get();
 
-            for chapter_found in response.data.into_iter() {
-                let chapter_id = chapter_found.id;
+    loop {
+        let chapter_response = MangadexClient::global()
+            .get_all_chapters_for_manga(&data.manga_id, data.lang, offset)
+            .await;
 
-                let start_fetch_time = Instant::now();
+        let response = match chapter_response {
+            Ok(response) => response,
+            Err(e) => {
+                match total_chapters {
+                    // Chapters which could not be retrieved count as failed so the progress can finish
+                    Some(total) if from_chapter.is_none() => {
+                        for _ in offset..total {
+                            data.tx.send(MangaPageEvents::SetDownloadAllChaptersProgress).ok();
+                        }
+                    },
+                    _ => {
+                        data.tx.send(MangaPageEvents::DownloadAllChaptersError).ok();
+                    },
+                }
+                write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                return false;
+            },
+        };
 
-                let pages_response = MangadexClient::global().get_chapter_pages(&chapter_id).await;
+        let total = match total_chapters {
+            Some(total) => total,
+            None => {
+                let total = response.total as u32;
+                if from_chapter.is_none() {
+                    data.tx.send(MangaPageEvents::StartDownloadProgress(total as f64)).ok();
+                }
 
-                let chapter_number = chapter_found.attributes.chapter.unwrap_or_default();
+                download_chapter_delay = if total < 40 {
+                    1
+                } else if (40..100).contains(&total) {
+                    3
+                } else if (100..200).contains(&total) {
+                    6
+                } else {
+                    8
+                };
 
-                let scanlator = chapter_found
-                    .relationships
-                    .iter()
-                    .find(|rel| rel.type_field == "scanlation_group")
-                    .map(|rel| rel.attributes.as_ref().unwrap().name.to_string());
+                total_chapters = Some(total);
+                total
+            },
+        };
 
-                let chapter_title = chapter_found.attributes.title.unwrap_or_default();
-                let scanlator = scanlator.unwrap_or_default();
+        let chapters_fetched = response.data.len() as u32;
 
-                match pages_response {
-                    Ok(res) => {
-                        let (files, quality) = match config.image_quality {
-                            ImageQuality::Low => (res.chapter.data_saver, PageType::LowQuality),
-                            ImageQuality::High => (res.chapter.data, PageType::HighQuality),
-                        };
+        let is_looking_for_start = from_chapter.is_some();
+        let chapters = skip_chapters_before(response.data, &mut from_chapter);
 
-                        let endpoint = format!("{}/{}/{}", res.base_url, quality, res.chapter.hash);
+        // the chapters before the one the download starts from are not counted
+        if is_looking_for_start && from_chapter.is_none() {
+            let skipped = chapters_fetched - chapters.len() as u32;
+            data.tx.send(MangaPageEvents::StartDownloadProgress((total - offset - skipped) as f64)).ok();
+        }
 
-                        let manga_title = to_filename(&data.manga_title);
-                        let chapter_title = to_filename(&chapter_title);
-                        let scanlator = to_filename(&scanlator);
+        for chapter_found in chapters.into_iter() {
+            let chapter_id = chapter_found.id;
 
-                        let chapter_to_download = DownloadChapter {
-                            id_chapter: &chapter_id,
-                            manga_id: &data.manga_id,
-                            manga_title: &manga_title,
-                            chapter_title: &chapter_title,
-                            number: &chapter_number,
-                            scanlator: &scanlator,
-                            lang: &data.lang.as_human_readable(),
-                        };
+            let start_fetch_time = Instant::now();
 
-                        let download_proccess = match config.download_type {
-                            DownloadType::Cbz => download_chapter_cbz(true, chapter_to_download, files, endpoint, data.tx.clone()),
-                            DownloadType::Raw => {
-                                download_chapter_raw_images(true, chapter_to_download, files, endpoint, data.tx.clone())
-                            },
-                            DownloadType::Epub => {
-                                download_chapter_epub(true, chapter_to_download, files, endpoint, data.tx.clone())
-                            },
-                        };
+            let pages_response = MangadexClient::global().get_chapter_pages(&chapter_id).await;
 
-                        if let Err(e) = download_proccess {
-                            let error_message = format!("Chapter: {} could not be downloaded, details: {}", chapter_title, e);
+            let chapter_number = chapter_found.attributes.chapter.unwrap_or_default();
 
-                            data.tx.send(MangaPageEvents::SetDownloadAllChaptersProgress).ok();
+            let scanlator = chapter_found
+                .relationships
+                .iter()
+                .find(|rel| rel.type_field == "scanlation_group")
+                .map(|rel| rel.attributes.as_ref().unwrap().name.to_string());
 
-                            write_to_error_log(ErrorType::FromError(Box::from(error_message)));
-                            return;
-                        }
+            let chapter_title = chapter_found.attributes.title.unwrap_or_default();
+            let scanlator = scanlator.unwrap_or_default();
 
-                        data.tx.send(MangaPageEvents::SaveChapterDownloadStatus(chapter_id, chapter_title)).ok();
-                    },
-                    Err(e) => {
+            match pages_response {
+                Ok(res) => {
+                    let (files, quality) = match config.image_quality {
+                        ImageQuality::Low => (res.chapter.data_saver, PageType::LowQuality),
+                        ImageQuality::High => (res.chapter.data, PageType::HighQuality),
+                    };
+
+                    let endpoint = format!("{}/{}/{}", res.base_url, quality, res.chapter.hash);
+
+                    let manga_title = to_filename(&data.manga_title);
+                    let chapter_title = to_filename(&chapter_title);
+                    let scanlator = to_filename(&scanlator);
+
+                    let chapter_to_download = DownloadChapter {
+                        id_chapter: &chapter_id,
+                        manga_id: &data.manga_id,
+                        manga_title: &manga_title,
+                        chapter_title: &chapter_title,
+                        number: &chapter_number,
+                        scanlator: &scanlator,
+                        lang: &data.lang.as_human_readable(),
+                    };
+
+                    let download_proccess =
+                        download_chapter(&config.download_type, true, chapter_to_download, files, endpoint, data.tx.clone());
+
+                    if let Err(e) = download_proccess {
                         let error_message = format!("Chapter: {} could not be downloaded, details: {}", chapter_title, e);
 
                         data.tx.send(MangaPageEvents::SetDownloadAllChaptersProgress).ok();
+
                         write_to_error_log(ErrorType::FromError(Box::from(error_message)));
-                    },
-                }
+                        return false;
+                    }
 
-                let time_since = start_fetch_time.elapsed();
-                std::thread::sleep(Duration::from_secs(download_chapter_delay).saturating_sub(time_since));
+                    data.tx.send(MangaPageEvents::SaveChapterDownloadStatus(chapter_id, chapter_title)).ok();
+                },
+                Err(e) => {
+                    let error_message = format!("Chapter: {} could not be downloaded, details: {}", chapter_title, e);
+
+                    data.tx.send(MangaPageEvents::SetDownloadAllChaptersProgress).ok();
+                    write_to_error_log(ErrorType::FromError(Box::from(error_message)));
+                },
             }
-        },
-        Err(e) => {
-            data.tx.send(MangaPageEvents::DownloadAllChaptersError).ok();
-            write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
-        },
+
+            let time_since = start_fetch_time.elapsed();
+            std::thread::sleep(Duration::from_secs(download_chapter_delay).saturating_sub(time_since));
+        }
+
+        offset += chapters_fetched;
+
+        if chapters_fetched == 0 || offset >= total {
+            break;
+        }
     }
+
+    if from_chapter.is_some() {
+        data.tx.send(MangaPageEvents::DownloadAllChaptersError).ok();
+        return false;
+    }
+
+    true
 }
 
 #[cfg(test)]
-pub async fn download_all_chapters_task(data: DownloadAllChaptersData) {
+pub async fn download_all_chapters_task(data: DownloadAllChaptersData) -> bool {
     data.tx.send(MangaPageEvents::StartDownloadProgress(10.0)).ok();
+    true
+}
+
+pub struct EstimateDownloadSizeData {
+    pub tx: UnboundedSender<MangaPageEvents>,
+    pub manga_id: String,
+    pub lang: Languages,
+    /// Only the chapters from this one on are counted, like in `DownloadAllChaptersData`
+    pub from_chapter: Option<String>,
+}
+
+/// How many pages of the first chapter are used to know the average page size
+#[cfg(not(test))]
+static PAGES_TO_SAMPLE: usize = 3;
+
+/// The estimate is `total pages * average page size`, the average is taken from a few pages of the
+/// first chapter
+#[cfg(not(test))]
+pub async fn estimate_download_size_task(data: EstimateDownloadSizeData) {
+    use crate::backend::error_log::{write_to_error_log, ErrorType};
+    use crate::backend::fetch::MangadexClient;
+    use crate::common::PageType;
+    use crate::config::{ImageQuality, MangaTuiConfig};
+
+    let mut offset: u32 = 0;
+    let mut total_pages: u64 = 0;
+    let mut total_chapters: u32 = 0;
+    let mut sample_chapter: Option<String> = None;
+    let mut from_chapter = data.from_chapter.clone();
+
+    loop {
+        match MangadexClient::global()
+            .get_all_chapters_for_manga(&data.manga_id, data.lang, offset)
+            .await
+        {
+            Ok(response) => {
+                let chapters_fetched = response.data.len() as u32;
+                let chapters = skip_chapters_before(response.data, &mut from_chapter);
+
+                if sample_chapter.is_none() {
+                    sample_chapter = chapters.first().map(|chapter| chapter.id.clone());
+                }
+
+                total_pages += chapters.iter().map(|chapter| chapter.attributes.pages.max(0) as u64).sum::<u64>();
+                total_chapters += chapters.len() as u32;
+                offset += chapters_fetched;
+
+                if chapters_fetched == 0 || offset as i64 >= response.total {
+                    break;
+                }
+            },
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                data.tx
+                    .send(MangaPageEvents::LoadDownloadSizeEstimate(DownloadSizeEstimate::Unknown))
+                    .ok();
+                return;
+            },
+        }
+    }
+
+    if from_chapter.is_some() {
+        data.tx
+            .send(MangaPageEvents::LoadDownloadSizeEstimate(DownloadSizeEstimate::Unknown))
+            .ok();
+        return;
+    }
+
+    let (default_page_size, page_type) = match MangaTuiConfig::get().image_quality {
+        ImageQuality::Low => (AVERAGE_PAGE_SIZE_LOW_QUALITY, PageType::LowQuality),
+        ImageQuality::High => (AVERAGE_PAGE_SIZE_HIGH_QUALITY, PageType::HighQuality),
+    };
+
+    let mut sampled_sizes: Vec<u64> = vec![];
+
+    if let Some(chapter_id) = sample_chapter {
+        if let Ok(response) = MangadexClient::global().get_chapter_pages(&chapter_id).await {
+            let files = match page_type {
+                PageType::LowQuality => response.chapter.data_saver,
+                PageType::HighQuality => response.chapter.data,
+            };
+
+            let endpoint = format!("{}/{}/{}", response.base_url, page_type, response.chapter.hash);
+
+            for file_name in files.iter().take(PAGES_TO_SAMPLE) {
+                if let Ok(Some(size)) = MangadexClient::global().get_chapter_page_size(&endpoint, file_name).await {
+                    sampled_sizes.push(size);
+                }
+            }
+        }
+    }
+
+    let average_page_size =
+        if sampled_sizes.is_empty() { default_page_size } else { sampled_sizes.iter().sum::<u64>() / sampled_sizes.len() as u64 };
+
+    data.tx
+        .send(MangaPageEvents::LoadDownloadSizeEstimate(DownloadSizeEstimate::Estimated {
+            chapters: total_chapters,
+            bytes: total_pages * average_page_size,
+        }))
+        .ok();
+}
+
+#[cfg(test)]
+pub async fn estimate_download_size_task(_data: EstimateDownloadSizeData) {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chapters(ids: &[&str]) -> Vec<ChapterData> {
+        ids.iter()
+            .map(|id| ChapterData {
+                id: id.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn ids(chapters: &[ChapterData]) -> Vec<&str> {
+        chapters.iter().map(|chapter| chapter.id.as_str()).collect()
+    }
+
+    #[test]
+    fn chapters_before_the_one_the_download_starts_from_are_skipped_across_batches() {
+        let mut from_chapter = Some("d".to_string());
+
+        assert!(skip_chapters_before(chapters(&["a", "b"]), &mut from_chapter).is_empty());
+        assert_eq!(Some("d"), from_chapter.as_deref());
+
+        assert_eq!(vec!["d", "e"], ids(&skip_chapters_before(chapters(&["c", "d", "e"]), &mut from_chapter)));
+        assert_eq!(None, from_chapter);
+
+        assert_eq!(vec!["f", "g"], ids(&skip_chapters_before(chapters(&["f", "g"]), &mut from_chapter)));
+    }
 }
@@ -11,6 +11,7 @@ pub mod home;
 pub mod manga;
 pub mod reader;
 pub mod search;
+pub mod updates;
 
 pub trait Component {
     type Actions;
@@ -21,6 +22,24 @@ pub trait Component {
 
     /// This is intended for stuff like aborting tasks and clearing vec's
     fn clean_up(&mut self);
+
+    /// Called once the page is removed from the app, the pages which are always kept around like
+    /// the home page are only blurred
+    fn on_unmount(&mut self) {
+        self.clean_up();
+    }
+
+    /// Called each time the page is shown, the place to start searching what it displays
+    fn on_focus(&mut self) {}
+
+    /// Called when another page is shown in place of this one
+    fn on_blur(&mut self) {}
+
+    /// While true the page gets every key, the global keybindings like going to another tab are
+    /// ignored, for example when the user is typing
+    fn wants_input(&self) -> bool {
+        false
+    }
 }
 
 pub trait StatefulWidgetFrame {
@@ -33,6 +52,33 @@ pub trait ImageHandler: Send + 'static {
     fn not_found(id: String) -> Self;
 }
 
+/// Draws with a `TestBackend` and returns each row of the terminal, trailing spaces are trimmed so
+/// that the layout snapshots only change when what is drawn does
+#[cfg(test)]
+pub fn render_to_lines(width: u16, height: u16, render: impl FnOnce(&mut Frame<'_>)) -> Vec<String> {
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+    terminal.draw(render).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    (0..height)
+        .map(|y| {
+            let mut line = String::new();
+            let mut skip = 0;
+            for x in 0..width {
+                // Wide characters take two cells, the second one is left blank
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                let symbol = buffer[(x, y)].symbol();
+                skip = ratatui::text::Span::raw(symbol).width().saturating_sub(1);
+                line.push_str(symbol);
+            }
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
 #[allow(dead_code)]
 // Use in testing
 pub fn press_key<T>(page: &mut dyn Component<Actions = T>, key: KeyCode) {
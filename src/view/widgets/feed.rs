@@ -1,10 +1,14 @@
+use std::collections::HashSet;
+
+use chrono::{Local, NaiveDateTime, TimeDelta, TimeZone};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Margin, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::Line;
-use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget, Wrap};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap};
 use tui_widget_list::PreRender;
 
+use crate::backend::database::ChapterRead;
 use crate::backend::filter::Languages;
 use crate::backend::ChapterResponse;
 use crate::global::CURRENT_LIST_ITEM_STYLE;
@@ -13,6 +17,8 @@ use crate::utils::display_dates_since_publication;
 pub enum FeedTabs {
     History,
     PlantToRead,
+    /// The chapters read, grouped by when they were read
+    Sessions,
 }
 
 #[derive(Clone)]
@@ -46,6 +52,8 @@ pub struct MangasRead {
     pub title: String,
     pub style: Style,
     pub recent_chapters: Vec<RecentChapters>,
+    /// It was added to the library more than once, the entries can be merged into this one
+    pub has_duplicates: bool,
 }
 
 impl Widget for MangasRead {
@@ -59,7 +67,13 @@ impl Widget for MangasRead {
 
         Block::bordered().border_style(self.style).render(area, buf);
 
-        Paragraph::new(self.title)
+        let mut title = vec![Line::from(self.title)];
+        if self.has_duplicates {
+            title.push(Line::default());
+            title.push(Line::from(vec!["Added more than once, merge into this entry ".into(), "<m>".bold().fg(Color::Yellow)]));
+        }
+
+        Paragraph::new(title)
             .block(Block::default().borders(Borders::RIGHT))
             .wrap(Wrap { trim: true })
             .render(title_area, buf);
@@ -173,3 +187,205 @@ impl StatefulWidget for HistoryWidget {
         StatefulWidget::render(list, list_area, buf, state);
     }
 }
+
+/// When the user stops reading for longer than this the next chapter they read starts a new session
+pub static SESSION_GAP_MINUTES: i64 = 30;
+
+/// Chapters read one after the other, the most recent first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadingSession {
+    pub chapters: Vec<ChapterRead>,
+}
+
+impl ReadingSession {
+    pub fn started_at(&self) -> NaiveDateTime {
+        self.chapters.last().map(|chapter| chapter.read_at).unwrap_or_default()
+    }
+
+    pub fn ended_at(&self) -> NaiveDateTime {
+        self.chapters.first().map(|chapter| chapter.read_at).unwrap_or_default()
+    }
+
+    pub fn duration(&self) -> TimeDelta {
+        self.ended_at() - self.started_at()
+    }
+
+    pub fn pages_read(&self) -> usize {
+        self.chapters.iter().map(|chapter| chapter.last_page + 1).sum()
+    }
+
+    pub fn mangas_read(&self) -> usize {
+        self.chapters
+            .iter()
+            .map(|chapter| chapter.manga_id.as_str())
+            .collect::<HashSet<&str>>()
+            .len()
+    }
+}
+
+/// `chapters` have to be sorted with the most recent first
+pub fn group_into_sessions(chapters: Vec<ChapterRead>) -> Vec<ReadingSession> {
+    let mut sessions: Vec<ReadingSession> = vec![];
+
+    for chapter in chapters {
+        match sessions.last_mut() {
+            Some(session)
+                if session.started_at() - chapter.read_at <= TimeDelta::try_minutes(SESSION_GAP_MINUTES).unwrap_or_default() =>
+            {
+                session.chapters.push(chapter)
+            },
+            _ => sessions.push(ReadingSession {
+                chapters: vec![chapter],
+            }),
+        }
+    }
+
+    sessions
+}
+
+fn to_local_time(date: NaiveDateTime) -> chrono::DateTime<Local> {
+    Local.from_utc_datetime(&date)
+}
+
+fn format_duration(duration: TimeDelta) -> String {
+    let minutes = duration.num_minutes();
+    if minutes >= 60 { format!("{}h {}m", minutes / 60, minutes % 60) } else { format!("{minutes}m") }
+}
+
+/// A session followed by the chapters read in it, each row of the list is either a session or a chapter
+#[derive(Clone, Default)]
+pub struct SessionsWidget {
+    pub sessions: Vec<ReadingSession>,
+    pub state: ListState,
+}
+
+impl SessionsWidget {
+    pub fn new(sessions: Vec<ReadingSession>) -> Self {
+        Self {
+            sessions,
+            state: ListState::default(),
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (&ReadingSession, Option<&ChapterRead>)> {
+        self.sessions.iter().flat_map(|session| {
+            std::iter::once((session, None)).chain(session.chapters.iter().map(move |chapter| (session, Some(chapter))))
+        })
+    }
+
+    pub fn select_next(&mut self) {
+        let last = self.rows().count().saturating_sub(1);
+        self.state
+            .select(Some(self.state.selected().map_or(0, |selected| (selected + 1).min(last))));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state
+            .select(Some(self.state.selected().map_or(0, |selected| selected.saturating_sub(1))));
+    }
+
+    /// The manga of the chapter selected, or of the most recent chapter if a session is selected
+    pub fn get_current_manga_selected(&self) -> Option<&str> {
+        let (session, chapter) = self.rows().nth(self.state.selected()?)?;
+        chapter.or(session.chapters.first()).map(|chapter| chapter.manga_id.as_str())
+    }
+}
+
+impl StatefulWidget for SessionsWidget {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let items: Vec<ListItem<'_>> = self
+            .rows()
+            .map(|(session, chapter)| match chapter {
+                None => {
+                    let started_at = to_local_time(session.started_at());
+                    let ended_at = to_local_time(session.ended_at());
+                    ListItem::new(Line::from(vec![
+                        format!("{} - {}", started_at.format("%a %d %b %Y, %H:%M"), ended_at.format("%H:%M")).bold(),
+                        format!(
+                            " | {} | {} chapters of {} mangas, {} pages",
+                            format_duration(session.duration()),
+                            session.chapters.len(),
+                            session.mangas_read(),
+                            session.pages_read()
+                        )
+                        .into(),
+                    ]))
+                },
+                Some(chapter) => ListItem::new(Line::from(vec![
+                    format!("  {} ", to_local_time(chapter.read_at).format("%H:%M")).into(),
+                    chapter.manga_title.clone().bold(),
+                    format!(" | Ch. {} {}", chapter.chapter_number, chapter.chapter_title).into(),
+                ])),
+            })
+            .collect();
+
+        StatefulWidget::render(
+            List::new(items)
+                .block(
+                    Block::bordered()
+                        .title(format!("Sessions, a break longer than {SESSION_GAP_MINUTES} minutes starts a new one")),
+                )
+                .highlight_style(*CURRENT_LIST_ITEM_STYLE),
+            area,
+            buf,
+            state,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chapter_read(manga_id: &str, last_page: usize, read_at: &str) -> ChapterRead {
+        ChapterRead {
+            manga_id: manga_id.to_string(),
+            manga_title: manga_id.to_string(),
+            chapter_number: "1".to_string(),
+            chapter_title: String::new(),
+            last_page,
+            read_at: NaiveDateTime::parse_from_str(read_at, "%Y-%m-%d %H:%M:%S").unwrap(),
+        }
+    }
+
+    #[test]
+    fn breaks_longer_than_the_gap_start_a_new_session() {
+        let sessions = group_into_sessions(vec![
+            chapter_read("a", 19, "2024-10-16 23:40:00"),
+            chapter_read("b", 9, "2024-10-16 23:15:00"),
+            chapter_read("a", 29, "2024-10-16 22:45:00"),
+            chapter_read("c", 0, "2024-10-15 21:00:00"),
+        ]);
+
+        assert_eq!(sessions.len(), 2);
+
+        let binge = &sessions[0];
+        assert_eq!(binge.chapters.len(), 3);
+        assert_eq!(binge.mangas_read(), 2);
+        assert_eq!(binge.pages_read(), 60);
+        assert_eq!(format_duration(binge.duration()), "55m");
+
+        assert_eq!(sessions[1].chapters.len(), 1);
+        assert_eq!(format_duration(sessions[1].duration()), "0m");
+        assert_eq!(format_duration(TimeDelta::try_minutes(158).unwrap()), "2h 38m");
+    }
+
+    #[test]
+    fn selecting_a_session_selects_its_latest_manga() {
+        let mut widget = SessionsWidget::new(group_into_sessions(vec![
+            chapter_read("a", 0, "2024-10-16 23:40:00"),
+            chapter_read("b", 0, "2024-10-16 23:30:00"),
+            chapter_read("c", 0, "2024-10-15 21:00:00"),
+        ]));
+
+        let mut selected = vec![];
+        for _ in 0..6 {
+            widget.select_next();
+            selected.push(widget.get_current_manga_selected().map(String::from));
+        }
+
+        assert_eq!(selected, ["a", "a", "b", "c", "c", "c"].map(|id| Some(id.to_string())));
+    }
+}
@@ -7,6 +7,7 @@ use ratatui::Frame;
 use state::*;
 
 use super::StatefulWidgetFrame;
+use crate::backend::filter::ContentRating;
 use crate::global::CURRENT_LIST_ITEM_STYLE;
 use crate::utils::{centered_rect, render_search_bar, set_filter_tags_style};
 
@@ -80,6 +81,9 @@ impl<'a> StatefulWidgetFrame for FilterWidget<'a> {
                     MangaFilters::Tags => state.tags_state.num_filters_active(),
                     MangaFilters::Authors => state.author_state.num_filters_active(),
                     MangaFilters::Artists => state.artist_state.num_filters_active(),
+                    MangaFilters::ScanlationGroup => state.group_state.num_filters_active(),
+                    MangaFilters::OriginalLanguage => state.original_language.num_filters_active(),
+                    MangaFilters::Year => state.year_state.num_filters_active(),
                 };
 
                 Line::from(vec![
@@ -116,6 +120,9 @@ impl<'a> StatefulWidgetFrame for FilterWidget<'a> {
                         buf,
                         &mut state.content_rating.state,
                     );
+                    if let Some(content_rating) = state.content_rating_to_confirm.as_ref() {
+                        render_content_rating_confirmation(content_rating, current_filter_area, buf);
+                    }
                 },
                 MangaFilters::SortBy => {
                     render_filter_list(state.sort_by_state.items.clone(), current_filter_area, buf, &mut state.sort_by_state.state);
@@ -183,9 +190,69 @@ impl<'a> StatefulWidgetFrame for FilterWidget<'a> {
 
                     render_search_bar(state.is_typing, input_help, &state.artist_state.search_bar, frame, input_area);
                 },
+                MangaFilters::ScanlationGroup => {
+                    let [list_area, input_area] =
+                        Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(current_filter_area);
+
+                    match state.group_state.items.as_mut() {
+                        Some(groups) => {
+                            render_filter_list(groups.clone(), list_area, buf, &mut state.group_state.state);
+                        },
+                        None => {
+                            Paragraph::new("Search scanlation groups, only one can be selected").render(list_area, buf);
+                        },
+                    }
+
+                    let input_help = if state.is_typing {
+                        Line::from(vec![
+                            "Press ".into(),
+                            " <Enter> ".bold().yellow(),
+                            "to search ".into(),
+                            " <Esc> ".bold().yellow(),
+                            "to stop typing".into(),
+                        ])
+                    } else {
+                        Line::from(vec!["Press".into(), " <l> ".bold().yellow(), "to search scanlation groups".into()])
+                    };
+
+                    render_search_bar(state.is_typing, input_help, &state.group_state.search_bar, frame, input_area);
+                },
                 MangaFilters::Languages => {
                     render_filter_list(state.lang_state.items.clone(), current_filter_area, buf, &mut state.lang_state.state);
                 },
+                MangaFilters::OriginalLanguage => {
+                    render_filter_list(
+                        state.original_language.items.clone(),
+                        current_filter_area,
+                        buf,
+                        &mut state.original_language.state,
+                    );
+                },
+                MangaFilters::Year => {
+                    let [year_area, input_area] =
+                        Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(current_filter_area);
+
+                    let year = match state.year_state.year {
+                        Some(year) => Line::from(format!("Published in {year}")).fg(Color::Yellow),
+                        None => Line::from("Any year"),
+                    };
+
+                    Paragraph::new(year).block(Block::bordered()).render(year_area, buf);
+
+                    let input_help = if state.is_typing {
+                        Line::from(vec![
+                            "Press ".into(),
+                            " <Enter> ".bold().yellow(),
+                            "to set the year, leave it empty for any year ".into(),
+                            " <Esc> ".bold().yellow(),
+                            "to stop typing".into(),
+                        ])
+                    } else {
+                        Line::from(vec!["Press".into(), " <l> ".bold().yellow(), "to type a year".into()])
+                    };
+
+                    render_search_bar(state.is_typing, input_help, &state.year_state.input, frame, input_area);
+                },
             }
         }
     }
@@ -268,6 +335,20 @@ where
     StatefulWidget::render(list, area, buf, state);
 }
 
+fn render_content_rating_confirmation(content_rating: &ContentRating, area: Rect, buf: &mut Buffer) {
+    let popup_area = centered_rect(area, 60, 40);
+
+    Clear.render(popup_area, buf);
+
+    Paragraph::new(vec![
+        Line::from(format!("Search {} mangas? They may not be suitable for everyone", content_rating)),
+        Line::from(vec!["Press ".into(), " <y> ".bold().yellow(), "to confirm, any other key to cancel".into()]),
+    ])
+    .block(Block::bordered().title(" Adult content "))
+    .wrap(Wrap { trim: true })
+    .render(popup_area, buf);
+}
+
 fn render_tags_list<'a, T>(items: T, area: Rect, buf: &mut Buffer, state: &mut ListState)
 where
     T: IntoIterator,
@@ -10,7 +10,8 @@ use tui_input::Input;
 use crate::backend::authors::AuthorsResponse;
 use crate::backend::fetch::MangadexClient;
 use crate::backend::filter::{
-    Artist, Author, ContentRating, Filters, Languages, MagazineDemographic, PublicationStatus, SortBy, TagData,
+    Artist, Author, ContentRating, Filters, Languages, MagazineDemographic, OriginalLanguage, PublicationStatus, SortBy,
+    TagData,
 };
 use crate::backend::tags::TagsResponse;
 use crate::backend::tui::Events;
@@ -18,6 +19,7 @@ use crate::backend::tui::Events;
 pub enum FilterEvents {
     LoadAuthors(Option<AuthorsResponse>),
     LoadArtists(Option<AuthorsResponse>),
+    LoadGroups(Option<AuthorsResponse>),
     SearchTags,
     LoadTags(TagsResponse),
 }
@@ -29,6 +31,9 @@ pub enum MangaFilters {
     Languages,
     #[strum(to_string = "Sort by")]
     SortBy,
+    #[strum(to_string = "Original language")]
+    OriginalLanguage,
+    Year,
     #[strum(to_string = "Publication status")]
     PublicationStatus,
     #[strum(to_string = "Magazine demographic")]
@@ -36,17 +41,22 @@ pub enum MangaFilters {
     Tags,
     Authors,
     Artists,
+    #[strum(to_string = "Scanlation group")]
+    ScanlationGroup,
 }
 
-pub const FILTERS: [MangaFilters; 8] = [
+pub const FILTERS: [MangaFilters; 11] = [
     MangaFilters::ContentRating,
     MangaFilters::Languages,
     MangaFilters::SortBy,
+    MangaFilters::OriginalLanguage,
+    MangaFilters::Year,
     MangaFilters::PublicationStatus,
     MangaFilters::Tags,
     MangaFilters::MagazineDemographic,
     MangaFilters::Authors,
     MangaFilters::Artists,
+    MangaFilters::ScanlationGroup,
 ];
 
 #[derive(Clone, Debug)]
@@ -71,6 +81,8 @@ pub struct SortByState;
 pub struct MagazineDemographicState;
 #[derive(Debug)]
 pub struct LanguageState;
+#[derive(Debug)]
+pub struct OriginalLanguageState;
 
 #[derive(Debug)]
 pub struct FilterList<T> {
@@ -111,25 +123,15 @@ impl<T> FilterList<T> {
 
 impl Default for FilterList<ContentRatingState> {
     fn default() -> Self {
+        let searched_by_default = ContentRating::searched_by_default();
         Self {
-            items: vec![
-                FilterListItem {
-                    is_selected: true,
-                    name: ContentRating::Safe.to_string(),
-                },
-                FilterListItem {
-                    is_selected: true,
-                    name: ContentRating::Suggestive.to_string(),
-                },
-                FilterListItem {
-                    is_selected: false,
-                    name: ContentRating::Erotic.to_string(),
-                },
-                FilterListItem {
-                    is_selected: false,
-                    name: ContentRating::Pornographic.to_string(),
-                },
-            ],
+            items: ContentRating::allowed()
+                .into_iter()
+                .map(|content_rating| FilterListItem {
+                    is_selected: searched_by_default.contains(&content_rating),
+                    name: content_rating.to_string(),
+                })
+                .collect(),
             state: ListState::default(),
             _state: PhantomData::<ContentRatingState>,
         }
@@ -179,6 +181,20 @@ impl Default for FilterList<PublicationStatusState> {
     }
 }
 
+impl Default for FilterList<OriginalLanguageState> {
+    fn default() -> Self {
+        let items = OriginalLanguage::iter().map(|language| FilterListItem {
+            is_selected: false,
+            name: language.to_string(),
+        });
+        Self {
+            items: items.collect(),
+            state: ListState::default(),
+            _state: PhantomData,
+        }
+    }
+}
+
 impl FilterList<SortByState> {
     pub fn toggle_sort_by(&mut self) {
         for item in self.items.iter_mut() {
@@ -208,6 +224,28 @@ impl Default for FilterList<LanguageState> {
     }
 }
 
+/// The year is typed since a list with every year would be too long to scroll through
+#[derive(Default, Debug)]
+pub struct YearState {
+    pub input: Input,
+    pub year: Option<u32>,
+}
+
+impl YearState {
+    /// An empty input means any year, anything which is not a year is discarded
+    fn set_year_from_input(&mut self) {
+        let value = self.input.value().trim();
+        self.year = value.parse().ok().filter(|year| (1900..=9999).contains(year));
+        if self.year.is_none() && !value.is_empty() {
+            self.input.reset();
+        }
+    }
+
+    pub fn num_filters_active(&self) -> usize {
+        usize::from(self.year.is_some())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ListItemId {
     pub id: String,
@@ -221,6 +259,9 @@ pub struct AuthorState;
 #[derive(Default, Debug)]
 pub struct ArtistState;
 
+#[derive(Default, Debug)]
+pub struct GroupState;
+
 // It's called dynamic because the items must be fetched
 #[derive(Default, Debug)]
 pub struct FilterListDynamic<T> {
@@ -251,6 +292,30 @@ impl FilterListDynamic<ArtistState> {
     }
 }
 
+impl FilterListDynamic<GroupState> {
+    fn search_groups(&mut self, tx: UnboundedSender<FilterEvents>) {
+        let name = self.get_name();
+        tokio::spawn(async move {
+            let res = MangadexClient::global().get_scanlation_groups(&name).await;
+            tx.send(FilterEvents::LoadGroups(res.ok())).ok();
+        });
+    }
+
+    /// Only one group can be searched at a time, so selecting one unselects the others
+    fn toggle_group(&mut self) {
+        let selected = self.state.selected();
+        if let Some(groups) = self.items.as_mut() {
+            for (index, group) in groups.iter_mut().enumerate() {
+                group.is_selected = Some(index) == selected && !group.is_selected;
+            }
+        }
+    }
+
+    fn group_selected(&self) -> Option<String> {
+        self.items.as_ref()?.iter().find(|group| group.is_selected).map(|group| group.id.clone())
+    }
+}
+
 impl<T> FilterListDynamic<T> {
     pub fn set_users_found(&mut self, response: AuthorsResponse) {
         self.items = Some(
@@ -429,7 +494,12 @@ pub struct FilterState {
     pub tags_state: TagsState,
     pub author_state: FilterListDynamic<AuthorState>,
     pub artist_state: FilterListDynamic<ArtistState>,
+    pub group_state: FilterListDynamic<GroupState>,
     pub lang_state: FilterList<LanguageState>,
+    pub original_language: FilterList<OriginalLanguageState>,
+    pub year_state: YearState,
+    /// An adult content rating which is turned on only after the user confirms it
+    pub content_rating_to_confirm: Option<ContentRating>,
     pub is_typing: bool,
     tx: UnboundedSender<FilterEvents>,
     rx: UnboundedReceiver<FilterEvents>,
@@ -450,7 +520,11 @@ impl FilterState {
             magazine_demographic: FilterList::<MagazineDemographicState>::default(),
             author_state: FilterListDynamic::<AuthorState>::default(),
             artist_state: FilterListDynamic::<ArtistState>::default(),
+            group_state: FilterListDynamic::<GroupState>::default(),
             lang_state: FilterList::<LanguageState>::default(),
+            original_language: FilterList::<OriginalLanguageState>::default(),
+            year_state: YearState::default(),
+            content_rating_to_confirm: None,
             is_typing: false,
             tx,
             rx,
@@ -474,21 +548,26 @@ impl FilterState {
         self.magazine_demographic = FilterList::<MagazineDemographicState>::default();
         self.sort_by_state = FilterList::<SortByState>::default();
         self.lang_state = FilterList::<LanguageState>::default();
+        self.original_language = FilterList::<OriginalLanguageState>::default();
+        self.year_state = YearState::default();
         self.author_state = FilterListDynamic::<AuthorState>::default();
         self.artist_state = FilterListDynamic::<ArtistState>::default();
+        self.group_state = FilterListDynamic::<GroupState>::default();
+        self.content_rating_to_confirm = None;
     }
 
     pub fn toggle(&mut self) {
         self.is_open = !self.is_open;
     }
 
-    fn tick(&mut self) {
+    pub fn tick(&mut self) {
         if let Ok(event) = self.rx.try_recv() {
             match event {
                 FilterEvents::SearchTags => self.search_tags(),
                 FilterEvents::LoadTags(res) => self.load_tags(res),
                 FilterEvents::LoadAuthors(res) => self.author_state.load_users(res),
                 FilterEvents::LoadArtists(res) => self.artist_state.load_users(res),
+                FilterEvents::LoadGroups(res) => self.group_state.load_users(res),
             }
         }
     }
@@ -514,6 +593,12 @@ impl FilterState {
                 self.author_state.search_authors(tx);
             } else if *filter == MangaFilters::Artists {
                 self.artist_state.search_artists(tx);
+            } else if *filter == MangaFilters::ScanlationGroup {
+                self.group_state.search_groups(tx);
+            } else if *filter == MangaFilters::Year {
+                self.year_state.set_year_from_input();
+                self.filters.set_year(self.year_state.year);
+                self.is_typing = false;
             }
         }
     }
@@ -527,7 +612,12 @@ impl FilterState {
     }
 
     pub fn handle_key_events(&mut self, key_event: KeyEvent) {
-        if self.is_typing {
+        if self.content_rating_to_confirm.is_some() {
+            match key_event.code {
+                KeyCode::Char('y') => self.confirm_content_rating(),
+                _ => self.content_rating_to_confirm = None,
+            }
+        } else if self.is_typing {
             match key_event.code {
                 KeyCode::Esc | KeyCode::Left => self.toggle_focus_input(),
                 KeyCode::Enter => self.search(),
@@ -549,6 +639,7 @@ impl FilterState {
                     }
                 },
                 KeyCode::Char('r') => self.reset(),
+                KeyCode::Char('a') => self.filters.toggle_available_chapters(),
                 KeyCode::Char('l') | KeyCode::Right => self.toggle_focus_input(),
                 _ => {},
             }
@@ -567,6 +658,12 @@ impl FilterState {
                 MangaFilters::Artists => {
                     self.artist_state.search_bar.handle_event(&crossterm::event::Event::Key(key_event));
                 },
+                MangaFilters::ScanlationGroup => {
+                    self.group_state.search_bar.handle_event(&crossterm::event::Event::Key(key_event));
+                },
+                MangaFilters::Year => {
+                    self.year_state.input.handle_event(&crossterm::event::Event::Key(key_event));
+                },
                 _ => {},
             }
         }
@@ -574,7 +671,11 @@ impl FilterState {
 
     fn toggle_focus_input(&mut self) {
         match FILTERS.get(self.id_filter).unwrap() {
-            MangaFilters::Tags | MangaFilters::Authors | MangaFilters::Artists => {
+            MangaFilters::Tags
+            | MangaFilters::Authors
+            | MangaFilters::Artists
+            | MangaFilters::ScanlationGroup
+            | MangaFilters::Year => {
                 self.is_typing = !self.is_typing;
             },
             _ => {},
@@ -624,12 +725,21 @@ impl FilterState {
                         self.artist_state.state.select_next();
                     }
                 },
+                MangaFilters::ScanlationGroup => {
+                    if self.group_state.items.is_some() {
+                        self.group_state.state.select_next();
+                    }
+                },
                 MangaFilters::Languages => {
                     self.lang_state.scroll_down();
                 },
                 MangaFilters::PublicationStatus => {
                     self.publication_status.scroll_down();
                 },
+                MangaFilters::OriginalLanguage => {
+                    self.original_language.scroll_down();
+                },
+                MangaFilters::Year => {},
             }
         }
     }
@@ -661,6 +771,11 @@ impl FilterState {
                         self.artist_state.state.select_previous();
                     }
                 },
+                MangaFilters::ScanlationGroup => {
+                    if self.group_state.items.is_some() {
+                        self.group_state.state.select_previous();
+                    }
+                },
 
                 MangaFilters::Languages => {
                     self.lang_state.scroll_up();
@@ -668,6 +783,10 @@ impl FilterState {
                 MangaFilters::PublicationStatus => {
                     self.publication_status.scroll_up();
                 },
+                MangaFilters::OriginalLanguage => {
+                    self.original_language.scroll_up();
+                },
+                MangaFilters::Year => {},
             }
         }
     }
@@ -676,8 +795,17 @@ impl FilterState {
         if let Some(filter) = FILTERS.get(self.id_filter) {
             match filter {
                 MangaFilters::ContentRating => {
-                    self.content_rating.toggle();
-                    self.set_content_rating();
+                    if self.selected_content_rating_needs_confirmation() {
+                        self.content_rating_to_confirm = self
+                            .content_rating
+                            .state
+                            .selected()
+                            .and_then(|index| self.content_rating.items.get(index))
+                            .map(|item| item.name.as_str().into());
+                    } else {
+                        self.content_rating.toggle();
+                        self.set_content_rating();
+                    }
                 },
                 MangaFilters::SortBy => {
                     self.sort_by_state.toggle_sort_by();
@@ -698,6 +826,10 @@ impl FilterState {
                     self.artist_state.toggle();
                     self.set_artists();
                 },
+                MangaFilters::ScanlationGroup => {
+                    self.group_state.toggle_group();
+                    self.filters.set_group(self.group_state.group_selected());
+                },
 
                 MangaFilters::Languages => {
                     self.lang_state.toggle();
@@ -707,6 +839,11 @@ impl FilterState {
                     self.publication_status.toggle();
                     self.set_publication_status();
                 },
+                MangaFilters::OriginalLanguage => {
+                    self.original_language.toggle();
+                    self.set_original_languages();
+                },
+                MangaFilters::Year => {},
             }
         }
     }
@@ -774,6 +911,25 @@ impl FilterState {
         }
     }
 
+    /// Turning on erotica or pornographic asks first, unless the config file already searches them
+    fn selected_content_rating_needs_confirmation(&self) -> bool {
+        self.content_rating
+            .state
+            .selected()
+            .and_then(|index| self.content_rating.items.get(index))
+            .is_some_and(|item| {
+                let content_rating = ContentRating::from(item.name.as_str());
+                !item.is_selected && content_rating.is_adult() && !ContentRating::searched_by_default().contains(&content_rating)
+            })
+    }
+
+    fn confirm_content_rating(&mut self) {
+        if self.content_rating_to_confirm.take().is_some() {
+            self.content_rating.toggle();
+            self.set_content_rating();
+        }
+    }
+
     fn set_content_rating(&mut self) {
         self.filters.set_content_rating(
             self.content_rating
@@ -789,6 +945,21 @@ impl FilterState {
         )
     }
 
+    fn set_original_languages(&mut self) {
+        self.filters.set_original_languages(
+            self.original_language
+                .items
+                .iter()
+                .filter_map(|item| {
+                    if item.is_selected {
+                        return item.name.as_str().try_into().ok();
+                    }
+                    None
+                })
+                .collect(),
+        )
+    }
+
     fn set_magazine_demographic(&mut self) {
         self.filters.set_magazine_demographic(
             self.magazine_demographic
@@ -851,10 +1022,20 @@ impl FilterState {
         )
     }
 
+    /// Called when a tag is autocompleted in the search bar
+    pub fn include_tag(&mut self, tag_id: &str) {
+        if let Some(tag) = self.tags_state.tags.as_mut().and_then(|tags| tags.iter_mut().find(|tag| tag.id == tag_id)) {
+            tag.state = TagListItemState::Included;
+        }
+        self.set_tags();
+    }
+
     /// This function is called from manga page
     pub fn set_author(&mut self, author: crate::common::Author) {
         self.filters.reset_author();
         self.filters.reset_artist();
+        self.filters.set_group(None);
+        self.group_state.items = None;
         self.artist_state.items = None;
         self.author_state.items = Some(vec![ListItemId {
             id: author.id.clone(),
@@ -868,6 +1049,8 @@ impl FilterState {
     pub fn set_artist(&mut self, artist: crate::common::Artist) {
         self.filters.reset_author();
         self.filters.reset_artist();
+        self.filters.set_group(None);
+        self.group_state.items = None;
 
         self.author_state.items = None;
         self.artist_state.items = Some(vec![ListItemId {
@@ -975,6 +1158,45 @@ mod test {
         assert!(filter_list.items.is_none());
     }
 
+    #[test]
+    fn only_one_scanlation_group_is_selected() {
+        let mut filter_state = FilterState::new();
+        let group = |id: &str| Data {
+            id: id.to_string(),
+            ..Default::default()
+        };
+
+        filter_state.group_state.load_users(Some(AuthorsResponse {
+            data: vec![group("group_1"), group("group_2")],
+            ..Default::default()
+        }));
+        filter_state.id_filter = FILTERS.iter().position(|filter| *filter == MangaFilters::ScanlationGroup).unwrap();
+
+        filter_state.handle_key_events(KeyCode::Char('j').into());
+        filter_state.handle_key_events(KeyCode::Char('s').into());
+        filter_state.handle_key_events(KeyCode::Char('j').into());
+        filter_state.handle_key_events(KeyCode::Char('s').into());
+
+        assert_eq!(1, filter_state.group_state.num_filters_active());
+        assert_eq!(Some("group_2".to_string()), filter_state.filters.group.0);
+
+        filter_state.handle_key_events(KeyCode::Char('s').into());
+
+        assert_eq!(None, filter_state.filters.group.0);
+    }
+
+    #[test]
+    fn looking_for_available_chapters_is_toggled() {
+        let mut filter_state = FilterState::new();
+        assert!(filter_state.filters.available_chapters.0);
+
+        filter_state.handle_key_events(KeyCode::Char('a').into());
+        assert!(!filter_state.filters.available_chapters.0);
+
+        filter_state.handle_key_events(KeyCode::Char('r').into());
+        assert!(filter_state.filters.available_chapters.0);
+    }
+
     #[test]
     fn tag_state_works() {
         let mut tag_state = TagsState {
@@ -1003,6 +1225,75 @@ mod test {
         );
     }
 
+    #[test]
+    fn adult_content_rating_asks_for_confirmation() {
+        let mut filter_state = FilterState::new();
+        filter_state.is_open = true;
+
+        // Go to erotica
+        filter_state.content_rating.state.select(Some(2));
+
+        press_s(&mut filter_state);
+
+        assert_eq!(Some(ContentRating::Erotic), filter_state.content_rating_to_confirm);
+        assert!(!filter_state.content_rating.items[2].is_selected);
+
+        // Cancelling leaves it turned off
+        filter_state.handle_events(Events::Key(KeyCode::Char('n').into()));
+
+        assert!(filter_state.content_rating_to_confirm.is_none());
+        assert!(!filter_state.content_rating.items[2].is_selected);
+
+        press_s(&mut filter_state);
+        filter_state.handle_events(Events::Key(KeyCode::Char('y').into()));
+
+        assert!(filter_state.content_rating.items[2].is_selected);
+        assert!(filter_state.filters.content_rating.contains(&ContentRating::Erotic));
+
+        // Turning it off doesn't ask
+        press_s(&mut filter_state);
+
+        assert!(filter_state.content_rating_to_confirm.is_none());
+        assert!(!filter_state.content_rating.items[2].is_selected);
+    }
+
+    #[test]
+    fn year_is_typed_and_original_language_selected() {
+        let mut filter_state = FilterState::new();
+        filter_state.is_open = true;
+
+        // Go to original language
+        next_tab(&mut filter_state);
+        next_tab(&mut filter_state);
+        next_tab(&mut filter_state);
+
+        scroll_down(&mut filter_state);
+        press_s(&mut filter_state);
+
+        assert_eq!(vec![OriginalLanguage::Japanese], filter_state.filters.original_languages);
+
+        // Go to year
+        next_tab(&mut filter_state);
+        start_typing(&mut filter_state);
+
+        type_a_letter(&mut filter_state, '2');
+        type_a_letter(&mut filter_state, '0');
+        type_a_letter(&mut filter_state, '1');
+        type_a_letter(&mut filter_state, '9');
+        filter_state.handle_events(Events::Key(KeyCode::Enter.into()));
+
+        assert!(!filter_state.is_typing);
+        assert_eq!(Some(2019), filter_state.filters.year.0);
+        assert_eq!(1, filter_state.year_state.num_filters_active());
+
+        start_typing(&mut filter_state);
+        type_a_letter(&mut filter_state, 'x');
+        filter_state.handle_events(Events::Key(KeyCode::Enter.into()));
+
+        assert_eq!(None, filter_state.filters.year.0);
+        assert_eq!("", filter_state.year_state.input.value());
+    }
+
     // simulate what the user can do
     fn next_tab(filter_state: &mut FilterState) {
         filter_state.handle_events(Events::Key(KeyCode::Tab.into()));
@@ -60,7 +60,7 @@ impl CarrouselItem {
             .title_bottom(self.manga.author.name.clone())
             .render(area, buf);
 
-        let mut tags: Vec<Span<'_>> = self.manga.tags.iter().map(|tag| set_tags_style(tag)).collect();
+        let mut tags: Vec<Span<'_>> = self.manga.tags.iter().map(|tag| set_tags_style(&tag.name)).collect();
 
         tags.push(set_status_style(&self.manga.status));
         tags.push(set_tags_style(&self.manga.content_rating));
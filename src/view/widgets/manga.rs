@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Margin, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Color, Modifier, Style, Stylize};
 use ratatui::symbols::line::THICK;
 use ratatui::text::{Line, ToSpan};
 use ratatui::widgets::{Block, LineGauge, Paragraph, StatefulWidget, Widget, Wrap};
@@ -23,6 +23,18 @@ pub enum ChapterItemState {
     DownloadError,
     /// When the user tried to read a chapter and there was an error
     ReadError,
+    /// The chapter was deleted or has no pages
+    Unavailable,
+}
+
+/// How a chapter changed since the last time the chapter list was refreshed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChapterChange {
+    #[default]
+    Unchanged,
+    New,
+    /// Mangadex sometimes deletes or retracts chapters
+    Removed,
 }
 
 #[derive(Clone, Debug)]
@@ -31,15 +43,34 @@ pub struct ChapterItem {
     pub title: String,
     pub readable_at: String,
     pub scanlator: String,
+    pub scanlator_id: String,
     pub chapter_number: String,
     pub is_read: bool,
     pub is_downloaded: bool,
     pub state: ChapterItemState,
     pub download_loading_state: Option<f64>,
     pub translated_language: Languages,
+    pub change: ChapterChange,
+    /// 0 when mangadex doesn't know it, for example for chapters hosted on other sites
+    pub pages: u32,
     style: Style,
 }
 
+/// Used for the size of chapters which were not downloaded and when the size of the sample pages of a
+/// download could not be retrieved
+pub static AVERAGE_PAGE_SIZE_LOW_QUALITY: u64 = 150 * 1024;
+pub static AVERAGE_PAGE_SIZE_HIGH_QUALITY: u64 = 500 * 1024;
+
+/// The pages of a chapter and roughly how much downloading them takes in data saver and in full quality
+pub fn chapter_size(pages: u32) -> String {
+    let megabytes = |page_size: u64| (pages as u64 * page_size) as f64 / (1024.0 * 1024.0);
+    format!(
+        "{pages} pages {:.1}/{:.1} MB",
+        megabytes(AVERAGE_PAGE_SIZE_LOW_QUALITY),
+        megabytes(AVERAGE_PAGE_SIZE_HIGH_QUALITY)
+    )
+}
+
 impl Widget for ChapterItem {
     fn render(self, area: Rect, buf: &mut Buffer)
     where
@@ -48,12 +79,13 @@ impl Widget for ChapterItem {
         let layout = Layout::horizontal([
             Constraint::Length(3),
             Constraint::Length(3),
-            Constraint::Fill(50),
-            Constraint::Fill(30),
+            Constraint::Fill(40),
             Constraint::Fill(20),
+            Constraint::Fill(16),
+            Constraint::Fill(24),
         ]);
 
-        let [is_read_area, is_downloaded_area, title_area, scanlator_area, readable_at_area] = layout.areas(area);
+        let [is_read_area, is_downloaded_area, title_area, scanlator_area, readable_at_area, size_area] = layout.areas(area);
 
         let is_read_icon = if self.is_read { "👀" } else { " " };
 
@@ -62,11 +94,31 @@ impl Widget for ChapterItem {
         Line::from(is_read_icon).style(self.style).render(is_read_area, buf);
         Line::from(is_downloaded_icon).style(self.style).render(is_downloaded_area, buf);
 
-        Paragraph::new(Line::from(vec![format!(" Ch. {} ", self.chapter_number).into(), self.title.into()]))
+        let mut title = vec![format!(" Ch. {} ", self.chapter_number).into(), self.title.into()];
+
+        match self.change {
+            ChapterChange::New => {
+                title.insert(0, "NEW".to_span().style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)))
+            },
+            ChapterChange::Removed => {
+                title = title.into_iter().map(|span| span.crossed_out()).collect();
+            },
+            ChapterChange::Unchanged => {},
+        }
+
+        Paragraph::new(Line::from(title))
             .wrap(Wrap { trim: true })
             .style(self.style)
             .render(title_area, buf);
 
+        if self.change == ChapterChange::Removed {
+            Paragraph::new("Removed from MangaDex".to_span().style(*ERROR_STYLE)).render(
+                Rect::new(scanlator_area.x, scanlator_area.y, scanlator_area.width + readable_at_area.width, scanlator_area.height),
+                buf,
+            );
+            return;
+        }
+
         match self.download_loading_state.as_ref() {
             Some(progress) => {
                 LineGauge::default()
@@ -87,6 +139,13 @@ impl Widget for ChapterItem {
                         .style(self.style)
                         .wrap(Wrap { trim: true })
                         .render(readable_at_area, buf);
+
+                    if self.pages > 0 {
+                        Paragraph::new(chapter_size(self.pages))
+                            .style(self.style)
+                            .wrap(Wrap { trim: true })
+                            .render(size_area, buf);
+                    }
                 },
                 ChapterItemState::DownloadError => {
                     Paragraph::new(
@@ -104,6 +163,17 @@ impl Widget for ChapterItem {
                         buf,
                     );
                 },
+                ChapterItemState::Unavailable => {
+                    Paragraph::new("This chapter is no longer available".to_span().style(*ERROR_STYLE)).render(
+                        Rect::new(
+                            scanlator_area.x,
+                            scanlator_area.y,
+                            scanlator_area.width + readable_at_area.width,
+                            scanlator_area.height,
+                        ),
+                        buf,
+                    );
+                },
                 ChapterItemState::ReadError => {
                     Paragraph::new("Cannot read this chapter due to an error, please try again".to_span().style(*ERROR_STYLE))
                         .render(
@@ -145,11 +215,14 @@ impl ChapterItem {
             title,
             readable_at,
             scanlator,
+            scanlator_id: String::new(),
             chapter_number,
             is_read: false,
             is_downloaded: false,
             download_loading_state: None,
             translated_language,
+            change: ChapterChange::default(),
+            pages: 0,
             style: Style::default(),
             state: ChapterItemState::Normal,
         }
@@ -164,6 +237,10 @@ impl ChapterItem {
         self.state = ChapterItemState::ReadError;
     }
 
+    pub fn set_unavailable(&mut self) {
+        self.state = ChapterItemState::Unavailable;
+    }
+
     pub fn set_normal_state(&mut self) {
         self.state = ChapterItemState::Normal;
     }
@@ -175,6 +252,52 @@ pub struct ChaptersListWidget {
 }
 
 impl ChaptersListWidget {
+    /// Keeps only the first upload of each chapter number, chapters without a number are all kept
+    pub fn dedupe(&mut self) {
+        let mut seen: Vec<String> = vec![];
+        self.chapters.retain(|chapter| {
+            if chapter.chapter_number.is_empty() {
+                return true;
+            }
+            if seen.contains(&chapter.chapter_number) {
+                return false;
+            }
+            seen.push(chapter.chapter_number.clone());
+            true
+        });
+    }
+
+    /// Marks the chapters which were not in `previous` as new and puts back the ones which are
+    /// gone where they used to be, marked as removed. The chapters listed after the last one that
+    /// is still there may have only moved to the next page, so they are only considered removed if
+    /// this is the last page
+    pub fn mark_changes(&mut self, previous: &[ChapterItem], is_last_page: bool) {
+        let last_kept = previous.iter().rposition(|old| self.chapters.iter().any(|chapter| chapter.id == old.id));
+
+        for chapter in self.chapters.iter_mut() {
+            if !previous.iter().any(|old| old.id == chapter.id) {
+                chapter.change = ChapterChange::New;
+            }
+        }
+
+        let mut insert_at = 0;
+        for (index, old) in previous.iter().enumerate() {
+            if let Some(position) = self.chapters.iter().position(|chapter| chapter.id == old.id) {
+                insert_at = position + 1;
+                continue;
+            }
+
+            if is_last_page || last_kept.is_some_and(|last| index < last) {
+                let mut removed = old.clone();
+                removed.change = ChapterChange::Removed;
+                removed.download_loading_state = None;
+                removed.set_normal_state();
+                self.chapters.insert(insert_at, removed);
+                insert_at += 1;
+            }
+        }
+    }
+
     pub fn from_response(response: &ChapterResponse) -> Self {
         let mut chapters: Vec<ChapterItem> = vec![];
 
@@ -192,20 +315,22 @@ impl ChaptersListWidget {
 
             let difference = today - parse_date.date_naive();
 
-            let scanlator = chapter
-                .relationships
-                .iter()
-                .find(|rel| rel.type_field == "scanlation_group")
-                .map(|rel| rel.attributes.as_ref().unwrap().name.to_string());
+            let scanlation_group = chapter.relationships.iter().find(|rel| rel.type_field == "scanlation_group");
+
+            let scanlator = scanlation_group.map(|rel| rel.attributes.as_ref().unwrap().name.to_string());
 
-            chapters.push(ChapterItem::new(
+            let mut chapter_item = ChapterItem::new(
                 id,
                 title,
                 chapter_number,
                 display_dates_since_publication(difference.num_days()),
                 scanlator.unwrap_or_default(),
                 translated_language,
-            ))
+            );
+            chapter_item.pages = chapter.attributes.pages.max(0) as u32;
+            chapter_item.scanlator_id = scanlation_group.map(|rel| rel.id.clone()).unwrap_or_default();
+
+            chapters.push(chapter_item)
         }
 
         Self { chapters }
@@ -232,9 +357,46 @@ pub enum DownloadPhase {
     ErrorChaptersData,
 }
 
+/// How much space downloading all chapters will take, shown before the user confirms
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub enum DownloadSizeEstimate {
+    #[default]
+    Estimating,
+    Estimated {
+        chapters: u32,
+        bytes: u64,
+    },
+    Unknown,
+}
+
+impl DownloadSizeEstimate {
+    fn as_human_readable(&self) -> String {
+        match self {
+            Self::Estimating => "Estimating download size...".to_string(),
+            Self::Unknown => "Could not estimate the download size".to_string(),
+            Self::Estimated { chapters, bytes } => {
+                let megabytes = *bytes as f64 / (1024.0 * 1024.0);
+                let size =
+                    if megabytes >= 1024.0 { format!("{:.1} GB", megabytes / 1024.0) } else { format!("{:.0} MB", megabytes) };
+                format!("{} chapters, estimated size: ~{}", chapters, size)
+            },
+        }
+    }
+}
+
+/// The chapter a download of it and the chapters after it starts from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadStart {
+    pub id: String,
+    pub number: String,
+}
+
 #[derive(Debug)]
 pub struct DownloadAllChaptersState {
     pub phase: DownloadPhase,
+    /// `None` when every chapter is downloaded
+    pub from_chapter: Option<DownloadStart>,
+    pub size_estimate: DownloadSizeEstimate,
     pub total_chapters: f64,
     pub loader_state: ThrobberState,
     pub download_progress: f64,
@@ -246,6 +408,8 @@ impl DownloadAllChaptersState {
     pub fn new(tx: UnboundedSender<MangaPageEvents>) -> Self {
         Self {
             phase: DownloadPhase::default(),
+            from_chapter: None,
+            size_estimate: DownloadSizeEstimate::default(),
             total_chapters: 0.0,
             loader_state: ThrobberState::default(),
             download_progress: 0.0,
@@ -274,9 +438,23 @@ impl DownloadAllChaptersState {
     pub fn ask_for_confirmation(&mut self) {
         if !self.is_downloading() {
             self.phase = DownloadPhase::Asking;
+            self.from_chapter = None;
+            self.size_estimate = DownloadSizeEstimate::Estimating;
+        }
+    }
+
+    /// Like `ask_for_confirmation` but only `chapter` and the ones after it are downloaded
+    pub fn ask_to_download_from(&mut self, chapter: DownloadStart) {
+        if !self.is_downloading() {
+            self.ask_for_confirmation();
+            self.from_chapter = Some(chapter);
         }
     }
 
+    pub fn set_size_estimate(&mut self, estimate: DownloadSizeEstimate) {
+        self.size_estimate = estimate;
+    }
+
     pub fn fetch_chapters_data(&mut self) {
         if !self.is_downloading() {
             self.phase = DownloadPhase::FetchingChaptersData;
@@ -360,8 +538,13 @@ impl<'a> DownloadAllChaptersWidget<'a> {
 
         let download_location = format!("Download location : {}", state.download_location.as_path().display(),);
 
+        let chapters = match state.from_chapter.as_ref() {
+            Some(chapter) => format!("Downloading the chapters from chapter {}, this will take a while, ", chapter.number),
+            None => "Downloading all chapters, this will take a while, ".to_string(),
+        };
+
         Paragraph::new(Line::from(vec![
-            "Downloading all chapters, this will take a while, ".into(),
+            chapters.into(),
             download_location.into(),
             " ".into(),
         ]))
@@ -391,14 +574,25 @@ impl<'a> StatefulWidget for DownloadAllChaptersWidget<'a> {
         match state.phase {
             DownloadPhase::ProccessNotStarted => {},
             DownloadPhase::Asking => {
+                let question = match state.from_chapter.as_ref() {
+                    Some(chapter) => format!("Do you want to download chapter {} and the ones after it? Yes: ", chapter.number),
+                    None => "Do you want to download all chapters? Yes: ".to_string(),
+                };
+
                 let instructions = vec![
-                    "Do you want to download all chapters? Yes: ".into(),
+                    question.into(),
                     "<Enter>".to_span().style(*INSTRUCTIONS_STYLE),
                     " no ".into(),
                     "<Esc>".to_span().style(*INSTRUCTIONS_STYLE),
                 ];
 
-                Paragraph::new(Line::from(instructions)).render(download_information_area, buf);
+                let estimate = state.size_estimate.as_human_readable();
+                let estimate = match state.size_estimate {
+                    DownloadSizeEstimate::Unknown => estimate.to_span().style(*ERROR_STYLE),
+                    _ => estimate.into(),
+                };
+
+                Paragraph::new(vec![Line::from(instructions), Line::from(estimate)]).render(download_information_area, buf);
             },
             DownloadPhase::AskAbortProcess => {
                 let instructions = vec![
@@ -455,9 +649,11 @@ impl<'a> StatefulWidget for DownloadAllChaptersWidget<'a> {
 
 #[cfg(test)]
 mod test {
+    use insta::assert_snapshot;
     use tokio::sync::mpsc;
 
     use super::*;
+    use crate::view::widgets::render_to_lines;
 
     #[tokio::test]
     async fn download_state_works() {
@@ -518,4 +714,155 @@ mod test {
 
         assert_eq!(MangaPageEvents::FinishedDownloadingAllChapters, download_finished);
     }
+
+    fn render_chapters_list(width: u16, height: u16) -> Vec<String> {
+        let mut read = chapter_with_id("a");
+        read.title = "The beginning".to_string();
+        read.chapter_number = "1".to_string();
+        read.scanlator = "Some group".to_string();
+        read.readable_at = "2 days ago".to_string();
+        read.is_read = true;
+        read.pages = 20;
+
+        let mut new = chapter_with_id("b");
+        new.title = "The end".to_string();
+        new.chapter_number = "2".to_string();
+        new.change = ChapterChange::New;
+
+        let mut failed = chapter_with_id("c");
+        failed.chapter_number = "3".to_string();
+        failed.set_download_error();
+
+        let mut state = tui_widget_list::ListState::default();
+        state.select(Some(0));
+
+        render_to_lines(width, height, |frame| {
+            StatefulWidget::render(
+                ChaptersListWidget {
+                    chapters: vec![read, new, failed],
+                },
+                frame.size(),
+                frame.buffer_mut(),
+                &mut state,
+            )
+        })
+    }
+
+    #[test]
+    fn chapters_list_layout() {
+        assert_snapshot!("chapters_list_wide", render_chapters_list(100, 3).join("\n"));
+        assert_snapshot!("chapters_list_narrow", render_chapters_list(60, 3).join("\n"));
+    }
+
+    fn render_download_all_chapters_modal(width: u16, height: u16) -> Vec<String> {
+        let (tx, _) = mpsc::unbounded_channel::<MangaPageEvents>();
+        let mut state = DownloadAllChaptersState::new(tx);
+        state.ask_for_confirmation();
+        state.set_size_estimate(DownloadSizeEstimate::Estimated {
+            chapters: 10,
+            bytes: 50 * 1024 * 1024,
+        });
+
+        render_to_lines(width, height, |frame| {
+            StatefulWidget::render(DownloadAllChaptersWidget::new("some_title"), frame.size(), frame.buffer_mut(), &mut state)
+        })
+    }
+
+    #[test]
+    fn download_all_chapters_modal_layout() {
+        assert_snapshot!("download_all_chapters_modal_wide", render_download_all_chapters_modal(70, 7).join("\n"));
+        assert_snapshot!("download_all_chapters_modal_narrow", render_download_all_chapters_modal(40, 6).join("\n"));
+    }
+
+    #[test]
+    fn download_size_estimate_is_human_readable() {
+        let estimate = DownloadSizeEstimate::Estimated {
+            chapters: 10,
+            bytes: 50 * 1024 * 1024,
+        };
+        assert_eq!("10 chapters, estimated size: ~50 MB", estimate.as_human_readable());
+
+        let estimate = DownloadSizeEstimate::Estimated {
+            chapters: 300,
+            bytes: 3 * 1024 * 1024 * 1024 / 2,
+        };
+        assert_eq!("300 chapters, estimated size: ~1.5 GB", estimate.as_human_readable());
+    }
+
+    #[test]
+    fn chapter_size_is_shown_in_data_saver_and_full_quality() {
+        assert_eq!("20 pages 2.9/9.8 MB", chapter_size(20));
+        assert_eq!("3 pages 0.4/1.5 MB", chapter_size(3));
+    }
+
+    fn chapter_with_id(id: &str) -> ChapterItem {
+        ChapterItem::new(
+            id.to_string(),
+            String::default(),
+            String::default(),
+            String::default(),
+            String::default(),
+            Languages::default(),
+        )
+    }
+
+    fn ids_and_changes(widget: &ChaptersListWidget) -> Vec<(String, ChapterChange)> {
+        widget.chapters.iter().map(|chapter| (chapter.id.clone(), chapter.change)).collect()
+    }
+
+    #[test]
+    fn chapter_list_marks_new_and_removed_chapters() {
+        let previous = vec![chapter_with_id("a"), chapter_with_id("b"), chapter_with_id("c"), chapter_with_id("d")];
+
+        // "b" was deleted, "new" was uploaded and "d" was pushed to the next page
+        let mut widget = ChaptersListWidget {
+            chapters: vec![chapter_with_id("new"), chapter_with_id("a"), chapter_with_id("c")],
+        };
+
+        widget.mark_changes(&previous, false);
+
+        assert_eq!(
+            vec![
+                ("new".to_string(), ChapterChange::New),
+                ("a".to_string(), ChapterChange::Unchanged),
+                ("b".to_string(), ChapterChange::Removed),
+                ("c".to_string(), ChapterChange::Unchanged),
+            ],
+            ids_and_changes(&widget)
+        );
+
+        let mut widget = ChaptersListWidget {
+            chapters: vec![chapter_with_id("a"), chapter_with_id("c")],
+        };
+
+        widget.mark_changes(&previous, true);
+
+        assert_eq!(
+            vec![
+                ("a".to_string(), ChapterChange::Unchanged),
+                ("b".to_string(), ChapterChange::Removed),
+                ("c".to_string(), ChapterChange::Unchanged),
+                ("d".to_string(), ChapterChange::Removed),
+            ],
+            ids_and_changes(&widget)
+        );
+    }
+
+    #[test]
+    fn only_the_first_upload_of_a_chapter_is_kept() {
+        let chapter = |id: &str, number: &str| {
+            let mut chapter = chapter_with_id(id);
+            chapter.chapter_number = number.to_string();
+            chapter
+        };
+
+        let mut widget = ChaptersListWidget {
+            chapters: vec![chapter("a", "2"), chapter("b", "2"), chapter("c", "1"), chapter("d", ""), chapter("e", "")],
+        };
+
+        widget.dedupe();
+
+        let ids: Vec<&str> = widget.chapters.iter().map(|chapter| chapter.id.as_str()).collect();
+        assert_eq!(vec!["a", "c", "d", "e"], ids);
+    }
 }
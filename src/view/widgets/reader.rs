@@ -1,17 +1,22 @@
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
 use ratatui::widgets::{Block, Paragraph, StatefulWidget, Widget, Wrap};
 use throbber_widgets_tui::{Throbber, ThrobberState};
 use tui_widget_list::PreRender;
 
-use crate::global::CURRENT_LIST_ITEM_STYLE;
+use crate::common::PageType;
+use crate::global::{CURRENT_LIST_ITEM_STYLE, INSTRUCTIONS_STYLE};
 
 #[derive(PartialEq, Eq, Clone)]
 pub enum PageItemState {
+    /// The page has not been requested yet
+    Waiting,
     Loading,
     FinishedLoad,
-    _NotFound,
+    /// The page could not be fetched or decoded, it can be retried
+    Failed,
 }
 
 #[derive(Clone)]
@@ -43,6 +48,11 @@ impl Widget for PagesItem {
             page.render(chapter_number_area, buf);
 
             StatefulWidget::render(loader, loader_area, buf, &mut self.loading_state);
+        } else if self.state == PageItemState::Failed {
+            page.render(chapter_number_area, buf);
+            Paragraph::new("Failed <r>")
+                .style(Style::default().fg(Color::Red))
+                .render(loader_area, buf);
         } else {
             page.render(area, buf);
         }
@@ -62,7 +72,7 @@ impl PagesItem {
     pub fn new(number: usize) -> Self {
         Self {
             number,
-            state: PageItemState::Loading,
+            state: PageItemState::Waiting,
             loading_state: ThrobberState::default(),
             style: Style::default(),
         }
@@ -98,3 +108,105 @@ impl StatefulWidget for PagesList {
         StatefulWidget::render(items, area, buf, state)
     }
 }
+
+/// The line below the reader with the chapter and how many of its pages have loaded
+pub struct ReaderStatusBar<'a> {
+    pub chapter_number: &'a str,
+    pub chapter_title: &'a str,
+    /// Starts from 1
+    pub current_page: usize,
+    pub total_pages: usize,
+    pub loaded_pages: usize,
+    pub pending_pages: usize,
+    pub failed_pages: usize,
+    pub page_type: PageType,
+    pub rotation: u16,
+    /// The screen of a tall page which is shown and how many screens it has
+    pub screen: Option<(u32, u32)>,
+    pub next_chapter_ready: bool,
+}
+
+impl<'a> Widget for ReaderStatusBar<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let mut chapter = format!("Ch. {}", self.chapter_number);
+        if !self.chapter_title.is_empty() {
+            chapter.push_str(&format!(" {}", self.chapter_title));
+        }
+
+        Paragraph::new(Line::from(vec![
+            chapter.into(),
+            " | ".into(),
+            format!("Page {} / {}", self.current_page.min(self.total_pages), self.total_pages).into(),
+            match self.screen {
+                Some((screen, screens)) => format!(" (screen {screen} / {screens})"),
+                None => String::new(),
+            }
+            .into(),
+            " | ".into(),
+            format!("Loaded {} / {}", self.loaded_pages, self.total_pages).into(),
+            if self.pending_pages > 0 { format!(", {} loading", self.pending_pages) } else { String::new() }.into(),
+            if self.failed_pages > 0 { format!(", {} failed", self.failed_pages) } else { String::new() }.red(),
+            " | ".into(),
+            self.page_type.as_human_readable().to_string().into(),
+            if self.rotation > 0 { format!(" | Rotated {}°", self.rotation) } else { String::new() }.into(),
+            if self.next_chapter_ready { " | Next chapter ready" } else { "" }.into(),
+        ]))
+        .style(*INSTRUCTIONS_STYLE)
+        .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use insta::assert_snapshot;
+
+    use super::*;
+    use crate::view::widgets::render_to_lines;
+
+    fn render_pages_list(width: u16, height: u16) -> Vec<String> {
+        let mut pages: Vec<PagesItem> = (1..=3).map(PagesItem::new).collect();
+        pages[1].state = PageItemState::Loading;
+        pages[2].state = PageItemState::Failed;
+
+        let mut state = tui_widget_list::ListState::default();
+        state.select(Some(0));
+
+        render_to_lines(width, height, |frame| {
+            StatefulWidget::render(PagesList::new(pages), frame.size(), frame.buffer_mut(), &mut state)
+        })
+    }
+
+    #[test]
+    fn pages_list_layout() {
+        assert_snapshot!("pages_list_wide", render_pages_list(24, 6).join("\n"));
+        assert_snapshot!("pages_list_narrow", render_pages_list(16, 4).join("\n"));
+    }
+
+    fn render_status_bar(width: u16) -> Vec<String> {
+        render_to_lines(width, 1, |frame| {
+            ReaderStatusBar {
+                chapter_number: "12",
+                chapter_title: "The festival",
+                current_page: 3,
+                total_pages: 20,
+                loaded_pages: 5,
+                pending_pages: 2,
+                failed_pages: 1,
+                page_type: PageType::LowQuality,
+                rotation: 90,
+                screen: Some((2, 7)),
+                next_chapter_ready: false,
+            }
+            .render(frame.size(), frame.buffer_mut())
+        })
+    }
+
+    #[test]
+    fn status_bar_layout() {
+        assert_snapshot!("status_bar_wide", render_status_bar(120).join("\n"));
+        assert_snapshot!("status_bar_narrow", render_status_bar(40).join("\n"));
+    }
+}
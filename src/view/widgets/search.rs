@@ -1,14 +1,15 @@
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Margin, Rect};
-use ratatui::style::Style;
+use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Paragraph, StatefulWidget, StatefulWidgetRef, Widget, Wrap};
 use ratatui_image::Image;
 use throbber_widgets_tui::{Throbber, ThrobberState};
 use tui_widget_list::PreRender;
 
+use crate::backend::database::MangaEngagement;
 use crate::backend::Data;
-use crate::common::{ImageState, Manga};
+use crate::common::{ImageState, Manga, MangaTag};
 use crate::global::CURRENT_LIST_ITEM_STYLE;
 use crate::utils::{from_manga_response, set_status_style, set_tags_style};
 
@@ -16,7 +17,7 @@ pub struct MangaPreview<'a> {
     id: &'a str,
     title: &'a str,
     description: &'a str,
-    tags: &'a Vec<String>,
+    tags: &'a Vec<MangaTag>,
     content_rating: &'a str,
     status: &'a str,
     can_display_images: bool,
@@ -29,7 +30,7 @@ impl<'a> MangaPreview<'a> {
         id: &'a str,
         title: &'a str,
         description: &'a str,
-        tags: &'a Vec<String>,
+        tags: &'a Vec<MangaTag>,
         content_rating: &'a str,
         status: &'a str,
         can_display_images: bool,
@@ -47,10 +48,23 @@ impl<'a> MangaPreview<'a> {
         }
     }
 
-    pub fn render_cover_and_details_area(&mut self, area: Rect, buf: &mut Buffer, state: &mut ImageState) {
-        let layout = Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(80)]);
+    /// Where the cover is drawn when the preview is rendered in `area`
+    pub fn cover_area(area: Rect) -> Rect {
+        let [cover_details_area, _] = Self::layout().areas(area);
+        let [cover_area, _] = Self::cover_and_details_layout().areas(cover_details_area);
+        cover_area
+    }
+
+    fn layout() -> Layout {
+        Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)])
+    }
+
+    fn cover_and_details_layout() -> Layout {
+        Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(80)])
+    }
 
-        let [cover_area, details_area] = layout.areas(area);
+    pub fn render_cover_and_details_area(&mut self, area: Rect, buf: &mut Buffer, state: &mut ImageState) {
+        let [cover_area, details_area] = Self::cover_and_details_layout().areas(area);
 
         if self.can_display_images {
             self.render_details(details_area, buf);
@@ -105,7 +119,7 @@ impl<'a> MangaPreview<'a> {
         let layout = Layout::vertical([Constraint::Percentage(20), Constraint::Percentage(80)]);
         let [details_area, tags_area] = layout.areas(area);
 
-        let tags_list: Vec<Span<'_>> = self.tags.iter().map(|tag| set_tags_style(tag)).collect();
+        let tags_list: Vec<Span<'_>> = self.tags.iter().map(|tag| set_tags_style(&tag.name)).collect();
 
         let content_rating = set_tags_style(self.content_rating);
 
@@ -121,18 +135,32 @@ impl<'a> StatefulWidget for MangaPreview<'a> {
     type State = ImageState;
 
     fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let [cover_details_area, description_area] =
-            Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(area);
+        let [cover_details_area, description_area] = Self::layout().areas(area);
 
         self.render_cover_and_details_area(cover_details_area, buf, state);
         self.render_description_area(description_area, buf);
     }
 }
 
+/// Shown next to a manga the user already added or read, the most engaged with first
+pub fn engagement_badge(engagement: &MangaEngagement) -> Option<String> {
+    if engagement.completed {
+        Some("completed".to_string())
+    } else if engagement.chapters_read > 0 {
+        Some(format!("read {} chapters", engagement.chapters_read))
+    } else if engagement.in_library {
+        Some("in library".to_string())
+    } else {
+        None
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct MangaItem {
     pub manga: Manga,
     pub style: Style,
+    /// Known once the local database was looked up, `None` if the user never added nor read it
+    pub engagement: Option<MangaEngagement>,
 }
 
 impl Widget for MangaItem {
@@ -140,7 +168,12 @@ impl Widget for MangaItem {
     where
         Self: Sized,
     {
-        Paragraph::new(self.manga.title)
+        let mut line = vec![Span::raw(self.manga.title)];
+        if let Some(badge) = self.engagement.as_ref().and_then(engagement_badge) {
+            line.push(format!(" [{badge}]").green());
+        }
+
+        Paragraph::new(Line::from(line))
             .wrap(Wrap { trim: true })
             .style(self.style)
             .render(area, buf);
@@ -169,6 +202,7 @@ impl MangaItem {
         Self {
             manga,
             style: Style::default(),
+            engagement: None,
         }
     }
 }
@@ -198,3 +232,52 @@ impl StatefulWidgetRef for ListMangasFoundWidget {
         StatefulWidget::render(list, area, buf, state);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use insta::assert_snapshot;
+
+    use super::*;
+    use crate::view::widgets::render_to_lines;
+
+    fn render_mangas_found(width: u16, height: u16) -> Vec<String> {
+        let widget = ListMangasFoundWidget {
+            mangas: ["Komi-san wa Komyushou desu", "Chainsaw Man", "Dandadan"]
+                .into_iter()
+                .map(|title| {
+                    MangaItem::new(Manga {
+                        title: title.to_string(),
+                        ..Default::default()
+                    })
+                })
+                .collect(),
+        };
+
+        let mut state = tui_widget_list::ListState::default();
+        state.select(Some(1));
+
+        render_to_lines(width, height, |frame| widget.render_ref(frame.size(), frame.buffer_mut(), &mut state))
+    }
+
+    #[test]
+    fn the_most_engaged_badge_is_shown() {
+        let badge = |in_library, chapters_read, completed| {
+            engagement_badge(&MangaEngagement {
+                in_library,
+                chapters_read,
+                completed,
+            })
+        };
+
+        assert_eq!(Some("completed".to_string()), badge(true, 12, true));
+        assert_eq!(Some("read 3 chapters".to_string()), badge(true, 3, false));
+        assert_eq!(Some("in library".to_string()), badge(true, 0, false));
+        assert_eq!(None, badge(false, 0, false));
+    }
+
+    #[test]
+    fn mangas_found_layout() {
+        assert_snapshot!("mangas_found_wide", render_mangas_found(40, 4).join("\n"));
+        assert_snapshot!("mangas_found_narrow", render_mangas_found(12, 3).join("\n"));
+    }
+}
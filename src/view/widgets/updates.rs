@@ -0,0 +1,260 @@
+use chrono::{DateTime, Utc};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, StatefulWidget};
+
+use crate::backend::{ChapterData, Data};
+use crate::common::Manga;
+use crate::global::CURRENT_LIST_ITEM_STYLE;
+use crate::utils::{display_dates_since_publication, from_manga_response};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdatedChapter {
+    pub id: String,
+    pub number: String,
+    pub title: String,
+    pub scanlator: String,
+    pub scanlator_id: String,
+    pub readable_at: String,
+}
+
+/// A manga and the chapters of it among the latest updates, the most recent first
+#[derive(Debug, Clone)]
+pub struct MangaUpdates {
+    pub manga: Manga,
+    pub chapters: Vec<UpdatedChapter>,
+}
+
+/// How long ago a chapter became readable, in minutes or hours for the ones uploaded today
+pub fn uploaded_ago(readable_at: &str, now: DateTime<Utc>) -> String {
+    let Ok(readable_at) = DateTime::parse_from_rfc3339(readable_at) else {
+        return String::new();
+    };
+
+    let elapsed = now.signed_duration_since(readable_at);
+    match (elapsed.num_minutes(), elapsed.num_hours()) {
+        (minutes, _) if minutes < 60 => format!("{} minutes ago", minutes.max(0)),
+        (_, hours) if hours < 24 => format!("{hours} hours ago"),
+        _ => display_dates_since_publication(elapsed.num_days()),
+    }
+}
+
+/// `chapters` have to be sorted with the most recent first, the mangas keep the order of their most recent
+/// chapter and the chapters of mangas which were not found are left out
+pub fn group_by_manga(chapters: Vec<ChapterData>, mangas: Vec<Data>, now: DateTime<Utc>) -> Vec<MangaUpdates> {
+    let mut mangas: Vec<Option<Data>> = mangas.into_iter().map(Some).collect();
+    let mut updates: Vec<MangaUpdates> = vec![];
+
+    for chapter in chapters {
+        let Some(manga_id) = chapter.manga_id().map(String::from) else {
+            continue;
+        };
+
+        let scanlation_group = chapter.relationships.iter().find(|relationship| relationship.type_field == "scanlation_group");
+
+        let updated_chapter = UpdatedChapter {
+            id: chapter.id.clone(),
+            number: chapter.attributes.chapter.clone().unwrap_or_default(),
+            title: chapter.attributes.title.clone().unwrap_or_default(),
+            scanlator: scanlation_group
+                .and_then(|relationship| relationship.attributes.as_ref())
+                .map(|attributes| attributes.name.clone())
+                .unwrap_or_default(),
+            scanlator_id: scanlation_group.map(|relationship| relationship.id.clone()).unwrap_or_default(),
+            readable_at: uploaded_ago(&chapter.attributes.readable_at, now),
+        };
+
+        if let Some(manga_updates) = updates.iter_mut().find(|updates| updates.manga.id == manga_id) {
+            manga_updates.chapters.push(updated_chapter);
+            continue;
+        }
+
+        let manga = mangas.iter_mut().find(|manga| manga.as_ref().is_some_and(|manga| manga.id == manga_id));
+        if let Some(manga) = manga.and_then(Option::take) {
+            updates.push(MangaUpdates {
+                manga: from_manga_response(manga),
+                chapters: vec![updated_chapter],
+            });
+        }
+    }
+
+    updates
+}
+
+/// A manga followed by its chapters, each row of the list is either a manga or a chapter
+#[derive(Clone, Default)]
+pub struct LatestUpdatesWidget {
+    pub updates: Vec<MangaUpdates>,
+    pub state: ListState,
+    /// The scanlation group the chapters were filtered by, shown in the title
+    pub group: Option<String>,
+}
+
+impl LatestUpdatesWidget {
+    pub fn new(updates: Vec<MangaUpdates>) -> Self {
+        let mut state = ListState::default();
+        if !updates.is_empty() {
+            state.select(Some(0));
+        }
+        Self {
+            updates,
+            state,
+            group: None,
+        }
+    }
+
+    pub fn with_group(mut self, group: Option<String>) -> Self {
+        self.group = group;
+        self
+    }
+
+    pub fn title(&self) -> String {
+        match &self.group {
+            Some(group) => format!("Latest chapters uploaded by {group}"),
+            None => "Latest chapters uploaded to mangadex".to_string(),
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (&MangaUpdates, Option<&UpdatedChapter>)> {
+        self.updates.iter().flat_map(|updates| {
+            std::iter::once((updates, None)).chain(updates.chapters.iter().map(move |chapter| (updates, Some(chapter))))
+        })
+    }
+
+    pub fn select_next(&mut self) {
+        let last = self.rows().count().saturating_sub(1);
+        self.state
+            .select(Some(self.state.selected().map_or(0, |selected| (selected + 1).min(last))));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state
+            .select(Some(self.state.selected().map_or(0, |selected| selected.saturating_sub(1))));
+    }
+
+    /// The manga selected, or the manga of the chapter selected together with the chapter
+    pub fn get_current_selected(&self) -> Option<(&Manga, Option<&UpdatedChapter>)> {
+        let (updates, chapter) = self.rows().nth(self.state.selected()?)?;
+        Some((&updates.manga, chapter))
+    }
+}
+
+impl StatefulWidget for LatestUpdatesWidget {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let title = self.title();
+        let items: Vec<ListItem<'_>> = self
+            .rows()
+            .map(|(updates, chapter)| match chapter {
+                None => ListItem::new(Line::from(vec![
+                    updates.manga.title.clone().bold(),
+                    format!(" | {} new chapters", updates.chapters.len()).into(),
+                ])),
+                Some(chapter) => {
+                    let mut line = vec![format!("  Ch. {} ", chapter.number).into()];
+                    if !chapter.title.is_empty() {
+                        line.push(chapter.title.clone().into());
+                    }
+                    if !chapter.scanlator.is_empty() {
+                        line.push(format!(" | {}", chapter.scanlator).into());
+                    }
+                    line.push(format!(" | {}", chapter.readable_at).into());
+                    ListItem::new(Line::from(line))
+                },
+            })
+            .collect();
+
+        StatefulWidget::render(
+            List::new(items)
+                .block(Block::bordered().title(title))
+                .highlight_style(*CURRENT_LIST_ITEM_STYLE),
+            area,
+            buf,
+            state,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::{ChapterAttribute, Relationship};
+
+    fn chapter(id: &str, manga_id: &str, readable_at: &str) -> ChapterData {
+        ChapterData {
+            id: id.to_string(),
+            attributes: ChapterAttribute {
+                chapter: Some(id.to_string()),
+                readable_at: readable_at.to_string(),
+                ..Default::default()
+            },
+            relationships: vec![Relationship {
+                id: manga_id.to_string(),
+                type_field: "manga".to_string(),
+                attributes: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn manga(id: &str) -> Data {
+        Data {
+            id: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-10-16T12:00:00+00:00").unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn chapters_are_grouped_by_manga_in_upload_order() {
+        let updates = group_by_manga(
+            vec![
+                chapter("3", "b", "2024-10-16T11:50:00+00:00"),
+                chapter("12", "a", "2024-10-16T09:00:00+00:00"),
+                chapter("2", "b", "2024-10-15T10:00:00+00:00"),
+                chapter("7", "missing", "2024-10-10T10:00:00+00:00"),
+            ],
+            vec![manga("a"), manga("b")],
+            now(),
+        );
+
+        let grouped: Vec<(&str, Vec<&str>)> = updates
+            .iter()
+            .map(|updates| (updates.manga.id.as_str(), updates.chapters.iter().map(|chapter| chapter.id.as_str()).collect()))
+            .collect();
+
+        assert_eq!(grouped, [("b", vec!["3", "2"]), ("a", vec!["12"])]);
+        assert_eq!(updates[0].chapters[0].readable_at, "10 minutes ago");
+        assert_eq!(updates[1].chapters[0].readable_at, "3 hours ago");
+        assert_eq!(updates[0].chapters[1].readable_at, "1 days ago");
+    }
+
+    #[test]
+    fn selecting_a_chapter_selects_its_manga() {
+        let mut widget = LatestUpdatesWidget::new(group_by_manga(
+            vec![
+                chapter("3", "b", "2024-10-16T11:50:00+00:00"),
+                chapter("12", "a", "2024-10-16T09:00:00+00:00"),
+            ],
+            vec![manga("a"), manga("b")],
+            now(),
+        ));
+
+        let mut selected = vec![];
+        for _ in 0..5 {
+            let (manga, chapter) = widget.get_current_selected().unwrap();
+            selected.push((manga.id.clone(), chapter.map(|chapter| chapter.id.clone())));
+            widget.select_next();
+        }
+
+        let expected = [("b", None), ("b", Some("3")), ("a", None), ("a", Some("12")), ("a", Some("12"))]
+            .map(|(manga, chapter)| (manga.to_string(), chapter.map(String::from)));
+        assert_eq!(selected, expected);
+    }
+}